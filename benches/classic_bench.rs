@@ -1,15 +1,31 @@
 use std::time::Duration;
 
-use divan::{bench, Bencher};
+use divan::{bench, counter::ItemsCount, Bencher};
+use indoc::indoc;
 use rand::Rng;
 use rand::RngCore;
 use rand_seeder::{SipHasher, SipRng};
-use sudoku_machine::{puzzles::classic::ClassicPuzzle, utility::seed::SeedRng};
+use sudoku_machine::{grids::bitboard::BitboardGrid, prelude::*};
 
 fn main() {
     divan::main();
 }
 
+/// A puzzle with the minimum number of clues (17), which takes a lot of searching to solve.
+const HARD_PUZZLE_MINIMUM_STR: &str = indoc! {"
+    . . . | . . . | . 1 .
+    . . . | . . 2 | . . 3
+    . . . | 4 . . | . . .
+    ------|-------|------
+    . . . | . . . | 5 . .
+    4 . 1 | 6 . . | . . .
+    . . 7 | 1 . . | . . .
+    ------|-------|------
+    . 5 . | . . . | 2 . .
+    . . . | . 8 . | . 4 .
+    . 3 . | 9 1 . | . . .
+"};
+
 struct MyRng(fastrand::Rng);
 
 impl RngCore for MyRng {
@@ -75,6 +91,37 @@ fn count_solutions_4_removed_iterative(bencher: Bencher) {
         });
 }
 
+/// Count the search nodes for the 17-clue puzzle so its benches also report nodes per second.
+fn nodes_expanded_17_clues() -> ItemsCount {
+    let mut stats = SolveStats::default();
+    ClassicPuzzle::visit_solutions_iterative_with_stats(
+        HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap(),
+        |_| true,
+        &mut stats,
+    );
+    ItemsCount::new(stats.nodes_expanded)
+}
+
+#[bench(min_time = Duration::from_secs(10))]
+fn count_solutions_17_clues_iterative(bencher: Bencher) {
+    bencher
+        .counter(nodes_expanded_17_clues())
+        .with_inputs(|| HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap())
+        .bench_values(|puzzle| {
+            let _ = ClassicPuzzle::count_solutions_iterative(puzzle);
+        });
+}
+
+#[bench(min_time = Duration::from_secs(10))]
+fn count_solutions_17_clues_recursive(bencher: Bencher) {
+    bencher
+        .counter(nodes_expanded_17_clues())
+        .with_inputs(|| HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap())
+        .bench_values(|puzzle| {
+            let _ = ClassicPuzzle::count_solutions_recursive(puzzle);
+        });
+}
+
 #[bench(min_time=Duration::from_secs(10))]
 fn fill_from_siprng(bencher: Bencher) {
     bencher
@@ -206,10 +253,95 @@ fn from_seed_recursive(bencher: Bencher) {
     bencher
         .with_inputs(|| {
             let mut rng = create_random_my_rng();
-            let seed = rng.gen_seed();
-            seed
+            rng.gen_seed()
         })
         .bench_values(|seed| {
             let _ = ClassicPuzzle::from_seed(seed);
         });
 }
+
+/// Finds the candidates of a cell by scanning its row, column, and box.
+fn array_candidates(grid: &ClassicGrid, (row, col): (u8, u8)) -> ElementSet {
+    let mut candidates = ElementSet::CLASSIC;
+    let box_index = (row / 3) * 3 + col / 3;
+    let peers = grid
+        .iter_row(row)
+        .chain(grid.iter_col(col))
+        .chain(grid.iter_box(box_index));
+    for val in peers.flatten() {
+        candidates.remove(*val);
+    }
+    candidates
+}
+
+/// Checks every row, column, and box for a repeated number by scanning the cells.
+fn array_has_conflict(grid: &ClassicGrid) -> bool {
+    (0..9).any(|i| {
+        let repeats = |cells: &mut dyn Iterator<Item = &Option<u8>>| {
+            let mut seen = ElementSet::default();
+            cells.flatten().any(|&val| {
+                let repeated = seen.has(val);
+                seen.insert(val);
+                repeated
+            })
+        };
+        repeats(&mut grid.iter_row(i))
+            || repeats(&mut grid.iter_col(i))
+            || repeats(&mut grid.iter_box(i))
+    })
+}
+
+#[bench]
+fn candidates_17_clues_array(bencher: Bencher) {
+    let grid = *HARD_PUZZLE_MINIMUM_STR
+        .parse::<ClassicPuzzle>()
+        .unwrap()
+        .grid();
+    bencher.bench(|| {
+        let mut total = 0;
+        for row in 0..9 {
+            for col in 0..9 {
+                total += array_candidates(divan::black_box(&grid), (row, col)).len();
+            }
+        }
+        total
+    });
+}
+
+#[bench]
+fn candidates_17_clues_bitboard(bencher: Bencher) {
+    let grid = *HARD_PUZZLE_MINIMUM_STR
+        .parse::<ClassicPuzzle>()
+        .unwrap()
+        .grid();
+    let bitboard = BitboardGrid::from(&grid);
+    bencher.bench(|| {
+        let mut total = 0;
+        for row in 0..9 {
+            for col in 0..9 {
+                total += divan::black_box(&bitboard)
+                    .candidate_mask((row, col))
+                    .count_ones();
+            }
+        }
+        total
+    });
+}
+
+#[bench]
+fn has_conflict_filled_array(bencher: Bencher) {
+    let mut rng = create_random_my_rng();
+    let mut puzzle = ClassicPuzzle::new();
+    puzzle.fill_from_rng(&mut rng);
+    let grid = *puzzle.grid();
+    bencher.bench(|| array_has_conflict(divan::black_box(&grid)));
+}
+
+#[bench]
+fn has_conflict_filled_bitboard(bencher: Bencher) {
+    let mut rng = create_random_my_rng();
+    let mut puzzle = ClassicPuzzle::new();
+    puzzle.fill_from_rng(&mut rng);
+    let bitboard = BitboardGrid::from(puzzle.grid());
+    bencher.bench(|| divan::black_box(&bitboard).has_conflict());
+}
@@ -0,0 +1,208 @@
+use super::classic::ClassicGrid;
+use crate::utility::element_set::ElementSet;
+
+/// The total number of cells in a classic 9x9 Sudoku board.
+const BOARD_SIZE: usize = 9 * 9;
+
+/// For every cell, a mask of the cells that share a row, column, or box with it (not including
+/// the cell itself).
+static PEER_MASKS: [u128; BOARD_SIZE] = build_peer_masks();
+
+/// A mask of the cells in each row, column, and box (in that order).
+static UNIT_MASKS: [u128; 27] = build_unit_masks();
+
+const fn build_unit_masks() -> [u128; 27] {
+    let mut masks = [0; 27];
+    let mut cell = 0;
+    while cell < BOARD_SIZE {
+        let (row, col) = (cell / 9, cell % 9);
+        let box_index = (row / 3) * 3 + col / 3;
+        masks[row] |= 1 << cell;
+        masks[9 + col] |= 1 << cell;
+        masks[18 + box_index] |= 1 << cell;
+        cell += 1;
+    }
+    masks
+}
+
+const fn build_peer_masks() -> [u128; BOARD_SIZE] {
+    let units = build_unit_masks();
+    let mut masks = [0; BOARD_SIZE];
+    let mut cell = 0;
+    while cell < BOARD_SIZE {
+        let (row, col) = (cell / 9, cell % 9);
+        let box_index = (row / 3) * 3 + col / 3;
+        masks[cell] = (units[row] | units[9 + col] | units[18 + box_index]) & !(1 << cell);
+        cell += 1;
+    }
+    masks
+}
+
+/// An alternative to `ClassicGrid` that stores, for each digit, a 128-bit mask of the cells (bit
+/// `row * 9 + col`) containing it. Candidates and conflicts can then be found with a few bitwise
+/// operations per digit instead of scanning the cells of each row, column, and box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BitboardGrid {
+    digits: [u128; 9],
+}
+
+impl BitboardGrid {
+    fn cell((row, col): (u8, u8)) -> usize {
+        row as usize * 9 + col as usize
+    }
+
+    /// Returns a mask of every filled cell.
+    #[must_use]
+    pub fn occupied(&self) -> u128 {
+        self.digits.iter().fold(0, |acc, digit| acc | digit)
+    }
+
+    /// Get the value of a cell in the grid by its row and column indices.
+    #[must_use]
+    pub fn get_by_row_col(&self, position: (u8, u8)) -> Option<u8> {
+        let bit = 1 << Self::cell(position);
+        self.digits
+            .iter()
+            .position(|digit| digit & bit != 0)
+            .map(|index| index as u8 + 1)
+    }
+
+    /// Set the value of a cell in the grid.
+    pub fn set(&mut self, position: (u8, u8), val: Option<u8>) {
+        let bit = 1 << Self::cell(position);
+        for digit in &mut self.digits {
+            *digit &= !bit;
+        }
+        if let Some(val) = val {
+            self.digits[val as usize - 1] |= bit;
+        }
+    }
+
+    /// Returns a mask with bit `n - 1` set for every number `n` that no peer of the cell contains.
+    /// This doesn't look at whether the cell itself is filled.
+    #[must_use]
+    pub fn candidate_mask(&self, position: (u8, u8)) -> u16 {
+        let peers = PEER_MASKS[Self::cell(position)];
+        self.digits
+            .iter()
+            .enumerate()
+            .fold(0, |mask, (index, digit)| {
+                mask | (u16::from(digit & peers == 0) << index)
+            })
+    }
+
+    /// Returns the set of numbers that no peer of the cell contains.
+    #[must_use]
+    pub fn candidates(&self, position: (u8, u8)) -> ElementSet {
+        let mask = self.candidate_mask(position);
+        ElementSet::from((1..=9).filter(|num| mask & (1 << (num - 1)) != 0))
+    }
+
+    /// Returns true if any row, column, or box contains a number more than once.
+    #[must_use]
+    pub fn has_conflict(&self) -> bool {
+        self.digits.iter().any(|digit| {
+            UNIT_MASKS
+                .iter()
+                .any(|unit| (digit & unit).count_ones() > 1)
+        })
+    }
+
+    /// Returns true if every cell is filled and there are no conflicts.
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        self.occupied().count_ones() as usize == BOARD_SIZE && !self.has_conflict()
+    }
+}
+
+impl From<&ClassicGrid> for BitboardGrid {
+    fn from(grid: &ClassicGrid) -> Self {
+        let mut bitboard = Self::default();
+        for (cell, val) in grid.iter_all().enumerate() {
+            if let Some(val) = val {
+                bitboard.digits[*val as usize - 1] |= 1 << cell;
+            }
+        }
+        bitboard
+    }
+}
+
+impl From<&BitboardGrid> for ClassicGrid {
+    fn from(bitboard: &BitboardGrid) -> Self {
+        let mut grid = ClassicGrid::default();
+        for row in 0..9 {
+            for col in 0..9 {
+                grid.set((row, col), bitboard.get_by_row_col((row, col)));
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const SOLUTION_STR: &str = indoc! {"
+        5 6 2 | 1 4 3 | 9 8 7
+        3 7 8 | 2 5 9 | 6 1 4
+        9 4 1 | 7 8 6 | 3 2 5
+        ------|-------|------
+        4 8 6 | 5 1 2 | 7 3 9
+        7 2 3 | 9 6 8 | 5 4 1
+        1 5 9 | 4 3 7 | 8 6 2
+        ------|-------|------
+        2 3 4 | 8 7 5 | 1 9 6
+        8 9 7 | 6 2 1 | 4 5 3
+        6 1 5 | 3 9 4 | 2 7 8
+    "};
+
+    #[test]
+    fn test_peer_masks() {
+        assert!(PEER_MASKS.iter().all(|mask| mask.count_ones() == 20));
+        assert_eq!(PEER_MASKS[0] & 1, 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        let bitboard = BitboardGrid::from(&grid);
+        assert_eq!(ClassicGrid::from(&bitboard), grid);
+        assert!(bitboard.is_solved());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut bitboard = BitboardGrid::default();
+        bitboard.set((4, 4), Some(5));
+        assert_eq!(bitboard.get_by_row_col((4, 4)), Some(5));
+        bitboard.set((4, 4), Some(6));
+        assert_eq!(bitboard.get_by_row_col((4, 4)), Some(6));
+        assert_eq!(bitboard.occupied().count_ones(), 1);
+        bitboard.set((4, 4), None);
+        assert_eq!(bitboard.occupied(), 0);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((0, 0), None);
+        let bitboard = BitboardGrid::from(&grid);
+        let candidates = bitboard.candidates((0, 0));
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates.has(5));
+        assert_eq!(BitboardGrid::default().candidates((0, 0)).len(), 9);
+    }
+
+    #[test]
+    fn test_has_conflict() {
+        let mut bitboard = BitboardGrid::default();
+        bitboard.set((0, 0), Some(1));
+        bitboard.set((8, 8), Some(1));
+        assert!(!bitboard.has_conflict());
+        bitboard.set((1, 1), Some(1));
+        assert!(bitboard.has_conflict());
+    }
+}
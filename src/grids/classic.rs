@@ -1,4 +1,11 @@
-use std::fmt::{Display, Write};
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::puzzles::{error::PuzzleError, validation::parse_grid};
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct ClassicGrid([[Option<u8>; 9]; 9]);
@@ -108,6 +115,108 @@ impl ClassicGrid {
     pub fn set(&mut self, (row, col): (u8, u8), val: Option<u8>) {
         self.0[row as usize][col as usize] = val;
     }
+
+    /// Returns a grid where each cell comes from the cell of this grid at the position `source`
+    /// gives for it.
+    fn remapped(&self, source: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        ClassicGrid(std::array::from_fn(|row| {
+            std::array::from_fn(|col| {
+                let (source_row, source_col) = source(row, col);
+                self.0[source_row][source_col]
+            })
+        }))
+    }
+
+    /// Returns the grid turned a quarter turn clockwise.
+    #[must_use]
+    pub fn rotated(&self) -> Self {
+        self.remapped(|row, col| (8 - col, row))
+    }
+
+    /// Returns the grid mirrored left to right.
+    #[must_use]
+    pub fn mirrored(&self) -> Self {
+        self.remapped(|row, col| (row, 8 - col))
+    }
+
+    /// Returns the grid with each number `num` replaced by `digits[num - 1]`. `digits` should be
+    /// a permutation of 1 to 9.
+    #[must_use]
+    pub fn with_digits_permuted(&self, digits: [u8; 9]) -> Self {
+        ClassicGrid(
+            self.0
+                .map(|row| row.map(|val| val.map(|num| digits[num as usize - 1]))),
+        )
+    }
+
+    /// Returns the grid with two bands (rows of boxes) swapped.
+    #[must_use]
+    pub fn with_bands_swapped(&self, a: u8, b: u8) -> Self {
+        let swap = |band| match band {
+            band if band == a as usize => b as usize,
+            band if band == b as usize => a as usize,
+            band => band,
+        };
+        self.remapped(|row, col| (swap(row / 3) * 3 + row % 3, col))
+    }
+
+    /// Returns the grid with two stacks (columns of boxes) swapped.
+    #[must_use]
+    pub fn with_stacks_swapped(&self, a: u8, b: u8) -> Self {
+        self.transposed().with_bands_swapped(a, b).transposed()
+    }
+
+    /// Returns the grid flipped over its main diagonal, so that rows become columns.
+    #[must_use]
+    pub fn transposed(&self) -> Self {
+        self.remapped(|row, col| (col, row))
+    }
+}
+
+/// A combination of the transformations that turn a puzzle into an equivalent one that looks
+/// different. Applying the same transformation to a puzzle and its solution keeps them matched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GridTransform {
+    /// Clockwise quarter turns, applied first
+    pub quarter_turns: u8,
+    pub mirror: bool,
+    /// What each number becomes, like `ClassicGrid::with_digits_permuted`
+    pub digits: [u8; 9],
+    /// The band that ends up at each band's position
+    pub band_order: [u8; 3],
+    /// The stack that ends up at each stack's position
+    pub stack_order: [u8; 3],
+}
+
+impl Default for GridTransform {
+    /// The transformation that leaves grids as they are.
+    fn default() -> Self {
+        Self {
+            quarter_turns: 0,
+            mirror: false,
+            digits: [1, 2, 3, 4, 5, 6, 7, 8, 9],
+            band_order: [0, 1, 2],
+            stack_order: [0, 1, 2],
+        }
+    }
+}
+
+impl GridTransform {
+    /// Applies the transformation to a grid.
+    #[must_use]
+    pub fn apply(&self, grid: &ClassicGrid) -> ClassicGrid {
+        let mut grid = (0..self.quarter_turns % 4).fold(*grid, |grid, _| grid.rotated());
+        if self.mirror {
+            grid = grid.mirrored();
+        }
+        let (band_order, stack_order) = (self.band_order, self.stack_order);
+        grid.with_digits_permuted(self.digits).remapped(|row, col| {
+            (
+                band_order[row / 3] as usize * 3 + row % 3,
+                stack_order[col / 3] as usize * 3 + col % 3,
+            )
+        })
+    }
 }
 
 impl Display for ClassicGrid {
@@ -136,25 +245,13 @@ impl Display for ClassicGrid {
     }
 }
 
-impl From<&str> for ClassicGrid {
-    /// Create a `ClassicGrid` from a string representation.
-    fn from(s: &str) -> Self {
-        let mut grid = ClassicGrid::default();
-
-        // Set the values in the grid
-        for (row, line) in s.lines().filter(|l| !l.starts_with('-')).enumerate() {
-            for (col, c) in line
-                .chars()
-                .filter(|&c| c.is_ascii_digit() || c == '.')
-                .enumerate()
-            {
-                if let Some(num) = c.to_digit(10) {
-                    grid.0[row][col] = Some(num as u8);
-                }
-            }
-        }
+impl FromStr for ClassicGrid {
+    type Err = PuzzleError;
 
-        grid
+    /// Reads a `ClassicGrid` from its string representation, which has to be 9 rows of 9 cells
+    /// with numbers from 1 to 9 or `.` for empty cells. See `puzzles::validation::parse_grid`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parse_grid(s)?)
     }
 }
 
@@ -211,9 +308,46 @@ mod tests {
 
     #[test]
     fn test_from_str() {
-        let grid_from_str = ClassicGrid::from(GRID_STR);
+        let grid_from_str: ClassicGrid = GRID_STR.parse().unwrap();
         let expected_grid = ClassicGrid::from(GRID_NUMS);
         assert_eq!(grid_from_str, expected_grid);
+        assert!(matches!(
+            "1 2 3".parse::<ClassicGrid>(),
+            Err(PuzzleError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = ClassicGrid::from(GRID_NUMS);
+        assert_eq!(grid.rotated().get_by_row_col((0, 8)), Some(5));
+        assert_eq!(grid.rotated().rotated().rotated().rotated(), grid);
+        assert_eq!(grid.mirrored().get_by_row_col((0, 7)), Some(3));
+        assert_eq!(grid.mirrored().mirrored(), grid);
+
+        let digits = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+        let permuted = grid.with_digits_permuted(digits);
+        assert_eq!(permuted.get_by_row_col((0, 0)), Some(5));
+        assert_eq!(permuted.get_by_row_col((0, 1)), Some(7));
+        assert_eq!(permuted.get_by_row_col((0, 2)), None);
+
+        let swapped = grid.with_bands_swapped(0, 2);
+        assert_eq!(swapped.get_by_row_col((6, 0)), Some(5));
+        assert_eq!(swapped.get_by_row_col((3, 0)), Some(8));
+        let swapped = grid.with_stacks_swapped(0, 1);
+        assert_eq!(swapped.get_by_row_col((1, 0)), Some(1));
+        assert_eq!(swapped.get_by_row_col((1, 3)), Some(6));
+
+        assert_eq!(GridTransform::default().apply(&grid), grid);
+        let transform = GridTransform {
+            band_order: [2, 1, 0],
+            stack_order: [1, 0, 2],
+            ..GridTransform::default()
+        };
+        assert_eq!(
+            transform.apply(&grid),
+            grid.with_bands_swapped(0, 2).with_stacks_swapped(0, 1)
+        );
     }
 
     #[test]
@@ -0,0 +1,758 @@
+use std::fmt::Display;
+
+use crate::{
+    puzzles::{classic::ClassicPuzzle, hint::Region},
+    utility::bitset::BitSet16,
+};
+
+use super::{bitboard::BitboardGrid, classic::ClassicGrid};
+
+/// The reasons an entry can be rejected by `ClassicGridState::set`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellEntryError {
+    /// The cell is outside of the 9x9 grid
+    PositionOutOfRange((u8, u8)),
+    /// The cell was given by the puzzle and can't be changed
+    Given((u8, u8)),
+    /// The value isn't a number from 1 to 9
+    ValueOutOfRange(u8),
+    /// The value is already in another cell (the second position) of the cell's row, column, or
+    /// box
+    Conflict((u8, u8), (u8, u8)),
+}
+
+impl Display for CellEntryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellEntryError::PositionOutOfRange((row, col)) => {
+                write!(f, "r{}c{} is not on the grid", row + 1, col + 1)
+            }
+            CellEntryError::Given((row, col)) => {
+                write!(f, "r{}c{} is given by the puzzle", row + 1, col + 1)
+            }
+            CellEntryError::ValueOutOfRange(val) => write!(f, "{val} is not a number from 1 to 9"),
+            CellEntryError::Conflict((row, col), (peer_row, peer_col)) => write!(
+                f,
+                "r{}c{} can't have the same number as r{}c{}",
+                row + 1,
+                col + 1,
+                peer_row + 1,
+                peer_col + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CellEntryError {}
+
+/// The kinds of pencil marks a cell can have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PencilMark {
+    /// Marks in the corners of a cell, for where a number could go within its box
+    Corner,
+    /// Marks in the center of a cell, for the numbers the cell could contain
+    Center,
+}
+
+/// Whether a cell of a puzzle was given or is left for the player to fill in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PuzzleCellKind {
+    Given,
+    #[default]
+    Open,
+}
+
+/// How a number fits in a cell, for highlighting the cells it could go in before it's entered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GhostPlacement {
+    /// The cell was given, so nothing is ever shown in it
+    Given,
+    /// The cell is filled, or a peer already has the number
+    Illegal,
+    /// The number could go in the cell
+    Legal,
+    /// The number is the only center mark left in the cell
+    OnlyCandidate,
+}
+
+/// Two cells of a row, column, or box whose center marks are the same two numbers, so neither
+/// number can go anywhere else in the unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NakedPair {
+    pub region: Region,
+    pub cells: [(u8, u8); 2],
+    pub vals: [u8; 2],
+}
+
+/// The grid a player is filling in, along with which of its cells were given by the puzzle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClassicGridState {
+    grid: ClassicGrid,
+    givens: [[bool; 9]; 9],
+    corner_marks: [[BitSet16; 9]; 9],
+    center_marks: [[BitSet16; 9]; 9],
+    mistakes: u8,
+}
+
+impl ClassicGridState {
+    /// Returns the grid with the givens and the player's entries.
+    #[must_use]
+    pub fn grid(&self) -> &ClassicGrid {
+        &self.grid
+    }
+
+    /// Returns how many entries made with `enter` didn't match the solution.
+    #[must_use]
+    pub fn mistakes(&self) -> u8 {
+        self.mistakes
+    }
+
+    /// Returns how many cells that weren't given have been filled with the solution's value.
+    #[must_use]
+    pub fn correct_entries(&self, solution: &ClassicGrid) -> usize {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&position| {
+                !self.is_given(position)
+                    && self
+                        .grid
+                        .get_by_row_col(position)
+                        .is_some_and(|val| solution.get_by_row_col(position) == Some(val))
+            })
+            .count()
+    }
+
+    /// Returns how many cells that weren't given have been filled with something other than the
+    /// solution's value.
+    #[must_use]
+    pub fn wrong_entries(&self, solution: &ClassicGrid) -> usize {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&position| {
+                !self.is_given(position)
+                    && self
+                        .grid
+                        .get_by_row_col(position)
+                        .is_some_and(|val| solution.get_by_row_col(position) != Some(val))
+            })
+            .count()
+    }
+
+    /// Returns the player's entries as 81 characters, row by row, with '.' for the givens and empty
+    /// cells. Along with the puzzle's seed, this is enough to pick a game up somewhere else.
+    #[must_use]
+    pub fn progress_string(&self) -> String {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .map(|position| match self.grid.get_by_row_col(position) {
+                Some(val) if !self.is_given(position) => char::from(b'0' + val),
+                _ => '.',
+            })
+            .collect()
+    }
+
+    /// Fills in the entries of a string from `progress_string`, e.g. when a saved game is picked up.
+    /// Characters other than 1 to 9 leave their cells as they are.
+    pub fn apply_progress(&mut self, progress: &str) -> Result<(), CellEntryError> {
+        let positions = (0..9).flat_map(|row| (0..9).map(move |col| (row, col)));
+        for (position, c) in positions.zip(progress.chars()) {
+            if let Some(val) = c.to_digit(10).filter(|val| *val > 0) {
+                self.set(position, Some(val as u8))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the pencil marks as 81 comma-separated cells, row by row. Each marked cell has its
+    /// corner marks, a '/', then its center marks, e.g. "13/2" - cells without marks are left empty.
+    #[must_use]
+    pub fn marks_string(&self) -> String {
+        let digits = |marks: BitSet16| marks.iter().map(|val| char::from(b'0' + val)).collect();
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .map(|position| {
+                let corner: String = digits(self.marks(position, PencilMark::Corner));
+                let center: String = digits(self.marks(position, PencilMark::Center));
+                if corner.is_empty() && center.is_empty() {
+                    String::new()
+                } else {
+                    format!("{corner}/{center}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Replaces the pencil marks with those of a string from `marks_string`, e.g. when a saved game
+    /// is picked up. Characters other than 1 to 9 are skipped.
+    pub fn apply_marks(&mut self, marks: &str) {
+        let marks_of = |digits: &str| {
+            let mut marks = BitSet16::default();
+            for val in digits
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .filter(|val| *val > 0)
+            {
+                marks.insert(val as u8);
+            }
+            marks
+        };
+        self.clear_marks();
+        for (index, cell) in marks.split(',').take(81).enumerate() {
+            let (row, col) = (index / 9, index % 9);
+            let (corner, center) = cell.split_once('/').unwrap_or((cell, ""));
+            self.corner_marks[row][col] = marks_of(corner);
+            self.center_marks[row][col] = marks_of(center);
+        }
+    }
+
+    /// Sets how many entries didn't match the solution, e.g. when a saved game is picked up.
+    pub fn set_mistakes(&mut self, mistakes: u8) {
+        self.mistakes = mistakes;
+    }
+
+    /// Returns true if the cell was given by the puzzle.
+    #[must_use]
+    pub fn is_given(&self, (row, col): (u8, u8)) -> bool {
+        row < 9 && col < 9 && self.givens[row as usize][col as usize]
+    }
+
+    /// Returns whether a cell was given by the puzzle or is left to be filled in.
+    #[must_use]
+    pub fn cell_kind(&self, position: (u8, u8)) -> PuzzleCellKind {
+        if self.is_given(position) {
+            PuzzleCellKind::Given
+        } else {
+            PuzzleCellKind::Open
+        }
+    }
+
+    /// Returns the grid with only the cells given by the puzzle.
+    #[must_use]
+    pub fn givens(&self) -> ClassicGrid {
+        let mut givens = self.grid;
+        for row in 0..9 {
+            for col in 0..9 {
+                if !self.is_given((row, col)) {
+                    givens.set((row, col), None);
+                }
+            }
+        }
+        givens
+    }
+
+    /// Returns the pencil marks of a kind in a cell. Marks are kept when the cell is filled, so that
+    /// they come back if it's cleared.
+    #[must_use]
+    pub fn marks(&self, (row, col): (u8, u8), kind: PencilMark) -> BitSet16 {
+        match kind {
+            PencilMark::Corner => self.corner_marks[row as usize][col as usize],
+            PencilMark::Center => self.center_marks[row as usize][col as usize],
+        }
+    }
+
+    /// Adds or removes a pencil mark of a kind in a cell that isn't given.
+    pub fn toggle_mark(
+        &mut self,
+        position: (u8, u8),
+        kind: PencilMark,
+        val: u8,
+    ) -> Result<(), CellEntryError> {
+        self.check_entry(position, Some(val))?;
+        let (row, col) = (position.0 as usize, position.1 as usize);
+        let marks = match kind {
+            PencilMark::Corner => &mut self.corner_marks[row][col],
+            PencilMark::Center => &mut self.center_marks[row][col],
+        };
+        if marks.has(val) {
+            marks.remove(val);
+        } else {
+            marks.insert(val);
+        }
+        Ok(())
+    }
+
+    /// Sets the center marks of every empty cell to the numbers that don't already appear in its
+    /// row, column, or box. Filled cells keep their marks.
+    pub fn fill_candidates(&mut self) {
+        let bitboard = BitboardGrid::from(&self.grid);
+        for row in 0..9 {
+            for col in 0..9 {
+                if self.grid.get_by_row_col((row, col)).is_some() {
+                    continue;
+                }
+                let mask = bitboard.candidate_mask((row, col));
+                let mut marks = BitSet16::default();
+                for val in (1..=9).filter(|val| mask & (1 << (val - 1)) != 0) {
+                    marks.insert(val);
+                }
+                self.center_marks[row as usize][col as usize] = marks;
+            }
+        }
+    }
+
+    /// Removes a number's pencil marks from the cells that share a row, column, or box with a cell,
+    /// e.g. after the number is entered there.
+    pub fn erase_peer_marks(&mut self, position: (u8, u8), val: u8) {
+        for (peer_row, peer_col) in ClassicPuzzle::iter_peers(position) {
+            self.corner_marks[peer_row as usize][peer_col as usize].remove(val);
+            self.center_marks[peer_row as usize][peer_col as usize].remove(val);
+        }
+    }
+
+    /// Removes every pencil mark from every cell.
+    pub fn clear_marks(&mut self) {
+        self.corner_marks = [[BitSet16::default(); 9]; 9];
+        self.center_marks = [[BitSet16::default(); 9]; 9];
+    }
+
+    /// Removes a number's pencil marks from every cell.
+    pub fn remove_marks(&mut self, val: u8) {
+        for marks in self
+            .corner_marks
+            .iter_mut()
+            .chain(self.center_marks.iter_mut())
+            .flatten()
+        {
+            marks.remove(val);
+        }
+    }
+
+    /// Returns the naked pairs in the center marks of the empty cells: units where exactly two cells
+    /// have the same two marks and nothing else. A pair in both a row or column and a box is
+    /// returned once for each.
+    #[must_use]
+    pub fn naked_pairs(&self) -> Vec<NakedPair> {
+        let regions = (0..9).flat_map(|i| [Region::Row(i), Region::Col(i), Region::Box(i)]);
+        let mut pairs = Vec::new();
+        for region in regions {
+            let marked: Vec<((u8, u8), BitSet16)> = region
+                .cells()
+                .filter(|&position| self.grid.get_by_row_col(position).is_none())
+                .map(|position| (position, self.marks(position, PencilMark::Center)))
+                .filter(|(_, marks)| marks.len() == 2)
+                .collect();
+            for (index, &(first, marks)) in marked.iter().enumerate() {
+                let mut matching = marked[index + 1..]
+                    .iter()
+                    .filter(|(_, other)| *other == marks);
+                let (Some(&(second, _)), None) = (matching.next(), matching.next()) else {
+                    continue;
+                };
+                // Only the first cell of the pair starts it, and three cells with the same marks
+                // aren't a pair
+                if marked[..index].iter().any(|(_, other)| *other == marks) {
+                    continue;
+                }
+                let mut vals = marks.iter();
+                let (Some(low), Some(high)) = (vals.next(), vals.next()) else {
+                    continue;
+                };
+                pairs.push(NakedPair {
+                    region,
+                    cells: [first, second],
+                    vals: [low, high],
+                });
+            }
+        }
+        pairs
+    }
+
+    /// Returns a cell sharing a row, column, or box with a cell that already has a value, if any.
+    #[must_use]
+    pub fn conflicting_peer(&self, position: (u8, u8), val: u8) -> Option<(u8, u8)> {
+        ClassicPuzzle::iter_peers(position)
+            .find(|&peer| self.grid.get_by_row_col(peer) == Some(val))
+    }
+
+    /// Returns an error if a cell can't be changed to a value.
+    fn check_entry(&self, position: (u8, u8), val: Option<u8>) -> Result<(), CellEntryError> {
+        let (row, col) = position;
+        if row >= 9 || col >= 9 {
+            return Err(CellEntryError::PositionOutOfRange(position));
+        }
+        if self.is_given(position) {
+            return Err(CellEntryError::Given(position));
+        }
+        if let Some(val) = val.filter(|val| !(1..=9).contains(val)) {
+            return Err(CellEntryError::ValueOutOfRange(val));
+        }
+        Ok(())
+    }
+
+    /// Set or clear the value of a cell that isn't given. Nothing changes if the entry is rejected.
+    pub fn set(&mut self, position: (u8, u8), val: Option<u8>) -> Result<(), CellEntryError> {
+        self.check_entry(position, val)?;
+        self.grid.set(position, val);
+        Ok(())
+    }
+
+    /// Set the value of a cell like `set`, counting a mistake if it doesn't match the solution.
+    /// Returns whether the value was correct.
+    pub fn enter(
+        &mut self,
+        position: (u8, u8),
+        val: u8,
+        solution: &ClassicGrid,
+    ) -> Result<bool, CellEntryError> {
+        self.set(position, Some(val))?;
+        let correct = solution.get_by_row_col(position) == Some(val);
+        if !correct {
+            self.mistakes = self.mistakes.saturating_add(1);
+        }
+        Ok(correct)
+    }
+
+    /// Returns how a number fits in a cell. Only candidates come from the center marks, so they
+    /// follow the candidates that were filled in (e.g. by the auto-candidates assist).
+    #[must_use]
+    pub fn ghost_placement(&self, position: (u8, u8), val: u8) -> GhostPlacement {
+        if self.is_given(position) {
+            GhostPlacement::Given
+        } else if self.grid.get_by_row_col(position).is_some()
+            || self.conflicting_peer(position, val).is_some()
+        {
+            GhostPlacement::Illegal
+        } else if self.marks(position, PencilMark::Center).iter().eq([val]) {
+            GhostPlacement::OnlyCandidate
+        } else {
+            GhostPlacement::Legal
+        }
+    }
+
+    /// Enter a value like `enter`, but reject it if a cell sharing its row, column, or box already
+    /// has it. Rejected entries aren't mistakes.
+    pub fn enter_strict(
+        &mut self,
+        position: (u8, u8),
+        val: u8,
+        solution: &ClassicGrid,
+    ) -> Result<bool, CellEntryError> {
+        self.check_entry(position, Some(val))?;
+        if let Some(peer) = self.conflicting_peer(position, val) {
+            return Err(CellEntryError::Conflict(position, peer));
+        }
+        self.enter(position, val, solution)
+    }
+}
+
+impl From<ClassicGrid> for ClassicGridState {
+    /// Start a state from a puzzle's grid, where every filled cell is a given.
+    fn from(grid: ClassicGrid) -> Self {
+        let mut givens = [[false; 9]; 9];
+        for (row, row_givens) in givens.iter_mut().enumerate() {
+            for (col, given) in row_givens.iter_mut().enumerate() {
+                *given = grid.get_by_row_col((row as u8, col as u8)).is_some();
+            }
+        }
+        Self {
+            grid,
+            givens,
+            corner_marks: [[BitSet16::default(); 9]; 9],
+            center_marks: [[BitSet16::default(); 9]; 9],
+            mistakes: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const GRID_STR: &str = indoc! {"
+        5 3 . | . 7 . | . . .
+        6 . . | 1 9 5 | . . .
+        . 9 8 | . . . | . 6 .
+        ------|-------|------
+        8 . . | . 6 . | . . 3
+        4 . . | 8 . 3 | . . 1
+        7 . . | . 2 . | . . 6
+        ------|-------|------
+        . 6 . | . . . | 2 8 .
+        . . . | 4 1 9 | . . 5
+        . . . | . 8 . | . 7 9
+    "};
+
+    #[test]
+    fn test_set_empty_cell() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert!(!state.is_given((0, 2)));
+        assert_eq!(state.set((0, 2), Some(4)), Ok(()));
+        assert_eq!(state.grid().get_by_row_col((0, 2)), Some(4));
+        assert_eq!(state.set((0, 2), None), Ok(()));
+        assert_eq!(state.grid().get_by_row_col((0, 2)), None);
+        // A player's entry never becomes a given
+        assert!(!state.is_given((0, 2)));
+        state.set((0, 2), Some(4)).unwrap();
+        assert_eq!(state.givens(), GRID_STR.parse::<ClassicGrid>().unwrap());
+    }
+
+    #[test]
+    fn test_set_given() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert!(state.is_given((0, 0)));
+        assert_eq!(state.cell_kind((0, 0)), PuzzleCellKind::Given);
+        assert_eq!(state.cell_kind((0, 2)), PuzzleCellKind::Open);
+        assert_eq!(
+            state.set((0, 0), Some(1)),
+            Err(CellEntryError::Given((0, 0)))
+        );
+        assert_eq!(state.set((0, 0), None), Err(CellEntryError::Given((0, 0))));
+        assert_eq!(state.grid().get_by_row_col((0, 0)), Some(5));
+    }
+
+    #[test]
+    fn test_enter_counts_mistakes() {
+        let mut solution = GRID_STR.parse::<ClassicGrid>().unwrap();
+        solution.set((0, 2), Some(4));
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert_eq!(state.enter((0, 2), 1, &solution), Ok(false));
+        assert_eq!(state.enter((0, 2), 4, &solution), Ok(true));
+        // Rejected entries aren't mistakes
+        assert_eq!(
+            state.enter((0, 0), 1, &solution),
+            Err(CellEntryError::Given((0, 0)))
+        );
+        assert_eq!(state.mistakes(), 1);
+    }
+
+    #[test]
+    fn test_enter_strict_rejects_conflicts() {
+        let mut solution = GRID_STR.parse::<ClassicGrid>().unwrap();
+        solution.set((0, 2), Some(4));
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        // 7 is in the row, 8 is in the column (and box), and 9 is in the box
+        for (val, peer) in [(7, (0, 4)), (8, (2, 2)), (9, (2, 1))] {
+            assert_eq!(
+                state.enter_strict((0, 2), val, &solution),
+                Err(CellEntryError::Conflict((0, 2), peer))
+            );
+        }
+        assert_eq!(state.grid().get_by_row_col((0, 2)), None);
+        assert_eq!(state.mistakes(), 0);
+        // Wrong numbers that don't clash with a peer are still entered
+        assert_eq!(state.enter_strict((0, 2), 1, &solution), Ok(false));
+        assert_eq!(state.enter_strict((0, 2), 4, &solution), Ok(true));
+        assert_eq!(state.mistakes(), 1);
+    }
+
+    #[test]
+    fn test_ghost_placement() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert_eq!(state.ghost_placement((0, 0), 1), GhostPlacement::Given);
+        assert_eq!(state.ghost_placement((0, 2), 7), GhostPlacement::Illegal);
+        assert_eq!(state.ghost_placement((0, 2), 1), GhostPlacement::Legal);
+        state.fill_candidates();
+        // r5c5 can only be 5
+        assert_eq!(
+            state.ghost_placement((4, 4), 5),
+            GhostPlacement::OnlyCandidate
+        );
+        assert_eq!(state.ghost_placement((0, 2), 1), GhostPlacement::Legal);
+    }
+
+    #[test]
+    fn test_correct_entries() {
+        let mut solution = GRID_STR.parse::<ClassicGrid>().unwrap();
+        solution.set((0, 2), Some(4));
+        solution.set((0, 3), Some(6));
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        // Givens don't count
+        assert_eq!(state.correct_entries(&solution), 0);
+        state.set((0, 2), Some(4)).unwrap();
+        state.set((0, 3), Some(2)).unwrap();
+        assert_eq!(state.correct_entries(&solution), 1);
+    }
+
+    #[test]
+    fn test_wrong_entries() {
+        let mut solution = GRID_STR.parse::<ClassicGrid>().unwrap();
+        solution.set((0, 2), Some(4));
+        solution.set((0, 3), Some(6));
+        solution.set((0, 5), Some(8));
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert_eq!(state.wrong_entries(&solution), 0);
+        state.set((0, 2), Some(4)).unwrap();
+        state.set((0, 3), Some(2)).unwrap();
+        state.set((0, 5), Some(1)).unwrap();
+        // Empty cells and correct entries aren't wrong
+        assert_eq!(state.wrong_entries(&solution), 2);
+    }
+
+    #[test]
+    fn test_toggle_mark() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        state.toggle_mark((0, 2), PencilMark::Corner, 1).unwrap();
+        state.toggle_mark((0, 2), PencilMark::Center, 2).unwrap();
+        state.toggle_mark((0, 2), PencilMark::Center, 4).unwrap();
+        let corner_marks = state.marks((0, 2), PencilMark::Corner);
+        let center_marks = state.marks((0, 2), PencilMark::Center);
+        assert_eq!(corner_marks.iter().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(center_marks.iter().collect::<Vec<_>>(), vec![2, 4]);
+        state.toggle_mark((0, 2), PencilMark::Center, 2).unwrap();
+        assert_eq!(state.marks((0, 2), PencilMark::Center).len(), 1);
+        assert_eq!(
+            state.toggle_mark((0, 0), PencilMark::Corner, 1),
+            Err(CellEntryError::Given((0, 0)))
+        );
+        assert_eq!(
+            state.toggle_mark((0, 2), PencilMark::Corner, 0),
+            Err(CellEntryError::ValueOutOfRange(0))
+        );
+    }
+
+    #[test]
+    fn test_set_out_of_range() {
+        let mut state = ClassicGridState::default();
+        assert_eq!(
+            state.set((0, 0), Some(0)),
+            Err(CellEntryError::ValueOutOfRange(0))
+        );
+        assert_eq!(
+            state.set((0, 0), Some(10)),
+            Err(CellEntryError::ValueOutOfRange(10))
+        );
+        assert_eq!(
+            state.set((9, 0), Some(1)),
+            Err(CellEntryError::PositionOutOfRange((9, 0)))
+        );
+        assert_eq!(*state.grid(), ClassicGrid::default());
+    }
+
+    #[test]
+    fn test_fill_candidates() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        state.fill_candidates();
+        let center = |position| state.marks(position, PencilMark::Center);
+        assert_eq!(center((0, 2)).iter().collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(center((4, 4)).iter().collect::<Vec<_>>(), vec![5]);
+        assert!(center((0, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_erase_peer_marks() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        state.fill_candidates();
+        state.toggle_mark((2, 0), PencilMark::Corner, 4).unwrap();
+        state.set((0, 2), Some(4)).unwrap();
+        state.erase_peer_marks((0, 2), 4);
+        assert!(!state.marks((2, 0), PencilMark::Corner).has(4));
+        assert!(!state.marks((0, 3), PencilMark::Center).has(4));
+        assert!(!state.marks((8, 2), PencilMark::Center).has(4));
+        // Cells outside the row, column, and box keep their marks
+        assert!(state.marks((3, 6), PencilMark::Center).has(4));
+    }
+
+    #[test]
+    fn test_clear_and_remove_marks() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        state.fill_candidates();
+        state.toggle_mark((2, 0), PencilMark::Corner, 4).unwrap();
+        state.remove_marks(4);
+        assert!(!state.marks((2, 0), PencilMark::Corner).has(4));
+        assert_eq!(
+            state
+                .marks((0, 2), PencilMark::Center)
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        state.clear_marks();
+        assert!(state.marks((0, 2), PencilMark::Center).is_empty());
+        assert!(state.marks((2, 0), PencilMark::Corner).is_empty());
+    }
+
+    #[test]
+    fn test_naked_pairs() {
+        let mut state = ClassicGridState::default();
+        for position in [(0, 0), (0, 1)] {
+            for val in [1, 2] {
+                state
+                    .toggle_mark(position, PencilMark::Center, val)
+                    .unwrap();
+            }
+        }
+        state.toggle_mark((0, 5), PencilMark::Center, 3).unwrap();
+        assert_eq!(
+            state.naked_pairs(),
+            vec![
+                NakedPair {
+                    region: Region::Row(0),
+                    cells: [(0, 0), (0, 1)],
+                    vals: [1, 2],
+                },
+                NakedPair {
+                    region: Region::Box(0),
+                    cells: [(0, 0), (0, 1)],
+                    vals: [1, 2],
+                },
+            ]
+        );
+        // A third cell in the row with the same marks means they aren't a pair in the row
+        state.toggle_mark((0, 5), PencilMark::Center, 3).unwrap();
+        for val in [1, 2] {
+            state.toggle_mark((0, 5), PencilMark::Center, val).unwrap();
+        }
+        assert_eq!(
+            state.naked_pairs(),
+            vec![NakedPair {
+                region: Region::Box(0),
+                cells: [(0, 0), (0, 1)],
+                vals: [1, 2],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_progress_string() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert_eq!(state.progress_string(), ".".repeat(81));
+        state.set((0, 2), Some(4)).unwrap();
+        state.set((8, 0), Some(3)).unwrap();
+        let progress = state.progress_string();
+        assert_eq!(progress.len(), 81);
+        assert_eq!(&progress[..3], "..4");
+        assert_eq!(&progress[72..73], "3");
+        assert_eq!(progress.matches('.').count(), 79);
+    }
+
+    #[test]
+    fn test_marks_string() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        assert_eq!(state.marks_string(), ",".repeat(80));
+        state.toggle_mark((0, 2), PencilMark::Corner, 1).unwrap();
+        state.toggle_mark((0, 2), PencilMark::Corner, 3).unwrap();
+        state.toggle_mark((0, 2), PencilMark::Center, 2).unwrap();
+        state.toggle_mark((8, 0), PencilMark::Center, 9).unwrap();
+        let marks = state.marks_string();
+        assert!(marks.starts_with(",,13/2,"));
+        assert!(marks.ends_with(",/9,,,,,,,,"));
+
+        let mut restored = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        restored.toggle_mark((4, 4), PencilMark::Corner, 5).unwrap();
+        restored.apply_marks(&marks);
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn test_apply_progress() {
+        let mut state = ClassicGridState::from(GRID_STR.parse::<ClassicGrid>().unwrap());
+        let mut saved = state;
+        saved.set((0, 2), Some(4)).unwrap();
+        saved.set((8, 0), Some(3)).unwrap();
+        state.apply_progress(&saved.progress_string()).unwrap();
+        assert_eq!(state, saved);
+        let given = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .find(|position| state.is_given(*position))
+            .unwrap();
+        let mut progress = vec!['.'; 81];
+        progress[usize::from(given.0) * 9 + usize::from(given.1)] = '5';
+        assert_eq!(
+            state.apply_progress(&progress.into_iter().collect::<String>()),
+            Err(CellEntryError::Given(given))
+        );
+    }
+}
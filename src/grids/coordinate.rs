@@ -0,0 +1,101 @@
+enum CoordinateMatch {
+    /// The input could become a coordinate with more characters.
+    Prefix,
+    /// The input is a full coordinate of a (row, col) cell.
+    Complete((u8, u8)),
+    None,
+}
+
+fn match_coordinate(input: &[u8]) -> CoordinateMatch {
+    match input {
+        [b'r'] | [b'r', b'1'..=b'9'] | [b'r', b'1'..=b'9', b'c'] | [b'a'..=b'i'] => {
+            CoordinateMatch::Prefix
+        }
+        [b'r', row @ b'1'..=b'9', b'c', col @ b'1'..=b'9'] => {
+            CoordinateMatch::Complete((row - b'1', col - b'1'))
+        }
+        [row @ b'a'..=b'i', col @ b'1'..=b'9'] => {
+            CoordinateMatch::Complete((row - b'a', col - b'1'))
+        }
+        _ => CoordinateMatch::None,
+    }
+}
+
+/// Parses a cell coordinate like "r4c7" or "D7" into its (row, col), ignoring case and
+/// surrounding whitespace.
+#[must_use]
+pub fn parse_coordinate(input: &str) -> Option<(u8, u8)> {
+    match match_coordinate(input.trim().to_ascii_lowercase().as_bytes()) {
+        CoordinateMatch::Complete(position) => Some(position),
+        CoordinateMatch::Prefix | CoordinateMatch::None => None,
+    }
+}
+
+/// Collects typed characters until they spell a cell coordinate. Characters that can't continue
+/// the coordinate start a new one (or are dropped), so stray keys never get in the way.
+#[derive(Default, Debug, Clone)]
+pub struct CoordinateInput {
+    buffer: String,
+}
+
+impl CoordinateInput {
+    /// Adds a typed character, returning the (row, col) once a full coordinate has been typed.
+    pub fn push(&mut self, c: char) -> Option<(u8, u8)> {
+        let c = c.to_ascii_lowercase();
+        self.buffer.push(c);
+        match match_coordinate(self.buffer.as_bytes()) {
+            CoordinateMatch::Complete(position) => {
+                self.buffer.clear();
+                return Some(position);
+            }
+            CoordinateMatch::Prefix => return None,
+            CoordinateMatch::None => self.buffer.clear(),
+        }
+        if c.is_ascii() && matches!(match_coordinate(&[c as u8]), CoordinateMatch::Prefix) {
+            self.buffer.push(c);
+        }
+        None
+    }
+
+    /// Returns what has been typed of the coordinate so far.
+    #[must_use]
+    pub fn pending(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_coordinate_forms() {
+        assert_eq!(parse_coordinate("r4c7"), Some((3, 6)));
+        assert_eq!(parse_coordinate(" R1C9 "), Some((0, 8)));
+        assert_eq!(parse_coordinate("D7"), Some((3, 6)));
+        assert_eq!(parse_coordinate("i1"), Some((8, 0)));
+        assert_eq!(parse_coordinate("r0c1"), None);
+        assert_eq!(parse_coordinate("j1"), None);
+        assert_eq!(parse_coordinate("r4"), None);
+    }
+
+    #[test]
+    fn coordinate_input_restarts_on_stray_keys() {
+        let mut input = CoordinateInput::default();
+        let typed: Vec<_> = "xr4zr4c7".chars().map(|c| input.push(c)).collect();
+        assert_eq!(typed.last(), Some(&Some((3, 6))));
+        assert!(typed[..typed.len() - 1].iter().all(Option::is_none));
+        assert_eq!(input.pending(), "");
+
+        // A letter that can't continue "r4c" starts a labelled coordinate instead
+        for c in "r4cb".chars() {
+            assert_eq!(input.push(c), None);
+        }
+        assert_eq!(input.pending(), "b");
+        assert_eq!(input.push('2'), Some((1, 1)));
+    }
+}
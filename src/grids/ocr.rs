@@ -0,0 +1,376 @@
+use std::collections::VecDeque;
+
+use super::classic::ClassicGrid;
+
+/// The size of each cell after the grid is straightened, in pixels.
+const WARPED_CELL_SIZE: usize = 32;
+
+/// The fraction of each side of a cell that's ignored so the grid lines aren't mistaken for ink.
+const CELL_MARGIN: f32 = 0.15;
+
+/// The fraction of the inside of a cell that must be ink for it to be treated as a given.
+const MIN_INK_FRACTION: f32 = 0.03;
+
+/// The digits 1 to 9 in a 5x7 font, which recognized digits are compared against.
+const DIGIT_TEMPLATES: [[&str; 7]; 9] = [
+    [
+        "..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###.",
+    ],
+    [
+        ".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####",
+    ],
+    [
+        "#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###.",
+    ],
+    [
+        "...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#.",
+    ],
+    [
+        "#####", "#....", "####.", "....#", "....#", "#...#", ".###.",
+    ],
+    [
+        "..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###.",
+    ],
+    [
+        "#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#...",
+    ],
+    [
+        ".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###.",
+    ],
+    [
+        ".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##..",
+    ],
+];
+
+/// A grayscale image, where 0 is black and 255 is white.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrayImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl GrayImage {
+    /// Creates an image from its pixels in reading order. Returns `None` if there aren't
+    /// `width * height` pixels.
+    #[must_use]
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Option<Self> {
+        (pixels.len() == width * height).then_some(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Returns the level that best separates dark pixels from light ones (Otsu's method).
+    fn threshold(&self) -> u8 {
+        let mut histogram = [0usize; 256];
+        for &pixel in &self.pixels {
+            histogram[pixel as usize] += 1;
+        }
+        let total = self.pixels.len() as f64;
+        let sum: f64 = histogram
+            .iter()
+            .enumerate()
+            .map(|(level, &count)| level as f64 * count as f64)
+            .sum();
+        let (mut dark_count, mut dark_sum) = (0.0, 0.0);
+        let (mut best_level, mut best_variance) = (0, 0.0);
+        for (level, &count) in histogram.iter().enumerate() {
+            dark_count += count as f64;
+            dark_sum += level as f64 * count as f64;
+            let light_count = total - dark_count;
+            if dark_count == 0.0 || light_count == 0.0 {
+                continue;
+            }
+            let mean_difference = dark_sum / dark_count - (sum - dark_sum) / light_count;
+            let variance = dark_count * light_count * mean_difference * mean_difference;
+            if variance > best_variance {
+                (best_level, best_variance) = (level, variance);
+            }
+        }
+        best_level as u8
+    }
+}
+
+/// Returns the corners (top left, top right, bottom right, bottom left) of the grid in an image,
+/// taken to be the largest connected shape of dark pixels.
+fn find_grid_corners(image: &GrayImage, threshold: u8) -> Option<[(f32, f32); 4]> {
+    let is_ink = |x: usize, y: usize| image.get(x, y) <= threshold;
+    let mut visited = vec![false; image.pixels.len()];
+    let mut largest: Vec<(usize, usize)> = Vec::new();
+    for start in 0..image.pixels.len() {
+        let (x, y) = (start % image.width, start / image.width);
+        if visited[start] || !is_ink(x, y) {
+            continue;
+        }
+        // Flood fill the shape
+        let mut shape = Vec::new();
+        let mut queue = VecDeque::from([(x, y)]);
+        visited[start] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            shape.push((x, y));
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbors {
+                if nx < image.width && ny < image.height {
+                    let index = ny * image.width + nx;
+                    if !visited[index] && is_ink(nx, ny) {
+                        visited[index] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+        if shape.len() > largest.len() {
+            largest = shape;
+        }
+    }
+
+    let corner = |key: fn(f32, f32) -> f32| {
+        largest
+            .iter()
+            .map(|&(x, y)| (x as f32, y as f32))
+            .max_by(|a, b| key(a.0, a.1).total_cmp(&key(b.0, b.1)))
+    };
+    let corners = [
+        corner(|x, y| -(x + y))?,
+        corner(|x, y| x - y)?,
+        corner(|x, y| x + y)?,
+        corner(|x, y| y - x)?,
+    ];
+    // Too small to hold 81 readable cells
+    let (top_left, bottom_right) = (corners[0], corners[2]);
+    if bottom_right.0 - top_left.0 < 9.0 * 7.0 || bottom_right.1 - top_left.1 < 9.0 * 7.0 {
+        return None;
+    }
+    Some(corners)
+}
+
+/// Straightens the grid between the corners into a square image, correcting for perspective.
+fn warp_grid(image: &GrayImage, corners: [(f32, f32); 4]) -> GrayImage {
+    // Map the unit square onto the corners (Heckbert's square to quadrilateral projection)
+    let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = corners;
+    let (dx1, dx2, dx3) = (x1 - x2, x3 - x2, x0 - x1 + x2 - x3);
+    let (dy1, dy2, dy3) = (y1 - y2, y3 - y2, y0 - y1 + y2 - y3);
+    let determinant = dx1 * dy2 - dx2 * dy1;
+    let g = (dx3 * dy2 - dx2 * dy3) / determinant;
+    let h = (dx1 * dy3 - dx3 * dy1) / determinant;
+    let (a, b, c) = (x1 - x0 + g * x1, x3 - x0 + h * x3, x0);
+    let (d, e, f) = (y1 - y0 + g * y1, y3 - y0 + h * y3, y0);
+
+    let size = 9 * WARPED_CELL_SIZE;
+    let mut pixels = Vec::with_capacity(size * size);
+    for row in 0..size {
+        for col in 0..size {
+            let (u, v) = (
+                (col as f32 + 0.5) / size as f32,
+                (row as f32 + 0.5) / size as f32,
+            );
+            let w = g * u + h * v + 1.0;
+            let x = ((a * u + b * v + c) / w)
+                .round()
+                .clamp(0.0, image.width as f32 - 1.0);
+            let y = ((d * u + e * v + f) / w)
+                .round()
+                .clamp(0.0, image.height as f32 - 1.0);
+            pixels.push(image.get(x as usize, y as usize));
+        }
+    }
+    GrayImage {
+        width: size,
+        height: size,
+        pixels,
+    }
+}
+
+/// Returns the digit in a cell of a straightened grid, or `None` if it's empty.
+fn recognize_cell(warped: &GrayImage, threshold: u8, (row, col): (usize, usize)) -> Option<u8> {
+    let margin = (WARPED_CELL_SIZE as f32 * CELL_MARGIN) as usize;
+    let (left, top) = (
+        col * WARPED_CELL_SIZE + margin,
+        row * WARPED_CELL_SIZE + margin,
+    );
+    let inner = WARPED_CELL_SIZE - 2 * margin;
+    let is_ink = |x: usize, y: usize| warped.get(x, y) <= threshold;
+
+    // Find the bounding box of the ink
+    let ink: Vec<_> = (top..top + inner)
+        .flat_map(|y| (left..left + inner).map(move |x| (x, y)))
+        .filter(|&(x, y)| is_ink(x, y))
+        .collect();
+    if (ink.len() as f32) < MIN_INK_FRACTION * (inner * inner) as f32 {
+        return None;
+    }
+    let min_x = ink.iter().map(|p| p.0).min()? as f32;
+    let max_x = ink.iter().map(|p| p.0).max()? as f32 + 1.0;
+    let min_y = ink.iter().map(|p| p.1).min()? as f32;
+    let max_y = ink.iter().map(|p| p.1).max()? as f32 + 1.0;
+
+    // Widen or heighten the box about its center to the templates' 5:7 shape, so that narrow
+    // digits like 1 aren't stretched
+    let (mut width, mut height) = (max_x - min_x, max_y - min_y);
+    if width * 7.0 < height * 5.0 {
+        width = height * 5.0 / 7.0;
+    } else {
+        height = width * 7.0 / 5.0;
+    }
+    let (box_left, box_top) = (
+        (min_x + max_x - width) / 2.0,
+        (min_y + max_y - height) / 2.0,
+    );
+
+    // Sample the box as a 5x7 bitmap and pick the closest template
+    let mut bitmap = [[false; 5]; 7];
+    for (template_row, bitmap_row) in bitmap.iter_mut().enumerate() {
+        for (template_col, bit) in bitmap_row.iter_mut().enumerate() {
+            let x0 = box_left + width * template_col as f32 / 5.0;
+            let y0 = box_top + height * template_row as f32 / 7.0;
+            let (x1, y1) = (x0 + width / 5.0, y0 + height / 7.0);
+            let (mut inked, mut total) = (0, 0);
+            for y in (y0.round() as i64)..(y1.round() as i64).max(y0.round() as i64 + 1) {
+                for x in (x0.round() as i64)..(x1.round() as i64).max(x0.round() as i64 + 1) {
+                    total += 1;
+                    if x >= 0
+                        && y >= 0
+                        && (x as usize) < warped.width
+                        && (y as usize) < warped.height
+                        && is_ink(x as usize, y as usize)
+                    {
+                        inked += 1;
+                    }
+                }
+            }
+            *bit = inked * 2 >= total;
+        }
+    }
+    DIGIT_TEMPLATES
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, template)| {
+            template
+                .iter()
+                .zip(&bitmap)
+                .map(|(template_row, bitmap_row)| {
+                    template_row
+                        .bytes()
+                        .zip(bitmap_row)
+                        .filter(|(pixel, bit)| (*pixel == b'#') != **bit)
+                        .count()
+                })
+                .sum::<usize>()
+        })
+        .map(|(index, _)| index as u8 + 1)
+}
+
+/// Finds a Sudoku grid in an image (a photo or screenshot) and reads its givens. Returns `None` if
+/// no grid was found. This is experimental and the result should be checked by the player.
+#[must_use]
+pub fn recognize_grid(image: &GrayImage) -> Option<ClassicGrid> {
+    let threshold = image.threshold();
+    let corners = find_grid_corners(image, threshold)?;
+    let warped = warp_grid(image, corners);
+    let mut grid = ClassicGrid::default();
+    for row in 0..9 {
+        for col in 0..9 {
+            grid.set(
+                (row as u8, col as u8),
+                recognize_cell(&warped, threshold, (row, col)),
+            );
+        }
+    }
+    Some(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const GRID_STR: &str = indoc! {"
+        5 3 . | . 7 . | . . .
+        6 . . | 1 9 5 | . . .
+        . 9 8 | . . . | . 6 .
+        ------|-------|------
+        8 . . | . 6 . | . . 3
+        4 . . | 8 . 3 | . . 1
+        7 . . | . 2 . | . . 6
+        ------|-------|------
+        . 6 . | . . . | 2 8 .
+        . . . | 4 1 9 | . . 5
+        . . . | . 8 . | . 7 9
+    "};
+
+    /// Draws a grid with its lines and digits (in the template font) on a white image.
+    fn draw_grid(grid: &ClassicGrid, origin: (usize, usize), cell_size: usize) -> GrayImage {
+        let (width, height) = (origin.0 * 2 + cell_size * 9, origin.1 * 2 + cell_size * 9);
+        let mut pixels = vec![255; width * height];
+        let mut fill = |x: usize, y: usize| pixels[y * width + x] = 20;
+        for line in 0..=9 {
+            let thickness = if line % 3 == 0 { 3 } else { 1 };
+            for offset in 0..thickness {
+                for along in 0..=cell_size * 9 {
+                    let across = (line * cell_size + offset).min(cell_size * 9);
+                    fill(origin.0 + across, origin.1 + along);
+                    fill(origin.0 + along, origin.1 + across);
+                }
+            }
+        }
+        let scale = cell_size / 12;
+        for row in 0..9 {
+            for col in 0..9 {
+                let Some(val) = grid.get_by_row_col((row as u8, col as u8)) else {
+                    continue;
+                };
+                let left = origin.0 + col * cell_size + (cell_size - 5 * scale) / 2;
+                let top = origin.1 + row * cell_size + (cell_size - 7 * scale) / 2;
+                for (template_row, line) in DIGIT_TEMPLATES[val as usize - 1].iter().enumerate() {
+                    for (template_col, pixel) in line.bytes().enumerate() {
+                        if pixel != b'#' {
+                            continue;
+                        }
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                fill(
+                                    left + template_col * scale + dx,
+                                    top + template_row * scale + dy,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        GrayImage::new(width, height, pixels).unwrap()
+    }
+
+    #[test]
+    fn test_recognize_grid() {
+        let grid = GRID_STR.parse::<ClassicGrid>().unwrap();
+        let image = draw_grid(&grid, (20, 30), 40);
+        assert_eq!(recognize_grid(&image), Some(grid));
+    }
+
+    #[test]
+    fn test_recognize_grid_small() {
+        let grid = GRID_STR.parse::<ClassicGrid>().unwrap();
+        let image = draw_grid(&grid, (5, 5), 24);
+        assert_eq!(recognize_grid(&image), Some(grid));
+    }
+
+    #[test]
+    fn test_no_grid() {
+        let blank = GrayImage::new(100, 100, vec![255; 100 * 100]).unwrap();
+        assert_eq!(recognize_grid(&blank), None);
+        assert_eq!(GrayImage::new(2, 2, vec![0; 3]), None);
+    }
+}
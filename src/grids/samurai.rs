@@ -0,0 +1,195 @@
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use crate::puzzles::error::PuzzleError;
+
+use super::classic::ClassicGrid;
+
+/// The number of rows and columns spanned by a Samurai board.
+pub const SAMURAI_SIZE: u8 = 21;
+
+/// The (row, column) of the top-left cell of each 9x9 grid in a Samurai board, ordered top-left,
+/// top-right, center, bottom-left, bottom-right.
+pub const SUBGRID_ORIGINS: [(u8, u8); 5] = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+
+/// The index of the center grid in `SUBGRID_ORIGINS`.
+pub const CENTER_SUBGRID: usize = 2;
+
+/// For each corner grid, the box of the corner grid that is shared with the center grid and the
+/// corresponding box of the center grid.
+pub const SHARED_BOXES: [(usize, u8, u8); 4] = [(0, 8, 0), (1, 6, 2), (3, 2, 6), (4, 0, 8)];
+
+/// Five overlapping 9x9 grids laid out on a 21x21 board. Positions that are not part of any of the
+/// five grids are always empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SamuraiGrid([[Option<u8>; SAMURAI_SIZE as usize]; SAMURAI_SIZE as usize]);
+
+impl Default for SamuraiGrid {
+    fn default() -> Self {
+        Self([[None; SAMURAI_SIZE as usize]; SAMURAI_SIZE as usize])
+    }
+}
+
+impl SamuraiGrid {
+    /// Returns true if the position is part of at least one of the five grids.
+    #[must_use]
+    pub fn is_on_board((row, col): (u8, u8)) -> bool {
+        SUBGRID_ORIGINS.iter().any(|&(origin_row, origin_col)| {
+            (origin_row..origin_row + 9).contains(&row)
+                && (origin_col..origin_col + 9).contains(&col)
+        })
+    }
+
+    /// Iterate over the positions of every cell on the board in row-major order.
+    pub fn iter_positions() -> impl Iterator<Item = (u8, u8)> {
+        (0..SAMURAI_SIZE)
+            .flat_map(|row| (0..SAMURAI_SIZE).map(move |col| (row, col)))
+            .filter(|&position| Self::is_on_board(position))
+    }
+
+    /// Get the value of a cell in the grid by its row and column indices.
+    #[must_use]
+    pub fn get_by_row_col(&self, (row, col): (u8, u8)) -> Option<u8> {
+        self.0[row as usize][col as usize]
+    }
+
+    /// Set the value of a cell in the grid.
+    pub fn set(&mut self, (row, col): (u8, u8), val: Option<u8>) {
+        debug_assert!(Self::is_on_board((row, col)));
+        self.0[row as usize][col as usize] = val;
+    }
+
+    /// Copies one of the five 9x9 grids out of the board.
+    #[must_use]
+    pub fn subgrid(&self, subgrid_index: usize) -> ClassicGrid {
+        let (origin_row, origin_col) = SUBGRID_ORIGINS[subgrid_index];
+        let mut grid = ClassicGrid::default();
+        for row in 0..9 {
+            for col in 0..9 {
+                grid.set(
+                    (row, col),
+                    self.get_by_row_col((origin_row + row, origin_col + col)),
+                );
+            }
+        }
+        grid
+    }
+
+    /// Writes a 9x9 grid into one of the five grids on the board.
+    pub fn set_subgrid(&mut self, subgrid_index: usize, grid: &ClassicGrid) {
+        let (origin_row, origin_col) = SUBGRID_ORIGINS[subgrid_index];
+        for row in 0..9 {
+            for col in 0..9 {
+                self.set(
+                    (origin_row + row, origin_col + col),
+                    grid.get_by_row_col((row, col)),
+                );
+            }
+        }
+    }
+}
+
+impl Display for SamuraiGrid {
+    /// Display the grid with one character per position: a digit, `.` for an empty cell, or a space
+    /// for positions that are not part of the board.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..SAMURAI_SIZE {
+            let mut line = String::with_capacity(SAMURAI_SIZE as usize);
+            for col in 0..SAMURAI_SIZE {
+                line.push(match self.get_by_row_col((row, col)) {
+                    Some(n) => std::char::from_digit(u32::from(n), 10).unwrap(),
+                    None if Self::is_on_board((row, col)) => '.',
+                    None => ' ',
+                });
+            }
+            f.write_str(line.trim_end())?;
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SamuraiGrid {
+    type Err = PuzzleError;
+
+    /// Reads a `SamuraiGrid` from the string representation produced by `Display`: 21 rows with a
+    /// number from 1 to 9 or `.` for each cell on the board, and spaces elsewhere.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines().count();
+        if rows != SAMURAI_SIZE as usize {
+            return Err(PuzzleError::ParseError(format!(
+                "the grid has {rows} rows instead of {SAMURAI_SIZE}"
+            )));
+        }
+        let mut grid = SamuraiGrid::default();
+        for (row, line) in (0..SAMURAI_SIZE).zip(s.lines()) {
+            let positions = line.chars().count();
+            if positions > SAMURAI_SIZE as usize {
+                return Err(PuzzleError::ParseError(format!(
+                    "row {} has {positions} positions instead of {SAMURAI_SIZE}",
+                    row + 1
+                )));
+            }
+            for (col, c) in (0..SAMURAI_SIZE).zip(line.chars()) {
+                let position = (row, col);
+                match (c, Self::is_on_board(position)) {
+                    ('1'..='9', true) => grid.set(position, c.to_digit(10).map(|num| num as u8)),
+                    ('.', true) | (' ', false) => {}
+                    _ => {
+                        return Err(PuzzleError::ParseError(format!(
+                            "r{}c{} has {c:?}, which doesn't belong there",
+                            row + 1,
+                            col + 1
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_on_board() {
+        assert!(SamuraiGrid::is_on_board((0, 0)));
+        assert!(SamuraiGrid::is_on_board((10, 10)));
+        assert!(SamuraiGrid::is_on_board((20, 20)));
+        assert!(!SamuraiGrid::is_on_board((0, 10)));
+        assert!(!SamuraiGrid::is_on_board((10, 0)));
+    }
+
+    #[test]
+    fn test_iter_positions() {
+        // Five 9x9 grids minus the four shared 3x3 boxes
+        assert_eq!(SamuraiGrid::iter_positions().count(), 5 * 81 - 4 * 9);
+    }
+
+    #[test]
+    fn test_subgrid_round_trip() {
+        let mut samurai = SamuraiGrid::default();
+        let grid = ClassicGrid::from([[1, 2, 3, 4, 5, 6, 7, 8, 9]; 9]);
+        samurai.set_subgrid(CENTER_SUBGRID, &grid);
+        assert_eq!(samurai.subgrid(CENTER_SUBGRID), grid);
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        let mut samurai = SamuraiGrid::default();
+        samurai.set((0, 0), Some(5));
+        samurai.set((10, 10), Some(3));
+        let samurai_str = samurai.to_string();
+        assert_eq!(samurai_str.parse(), Ok(samurai));
+        assert_eq!(samurai_str.lines().next(), Some("5........   ........."));
+        assert!(samurai_str
+            .replacen('5', "x", 1)
+            .parse::<SamuraiGrid>()
+            .is_err());
+        assert!("5........".parse::<SamuraiGrid>().is_err());
+    }
+}
@@ -1,34 +1,86 @@
 use bevy::prelude::*;
-use plugins::game::PuzzleType;
+use grids::classic::GridTransform;
+use plugins::game::{AssistLevel, PuzzleType};
+use puzzles::{classic::ClassicRules, hint::Technique};
 
 pub mod plugins {
     pub mod common;
+    pub mod console;
+    pub mod deep_link;
+    pub mod diagnostics;
     pub mod fps;
     pub mod game;
+    pub mod interaction_log;
+    pub mod loading;
     pub mod menu;
     pub mod nav;
+    #[cfg(test)]
+    mod startup_budget;
+    #[cfg(feature = "update-check")]
+    pub mod update_check;
 }
 
 pub mod puzzles {
+    pub mod arrow;
     pub mod classic;
+    pub mod conflict;
+    pub mod error;
+    pub mod hint;
+    pub mod progress;
+    pub mod samurai;
+    pub mod solver_job;
+    pub mod uniqueness;
+    pub mod validation;
 }
 
 pub mod grids {
+    pub mod bitboard;
     pub mod classic;
+    pub mod classic_state;
+    pub mod coordinate;
+    pub mod ocr;
+    pub mod samurai;
 }
 
 pub mod utility {
     pub mod bitset;
+    pub mod budget_scheduler;
     pub mod element_set;
+    pub mod lru_cache;
     pub mod priority_queue;
+    pub mod rng_streams;
     pub mod seed;
+    pub mod share_code;
+    pub mod stroke;
+    pub mod week;
+}
+
+/// The types most users of the library need to generate, solve, and play puzzles.
+pub mod prelude {
+    pub use crate::{
+        grids::{
+            classic::ClassicGrid,
+            classic_state::{CellEntryError, ClassicGridState, PencilMark},
+            samurai::SamuraiGrid,
+        },
+        puzzles::{
+            arrow::{Arrow, ArrowPuzzle},
+            classic::{ClassicPuzzle, ClassicRules, MoveToken, SolveStats},
+            error::PuzzleError,
+            hint::{grade, GradeReport, Technique},
+            samurai::SamuraiPuzzle,
+        },
+        utility::{element_set::ElementSet, seed::SeedRng},
+    };
 }
 
 pub const APP_TITLE: &str = "Sudoku Machine";
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq, States)]
 pub enum AppState {
+    /// Showing a splash screen while the assets needed by the menus load
     #[default]
+    Loading,
     Menu,
     Game,
 }
@@ -40,8 +92,37 @@ pub fn despawn_component<T: Component>(to_despawn: Query<Entity, With<T>>, mut c
     }
 }
 
-#[derive(Default, Resource)]
+/// The number of mistakes that end the game when the mistake limit is on.
+pub const DEFAULT_MISTAKE_LIMIT: u8 = 3;
+
+// Generic system that takes a resource as a parameter, and will remove it
+pub fn remove_resource<R: Resource>(mut commands: Commands) {
+    commands.remove_resource::<R>();
+}
+
+#[derive(Clone, Default, Resource)]
 pub struct PuzzleSettings {
     pub puzzle_type: PuzzleType,
     pub seed: String,
+    pub rules: ClassicRules,
+    /// End the game after this many incorrect entries (off if `None`)
+    pub mistake_limit: Option<u8>,
+    /// Score as many correct entries as possible before a countdown runs out
+    pub blitz: bool,
+    /// How much the game helps while solving
+    pub assist_level: AssistLevel,
+    /// Fill in the center marks of every empty cell when the puzzle starts
+    pub fill_candidates: bool,
+    /// Generate a puzzle that needs this technique (classic puzzles only)
+    pub practice: Option<Technique>,
+    /// Play this many puzzles back to back, timing each one (off if `None`)
+    pub marathon: Option<u8>,
+    /// Transform the generated puzzle into an equivalent one that looks different (classic grids
+    /// only)
+    pub remix: Option<GridTransform>,
+    /// Regenerate the puzzle this many times with the same solution, making it harder each time
+    /// (classic puzzles only)
+    pub challenge: u8,
+    /// Reject numbers that are already in the cell's row, column, or box instead of entering them
+    pub strict_entry: bool,
 }
@@ -1,40 +1,68 @@
 #[cfg(not(target_family = "wasm"))]
 use bevy::window::WindowMode;
-use bevy::{prelude::*, window::PresentMode};
+use bevy::{log::LogPlugin, prelude::*, window::PresentMode};
+#[cfg(feature = "update-check")]
+use sudoku_machine::plugins::update_check;
 #[cfg(debug_assertions)]
-use sudoku_machine::plugins::fps;
+use sudoku_machine::plugins::{console, fps};
 use sudoku_machine::{
-    plugins::{common::theme, game, menu, nav},
+    plugins::{
+        common::{background, bundles::mini_board, rng, theme},
+        deep_link, diagnostics, game, interaction_log, loading, menu, nav,
+    },
     AppState, PuzzleSettings, APP_TITLE,
 };
 
 fn main() {
+    // The solver worker loads the game's bundle too, but only to call into the solver
+    #[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+    if web_sys::window().is_none() {
+        return;
+    }
     App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: APP_TITLE.into(),
-                present_mode: PresentMode::AutoVsync,
-                #[cfg(not(target_family = "wasm"))]
-                mode: WindowMode::BorderlessFullscreen(MonitorSelection::Current),
-                // Tells bevy to use the system theme
-                window_theme: None,
-                // Tells Wasm to resize the window according to the available canvas
-                fit_canvas_to_parent: true,
-                // Tells Wasm not to override default event handling, like F5, Ctrl+R etc.
-                prevent_default_event_handling: false,
-                ..default()
-            }),
-            ..default()
-        }))
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: APP_TITLE.into(),
+                        present_mode: PresentMode::AutoVsync,
+                        #[cfg(not(target_family = "wasm"))]
+                        mode: WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+                        // Tells bevy to use the system theme
+                        window_theme: None,
+                        // Tells Wasm to resize the window according to the available canvas
+                        fit_canvas_to_parent: true,
+                        // Tells Wasm not to override default event handling, like F5, Ctrl+R etc.
+                        prevent_default_event_handling: false,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(LogPlugin {
+                    custom_layer: diagnostics::diagnostics_log_layer,
+                    ..default()
+                }),
+        )
         .init_state::<AppState>()
         .init_resource::<PuzzleSettings>()
         .add_plugins((
+            rng::rng_plugin,
+            background::background_jobs_plugin,
             theme::theme_plugin,
+            mini_board::mini_board_plugin,
+            loading::loading_plugin,
             menu::menu_plugin,
             nav::nav_plugin,
+            deep_link::deep_link_plugin,
             game::game_plugin,
             #[cfg(debug_assertions)]
             fps::fps_plugin,
+            #[cfg(debug_assertions)]
+            console::console_plugin,
+            diagnostics::diagnostics_plugin,
+            interaction_log::interaction_log_plugin,
+            #[cfg(feature = "update-check")]
+            update_check::update_check_plugin,
         ))
         .run();
 }
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::utility::budget_scheduler::{BudgetScheduler, JobPriority, JobStep};
+
+/// How much of each frame background jobs can take by default.
+const DEFAULT_FRAME_BUDGET: Duration = Duration::from_millis(2);
+
+/// Runs the jobs queued on `BackgroundJobs` at the end of every frame, within the frame budget, so
+/// that incremental work (e.g. pregeneration or regrading) never causes a hitch.
+pub fn background_jobs_plugin(app: &mut App) {
+    app.init_resource::<BackgroundJobs>()
+        .add_systems(Last, background_jobs_system);
+}
+
+/// Background work that's done a step at a time at the end of each frame, with mutable access to
+/// the world. Jobs can't queue more jobs while they're running, since the queue is taken out of
+/// the world to run them.
+#[derive(Resource)]
+pub struct BackgroundJobs {
+    scheduler: BudgetScheduler<World>,
+    /// How much of each frame the jobs can take
+    pub frame_budget: Duration,
+}
+
+impl Default for BackgroundJobs {
+    fn default() -> Self {
+        Self {
+            scheduler: BudgetScheduler::default(),
+            frame_budget: DEFAULT_FRAME_BUDGET,
+        }
+    }
+}
+
+impl BackgroundJobs {
+    /// Queues a job, named for diagnostics. The job is called once per step until it's done.
+    pub fn push(
+        &mut self,
+        name: &'static str,
+        priority: JobPriority,
+        job: impl FnMut(&mut World) -> JobStep + Send + Sync + 'static,
+    ) {
+        self.scheduler.push(name, priority, job);
+    }
+
+    /// Returns how many jobs are waiting to finish.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scheduler.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scheduler.is_empty()
+    }
+
+    /// Returns the names of the jobs waiting to finish.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.scheduler.names()
+    }
+}
+
+fn background_jobs_system(world: &mut World) {
+    if world.resource::<BackgroundJobs>().is_empty() {
+        return;
+    }
+    world.resource_scope(|world, mut background_jobs: Mut<BackgroundJobs>| {
+        let start = web_time::Instant::now();
+        let budget = background_jobs.frame_budget;
+        background_jobs
+            .scheduler
+            .run(world, budget, || start.elapsed());
+    });
+}
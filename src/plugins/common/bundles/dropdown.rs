@@ -1,7 +1,7 @@
 use bevy::{ecs::spawn::SpawnIter, prelude::*};
-use strum_macros::Display;
 
 use crate::plugins::common::theme::{
+    icon::{icon_bundle, Icon, IconId},
     node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
     text::{ThemedFontWeight, ThemedTextColor},
 };
@@ -26,36 +26,21 @@ pub struct DropdownContainer {
     pub options: Vec<String>,
 }
 
-#[derive(Default, Display)]
-enum DropdownIcon {
-    #[default]
-    #[strum(to_string = "v")]
-    Closed,
-    #[strum(to_string = "^")]
-    Open,
-}
-
-impl From<DropdownIcon> for String {
-    fn from(value: DropdownIcon) -> Self {
-        value.to_string()
+/// The icon that shows whether the list is open.
+fn dropdown_icon(open: bool) -> IconId {
+    if open {
+        IconId::ChevronUp
+    } else {
+        IconId::ChevronDown
     }
 }
 
-#[derive(Display)]
-enum SelectionIcon {
-    #[strum(to_string = "*")]
-    Selected,
-    #[strum(to_string = "")]
-    Unselected,
-}
-
-impl From<bool> for SelectionIcon {
-    fn from(b: bool) -> Self {
-        if b {
-            SelectionIcon::Selected
-        } else {
-            SelectionIcon::Unselected
-        }
+/// Shows the check mark next to the selected option only.
+fn selection_icon_visibility(selected: bool) -> Visibility {
+    if selected {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
     }
 }
 
@@ -74,7 +59,6 @@ struct DropdownButton;
 struct DropdownButtonText;
 
 #[derive(Component)]
-#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
 struct DropdownButtonIcon;
 
 #[derive(Component)]
@@ -96,7 +80,6 @@ struct DropdownListItem(usize);
 struct DropdownListItemText;
 
 #[derive(Component)]
-#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
 struct DropdownListItemIcon;
 
 struct DropdownButtonBundleOptions {
@@ -123,8 +106,7 @@ fn dropdown_button_bundle(options: DropdownButtonBundleOptions) -> impl Bundle {
 
     let dropdown_button_icon_bundle = (
         DropdownButtonIcon,
-        Text::new(DropdownIcon::Closed.to_string()),
-        text_font,
+        icon_bundle(dropdown_icon(false), text_font.font_size),
     );
 
     (
@@ -160,8 +142,8 @@ fn dropdown_list_item_bundle(options: DropdownListItemBundleOptions) -> impl Bun
 
     let dropdown_list_item_icon = (
         DropdownListItemIcon,
-        Text::new(SelectionIcon::from(selected).to_string()),
-        text_font,
+        icon_bundle(IconId::Check, text_font.font_size),
+        selection_icon_visibility(selected),
     );
 
     (
@@ -288,7 +270,7 @@ fn dropdown_button_icon_system(
     list_query: Query<(&ChildOf, &Visibility), (Changed<Visibility>, With<DropdownList>)>,
     container_query: Query<&Children, With<DropdownContainer>>,
     button_query: Query<&Children, With<DropdownButton>>,
-    mut button_icon_query: Query<&mut Text, With<DropdownButtonIcon>>,
+    mut button_icon_query: Query<&mut Icon, With<DropdownButtonIcon>>,
 ) {
     for (list_childof, list_visibility) in list_query.iter() {
         // Get the container and its children
@@ -300,11 +282,7 @@ fn dropdown_button_icon_system(
         // Set the corresponding icon
         let button_icon_id = button_children[1];
         let mut button_icon = button_icon_query.get_mut(button_icon_id).unwrap();
-        button_icon.0 = match *list_visibility {
-            Visibility::Visible => DropdownIcon::Open,
-            _ => DropdownIcon::Closed,
-        }
-        .to_string();
+        button_icon.0 = dropdown_icon(*list_visibility == Visibility::Visible);
     }
 }
 
@@ -342,7 +320,7 @@ fn dropdown_list_selection_system(
     list_query: Query<(&ChildOf, &Children), With<DropdownList>>,
     mut container_query: Query<&mut DropdownContainer>,
     previous_list_item_query: Query<&Children, With<DropdownListItem>>,
-    mut list_item_icon_query: Query<&mut Text, With<DropdownListItemIcon>>,
+    mut list_item_icon_query: Query<&mut Visibility, With<DropdownListItemIcon>>,
 ) {
     for (interaction, childof, list_item, interacted_list_item_children) in
         interacted_list_item_query.iter()
@@ -363,7 +341,7 @@ fn dropdown_list_selection_system(
         let mut previous_list_item_icon = list_item_icon_query
             .get_mut(previous_list_item_icon_id)
             .unwrap();
-        previous_list_item_icon.0 = SelectionIcon::Unselected.to_string();
+        *previous_list_item_icon = selection_icon_visibility(false);
         // Change the selected option in the container
         dropdown_container.selected = list_item.0;
         // Add the selected icon to the newly selected option
@@ -371,7 +349,7 @@ fn dropdown_list_selection_system(
         let mut pressed_list_item_icon = list_item_icon_query
             .get_mut(pressed_list_item_icon_id)
             .unwrap();
-        pressed_list_item_icon.0 = SelectionIcon::Selected.to_string();
+        *pressed_list_item_icon = selection_icon_visibility(true);
     }
 }
 
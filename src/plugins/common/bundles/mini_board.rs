@@ -0,0 +1,198 @@
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::UiSystem, window::PrimaryWindow};
+
+use crate::{
+    grids::classic::ClassicGrid,
+    plugins::{
+        common::theme::{
+            node::ThemedBorderColor,
+            text::{ThemedFontWeight, ThemedTextColor},
+        },
+        game::overlay::BoardCell,
+    },
+};
+
+/// The width of a cell of a mini board at a scale of 1.
+const MINI_CELL_SIZE: f32 = 16.0;
+
+/// Keeps the lines of boards crisp by snapping their cells and lines to whole physical pixels,
+/// again whenever the window's scale factor or the UI scale changes. Also sizes the text of cells
+/// to fit them once they're laid out.
+pub fn mini_board_plugin(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        (
+            board_snap_system.before(UiSystem::Layout),
+            cell_font_size_system.after(UiSystem::Layout),
+        ),
+    );
+}
+
+/// The sizes a board was made with, in logical pixels, before they're snapped to physical pixels.
+/// Without snapping, fractional layouts can blur the thin lines between cells or drop them.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct BoardLines {
+    pub cell_size: f32,
+    pub thin: f32,
+    pub thick: f32,
+}
+
+impl BoardLines {
+    /// Returns the sizes snapped to whole physical pixels, given how many physical pixels there are
+    /// per logical pixel. The lines stay at least a physical pixel wide so that they don't vanish.
+    #[must_use]
+    pub fn snapped(&self, scale: f32) -> Self {
+        let snap = |size: f32, min: f32| (size * scale).round().max(min) / scale;
+        Self {
+            cell_size: snap(self.cell_size, 1.0),
+            thin: snap(self.thin, 1.0),
+            thick: snap(self.thick, 1.0),
+        }
+    }
+
+    /// Returns the width of the whole board, including its outer border, which divides into 9
+    /// cells with nothing left over.
+    #[must_use]
+    pub fn board_size(&self) -> f32 {
+        self.cell_size * 9.0 + self.thick * 2.0
+    }
+}
+
+/// Sizes the text of a cell of a board to the cell's laid-out height, as a fraction of it, so that
+/// numbers and pencil marks fit however big the board is drawn, e.g. on small windows. The text is
+/// resized whenever the cell is laid out again. The cell's size can't depend on its text, or
+/// resizing the text would lay the cell out again.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct CellFontSize(pub f32);
+
+/// A cell of a mini board, whose text shows the number at a (row, col).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MiniBoardCell(pub (u8, u8));
+
+/// Returns the text a mini board shows for a value.
+#[must_use]
+pub fn mini_board_cell_text(val: Option<u8>) -> String {
+    val.map_or_else(String::new, |val| val.to_string())
+}
+
+/// Returns the border of a cell of a board, so that the cells draw the lines between them: thin
+/// lines between cells, and thick lines between boxes. Each cell draws the lines to its right and
+/// below, leaving the edge of the board to the board's own border.
+#[must_use]
+pub fn board_cell_border((row, col): (u8, u8), thin: f32, thick: f32) -> UiRect {
+    let line = |index: u8| match index {
+        8 => 0.0,
+        2 | 5 => thick,
+        _ => thin,
+    };
+    UiRect {
+        right: Val::Px(line(col)),
+        bottom: Val::Px(line(row)),
+        ..default()
+    }
+}
+
+/// Returns a read-only rendering of a grid, with cells `MINI_CELL_SIZE * scale` pixels wide, for
+/// previews. The boxes are separated by thicker lines, like the board.
+#[must_use]
+pub fn mini_board_bundle(grid: &ClassicGrid, scale: f32) -> impl Bundle {
+    let cell_size = MINI_CELL_SIZE * scale;
+    let thin = scale.max(1.0) / 2.0;
+    let thick = thin * 4.0;
+    let cell_bundles: Vec<_> = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .map(|position: (u8, u8)| {
+            (
+                MiniBoardCell(position),
+                BoardCell(position),
+                Node {
+                    width: Val::Px(cell_size),
+                    height: Val::Px(cell_size),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    border: board_cell_border(position, thin, thick),
+                    ..default()
+                },
+                BorderColor::default(),
+                ThemedBorderColor,
+                Text::new(mini_board_cell_text(grid.get_by_row_col(position))),
+                TextFont::from_font_size(cell_size * 0.7),
+                CellFontSize(0.7),
+                TextLayout::new_with_justify(JustifyText::Center),
+                ThemedFontWeight::Regular,
+                ThemedTextColor,
+            )
+        })
+        .collect();
+
+    (
+        BoardLines {
+            cell_size,
+            thin,
+            thick,
+        },
+        Node {
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::auto(9),
+            border: UiRect::all(Val::Px(thick)),
+            align_self: AlignSelf::Center,
+            ..default()
+        },
+        BorderColor::default(),
+        ThemedBorderColor,
+        Children::spawn(SpawnIter(cell_bundles.into_iter())),
+    )
+}
+
+// Snap the sizes of new boards, and of every board when the pixel density changes
+fn board_snap_system(
+    window_query: Query<Ref<Window>, With<PrimaryWindow>>,
+    ui_scale: Option<Res<UiScale>>,
+    mut board_query: Query<(Ref<BoardLines>, &mut Node, &Children)>,
+    mut cell_query: Query<(&MiniBoardCell, &mut Node), Without<BoardLines>>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let rescaled = window.is_changed()
+        || ui_scale
+            .as_ref()
+            .is_some_and(|ui_scale| ui_scale.is_changed());
+    let scale = window.scale_factor() * ui_scale.map_or(1.0, |ui_scale| ui_scale.0);
+    for (lines, mut node, cells) in &mut board_query {
+        if !rescaled && !lines.is_added() {
+            continue;
+        }
+        let snapped = lines.snapped(scale);
+        let board_size = Val::Px(snapped.board_size());
+        if node.width != board_size {
+            node.width = board_size;
+            node.height = board_size;
+            node.border = UiRect::all(Val::Px(snapped.thick));
+        }
+        for &cell in cells {
+            let Ok((MiniBoardCell(position), mut cell_node)) = cell_query.get_mut(cell) else {
+                continue;
+            };
+            let cell_size = Val::Px(snapped.cell_size);
+            let border = board_cell_border(*position, snapped.thin, snapped.thick);
+            if cell_node.width != cell_size || cell_node.border != border {
+                cell_node.width = cell_size;
+                cell_node.height = cell_size;
+                cell_node.border = border;
+            }
+        }
+    }
+}
+
+// Size the text of cells to their height once they're laid out, e.g. after the window is resized
+fn cell_font_size_system(
+    mut cell_query: Query<(&CellFontSize, &ComputedNode, &mut TextFont), Changed<ComputedNode>>,
+) {
+    for (CellFontSize(fraction), computed_node, mut text_font) in &mut cell_query {
+        let height = computed_node.size().y * computed_node.inverse_scale_factor();
+        let font_size = (height * fraction).round();
+        if font_size > 0.0 && text_font.font_size != font_size {
+            text_font.font_size = font_size;
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod dropdown;
+pub mod mini_board;
 pub mod text_input;
+pub mod toggle;
@@ -2,26 +2,31 @@ use bevy::input::keyboard::Key;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::input::ButtonState;
 use bevy::prelude::*;
+use bevy::window::{Ime, PrimaryWindow};
 
 use crate::plugins::common::clipboard::clipboard_plugin;
 use crate::plugins::common::clipboard::ClipboardResource;
+use crate::plugins::common::soft_keyboard::soft_keyboard_plugin;
 use crate::plugins::common::theme::focus::FocusedEntity;
 use crate::plugins::common::theme::node::ThemedBackgroundColor;
 use crate::plugins::common::theme::node::ThemedBorderColor;
 use crate::plugins::common::theme::node::ThemedBorderRadius;
 use crate::plugins::common::theme::node::ThemedBorderRect;
+use crate::plugins::common::theme::text::MutedText;
 use crate::plugins::common::theme::text::ThemedFontWeight;
 use crate::plugins::common::theme::text::ThemedTextColor;
 
 pub fn text_input_plugin(app: &mut App) {
-    app.add_plugins(clipboard_plugin)
+    app.add_plugins((clipboard_plugin, soft_keyboard_plugin))
         .insert_resource(BlinkTimer(Timer::from_seconds(0.5, TimerMode::Repeating)))
         .add_systems(
             Update,
             (
                 text_input_focus_system,
+                text_input_ime_focus_system.run_if(resource_changed::<FocusedEntity>),
                 text_input_cursor_blink_system,
                 typing_system,
+                ime_system,
             ),
         );
 }
@@ -48,6 +53,12 @@ struct TextInputText;
 #[require(Node)]
 pub struct TextInputCursor;
 
+/// The text being composed with an input method, shown faded after the typed text until it's
+/// committed.
+#[derive(Component)]
+#[require(TextSpan, ThemedFontWeight::Regular, ThemedTextColor, MutedText)]
+struct ImePreedit;
+
 #[derive(Default)]
 pub struct TextInputBundleOptions {
     pub placeholder_text: String,
@@ -76,7 +87,8 @@ pub fn text_input_bundle(options: TextInputBundleOptions) -> impl Bundle {
             justify_content: JustifyContent::Center,
             ..text_node
         },
-        text_font,
+        text_font.clone(),
+        children![(ImePreedit, text_font)],
     );
 
     let text_input_cursor_bundle = (
@@ -250,3 +262,86 @@ fn typing_system(
         text_input_data.is_empty = is_empty;
     }
 }
+
+// Turn the input method editor on while a text input is focused, with its candidate box under the
+// input, and drop anything left half-composed when the focus moves
+fn text_input_ime_focus_system(
+    focused_entity: Res<FocusedEntity>,
+    container_query: Query<(&ComputedNode, &GlobalTransform), With<TextInputContainer>>,
+    mut preedit_query: Query<&mut TextSpan, With<ImePreedit>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for mut preedit in &mut preedit_query {
+        if !preedit.0.is_empty() {
+            preedit.0.clear();
+        }
+    }
+    let Ok(mut window) = window_query.single_mut() else {
+        return;
+    };
+    let focused_input = focused_entity
+        .current
+        .and_then(|entity| container_query.get(entity).ok());
+    let Some((computed_node, global_transform)) = focused_input else {
+        if window.ime_enabled {
+            window.ime_enabled = false;
+        }
+        return;
+    };
+    // Layout positions are in physical pixels, while the candidate box is placed in logical ones
+    let bottom_left = global_transform.translation().truncate()
+        + Vec2::new(-computed_node.size().x, computed_node.size().y) / 2.0;
+    window.ime_enabled = true;
+    window.ime_position = bottom_left / window.scale_factor();
+}
+
+// Show the text being composed after the typed text, and type it once it's committed. The cursor is
+// hidden while the input method hides it.
+fn ime_system(
+    focused_entity: Res<FocusedEntity>,
+    mut ime_events: EventReader<Ime>,
+    mut container_query: Query<(&mut TextInputContainer, &Children)>,
+    mut text_query: Query<(&mut Text, &Children), With<TextInputText>>,
+    mut preedit_query: Query<&mut TextSpan, With<ImePreedit>>,
+    mut text_cursor_query: Query<&mut Visibility, With<TextInputCursor>>,
+) {
+    let Some((mut text_input_data, container_children)) = focused_entity
+        .current
+        .and_then(|entity| container_query.get_mut(entity).ok())
+    else {
+        ime_events.clear();
+        return;
+    };
+    let Ok((mut text, text_children)) = text_query.get_mut(container_children[0]) else {
+        return;
+    };
+    let Ok(mut preedit) = preedit_query.get_mut(text_children[0]) else {
+        return;
+    };
+    for ime_event in ime_events.read() {
+        let cursor_visibility = match ime_event {
+            Ime::Preedit { value, cursor, .. } => {
+                preedit.0.clone_from(value);
+                if cursor.is_some() {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                }
+            }
+            Ime::Commit { value, .. } => {
+                preedit.0.clear();
+                text.0.push_str(value);
+                text_input_data.is_empty = text.0.is_empty();
+                Visibility::Visible
+            }
+            Ime::Enabled { .. } => continue,
+            Ime::Disabled { .. } => {
+                preedit.0.clear();
+                Visibility::Visible
+            }
+        };
+        if let Ok(mut visibility) = text_cursor_query.get_mut(container_children[1]) {
+            *visibility = cursor_visibility;
+        }
+    }
+}
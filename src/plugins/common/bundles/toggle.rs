@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use strum_macros::Display;
+
+use crate::plugins::common::theme::{
+    node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+    text::{MutedText, ThemedFontWeight, ThemedTextColor},
+};
+
+pub fn toggle_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            toggle_press_system,
+            (toggle_icon_system, toggle_disabled_system),
+        )
+            .chain(),
+    );
+}
+
+#[derive(Default, Component, Clone)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+pub struct ToggleContainer {
+    pub on: bool,
+    /// Greys out the toggle and ignores presses
+    pub disabled: bool,
+}
+
+#[derive(Display)]
+enum ToggleIcon {
+    #[strum(to_string = "[x]")]
+    On,
+    #[strum(to_string = "[ ]")]
+    Off,
+}
+
+impl From<bool> for ToggleIcon {
+    fn from(b: bool) -> Self {
+        if b {
+            ToggleIcon::On
+        } else {
+            ToggleIcon::Off
+        }
+    }
+}
+
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct ToggleText;
+
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct ToggleIconText;
+
+#[derive(Default)]
+pub struct ToggleBundleOptions {
+    pub text: String,
+    pub on: bool,
+    pub text_font: TextFont,
+    pub container_node: Node,
+}
+
+#[must_use]
+pub fn toggle_bundle(options: ToggleBundleOptions) -> impl Bundle {
+    let ToggleBundleOptions {
+        text,
+        on,
+        text_font,
+        container_node,
+    } = options;
+
+    let toggle_text_bundle = (ToggleText, Text::new(text), text_font.clone());
+
+    let toggle_icon_bundle = (
+        ToggleIconText,
+        Text::new(ToggleIcon::from(on).to_string()),
+        text_font,
+    );
+
+    (
+        ToggleContainer {
+            on,
+            disabled: false,
+        },
+        Node {
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..container_node
+        },
+        children![toggle_text_bundle, toggle_icon_bundle],
+    )
+}
+
+fn toggle_press_system(
+    mut toggle_query: Query<(&Interaction, &mut ToggleContainer), Changed<Interaction>>,
+) {
+    for (interaction, mut toggle) in &mut toggle_query {
+        if *interaction == Interaction::Pressed && !toggle.disabled {
+            toggle.on = !toggle.on;
+        }
+    }
+}
+
+fn toggle_icon_system(
+    toggle_query: Query<(&ToggleContainer, &Children), Changed<ToggleContainer>>,
+    mut icon_query: Query<&mut Text, With<ToggleIconText>>,
+) {
+    for (toggle, children) in toggle_query.iter() {
+        let mut icon = icon_query.get_mut(children[1]).unwrap();
+        icon.0 = ToggleIcon::from(toggle.on).to_string();
+    }
+}
+
+fn toggle_disabled_system(
+    toggle_query: Query<(&ToggleContainer, &Children), Changed<ToggleContainer>>,
+    mut commands: Commands,
+) {
+    for (toggle, children) in toggle_query.iter() {
+        for &child in children {
+            if toggle.disabled {
+                commands.entity(child).insert(MutedText);
+            } else {
+                commands.entity(child).remove::<MutedText>();
+            }
+        }
+    }
+}
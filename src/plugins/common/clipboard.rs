@@ -2,17 +2,24 @@ use bevy::prelude::*;
 #[cfg(target_family = "wasm")]
 use bevy_defer::{AsyncAccess, AsyncCommandsExtension};
 
-#[cfg(not(target_family = "wasm"))]
+#[cfg(all(not(target_family = "wasm"), not(test)))]
 #[derive(Resource)]
 pub struct ClipboardResource {
     native_clipboard: arboard::Clipboard,
 }
 
+/// Tests run headless, where there may be no system clipboard, so they copy to memory instead.
+#[cfg(all(not(target_family = "wasm"), test))]
+#[derive(Resource, Default)]
+pub struct ClipboardResource {
+    text: String,
+}
+
 #[cfg(target_family = "wasm")]
 #[derive(Resource, Default)]
 pub struct ClipboardResource;
 
-#[cfg(not(target_family = "wasm"))]
+#[cfg(all(not(target_family = "wasm"), not(test)))]
 impl Default for ClipboardResource {
     fn default() -> Self {
         Self {
@@ -22,20 +29,30 @@ impl Default for ClipboardResource {
 }
 
 impl ClipboardResource {
-    #[cfg(not(target_family = "wasm"))]
+    #[cfg(all(not(target_family = "wasm"), not(test)))]
     fn native_write_text(&mut self, val: String) {
         self.native_clipboard
             .set_text(val)
             .expect("Failed to write text to native clipboard");
     }
 
-    #[cfg(not(target_family = "wasm"))]
+    #[cfg(all(not(target_family = "wasm"), not(test)))]
     fn native_read_text(&mut self) -> String {
         self.native_clipboard
             .get_text()
             .expect("Failed to read text from native clipboard")
     }
 
+    #[cfg(all(not(target_family = "wasm"), test))]
+    fn native_write_text(&mut self, val: String) {
+        self.text = val;
+    }
+
+    #[cfg(all(not(target_family = "wasm"), test))]
+    fn native_read_text(&mut self) -> String {
+        self.text.clone()
+    }
+
     #[cfg(target_family = "wasm")]
     fn wasm_get_clipboard() -> web_sys::Clipboard {
         web_sys::window()
@@ -0,0 +1,32 @@
+/// Saves text to a file for the player to keep, returning where it went. Native builds write it to
+/// the working directory, like saved screenshots, and the web downloads it.
+#[cfg(not(target_family = "wasm"))]
+pub fn export_file(file_name: &str, contents: &str) -> Result<String, String> {
+    std::fs::write(file_name, contents).map_err(|err| err.to_string())?;
+    let path = std::fs::canonicalize(file_name).unwrap_or_else(|_| file_name.into());
+    Ok(path.display().to_string())
+}
+
+/// Saves text to a file for the player to keep, returning where it went. Native builds write it to
+/// the working directory, like saved screenshots, and the web downloads it.
+#[cfg(target_family = "wasm")]
+pub fn export_file(file_name: &str, contents: &str) -> Result<String, String> {
+    let parts = js_sys::Array::of1(&js_sys::JsString::from(contents));
+    let blob = web_sys::Blob::new_with_str_sequence(&parts)
+        .map_err(|_| "the file couldn't be made".to_string())?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(|_| "the file couldn't be made".to_string())?;
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("there's no page to download from")?;
+    // Clicking a link to the file with a download name downloads it instead of opening it
+    let downloaded = document.create_element("a").and_then(|anchor| {
+        anchor.set_attribute("href", &url)?;
+        anchor.set_attribute("download", file_name)?;
+        let click = web_sys::MouseEvent::new("click")?;
+        anchor.dispatch_event(&click)
+    });
+    let _ = web_sys::Url::revoke_object_url(&url);
+    downloaded.map_err(|_| "the download couldn't be started".to_string())?;
+    Ok(format!("your downloads as {file_name}"))
+}
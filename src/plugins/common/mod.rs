@@ -1,3 +1,9 @@
+pub mod background;
 pub mod bundles;
 pub mod clipboard;
+pub mod export;
+pub mod rng;
+pub mod soft_keyboard;
+pub mod solver;
+pub mod storage;
 pub mod theme;
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use crate::utility::{rng_streams::RngStreams, seed::SeedRng};
+
+/// The command line argument that sets the session seed, like `--rng-seed 0123456789ABCDEF`.
+const RNG_SEED_ARG: &str = "--rng-seed";
+
+/// The environment variable that sets the session seed when the argument isn't given.
+const RNG_SEED_ENV: &str = "SUDOKU_MACHINE_RNG_SEED";
+
+/// The stream for the seeds of puzzles started from the menus.
+pub const SEED_STREAM: &str = "seeds";
+
+/// The stream for the seeds of pregenerated puzzles, which are made in the background.
+pub const PREGEN_STREAM: &str = "pregen";
+
+/// The stream for the seeds tried when looking for a puzzle like the one just solved.
+pub const SIMILAR_STREAM: &str = "similar";
+
+/// The stream for remix transforms.
+pub const REMIX_STREAM: &str = "remix";
+
+/// Adds the `RngService`, logging its session seed so that a session can be reproduced.
+pub fn rng_plugin(app: &mut App) {
+    app.init_resource::<RngService>();
+    info!(
+        "RNG session seed: {}",
+        app.world().resource::<RngService>().session_seed()
+    );
+}
+
+/// Hands out the random number streams used across the app, like the ones for new seeds and
+/// remixes. The session seed comes from `--rng-seed` or `SUDOKU_MACHINE_RNG_SEED` if either is
+/// set, so that a session can be replayed while debugging, and is random otherwise.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RngService(RngStreams);
+
+impl Default for RngService {
+    fn default() -> Self {
+        let mut args = std::env::args();
+        let session_seed = args
+            .position(|arg| arg == RNG_SEED_ARG)
+            .and_then(|_| args.next())
+            .or_else(|| std::env::var(RNG_SEED_ENV).ok())
+            .unwrap_or_else(|| rand::rng().gen_seed());
+        Self(RngStreams::new(session_seed))
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::plugins::common::{
+    bundles::text_input::TextInputContainer, theme::focus::FocusedEntity,
+};
+
+/// How much of its full height the window has to lose while a text input is focused for an
+/// on-screen keyboard to be assumed to cover the rest.
+const KEYBOARD_HEIGHT_RATIO: f32 = 0.8;
+
+/// How much the UI is scaled down while the on-screen keyboard is visible.
+const KEYBOARD_UI_SCALE: f32 = 0.85;
+
+/// How much room is left between the focused input and the top of the keyboard, in pixels.
+const KEYBOARD_MARGIN: f32 = 16.0;
+
+/// Keeps the focused text input in view while an on-screen keyboard is visible, which is noticed
+/// by the window getting shorter, e.g. when the browser's viewport shrinks on mobile. The layout is
+/// compressed, and the focused input's root node is shifted up if it's still under the keyboard.
+pub fn soft_keyboard_plugin(app: &mut App) {
+    app.init_resource::<SoftKeyboard>().add_systems(
+        Update,
+        (
+            soft_keyboard_detect_system,
+            soft_keyboard_scale_system.run_if(resource_changed::<SoftKeyboard>),
+            soft_keyboard_shift_system,
+        )
+            .chain(),
+    );
+}
+
+/// Whether an on-screen keyboard seems to be covering the bottom of the window.
+#[derive(Resource, Default)]
+pub struct SoftKeyboard {
+    visible: bool,
+    /// The width the window had when `full_height` was measured
+    width: f32,
+    /// The tallest the window has been at its current width
+    full_height: f32,
+    /// The UI scale to restore once the keyboard is hidden
+    restore_scale: Option<f32>,
+}
+
+impl SoftKeyboard {
+    #[must_use]
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+}
+
+/// A root node that's shifted up so the focused input stays above the keyboard.
+#[derive(Component)]
+struct KeyboardShift {
+    /// How far the node is shifted up, in logical pixels
+    shift: f32,
+    /// The node's `top` from before it was shifted
+    original_top: Val,
+}
+
+// Compare the window's height with its full height while a text input is focused
+fn soft_keyboard_detect_system(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    focused_entity: Res<FocusedEntity>,
+    text_input_query: Query<(), With<TextInputContainer>>,
+    mut soft_keyboard: ResMut<SoftKeyboard>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let (width, height) = (window.width(), window.height());
+    // Rotating the device or resizing the window changes the height the keyboard is measured from
+    if width != soft_keyboard.width {
+        soft_keyboard.width = width;
+        soft_keyboard.full_height = height;
+    }
+    let full_height = soft_keyboard.full_height.max(height);
+    soft_keyboard.bypass_change_detection().full_height = full_height;
+    let typing = focused_entity
+        .current
+        .is_some_and(|entity| text_input_query.contains(entity));
+    let visible = typing && height < full_height * KEYBOARD_HEIGHT_RATIO;
+    if soft_keyboard.visible != visible {
+        soft_keyboard.visible = visible;
+    }
+}
+
+// Compress the layout while the keyboard is visible, and restore it once it's hidden
+fn soft_keyboard_scale_system(
+    mut soft_keyboard: ResMut<SoftKeyboard>,
+    ui_scale: Option<ResMut<UiScale>>,
+) {
+    let Some(mut ui_scale) = ui_scale else {
+        return;
+    };
+    match (soft_keyboard.visible, soft_keyboard.restore_scale) {
+        (true, None) => {
+            soft_keyboard.restore_scale = Some(ui_scale.0);
+            ui_scale.0 *= KEYBOARD_UI_SCALE;
+        }
+        (false, Some(restore_scale)) => {
+            soft_keyboard.restore_scale = None;
+            ui_scale.0 = restore_scale;
+        }
+        _ => {}
+    }
+}
+
+// Shift the focused input's root node up until the input is above the keyboard, and put the
+// shifted nodes back once the keyboard is hidden
+#[allow(clippy::too_many_arguments)]
+fn soft_keyboard_shift_system(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    soft_keyboard: Res<SoftKeyboard>,
+    focused_entity: Res<FocusedEntity>,
+    input_query: Query<(&ComputedNode, &GlobalTransform), With<TextInputContainer>>,
+    parent_query: Query<&ChildOf>,
+    mut shifted_query: Query<(Entity, &mut Node, &mut KeyboardShift)>,
+    mut root_query: Query<&mut Node, Without<KeyboardShift>>,
+    mut commands: Commands,
+) {
+    let focused_input = focused_entity
+        .current
+        .filter(|_| soft_keyboard.visible)
+        .and_then(|entity| Some((entity, input_query.get(entity).ok()?)));
+    let root =
+        focused_input.map(|(input, _)| parent_query.iter_ancestors(input).last().unwrap_or(input));
+    for (entity, mut node, keyboard_shift) in &mut shifted_query {
+        if Some(entity) != root {
+            node.top = keyboard_shift.original_top;
+            commands.entity(entity).remove::<KeyboardShift>();
+        }
+    }
+    let (Some((_, (computed_node, global_transform))), Some(root)) = (focused_input, root) else {
+        return;
+    };
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    // Layout positions are in physical pixels, while node offsets are in scaled logical ones
+    let inverse_scale_factor = computed_node.inverse_scale_factor();
+    let input_bottom = global_transform.translation().y + computed_node.size().y / 2.0;
+    let overflow =
+        (input_bottom - window.physical_height() as f32) * inverse_scale_factor + KEYBOARD_MARGIN;
+
+    if let Ok((_, mut node, mut keyboard_shift)) = shifted_query.get_mut(root) {
+        let shift = (keyboard_shift.shift + overflow).max(0.0);
+        if (shift - keyboard_shift.shift).abs() >= 0.5 {
+            keyboard_shift.shift = shift;
+            node.top = Val::Px(-shift);
+        }
+    } else if overflow > 0.0 {
+        let Ok(mut node) = root_query.get_mut(root) else {
+            return;
+        };
+        commands.entity(root).insert(KeyboardShift {
+            shift: overflow,
+            original_top: node.top,
+        });
+        node.top = Val::Px(-overflow);
+    }
+}
@@ -0,0 +1,169 @@
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::prelude::*;
+#[cfg(not(all(target_family = "wasm", feature = "solver-worker")))]
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::puzzles::solver_job::{SolverMessage, SolverRequest, SolverResponse};
+
+/// Where the solver worker's script is served, next to the game's bundle.
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+const SOLVER_WORKER_SCRIPT: &str = "./solver-worker.js";
+
+/// Runs the solving work submitted to `SolverJobs` off of the game's frames. With the
+/// `solver-worker` feature on the web, the work runs in a web worker, since the task pools there
+/// run on the main thread. Everywhere else, it runs on the async compute task pool.
+pub fn solver_plugin(app: &mut App) {
+    app.init_resource::<SolverJobs>()
+        .add_event::<SolverJobFinished>();
+    #[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+    {
+        if let Some(solver_worker) = SolverWorker::spawn() {
+            app.insert_non_send_resource(solver_worker);
+        }
+        app.add_systems(Update, solver_worker_system);
+    }
+    #[cfg(not(all(target_family = "wasm", feature = "solver-worker")))]
+    app.add_systems(Update, solver_task_system);
+}
+
+/// Identifies a submitted job in its `SolverJobFinished` event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SolverJobId(u64);
+
+/// Sent once a submitted job has finished.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SolverJobFinished {
+    pub id: SolverJobId,
+    pub response: SolverResponse,
+}
+
+/// The solving work waiting to run or running.
+#[derive(Resource, Default)]
+pub struct SolverJobs {
+    next_id: u64,
+    queued: Vec<SolverMessage<SolverRequest>>,
+    #[cfg(not(all(target_family = "wasm", feature = "solver-worker")))]
+    running: Vec<Task<SolverMessage<SolverResponse>>>,
+}
+
+impl SolverJobs {
+    /// Queues a request, returning the id its `SolverJobFinished` event will have.
+    pub fn submit(&mut self, request: SolverRequest) -> SolverJobId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queued.push(SolverMessage { id, body: request });
+        SolverJobId(id)
+    }
+}
+
+// Start the queued jobs on the task pool, and send the results of the ones that have finished
+#[cfg(not(all(target_family = "wasm", feature = "solver-worker")))]
+fn solver_task_system(
+    mut solver_jobs: ResMut<SolverJobs>,
+    mut finished_events: EventWriter<SolverJobFinished>,
+) {
+    let queued = std::mem::take(&mut solver_jobs.queued);
+    for SolverMessage { id, body } in queued {
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            SolverMessage {
+                id,
+                body: body.run(),
+            }
+        });
+        solver_jobs.running.push(task);
+    }
+    solver_jobs.running.retain_mut(|task| {
+        let Some(SolverMessage { id, body }) = block_on(future::poll_once(task)) else {
+            return true;
+        };
+        finished_events.write(SolverJobFinished {
+            id: SolverJobId(id),
+            response: body,
+        });
+        false
+    });
+}
+
+/// The web worker that runs the puzzle core, and the replies it has posted back.
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+struct SolverWorker {
+    worker: web_sys::Worker,
+    replies: Rc<RefCell<Vec<String>>>,
+    // Kept so the worker can keep calling it
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+impl SolverWorker {
+    /// Starts the worker, or returns `None` if the browser won't start it.
+    fn spawn() -> Option<Self> {
+        let options = web_sys::WorkerOptions::new();
+        options.set_type(web_sys::WorkerType::Module);
+        let worker = web_sys::Worker::new_with_options(SOLVER_WORKER_SCRIPT, &options).ok()?;
+        let replies = Rc::new(RefCell::new(Vec::new()));
+        let inbox = Rc::clone(&replies);
+        let on_message = Closure::<dyn FnMut(_)>::new(move |event: web_sys::MessageEvent| {
+            if let Some(text) = event.data().as_string() {
+                inbox.borrow_mut().push(text);
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        Some(Self {
+            worker,
+            replies,
+            _on_message: on_message,
+        })
+    }
+}
+
+// Post the queued jobs to the worker, and send the results it has posted back. Without a worker,
+// the jobs run right away instead.
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+fn solver_worker_system(
+    solver_worker: Option<NonSend<SolverWorker>>,
+    mut solver_jobs: ResMut<SolverJobs>,
+    mut finished_events: EventWriter<SolverJobFinished>,
+) {
+    let queued = std::mem::take(&mut solver_jobs.queued);
+    let Some(solver_worker) = solver_worker else {
+        for SolverMessage { id, body } in queued {
+            finished_events.write(SolverJobFinished {
+                id: SolverJobId(id),
+                response: body.run(),
+            });
+        }
+        return;
+    };
+    for message in queued {
+        let posted = solver_worker
+            .worker
+            .post_message(&JsValue::from_str(&message.encode()));
+        if posted.is_err() {
+            warn!("Failed to post solver job {} to the worker", message.id);
+        }
+    }
+    for reply in solver_worker.replies.borrow_mut().drain(..) {
+        match SolverMessage::<SolverResponse>::decode(&reply) {
+            Ok(SolverMessage { id, body }) => {
+                finished_events.write(SolverJobFinished {
+                    id: SolverJobId(id),
+                    response: body,
+                });
+            }
+            Err(err) => warn!("Failed to read a reply from the solver worker: {err}"),
+        }
+    }
+}
+
+/// The entry point of the solver worker's script, which runs a request posted as text and returns
+/// the response as text.
+#[cfg(all(target_family = "wasm", feature = "solver-worker"))]
+#[wasm_bindgen]
+pub fn solver_worker_handle(message: &str) -> Result<String, JsValue> {
+    crate::puzzles::solver_job::handle_solver_message(message)
+        .map_err(|err| JsValue::from_str(&err))
+}
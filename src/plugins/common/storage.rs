@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The folder (or key prefix on the web) everything is saved under.
+#[cfg(any(target_family = "wasm", not(test)))]
+const APP_DIR: &str = "sudoku_machine";
+
+/// Returns the folder the app saves its data in on this platform.
+#[cfg(all(not(target_family = "wasm"), not(test)))]
+fn data_dir() -> Option<std::path::PathBuf> {
+    use std::{env::var_os, path::PathBuf};
+
+    let base = if cfg!(target_os = "windows") {
+        var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    Some(base?.join(APP_DIR))
+}
+
+/// The file in the data folder that the running instance holds a lock on.
+#[cfg(all(not(target_family = "wasm"), not(test)))]
+const LOCK_FILE_NAME: &str = "instance.lock";
+
+/// Returns whether another instance of the app is running and saving to the same folder, in which
+/// case this one doesn't save so the two don't overwrite each other's saves. The first instance to
+/// check holds the lock until it exits, and the OS releases it even if the app crashes. Saving
+/// isn't stopped when locks aren't supported, since a second instance is rare.
+#[cfg(all(not(target_family = "wasm"), not(test)))]
+#[must_use]
+pub fn saves_locked() -> bool {
+    use std::{
+        fs::{File, TryLockError},
+        sync::OnceLock,
+    };
+
+    static INSTANCE_LOCK: OnceLock<(Option<File>, bool)> = OnceLock::new();
+    let (_, locked) = INSTANCE_LOCK.get_or_init(|| {
+        let Some(dir) = data_dir() else {
+            return (None, false);
+        };
+        let file = std::fs::create_dir_all(&dir).and_then(|()| {
+            File::options()
+                .create(true)
+                .write(true)
+                .truncate(false)
+                .open(dir.join(LOCK_FILE_NAME))
+        });
+        let file = match file {
+            Ok(file) => file,
+            Err(err) => {
+                warn!("Couldn't open the lock on the saves: {err}");
+                return (None, false);
+            }
+        };
+        match file.try_lock() {
+            Ok(()) => (Some(file), false),
+            Err(TryLockError::WouldBlock) => (None, true),
+            Err(TryLockError::Error(err)) => {
+                warn!("Couldn't lock the saves: {err}");
+                (None, false)
+            }
+        }
+    });
+    *locked
+}
+
+/// Every tab on the web shares the same local storage, and tests don't save, so saves are never
+/// locked.
+#[cfg(any(target_family = "wasm", test))]
+#[must_use]
+pub fn saves_locked() -> bool {
+    false
+}
+
+#[cfg(all(not(target_family = "wasm"), not(test)))]
+fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(data_dir()?.join(format!("{name}.ron"))).ok()
+}
+
+#[cfg(all(not(target_family = "wasm"), not(test)))]
+fn write(name: &str, contents: &str) -> Result<(), String> {
+    if saves_locked() {
+        return Err("another instance of the app is saving to the same folder".into());
+    }
+    let dir = data_dir().ok_or("there's no data folder")?;
+    std::fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    std::fs::write(dir.join(format!("{name}.ron")), contents).map_err(|err| err.to_string())
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn read(name: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{APP_DIR}/{name}"))
+        .ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn write(name: &str, contents: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("there's no local storage")?
+        .set_item(&format!("{APP_DIR}/{name}"), contents)
+        .map_err(|_| "local storage is full or blocked".to_string())
+}
+
+/// Tests run headless and shouldn't touch the player's data, so nothing is saved.
+#[cfg(all(not(target_family = "wasm"), test))]
+fn read(_name: &str) -> Option<String> {
+    None
+}
+
+#[cfg(all(not(target_family = "wasm"), test))]
+fn write(_name: &str, _contents: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Returns the value last saved under a name, or `None` if nothing was saved or it can't be read
+/// (e.g. because it was saved by a version that stored something else).
+#[must_use]
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let contents = read(name)?;
+    ron::from_str(&contents)
+        .inspect_err(|err| warn!("Couldn't read the saved {name}: {err}"))
+        .ok()
+}
+
+/// Saves a value under a name, so that it can be loaded in later sessions. Failures are logged,
+/// since there's nothing the player can do about them.
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let result = ron::to_string(value)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| write(name, &contents));
+    if let Err(err) = result {
+        warn!("Couldn't save the {name}: {err}");
+    }
+}
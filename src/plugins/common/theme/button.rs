@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::{node::ThemedBackgroundColor, Theme};
+use super::{color::ThemeColorRole, node::ThemedBackgroundColor, Theme};
 
 pub fn themed_button_plugin(app: &mut App) {
     app.add_systems(Update, themed_button_interaction_system);
@@ -18,10 +18,10 @@ fn themed_button_interaction_system(
     >,
 ) {
     for (mut background_color, interaction) in &mut themed_button_query {
-        *background_color = match *interaction {
-            Interaction::None => theme.button_normal_background,
-            Interaction::Hovered => theme.button_hovered_background,
-            Interaction::Pressed => theme.button_pressed_background,
-        };
+        background_color.0 = theme.color(match *interaction {
+            Interaction::None => ThemeColorRole::Surface,
+            Interaction::Hovered => ThemeColorRole::SurfaceHovered,
+            Interaction::Pressed => ThemeColorRole::SurfacePressed,
+        });
     }
 }
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use super::text::ThemedFontWeight;
+
+/// The font size `ch` units are measured at
+const CH_FONT_SIZE: f32 = 16.0;
+
+/// The text measured to find the width of a `ch` (dividing by its length averages out rounding)
+const CH_SAMPLE: &str = "0000000000";
+
+/// Measures the width of a character at runtime, so that layouts sized in `ch` units are correct
+/// for the loaded font and the window's scale factor.
+pub fn ch_plugin(app: &mut App) {
+    app.init_resource::<ChWidth>()
+        .add_systems(Startup, ch_measure_setup)
+        .add_systems(Update, (ch_measure_system, ch_size_system).chain());
+}
+
+/// The width of a "0" in the regular theme font, in logical pixels. Like the CSS `ch` unit,
+/// this is used to size text blocks so that lines stay a readable length.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct ChWidth(f32);
+
+impl Default for ChWidth {
+    /// An estimate used until the font has been measured
+    fn default() -> Self {
+        Self(10.5)
+    }
+}
+
+impl ChWidth {
+    /// Returns the width of a number of characters.
+    #[must_use]
+    pub fn ch(&self, units: f32) -> Val {
+        Val::Px(units * self.0)
+    }
+}
+
+/// Sizes a node in `ch` units, keeping it up to date when the measured width changes.
+#[derive(Component, Clone, Copy, Debug, Default)]
+#[require(Node)]
+pub struct ChSize {
+    pub width: Option<f32>,
+    pub max_width: Option<f32>,
+}
+
+/// Hidden text that is laid out so its width can be read back
+#[derive(Component)]
+struct ChMeasure;
+
+fn ch_measure_setup(mut commands: Commands) {
+    commands.spawn((
+        ChMeasure,
+        Text::new(CH_SAMPLE),
+        TextFont::from_font_size(CH_FONT_SIZE),
+        ThemedFontWeight::Regular,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+    ));
+}
+
+fn ch_measure_system(
+    measure_query: Query<&ComputedNode, (Changed<ComputedNode>, With<ChMeasure>)>,
+    mut ch_width: ResMut<ChWidth>,
+) {
+    for computed_node in &measure_query {
+        // Computed sizes are in physical pixels, but layouts are in logical pixels
+        let width =
+            computed_node.size().x * computed_node.inverse_scale_factor() / CH_SAMPLE.len() as f32;
+        if width > 0.0 && ch_width.0 != width {
+            ch_width.0 = width;
+        }
+    }
+}
+
+fn ch_size_system(ch_width: Res<ChWidth>, mut ch_size_query: Query<(Ref<ChSize>, &mut Node)>) {
+    for (ch_size, mut node) in &mut ch_size_query {
+        if !ch_width.is_changed() && !ch_size.is_changed() {
+            continue;
+        }
+        if let Some(width) = ch_size.width {
+            node.width = ch_width.ch(width);
+        }
+        if let Some(max_width) = ch_size.max_width {
+            node.max_width = ch_width.ch(max_width);
+        }
+    }
+}
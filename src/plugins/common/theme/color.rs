@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use super::Theme;
+
+/// The roles a color can play in the UI. Each theme's palette has a color for every role, so
+/// new UI can be themed by picking a role instead of adding a field to the theme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThemeColorRole {
+    /// Behind everything else
+    Background,
+    /// Buttons, inputs, and panels
+    Surface,
+    SurfaceHovered,
+    SurfacePressed,
+    Text,
+    Border,
+    /// Draws attention, e.g. to the focused entity
+    Accent,
+    /// Mistakes and destructive actions
+    Danger,
+    /// Correct entries and completed puzzles
+    Success,
+}
+
+/// A color for every role.
+#[derive(Clone, Debug)]
+pub(super) struct Palette {
+    background: Color,
+    surface: Color,
+    surface_hovered: Color,
+    surface_pressed: Color,
+    text: Color,
+    border: Color,
+    accent: Color,
+    danger: Color,
+    success: Color,
+}
+
+impl Palette {
+    pub(super) fn dark() -> Self {
+        Self {
+            background: Color::srgb_u8(13, 17, 23), // #0D1117
+            surface: Color::srgb_u8(21, 26, 35),    // #151A23
+            surface_hovered: Color::srgb(0.25, 0.25, 0.25),
+            surface_pressed: Color::srgb(0.35, 0.35, 0.85),
+            text: Color::srgb(1.0, 1.0, 1.0),
+            border: Color::srgb_u8(48, 54, 61), // #30363D
+            accent: Color::srgb(0.35, 0.35, 0.85),
+            danger: Color::srgb_u8(248, 81, 73),  // #F85149
+            success: Color::srgb_u8(63, 185, 80), // #3FB950
+        }
+    }
+
+    pub(super) fn light() -> Self {
+        Self {
+            background: Color::srgb(1.0, 1.0, 1.0),
+            surface: Color::srgb(1.0, 1.0, 1.0),
+            surface_hovered: Color::srgb(0.75, 0.75, 0.75),
+            surface_pressed: Color::srgb(0.35, 0.35, 0.85),
+            text: Color::srgb(0.0, 0.0, 0.0),
+            border: Color::srgb(0.1, 0.1, 0.1),
+            accent: Color::srgb(0.35, 0.35, 0.85),
+            danger: Color::srgb_u8(207, 34, 46),  // #CF222E
+            success: Color::srgb_u8(26, 127, 55), // #1A7F37
+        }
+    }
+
+    /// Black on white with no shading, for printing the board on paper.
+    pub(super) const PRINT: Self = Self {
+        background: Color::WHITE,
+        surface: Color::WHITE,
+        surface_hovered: Color::WHITE,
+        surface_pressed: Color::WHITE,
+        text: Color::BLACK,
+        border: Color::BLACK,
+        accent: Color::BLACK,
+        danger: Color::BLACK,
+        success: Color::BLACK,
+    };
+
+    /// Returns the color for a role.
+    #[must_use]
+    pub(super) fn get(&self, role: ThemeColorRole) -> Color {
+        match role {
+            ThemeColorRole::Background => self.background,
+            ThemeColorRole::Surface => self.surface,
+            ThemeColorRole::SurfaceHovered => self.surface_hovered,
+            ThemeColorRole::SurfacePressed => self.surface_pressed,
+            ThemeColorRole::Text => self.text,
+            ThemeColorRole::Border => self.border,
+            ThemeColorRole::Accent => self.accent,
+            ThemeColorRole::Danger => self.danger,
+            ThemeColorRole::Success => self.success,
+        }
+    }
+}
+
+/// Sets the background color of a node to the theme's color for a role.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(BackgroundColor)]
+pub struct ThemedColor(pub ThemeColorRole);
+
+pub fn themed_color_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            themed_color_change_system.run_if(resource_changed::<Theme>),
+            themed_color_role_change_system,
+        ),
+    );
+}
+
+fn themed_color_change_system(
+    theme: Res<Theme>,
+    mut themed_color_query: Query<(&mut BackgroundColor, &ThemedColor)>,
+) {
+    for (mut background_color, themed_color) in &mut themed_color_query {
+        background_color.0 = theme.color(themed_color.0);
+    }
+}
+
+fn themed_color_role_change_system(
+    theme: Res<Theme>,
+    mut themed_color_query: Query<(&mut BackgroundColor, &ThemedColor), Changed<ThemedColor>>,
+) {
+    for (mut background_color, themed_color) in &mut themed_color_query {
+        background_color.0 = theme.color(themed_color.0);
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::{node::ThemedBorderColor, Theme};
+use super::{color::ThemeColorRole, node::ThemedBorderColor, style::StyleSheet, Theme};
 
 #[derive(Resource, Default)]
 pub struct FocusedEntity {
@@ -8,14 +8,58 @@ pub struct FocusedEntity {
     pub current: Option<Entity>,
 }
 
+impl FocusedEntity {
+    /// Moves focus to the given entity, remembering the previously focused entity.
+    pub fn set(&mut self, entity: Option<Entity>) {
+        self.last = self.current;
+        self.current = entity;
+    }
+}
+
+/// Groups the focusable descendants of an entity. Tab and Shift+Tab cycle through the focusable
+/// entities of every visible scope in order, unless focus is inside a scope that traps it, in which
+/// case cycling stays within that scope. Each scope remembers the last entity focused inside it so
+/// that focus can be restored when the scope becomes visible again.
+#[derive(Component, Default)]
+#[require(Node)]
+pub struct FocusScope {
+    pub trap: bool,
+    /// Where the scope comes in the Tab order, lowest first. Scopes with the same order go in
+    /// reading order, by the top left corners of their nodes.
+    pub order: i32,
+    pub last_focused: Option<Entity>,
+}
+
+impl FocusScope {
+    /// Creates a scope that keeps Tab cycling within its descendants while one of them is focused.
+    #[must_use]
+    pub fn trap() -> Self {
+        Self {
+            trap: true,
+            ..default()
+        }
+    }
+
+    /// Creates a scope that comes at some place in the Tab order.
+    #[must_use]
+    pub fn ordered(order: i32) -> Self {
+        Self { order, ..default() }
+    }
+}
+
 pub fn focus_plugin(app: &mut App) {
     app.init_resource::<FocusedEntity>().add_systems(
         Update,
         (
             focus_system,
             unfocus_system,
+            focus_cycle_system,
+            focus_scope_memory_system.run_if(resource_exists_and_changed::<FocusedEntity>),
+            focus_scope_restore_system,
             focus_outline_system.run_if(resource_exists_and_changed::<FocusedEntity>),
-        ),
+            focus_outline_pulse_system,
+        )
+            .chain(),
     );
 }
 
@@ -25,8 +69,7 @@ fn focus_system(
 ) {
     for (&interaction, interacted_entity) in interaction_query.iter() {
         if interaction == Interaction::Pressed {
-            focused_entity.last = focused_entity.current;
-            focused_entity.current = Some(interacted_entity);
+            focused_entity.set(Some(interacted_entity));
         }
     }
 }
@@ -39,8 +82,149 @@ fn unfocus_system(
     if buttons.get_just_pressed().len() > 0
         && interaction_query.iter().all(|&i| i != Interaction::Pressed)
     {
-        focused_entity.last = focused_entity.current;
-        focused_entity.current = None;
+        focused_entity.set(None);
+    }
+}
+
+/// Collects the visible focusable entities below `root` in depth-first (layout) order.
+fn collect_focusables(
+    root: Entity,
+    children_query: &Query<&Children>,
+    focusable_query: &Query<&InheritedVisibility, With<Interaction>>,
+    out: &mut Vec<Entity>,
+) {
+    if let Ok(visibility) = focusable_query.get(root) {
+        if visibility.get() {
+            out.push(root);
+        }
+    }
+    if let Ok(children) = children_query.get(root) {
+        for &child in children {
+            collect_focusables(child, children_query, focusable_query, out);
+        }
+    }
+}
+
+/// Returns the scope that contains `entity`, if any.
+fn find_scope(
+    entity: Entity,
+    parent_query: &Query<&ChildOf>,
+    scope_query: &Query<(Entity, &FocusScope, &InheritedVisibility)>,
+) -> Option<Entity> {
+    std::iter::once(entity)
+        .chain(parent_query.iter_ancestors(entity))
+        .find(|&ancestor| scope_query.contains(ancestor))
+}
+
+/// Returns where a scope comes in the Tab order: by its order, and then by its top left corner from
+/// top to bottom and left to right.
+fn scope_sort_key(
+    scope: Entity,
+    scope_query: &Query<(Entity, &FocusScope, &InheritedVisibility)>,
+    layout_query: &Query<(&ComputedNode, &GlobalTransform)>,
+) -> (i32, f32, f32) {
+    let order = scope_query
+        .get(scope)
+        .map_or(0, |(_, focus_scope, _)| focus_scope.order);
+    let top_left = layout_query
+        .get(scope)
+        .map_or(Vec2::ZERO, |(node, transform)| {
+            transform.translation().truncate() - node.size() / 2.0
+        });
+    (order, top_left.y, top_left.x)
+}
+
+fn focus_cycle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    scope_query: Query<(Entity, &FocusScope, &InheritedVisibility)>,
+    layout_query: Query<(&ComputedNode, &GlobalTransform)>,
+    children_query: Query<&Children>,
+    parent_query: Query<&ChildOf>,
+    focusable_query: Query<&InheritedVisibility, With<Interaction>>,
+    mut focused_entity: ResMut<FocusedEntity>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let backwards = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    // Use the trapping scope around the current focus, or else every visible scope in Tab order
+    let trapping_scope = focused_entity
+        .current
+        .and_then(|current| find_scope(current, &parent_query, &scope_query))
+        .filter(|&scope| scope_query.get(scope).is_ok_and(|(_, s, _)| s.trap));
+    let mut roots: Vec<Entity> = match trapping_scope {
+        Some(scope) => vec![scope],
+        None => scope_query
+            .iter()
+            .filter(|(_, _, visibility)| visibility.get())
+            .map(|(entity, ..)| entity)
+            .collect(),
+    };
+    roots.sort_by(|&a, &b| {
+        let (a_order, a_y, a_x) = scope_sort_key(a, &scope_query, &layout_query);
+        let (b_order, b_y, b_x) = scope_sort_key(b, &scope_query, &layout_query);
+        a_order
+            .cmp(&b_order)
+            .then(a_y.total_cmp(&b_y))
+            .then(a_x.total_cmp(&b_x))
+    });
+
+    let mut focusables = Vec::new();
+    for root in roots {
+        collect_focusables(root, &children_query, &focusable_query, &mut focusables);
+    }
+    if focusables.is_empty() {
+        return;
+    }
+
+    // Move to the next (or previous) focusable, wrapping around at either end
+    let position = focused_entity
+        .current
+        .and_then(|current| focusables.iter().position(|&e| e == current));
+    let len = focusables.len();
+    let next_index = match (position, backwards) {
+        (Some(i), false) => (i + 1) % len,
+        (Some(i), true) => (i + len - 1) % len,
+        (None, false) => 0,
+        (None, true) => len - 1,
+    };
+    focused_entity.set(Some(focusables[next_index]));
+}
+
+fn focus_scope_memory_system(
+    focused_entity: Res<FocusedEntity>,
+    parent_query: Query<&ChildOf>,
+    mut scope_query: Query<(Entity, &mut FocusScope, &InheritedVisibility)>,
+) {
+    let Some(current) = focused_entity.current else {
+        return;
+    };
+    let Some(scope) = find_scope(current, &parent_query, &scope_query.as_readonly()) else {
+        return;
+    };
+    if let Ok((_, mut focus_scope, _)) = scope_query.get_mut(scope) {
+        focus_scope.last_focused = Some(current);
+    }
+}
+
+fn focus_scope_restore_system(
+    scope_query: Query<(&FocusScope, &InheritedVisibility), Changed<InheritedVisibility>>,
+    entity_query: Query<&InheritedVisibility>,
+    mut focused_entity: ResMut<FocusedEntity>,
+) {
+    if focused_entity.current.is_some() {
+        return;
+    }
+    for (focus_scope, visibility) in scope_query.iter() {
+        let Some(last_focused) = focus_scope.last_focused else {
+            continue;
+        };
+        // Only restore focus to entities that still exist and are visible
+        if visibility.get() && entity_query.get(last_focused).is_ok_and(|v| v.get()) {
+            focused_entity.set(Some(last_focused));
+            return;
+        }
     }
 }
 
@@ -51,12 +235,32 @@ fn focus_outline_system(
 ) {
     if let Some(last) = focused_entity.last {
         if let Ok(mut last_border) = border_query.get_mut(last) {
-            *last_border = theme.border_color;
+            last_border.0 = theme.color(ThemeColorRole::Border);
         }
     }
     if let Some(current) = focused_entity.current {
         if let Ok(mut current_border) = border_query.get_mut(current) {
-            *current_border = BorderColor(theme.button_pressed_background.0);
+            current_border.0 = theme.color(ThemeColorRole::Accent);
         }
     }
 }
+
+/// Gently pulses the alpha of the focused entity's outline.
+fn focus_outline_pulse_system(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    style_sheet: Res<StyleSheet>,
+    focused_entity: Res<FocusedEntity>,
+    mut border_query: Query<&mut BorderColor, With<ThemedBorderColor>>,
+) {
+    let Some(current) = focused_entity.current else {
+        return;
+    };
+    if let Ok(mut current_border) = border_query.get_mut(current) {
+        let phase = time.elapsed_secs() * std::f32::consts::TAU / style_sheet.focus_pulse_period;
+        let wave = (phase.sin() + 1.0) / 2.0;
+        let min_alpha = style_sheet.focus_pulse_min_alpha;
+        let alpha = min_alpha + (1.0 - min_alpha) * wave;
+        current_border.0 = theme.color(ThemeColorRole::Accent).with_alpha(alpha);
+    }
+}
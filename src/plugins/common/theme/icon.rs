@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI},
+};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::utility::stroke::{arc, stroke_polylines, Point};
+
+use super::{
+    text::{themed_text_color, MutedText},
+    Theme,
+};
+
+/// The width of the coordinates the icons are drawn in, which matches the Heroicons they copy.
+const VIEW_BOX: f32 = 24.0;
+const STROKE_WIDTH: f32 = 1.5;
+/// Icons are drawn at this many pixels across and scaled down by the UI.
+const ICON_IMAGE_SIZE: u32 = 96;
+
+pub fn icon_plugin(app: &mut App) {
+    app.init_resource::<IconImages>().add_systems(
+        Update,
+        (
+            icon_image_system,
+            icon_theme_change_system.run_if(resource_changed::<Theme>),
+            muted_icon_removed_system,
+        ),
+    );
+}
+
+/// The icons the UI draws. They're outlines traced from Heroicons, drawn in the theme's text color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IconId {
+    Back,
+    Pause,
+    ArrowLeft,
+    ArrowRight,
+    ChevronDown,
+    ChevronUp,
+    Check,
+    Copy,
+}
+
+fn rounded_rect_path(left: f32, top: f32, right: f32, bottom: f32, radius: f32) -> Vec<Point> {
+    [
+        ((right - radius, top + radius), -FRAC_PI_2),
+        ((right - radius, bottom - radius), 0.0),
+        ((left + radius, bottom - radius), FRAC_PI_2),
+        ((left + radius, top + radius), PI),
+    ]
+    .into_iter()
+    .flat_map(|(center, start)| arc(center, radius, start, start + FRAC_PI_2))
+    .chain([(right - radius, top)])
+    .collect()
+}
+
+impl IconId {
+    /// Returns the lines that draw the icon, in a 24 by 24 box.
+    fn polylines(self) -> Vec<Vec<Point>> {
+        match self {
+            IconId::Back => vec![
+                vec![(9.0, 15.0), (3.0, 9.0), (9.0, 3.0)],
+                [(3.0, 9.0)]
+                    .into_iter()
+                    .chain(arc((15.0, 15.0), 6.0, -FRAC_PI_2, FRAC_PI_2))
+                    .chain([(12.0, 21.0)])
+                    .collect(),
+            ],
+            IconId::Pause => vec![
+                vec![(15.75, 5.25), (15.75, 18.75)],
+                vec![(8.25, 5.25), (8.25, 18.75)],
+            ],
+            IconId::ArrowLeft => vec![
+                vec![(10.5, 19.5), (3.0, 12.0), (10.5, 4.5)],
+                vec![(3.0, 12.0), (21.0, 12.0)],
+            ],
+            IconId::ArrowRight => vec![
+                vec![(13.5, 4.5), (21.0, 12.0), (13.5, 19.5)],
+                vec![(21.0, 12.0), (3.0, 12.0)],
+            ],
+            IconId::ChevronDown => vec![vec![(19.5, 8.25), (12.0, 15.75), (4.5, 8.25)]],
+            IconId::ChevronUp => vec![vec![(4.5, 15.75), (12.0, 8.25), (19.5, 15.75)]],
+            IconId::Check => vec![vec![(4.5, 12.75), (10.5, 18.75), (19.5, 5.25)]],
+            // A square in front of the corner of another
+            IconId::Copy => vec![
+                rounded_rect_path(8.25, 8.25, 20.25, 20.25, 2.25),
+                [(16.5, 8.25)]
+                    .into_iter()
+                    .chain(arc((14.25, 6.0), 2.25, 0.0, -FRAC_PI_2))
+                    .chain(arc((6.0, 6.0), 2.25, -FRAC_PI_2, -PI))
+                    .chain(arc((6.0, 14.25), 2.25, PI, FRAC_PI_2))
+                    .chain([(8.25, 16.5)])
+                    .collect(),
+            ],
+        }
+    }
+
+    /// Draws the icon in white, so that the image node's color tints it.
+    fn image(self) -> Image {
+        let coverage = stroke_polylines(&self.polylines(), VIEW_BOX, STROKE_WIDTH, ICON_IMAGE_SIZE);
+        let data = coverage
+            .into_iter()
+            .flat_map(|alpha| [255, 255, 255, alpha])
+            .collect();
+        Image::new(
+            Extent3d {
+                width: ICON_IMAGE_SIZE,
+                height: ICON_IMAGE_SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        )
+    }
+}
+
+/// Shows an icon in the theme's text color. Set the node's size to size the icon.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(ImageNode)]
+pub struct Icon(pub IconId);
+
+/// Returns an icon that's `size` pixels across.
+#[must_use]
+pub fn icon_bundle(icon_id: IconId, size: f32) -> impl Bundle {
+    (
+        Icon(icon_id),
+        Node {
+            width: Val::Px(size),
+            height: Val::Px(size),
+            ..default()
+        },
+    )
+}
+
+/// The images of the icons that have been drawn so far. Each is drawn the first time it's shown.
+#[derive(Resource, Default)]
+struct IconImages(HashMap<IconId, Handle<Image>>);
+
+#[allow(clippy::type_complexity)]
+fn icon_image_system(
+    theme: Res<Theme>,
+    mut icon_images: ResMut<IconImages>,
+    mut images: ResMut<Assets<Image>>,
+    mut icon_query: Query<
+        (&Icon, &mut ImageNode, Has<MutedText>),
+        Or<(Changed<Icon>, Added<MutedText>)>,
+    >,
+) {
+    for (icon, mut image_node, muted) in &mut icon_query {
+        image_node.image = icon_images
+            .0
+            .entry(icon.0)
+            .or_insert_with(|| images.add(icon.0.image()))
+            .clone();
+        image_node.color = themed_text_color(&theme, muted);
+    }
+}
+
+fn icon_theme_change_system(
+    theme: Res<Theme>,
+    mut icon_query: Query<(&mut ImageNode, Has<MutedText>), With<Icon>>,
+) {
+    for (mut image_node, muted) in &mut icon_query {
+        image_node.color = themed_text_color(&theme, muted);
+    }
+}
+
+fn muted_icon_removed_system(
+    theme: Res<Theme>,
+    mut removed_muted_text: RemovedComponents<MutedText>,
+    mut icon_query: Query<&mut ImageNode, With<Icon>>,
+) {
+    for entity in removed_muted_text.read() {
+        if let Ok(mut image_node) = icon_query.get_mut(entity) {
+            image_node.color = themed_text_color(&theme, false);
+        }
+    }
+}
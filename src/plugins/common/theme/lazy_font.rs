@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use super::{text::ThemedFontWeight, Theme};
+
+pub(super) const TEXT_FONT_BOLD_PATH: &str = "fonts/OpenSans-Bold.ttf";
+pub(super) const TEXT_FONT_SYMBOLS_PATH: &str = "fonts/NotoSansSymbols2-Regular.ttf";
+
+/// Loads the bold and symbol fonts the first time text uses them, in builds that don't load them
+/// at startup (the slim web build). Until a font has loaded, its text is drawn in the regular font.
+pub fn lazy_font_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (lazy_font_request_system, lazy_font_loaded_system)
+            .chain()
+            .run_if(resource_exists::<LazyFonts>),
+    );
+}
+
+/// A font that's only loaded once text needs it.
+#[derive(Default)]
+struct LazyFont {
+    handle: Option<Handle<Font>>,
+    loaded: bool,
+}
+
+impl LazyFont {
+    fn request(&mut self, asset_server: &AssetServer, path: &'static str) {
+        if self.handle.is_none() {
+            self.handle = Some(asset_server.load(path));
+        }
+    }
+
+    /// Returns the font once it has loaded, the first time it's asked after that.
+    fn take_loaded(&mut self, asset_server: &AssetServer) -> Option<Handle<Font>> {
+        let handle = self.handle.as_ref().filter(|_| !self.loaded)?;
+        if !asset_server.is_loaded_with_dependencies(handle) {
+            return None;
+        }
+        self.loaded = true;
+        Some(handle.clone())
+    }
+}
+
+/// The fonts that are loaded when they're first needed, while the theme uses the regular font in
+/// their place.
+#[derive(Resource, Default)]
+pub(super) struct LazyFonts {
+    bold: LazyFont,
+    symbols: LazyFont,
+}
+
+// Start loading a font when text that uses it is added
+fn lazy_font_request_system(
+    asset_server: Res<AssetServer>,
+    mut lazy_fonts: ResMut<LazyFonts>,
+    font_weight_query: Query<&ThemedFontWeight, Added<ThemedFontWeight>>,
+) {
+    for font_weight in &font_weight_query {
+        match font_weight {
+            ThemedFontWeight::Regular => {}
+            ThemedFontWeight::Bold => lazy_fonts.bold.request(&asset_server, TEXT_FONT_BOLD_PATH),
+            ThemedFontWeight::Symbolic => lazy_fonts
+                .symbols
+                .request(&asset_server, TEXT_FONT_SYMBOLS_PATH),
+        }
+    }
+}
+
+// Swap a font into the theme once it has loaded, which redraws the text that uses it
+fn lazy_font_loaded_system(
+    asset_server: Res<AssetServer>,
+    mut lazy_fonts: ResMut<LazyFonts>,
+    mut theme: ResMut<Theme>,
+) {
+    if let Some(handle) = lazy_fonts.bold.take_loaded(&asset_server) {
+        theme.text_font_bold = handle;
+    }
+    if let Some(handle) = lazy_fonts.symbols.take_loaded(&asset_server) {
+        theme.text_font_symbols = handle;
+    }
+}
@@ -3,69 +3,104 @@ use bevy::{
     window::{WindowTheme, WindowThemeChanged},
 };
 use button::themed_button_plugin;
+use ch::ch_plugin;
+use color::{themed_color_plugin, Palette, ThemeColorRole};
 use node::themed_node_plugin;
+use style::{style_sheet_plugin, StyleSheet};
 use text::themed_text_plugin;
 
 use focus::focus_plugin;
+use icon::icon_plugin;
+use lazy_font::{lazy_font_plugin, LazyFonts, TEXT_FONT_BOLD_PATH, TEXT_FONT_SYMBOLS_PATH};
+
+use crate::plugins::loading::LoadingAssets;
 
 pub mod button;
+pub mod ch;
+pub mod color;
 pub mod focus;
+pub mod icon;
+mod lazy_font;
 pub mod node;
+pub mod style;
 pub mod text;
 
 #[derive(Resource, Clone)]
 struct Theme {
-    clear_color: Color,
+    palette: Palette,
     text_font_regular: Handle<Font>,
     text_font_bold: Handle<Font>,
     text_font_symbols: Handle<Font>,
-    text_color: Color,
     border_rect: UiRect,
-    border_color: BorderColor,
     border_radius: BorderRadius,
-    button_normal_background: BackgroundColor, // (not hovered or pressed)
-    button_hovered_background: BackgroundColor,
-    button_pressed_background: BackgroundColor,
+    /// Whether the print palette is used instead, while `PrintMode` is on
+    printing: bool,
 }
 
+/// While this is on, everything is drawn in black and white for printing the board on paper.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrintMode(pub bool);
+
 impl Theme {
-    fn dark(
+    fn new(
+        palette: Palette,
         text_font_regular: Handle<Font>,
         text_font_bold: Handle<Font>,
         text_font_symbols: Handle<Font>,
     ) -> Self {
         Self {
-            clear_color: Color::srgb_u8(13, 17, 23), // #0D1117
+            palette,
             text_font_regular,
             text_font_bold,
             text_font_symbols,
-            text_color: Color::srgb(1.0, 1.0, 1.0),
-            border_rect: UiRect::all(Val::Px(2.0)),
-            border_color: BorderColor(Color::srgb_u8(48, 54, 61)), // #30363D
-            border_radius: BorderRadius::all(Val::Px(6.0)),
-            button_normal_background: BackgroundColor(Color::srgb_u8(21, 26, 35)), // #151A23
-            button_hovered_background: BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
-            button_pressed_background: BackgroundColor(Color::srgb(0.35, 0.35, 0.85)),
+            border_rect: default(),
+            border_radius: default(),
+            printing: false,
+        }
+        .styled(&StyleSheet::default())
+    }
+
+    /// Takes the border sizes from the style sheet.
+    fn styled(self, style_sheet: &StyleSheet) -> Self {
+        Self {
+            border_rect: UiRect::all(Val::Px(style_sheet.border_width)),
+            border_radius: BorderRadius::all(Val::Px(style_sheet.border_radius)),
+            ..self
         }
     }
 
+    fn dark(
+        text_font_regular: Handle<Font>,
+        text_font_bold: Handle<Font>,
+        text_font_symbols: Handle<Font>,
+    ) -> Self {
+        Self::new(
+            Palette::dark(),
+            text_font_regular,
+            text_font_bold,
+            text_font_symbols,
+        )
+    }
+
     fn light(
         text_font_regular: Handle<Font>,
         text_font_bold: Handle<Font>,
         text_font_symbols: Handle<Font>,
     ) -> Self {
-        Self {
-            clear_color: Color::srgb(1.0, 1.0, 1.0),
+        Self::new(
+            Palette::light(),
             text_font_regular,
             text_font_bold,
             text_font_symbols,
-            text_color: Color::srgb(0.0, 0.0, 0.0),
-            border_rect: UiRect::all(Val::Px(2.0)),
-            border_color: BorderColor(Color::srgb(0.1, 0.1, 0.1)),
-            border_radius: BorderRadius::all(Val::Px(6.0)),
-            button_normal_background: BackgroundColor(Color::srgb(1.0, 1.0, 1.0)),
-            button_hovered_background: BackgroundColor(Color::srgb(0.75, 0.75, 0.75)),
-            button_pressed_background: BackgroundColor(Color::srgb(0.35, 0.35, 0.85)),
+        )
+    }
+
+    /// Returns the palette's color for a role.
+    fn color(&self, role: ThemeColorRole) -> Color {
+        if self.printing {
+            Palette::PRINT.get(role)
+        } else {
+            self.palette.get(role)
         }
     }
 }
@@ -78,23 +113,52 @@ impl Default for Theme {
 
 pub fn theme_plugin(app: &mut App) {
     app.init_resource::<Theme>()
+        .init_resource::<PrintMode>()
         .add_systems(Startup, theme_init_system)
-        .add_systems(Update, (theme_change_system, clear_color_system))
+        .add_systems(
+            Update,
+            (
+                theme_change_system,
+                theme_style_system.run_if(resource_changed::<StyleSheet>),
+                print_mode_system.run_if(resource_changed::<PrintMode>),
+                clear_color_system,
+            ),
+        )
         .add_plugins((
             themed_text_plugin,
             themed_node_plugin,
             themed_button_plugin,
             focus_plugin,
+            ch_plugin,
+            themed_color_plugin,
+            style_sheet_plugin,
+            icon_plugin,
+            lazy_font_plugin,
         ));
 }
 
-fn theme_init_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn theme_init_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
     // Create a camera
     commands.spawn(Camera2d);
 
     let text_font_regular = asset_server.load("fonts/OpenSans-Regular.ttf");
-    let text_font_bold = asset_server.load("fonts/OpenSans-Bold.ttf");
-    let text_font_symbols = asset_server.load("fonts/NotoSansSymbols2-Regular.ttf");
+    loading_assets.track(&text_font_regular);
+    // The slim web build shows the menus once the regular font is ready, and only downloads the
+    // others once text uses them, drawing that text in the regular font until they've loaded
+    let (text_font_bold, text_font_symbols) = if cfg!(feature = "wasm-slim") {
+        commands.init_resource::<LazyFonts>();
+        (text_font_regular.clone(), text_font_regular.clone())
+    } else {
+        let text_font_bold = asset_server.load(TEXT_FONT_BOLD_PATH);
+        let text_font_symbols = asset_server.load(TEXT_FONT_SYMBOLS_PATH);
+        loading_assets.track(&text_font_bold);
+        loading_assets.track(&text_font_symbols);
+        (text_font_bold, text_font_symbols)
+    };
 
     // Use system theme to set initial app theme
     let app_theme: Theme = match dark_light::detect().unwrap_or(dark_light::Mode::Unspecified) {
@@ -111,6 +175,7 @@ fn theme_init_system(mut commands: Commands, asset_server: Res<AssetServer>) {
 fn theme_change_system(
     mut ev_window_theme_changed: EventReader<WindowThemeChanged>,
     current_theme: Res<Theme>,
+    style_sheet: Res<StyleSheet>,
     mut commands: Commands,
 ) {
     for ev in ev_window_theme_changed.read() {
@@ -126,10 +191,21 @@ fn theme_change_system(
         };
 
         // Update the app theme
-        commands.insert_resource(app_theme);
+        commands.insert_resource(Theme {
+            printing: current_theme.printing,
+            ..app_theme.styled(&style_sheet)
+        });
     }
 }
 
+fn theme_style_system(style_sheet: Res<StyleSheet>, mut theme: ResMut<Theme>) {
+    *theme = theme.clone().styled(&style_sheet);
+}
+
+fn print_mode_system(print_mode: Res<PrintMode>, mut theme: ResMut<Theme>) {
+    theme.printing = print_mode.0;
+}
+
 fn clear_color_system(mut clear_color: ResMut<ClearColor>, theme: Res<Theme>) {
-    clear_color.0 = theme.clear_color;
+    clear_color.0 = theme.color(ThemeColorRole::Background);
 }
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::Theme;
+use super::{color::ThemeColorRole, Theme};
 
 #[derive(Component, Default)]
 pub struct ThemedBackgroundColor;
@@ -35,11 +35,11 @@ fn theme_changed_system(
     mut node_query: Query<&mut Node, With<ThemedBorderRect>>,
 ) {
     for mut background_color in &mut background_color_query {
-        *background_color = theme.button_normal_background;
+        background_color.0 = theme.color(ThemeColorRole::Surface);
     }
 
     for mut border_color in &mut border_color_query {
-        *border_color = theme.border_color;
+        border_color.0 = theme.color(ThemeColorRole::Border);
     }
 
     for mut border_radius in &mut border_radius_query {
@@ -56,7 +56,7 @@ fn themed_background_color_added_system(
     mut background_color_query: Query<&mut BackgroundColor, Added<ThemedBackgroundColor>>,
 ) {
     for mut background_color in &mut background_color_query {
-        *background_color = theme.button_normal_background;
+        background_color.0 = theme.color(ThemeColorRole::Surface);
     }
 }
 
@@ -65,7 +65,7 @@ fn themed_border_color_added_system(
     mut border_color_query: Query<&mut BorderColor, Added<ThemedBorderColor>>,
 ) {
     for mut border_color in &mut border_color_query {
-        *border_color = theme.border_color;
+        border_color.0 = theme.color(ThemeColorRole::Border);
     }
 }
 
@@ -0,0 +1,178 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::plugins::loading::LoadingAssets;
+
+/// The style sheet's path within the assets folder.
+const STYLE_SHEET_PATH: &str = "ui.style.ron";
+
+pub fn style_sheet_plugin(app: &mut App) {
+    app.init_asset::<StyleSheet>()
+        .init_asset_loader::<StyleSheetLoader>()
+        .init_resource::<StyleSheet>()
+        .add_systems(Startup, style_sheet_init_system)
+        .add_systems(Update, style_sheet_update_system);
+    #[cfg(all(debug_assertions, not(target_family = "wasm")))]
+    app.add_systems(Update, style_sheet_watch_system);
+}
+
+/// The sizes used across the UI, loaded from `assets/ui.style.ron`. Bundle constructors read the
+/// resource when they're spawned, so changes to sizes show up the next time a screen is built,
+/// while changes to borders show up right away.
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct StyleSheet {
+    pub border_width: f32,
+    pub border_radius: f32,
+    /// How long one cycle of the focus outline pulse takes, in seconds.
+    pub focus_pulse_period: f32,
+    /// The lowest alpha the focus outline reaches during a pulse.
+    pub focus_pulse_min_alpha: f32,
+    pub title_font_size: f32,
+    pub heading_font_size: f32,
+    pub menu_button_font_size: f32,
+    pub button_font_size: f32,
+    pub body_font_size: f32,
+    /// The space between groups of items.
+    pub gap: f32,
+    pub button_padding: f32,
+    pub side_panel_width: f32,
+}
+
+impl Default for StyleSheet {
+    fn default() -> Self {
+        Self {
+            border_width: 2.0,
+            border_radius: 6.0,
+            focus_pulse_period: 1.6,
+            focus_pulse_min_alpha: 0.55,
+            title_font_size: 80.0,
+            heading_font_size: 36.0,
+            menu_button_font_size: 40.0,
+            button_font_size: 24.0,
+            body_font_size: 20.0,
+            gap: 20.0,
+            button_padding: 10.0,
+            side_panel_width: 220.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StyleSheetLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for StyleSheetLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleSheetLoaderError::Io(err) => write!(f, "Couldn't read the style sheet: {err}"),
+            StyleSheetLoaderError::Ron(err) => write!(f, "Couldn't parse the style sheet: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StyleSheetLoaderError {}
+
+impl From<std::io::Error> for StyleSheetLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        StyleSheetLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for StyleSheetLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        StyleSheetLoaderError::Ron(err)
+    }
+}
+
+#[derive(Default)]
+struct StyleSheetLoader;
+
+impl AssetLoader for StyleSheetLoader {
+    type Asset = StyleSheet;
+    type Settings = ();
+    type Error = StyleSheetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<StyleSheet, StyleSheetLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["style.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct StyleSheetHandle(Handle<StyleSheet>);
+
+fn style_sheet_init_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(STYLE_SHEET_PATH);
+    loading_assets.track(&handle);
+    commands.insert_resource(StyleSheetHandle(handle));
+}
+
+// Copy the style sheet into the resource whenever it (re)loads. If it fails to load, the built-in
+// values are kept.
+fn style_sheet_update_system(
+    mut asset_events: EventReader<AssetEvent<StyleSheet>>,
+    style_sheets: Res<Assets<StyleSheet>>,
+    style_sheet_handle: Option<Res<StyleSheetHandle>>,
+    mut style_sheet: ResMut<StyleSheet>,
+) {
+    let Some(style_sheet_handle) = style_sheet_handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if event.is_loaded_with_dependencies(&style_sheet_handle.0)
+            || event.is_modified(&style_sheet_handle.0)
+        {
+            if let Some(loaded) = style_sheets.get(&style_sheet_handle.0) {
+                style_sheet.set_if_neq(loaded.clone());
+            }
+        }
+    }
+}
+
+/// Reloads the style sheet when its file is saved. Bevy's `file_watcher` feature would do this for
+/// every asset, but this avoids building the file watcher for one file.
+#[cfg(all(debug_assertions, not(target_family = "wasm")))]
+fn style_sheet_watch_system(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut poll_timer: Local<Option<Timer>>,
+    mut last_modified: Local<Option<std::time::SystemTime>>,
+) {
+    let poll_timer =
+        poll_timer.get_or_insert_with(|| Timer::from_seconds(1.0, TimerMode::Repeating));
+    if !poll_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let path = bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets")
+        .join(STYLE_SHEET_PATH);
+    let Ok(modified) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+        return;
+    };
+    if last_modified
+        .replace(modified)
+        .is_some_and(|last| last != modified)
+    {
+        asset_server.reload(STYLE_SHEET_PATH);
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::plugins::common::bundles::text_input::TextInputCursor;
 
-use super::Theme;
+use super::{color::ThemeColorRole, Theme};
 
 #[derive(Component, Default, Clone, Copy)]
 #[require(TextFont)]
@@ -16,6 +16,22 @@ pub enum ThemedFontWeight {
 #[derive(Component, Default, Clone, Copy)]
 pub struct ThemedTextColor;
 
+/// Fades themed text, e.g. for options that can't be used right now.
+#[derive(Component, Default, Clone, Copy)]
+pub struct MutedText;
+
+/// How opaque muted text is.
+const MUTED_TEXT_ALPHA: f32 = 0.4;
+
+pub(super) fn themed_text_color(theme: &Theme, muted: bool) -> Color {
+    let color = theme.color(ThemeColorRole::Text);
+    if muted {
+        color.with_alpha(MUTED_TEXT_ALPHA)
+    } else {
+        color
+    }
+}
+
 /// Sets and changes the text color and font using the theme
 pub fn themed_text_plugin(app: &mut App) {
     app.add_systems(
@@ -23,6 +39,7 @@ pub fn themed_text_plugin(app: &mut App) {
         (
             themed_text_change_system.run_if(resource_changed::<Theme>),
             themed_text_color_added_system,
+            muted_text_removed_system,
             themed_font_weight_system,
         ),
     );
@@ -31,15 +48,15 @@ pub fn themed_text_plugin(app: &mut App) {
 /// Sets and changes the text color and font using the theme
 fn themed_text_change_system(
     theme: Res<Theme>,
-    mut themed_text_color_query: Query<&mut TextColor, With<ThemedTextColor>>,
+    mut themed_text_color_query: Query<(&mut TextColor, Has<MutedText>), With<ThemedTextColor>>,
     mut text_cursor_query: Query<&mut BackgroundColor, With<TextInputCursor>>,
     mut themed_font_weight_query: Query<(&mut TextFont, &ThemedFontWeight)>,
 ) {
-    for mut text_color in &mut themed_text_color_query {
-        text_color.0 = theme.text_color;
+    for (mut text_color, muted) in &mut themed_text_color_query {
+        text_color.0 = themed_text_color(&theme, muted);
     }
     for mut text_input_cursor_background_color in &mut text_cursor_query {
-        text_input_cursor_background_color.0 = theme.text_color;
+        text_input_cursor_background_color.0 = theme.color(ThemeColorRole::Text);
     }
     for (mut text_font, font_weight) in &mut themed_font_weight_query {
         text_font.font = match font_weight {
@@ -50,16 +67,35 @@ fn themed_text_change_system(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn themed_text_color_added_system(
     theme: Res<Theme>,
-    mut themed_text_color_query: Query<&mut TextColor, Added<ThemedTextColor>>,
+    mut themed_text_color_query: Query<
+        (&mut TextColor, Has<MutedText>),
+        (
+            With<ThemedTextColor>,
+            Or<(Added<ThemedTextColor>, Added<MutedText>)>,
+        ),
+    >,
     mut text_cursor_query: Query<&mut BackgroundColor, Added<TextInputCursor>>,
 ) {
-    for mut text_color in &mut themed_text_color_query {
-        text_color.0 = theme.text_color;
+    for (mut text_color, muted) in &mut themed_text_color_query {
+        text_color.0 = themed_text_color(&theme, muted);
     }
     for mut text_cursor_background_color in &mut text_cursor_query {
-        text_cursor_background_color.0 = theme.text_color;
+        text_cursor_background_color.0 = theme.color(ThemeColorRole::Text);
+    }
+}
+
+fn muted_text_removed_system(
+    theme: Res<Theme>,
+    mut removed_muted_text: RemovedComponents<MutedText>,
+    mut themed_text_color_query: Query<&mut TextColor, With<ThemedTextColor>>,
+) {
+    for entity in removed_muted_text.read() {
+        if let Ok(mut text_color) = themed_text_color_query.get_mut(entity) {
+            text_color.0 = themed_text_color(&theme, false);
+        }
     }
 }
 
@@ -0,0 +1,319 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::{plugins::menu::MenuState, AppState};
+
+use super::{
+    common::{
+        bundles::text_input::{text_input_bundle, TextInputBundleOptions, TextInputContainer},
+        theme::{
+            focus::FocusedEntity,
+            node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRect},
+            text::{ThemedFontWeight, ThemedTextColor},
+        },
+    },
+    game::GameState,
+    nav::{NavState, Navigator, Screen},
+};
+
+/// How many lines of input and output the console shows.
+const CONSOLE_LOG_LINES: usize = 12;
+
+const CONSOLE_TOGGLE_CHAR: char = '`';
+
+/// A developer console, toggled with the backtick key, that runs the commands registered with
+/// `register_console_command`. It's only added to debug builds.
+pub fn console_plugin(app: &mut App) {
+    app.init_resource::<ConsoleCommands>()
+        .init_resource::<ConsoleLog>()
+        .add_event::<ConsolePrint>()
+        .add_systems(Startup, console_setup)
+        .add_systems(
+            Update,
+            (
+                console_toggle_system,
+                console_submit_system,
+                console_run_system,
+                console_print_system,
+                console_log_system,
+            )
+                .chain(),
+        );
+    for command in [
+        ConsoleCommand {
+            name: "help",
+            usage: "help",
+            help: "List the commands",
+            run: help_command,
+        },
+        ConsoleCommand {
+            name: "goto",
+            usage: "goto <home|new-puzzle|history|practice|learn>",
+            help: "Show a menu screen",
+            run: goto_command,
+        },
+        ConsoleCommand {
+            name: "stats",
+            usage: "stats",
+            help: "Show the states, entity count, and frame rate",
+            run: stats_command,
+        },
+    ] {
+        register_console_command(app, command);
+    }
+}
+
+/// Runs a command with the words typed after its name, returning what to print.
+pub type ConsoleCommandFn = fn(&mut World, &[&str]) -> Result<String, String>;
+
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    /// How to type the command, like `seed <seed>`
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub run: ConsoleCommandFn,
+}
+
+/// The commands the console can run. Plugins add their own with `register_console_command`.
+#[derive(Resource, Default)]
+pub struct ConsoleCommands(Vec<ConsoleCommand>);
+
+impl ConsoleCommands {
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.0.iter().find(|command| command.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ConsoleCommand> {
+        self.0.iter()
+    }
+}
+
+/// Adds a command to the console, replacing any command with the same name.
+pub fn register_console_command(app: &mut App, command: ConsoleCommand) {
+    let mut commands = app
+        .world_mut()
+        .get_resource_or_insert_with(ConsoleCommands::default);
+    commands.0.retain(|existing| existing.name != command.name);
+    commands.0.push(command);
+}
+
+/// Prints a line to the console, for commands whose output is ready after they return.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct ConsolePrint(pub String);
+
+/// The lines typed into the console and what they printed, plus the lines waiting to run.
+#[derive(Resource, Default)]
+struct ConsoleLog {
+    lines: VecDeque<String>,
+    pending: Vec<String>,
+}
+
+impl ConsoleLog {
+    fn push(&mut self, line: String) {
+        for line in line.lines() {
+            if self.lines.len() == CONSOLE_LOG_LINES {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.to_string());
+        }
+    }
+}
+
+#[derive(Component)]
+#[require(Node, ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRect)]
+struct Console;
+
+#[derive(Component)]
+struct ConsoleLogText;
+
+#[derive(Component)]
+struct ConsoleInput;
+
+fn console_setup(mut commands: Commands) {
+    commands.spawn((
+        Console,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.0)),
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        GlobalZIndex(20),
+        Visibility::Hidden,
+        children![
+            (
+                ConsoleLogText,
+                Text::new("Type help to list the commands"),
+                TextFont::from_font_size(16.0),
+                ThemedFontWeight::Regular,
+                ThemedTextColor,
+            ),
+            (
+                ConsoleInput,
+                text_input_bundle(TextInputBundleOptions {
+                    placeholder_text: "Enter a command".into(),
+                    text_font: TextFont::from_font_size(16.0),
+                    container_node: Node {
+                        width: Val::Percent(100.0),
+                        padding: UiRect::horizontal(Val::Px(6.0)),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            ),
+        ],
+    ));
+}
+
+// Show or hide the console with the backtick key, focusing its input while it's shown
+fn console_toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut console_query: Query<&mut Visibility, With<Console>>,
+    input_query: Query<Entity, With<ConsoleInput>>,
+    mut focused_entity: ResMut<FocusedEntity>,
+) {
+    if !keys.just_pressed(KeyCode::Backquote) {
+        return;
+    }
+    let (Ok(mut visibility), Ok(input)) = (console_query.single_mut(), input_query.single()) else {
+        return;
+    };
+    if *visibility == Visibility::Hidden {
+        *visibility = Visibility::Visible;
+        focused_entity.set(Some(input));
+    } else {
+        *visibility = Visibility::Hidden;
+        if focused_entity.current == Some(input) {
+            focused_entity.set(None);
+        }
+    }
+}
+
+// Take the typed line when Enter is pressed and clear the input. The text input also types the
+// backtick that toggles the console, so it's removed.
+fn console_submit_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    focused_entity: Res<FocusedEntity>,
+    mut input_query: Query<(Entity, &mut TextInputContainer, &Children), With<ConsoleInput>>,
+    mut text_query: Query<&mut Text>,
+    mut console_log: ResMut<ConsoleLog>,
+) {
+    let Ok((input, mut text_input, children)) = input_query.single_mut() else {
+        return;
+    };
+    let Ok(mut text) = text_query.get_mut(children[0]) else {
+        return;
+    };
+    if focused_entity.current != Some(input) {
+        return;
+    }
+    if text.0.contains(CONSOLE_TOGGLE_CHAR) {
+        text.0.retain(|c| c != CONSOLE_TOGGLE_CHAR);
+        text_input.is_empty = text.0.is_empty();
+    }
+    if keys.just_pressed(KeyCode::Enter) && !text_input.is_empty {
+        console_log.pending.push(std::mem::take(&mut text.0));
+        text_input.is_empty = true;
+    }
+}
+
+// Run the submitted lines, printing their output to the console
+fn console_run_system(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<ConsoleLog>().pending);
+    for line in pending {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            continue;
+        };
+        let run = world
+            .resource::<ConsoleCommands>()
+            .get(name)
+            .map(|command| command.run);
+        let output = match run {
+            Some(run) => run(world, args).unwrap_or_else(|err| format!("error: {err}")),
+            None => format!("error: there's no command named {name}. Type help to list them."),
+        };
+        let mut console_log = world.resource_mut::<ConsoleLog>();
+        console_log.push(format!("> {line}"));
+        if !output.is_empty() {
+            console_log.push(output);
+        }
+    }
+}
+
+fn console_print_system(
+    mut console_print_events: EventReader<ConsolePrint>,
+    mut console_log: ResMut<ConsoleLog>,
+) {
+    for ConsolePrint(line) in console_print_events.read() {
+        console_log.push(line.clone());
+    }
+}
+
+fn console_log_system(
+    console_log: Res<ConsoleLog>,
+    mut log_text_query: Query<&mut Text, With<ConsoleLogText>>,
+) {
+    if !console_log.is_changed() || console_log.lines.is_empty() {
+        return;
+    }
+    for mut text in &mut log_text_query {
+        text.0 = Vec::from_iter(console_log.lines.iter().cloned()).join("\n");
+    }
+}
+
+fn help_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    Ok(world
+        .resource::<ConsoleCommands>()
+        .iter()
+        .map(|command| format!("{} - {}", command.usage, command.help))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn goto_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let screen = match args {
+        ["home"] => MenuState::Home,
+        ["new-puzzle"] => MenuState::NewPuzzle,
+        ["history"] => MenuState::History,
+        ["practice"] => MenuState::Practice,
+        ["learn"] => MenuState::Learn,
+        _ => return Err("usage: goto <home|new-puzzle|history|practice|learn>".into()),
+    };
+    world
+        .resource_mut::<Navigator>()
+        .push(Screen::Menu(screen.clone()));
+    Ok(format!("Going to {screen:?}"))
+}
+
+fn stats_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let mut lines = vec![
+        format!("app state: {:?}", world.resource::<State<AppState>>().get()),
+        format!(
+            "menu state: {:?}",
+            world.resource::<State<MenuState>>().get()
+        ),
+        format!(
+            "game state: {:?}",
+            world.resource::<State<GameState>>().get()
+        ),
+        format!("nav state: {:?}", world.resource::<State<NavState>>().get()),
+        format!("entities: {}", world.entities().len()),
+    ];
+    if let Some(fps) = world
+        .get_resource::<DiagnosticsStore>()
+        .and_then(|diagnostics| diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS))
+        .and_then(Diagnostic::smoothed)
+    {
+        lines.push(format!("fps: {fps:.1}"));
+    }
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    plugins::{
+        game::{GameState, PuzzleType},
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    utility::share_code::ShareCode,
+    AppState, PuzzleSettings,
+};
+
+/// Keeps the screen being shown in the page's URL hash on the web, like `#game/classic/v1-1234`
+/// or `#menu/history`, and goes back to that screen when the page is loaded. Links can then be
+/// shared straight into a puzzle, and refreshing the page doesn't lose it. Nothing happens on
+/// other platforms, which have no URL.
+pub fn deep_link_plugin(app: &mut App) {
+    app.init_resource::<DeepLink>().add_systems(
+        Update,
+        (
+            deep_link_write_system.run_if(|deep_link: Res<DeepLink>| deep_link.restored),
+            deep_link_restore_system.run_if(in_state(AppState::Menu)),
+        )
+            .chain(),
+    );
+}
+
+/// Whether the screen from the URL the page was loaded with has been restored. The URL isn't
+/// changed before then so that the screen to restore isn't overwritten while the app loads.
+#[derive(Resource, Default)]
+struct DeepLink {
+    restored: bool,
+}
+
+/// A screen a link can go to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Route {
+    Menu(MenuState),
+    Game {
+        puzzle_type: PuzzleType,
+        share_code: ShareCode,
+    },
+}
+
+/// The menus that can be linked to, with their names in links. The solve details aren't
+/// included since they need a solve to be picked first.
+const MENU_ROUTES: [(MenuState, &str); 5] = [
+    (MenuState::Home, ""),
+    (MenuState::NewPuzzle, "new-puzzle"),
+    (MenuState::History, "history"),
+    (MenuState::Practice, "practice"),
+    (MenuState::Learn, "learn"),
+];
+
+impl Route {
+    /// Parses a URL hash, with or without its `#`. Returns `None` for hashes that aren't routes.
+    fn parse(hash: &str) -> Option<Self> {
+        let hash = hash.strip_prefix('#').unwrap_or(hash);
+        let mut segments = hash.split('/');
+        match segments.next()? {
+            "menu" => {
+                let name = segments.next().unwrap_or_default();
+                MENU_ROUTES
+                    .into_iter()
+                    .find(|(_, route_name)| *route_name == name)
+                    .map(|(menu_state, _)| Route::Menu(menu_state))
+            }
+            "game" => {
+                let slug = segments.next()?;
+                let puzzle_type =
+                    PuzzleType::iter().find(|puzzle_type| puzzle_type.slug() == slug)?;
+                let share_code = percent_decode(segments.next()?)?.parse().ok()?;
+                Some(Route::Game {
+                    puzzle_type,
+                    share_code,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the URL hash for the route, including its `#`.
+    fn hash(&self) -> String {
+        match self {
+            Route::Menu(menu_state) => {
+                let name = MENU_ROUTES
+                    .into_iter()
+                    .find(|(route_state, _)| route_state == menu_state)
+                    .map_or("", |(_, name)| name);
+                if name.is_empty() {
+                    "#menu".into()
+                } else {
+                    format!("#menu/{name}")
+                }
+            }
+            Route::Game {
+                puzzle_type,
+                share_code,
+            } => format!(
+                "#game/{}/{}",
+                puzzle_type.slug(),
+                percent_encode(&share_code.to_string())
+            ),
+        }
+    }
+}
+
+/// Escapes everything but the characters that are always safe in a URL, since seeds can be typed
+/// in with any characters.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Undoes `percent_encode`, returning `None` if an escape is malformed.
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut input = text.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            // Each escape is exactly two hex digits, so signs like the `+` that `from_str_radix`
+            // allows aren't accepted
+            let mut hex_digit = || char::from(input.next()?).to_digit(16);
+            let (high, low) = (hex_digit()?, hex_digit()?);
+            bytes.push((high << 4 | low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(target_family = "wasm")]
+fn read_hash() -> Option<String> {
+    web_sys::window()?.location().hash().ok()
+}
+
+#[cfg(target_family = "wasm")]
+fn write_hash(hash: &str) {
+    // Replacing the URL instead of setting the hash keeps every screen out of the back history
+    if let Some(window) = web_sys::window() {
+        if window.location().hash().ok().as_deref() != Some(hash) {
+            let _ = window.location().replace(hash);
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_hash() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_hash(_hash: &str) {}
+
+// Put the screen being shown in the URL whenever it changes
+fn deep_link_write_system(
+    app_state: Res<State<AppState>>,
+    menu_state: Res<State<MenuState>>,
+    game_state: Res<State<GameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+) {
+    if !app_state.is_changed() && !menu_state.is_changed() && !game_state.is_changed() {
+        return;
+    }
+    let route = match (app_state.get(), menu_state.get()) {
+        (AppState::Game, _) => Route::Game {
+            puzzle_type: puzzle_settings.puzzle_type,
+            share_code: ShareCode::new(puzzle_settings.seed.clone()),
+        },
+        (AppState::Menu, MenuState::SolveDetail) => Route::Menu(MenuState::History),
+        (AppState::Menu, MenuState::Disabled) | (AppState::Loading, _) => return,
+        (AppState::Menu, menu_state) => Route::Menu(menu_state.clone()),
+    };
+    write_hash(&route.hash());
+}
+
+// Go to the screen from the URL once the menus are first shown
+fn deep_link_restore_system(
+    mut deep_link: ResMut<DeepLink>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut navigator: ResMut<Navigator>,
+) {
+    if deep_link.restored {
+        return;
+    }
+    deep_link.restored = true;
+    let Some(hash) = read_hash() else {
+        return;
+    };
+    match Route::parse(&hash) {
+        Some(Route::Menu(menu_state)) => navigator.replace(Screen::Menu(menu_state)),
+        Some(Route::Game {
+            puzzle_type,
+            share_code,
+        }) => {
+            info!("Opening the linked {puzzle_type} puzzle {share_code}");
+            puzzle_settings.puzzle_type = puzzle_type;
+            puzzle_settings.seed = share_code.seed;
+            puzzle_settings.remix = None;
+            navigator.replace(Screen::Game);
+        }
+        None if !hash.is_empty() => warn!("Couldn't open the link {hash}"),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_round_trip() {
+        let game = Route::Game {
+            puzzle_type: PuzzleType::Classic,
+            share_code: ShareCode::new("my seed/ü%#"),
+        };
+        let menus = MENU_ROUTES
+            .into_iter()
+            .map(|(menu_state, _)| Route::Menu(menu_state));
+        for route in menus.chain([game]) {
+            let hash = route.hash();
+            assert_eq!(Route::parse(&hash), Some(route.clone()), "{hash}");
+            assert_eq!(Route::parse(&hash[1..]), Some(route), "{hash}");
+        }
+        assert_eq!(Route::parse("#menu"), Some(Route::Menu(MenuState::Home)));
+    }
+
+    #[test]
+    fn test_unknown_routes() {
+        for hash in [
+            "",
+            "#",
+            "#settings",
+            "#menu/settings",
+            "#menu/solve-detail",
+            "#game",
+            "#game/unknown/v1-1234",
+            "#game/classic",
+            "#game/classic/1234",
+            "#game/classic/v1-12%4",
+        ] {
+            assert_eq!(Route::parse(hash), None, "{hash}");
+        }
+    }
+
+    #[test]
+    fn test_percent_round_trip() {
+        let text = "A seed/with ü, 100% & #";
+        let encoded = percent_encode(text);
+        assert_eq!(encoded, "A%20seed%2Fwith%20%C3%BC%2C%20100%25%20%26%20%23");
+        assert_eq!(percent_decode(&encoded).as_deref(), Some(text));
+        assert_eq!(percent_decode("a-b_c.d~e").as_deref(), Some("a-b_c.d~e"));
+        // Escapes can be lowercase even though they're written in uppercase
+        assert_eq!(percent_decode("%2f").as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn test_percent_decode_malformed() {
+        for text in ["%", "%4", "abc%", "%G1", "%-1", "%+F", "%FF", "%C3"] {
+            assert_eq!(percent_decode(text), None, "{text}");
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::system::SystemParam,
+    log::{
+        tracing::{
+            field::{Field, Visit},
+            span, Event, Subscriber,
+        },
+        tracing_subscriber::{layer::Context, registry::LookupSpan, Layer},
+        BoxedLayer,
+    },
+    prelude::*,
+};
+
+use crate::{
+    plugins::{
+        common::{
+            clipboard::{clipboard_plugin, ClipboardResource},
+            export::export_file,
+            rng::RngService,
+        },
+        game::{classic::ClassicGameState, solve_history::SolveMove, GameState},
+        menu::MenuState,
+        nav::NavState,
+    },
+    AppState, PuzzleSettings, APP_TITLE,
+};
+
+/// The maximum number of log lines kept for the diagnostics report.
+const RECENT_LOGS_CAPACITY: usize = 100;
+
+/// The name the diagnostics report is saved under.
+const DIAGNOSTICS_FILE_NAME: &str = "sudoku-diagnostics.txt";
+
+/// Collects a diagnostics report (version, platform, puzzle settings, the game being played,
+/// states, frame times, span timings in debug builds, and recent logs). Ctrl+Shift+D copies it to
+/// the clipboard, and Ctrl+Alt+Shift+D saves it to a file.
+pub fn diagnostics_plugin(app: &mut App) {
+    if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+    }
+    if !app.world().contains_resource::<ClipboardResource>() {
+        app.add_plugins(clipboard_plugin);
+    }
+    app.init_resource::<RecentLogs>()
+        .add_systems(Update, diagnostics_export_system);
+    #[cfg(debug_assertions)]
+    crate::plugins::console::register_console_command(
+        app,
+        crate::plugins::console::ConsoleCommand {
+            name: "diagnostics",
+            usage: "diagnostics [copy|save]",
+            help: "Copy the diagnostics report to the clipboard, or save it to a file",
+            run: diagnostics_command,
+        },
+    );
+}
+
+/// A ring buffer of the most recent log lines, shared with the log layer. Only tracing events
+/// (`info!`, `warn!`, and so on) are recorded, so messages for the player are logged with those
+/// instead of being printed.
+#[derive(Resource, Clone, Default)]
+pub struct RecentLogs(Arc<Mutex<VecDeque<String>>>);
+
+impl RecentLogs {
+    fn push(&self, line: String) {
+        let mut lines = self.0.lock().unwrap();
+        if lines.len() == RECENT_LOGS_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns a copy of the recorded log lines, oldest first.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// A log layer that records every event into `RecentLogs`.
+struct RecentLogsLayer(RecentLogs);
+
+/// Formats the fields of a log event into a single line.
+#[derive(Default)]
+struct LogLineVisitor(String);
+
+impl Visit for LogLineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?} ");
+        } else {
+            let _ = write!(self.0, "{}={value:?} ", field.name());
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = LogLineVisitor::default();
+        event.record(&mut visitor);
+        self.0.push(format!(
+            "{} {}: {}",
+            metadata.level(),
+            metadata.target(),
+            visitor.0.trim_end()
+        ));
+    }
+}
+
+/// How long the spans with a name took, over every time one was entered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SpanTiming {
+    pub count: u32,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl std::fmt::Display for SpanTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} times, {:.2}ms total, {:.2}ms max",
+            self.count,
+            self.total.as_secs_f64() * 1000.0,
+            self.max.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+/// The timings of the spans around generating and solving puzzles (like `fill`, `minimize`,
+/// `uniqueness_check`, and `hint_search`), by span name, shared with the log layer. They're only
+/// recorded in debug builds.
+#[derive(Resource, Clone, Default)]
+pub struct SpanTimings(Arc<Mutex<BTreeMap<&'static str, SpanTiming>>>);
+
+impl SpanTimings {
+    fn record(&self, name: &'static str, elapsed: Duration) {
+        let mut timings = self.0.lock().unwrap();
+        let timing = timings.entry(name).or_default();
+        timing.count += 1;
+        timing.total += elapsed;
+        timing.max = timing.max.max(elapsed);
+    }
+
+    /// Returns a copy of the recorded timings, sorted by span name.
+    #[must_use]
+    pub fn timings(&self) -> Vec<(&'static str, SpanTiming)> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, timing)| (*name, *timing))
+            .collect()
+    }
+}
+
+/// A log layer that times every span it sees into `SpanTimings`.
+struct SpanTimingsLayer(SpanTimings);
+
+/// When the span was entered, which is kept in the span's extensions until it's exited.
+struct SpanEntered(web_time::Instant);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanTimingsLayer {
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut()
+                .replace(SpanEntered(web_time::Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let entered = span.extensions_mut().remove::<SpanEntered>();
+        if let Some(SpanEntered(entered)) = entered {
+            self.0.record(span.name(), entered.elapsed());
+        }
+    }
+}
+
+/// Used as the `LogPlugin` custom layer so that recent logs, and span timings in debug builds, can
+/// be included in the report.
+pub fn diagnostics_log_layer(app: &mut App) -> Option<BoxedLayer> {
+    let recent_logs = RecentLogs::default();
+    app.insert_resource(recent_logs.clone());
+    let layer = RecentLogsLayer(recent_logs);
+    if cfg!(debug_assertions) {
+        let span_timings = SpanTimings::default();
+        app.insert_resource(span_timings.clone());
+        return Some(Box::new(layer.and_then(SpanTimingsLayer(span_timings))));
+    }
+    Some(Box::new(layer))
+}
+
+/// Everything included in a diagnostics report.
+pub struct DiagnosticsReport {
+    pub puzzle_type: String,
+    pub seed: String,
+    /// The seed of the session's `RngService`, for replaying the session
+    pub rng_session_seed: Option<String>,
+    /// The puzzle's grid, as a grid string (`None` outside a classic game)
+    pub givens: Option<String>,
+    /// The player's entries, as a progress string (`None` outside a classic game)
+    pub progress: Option<String>,
+    /// The entries made so far, in order
+    pub moves: Vec<SolveMove>,
+    pub states: Vec<(&'static str, String)>,
+    pub fps: Option<f64>,
+    pub frame_time_ms: Option<f64>,
+    /// Empty unless span timings were recorded
+    pub span_timings: Vec<(&'static str, SpanTiming)>,
+    pub logs: Vec<String>,
+}
+
+impl std::fmt::Display for DiagnosticsReport {
+    /// Formats the report as plain text that can be pasted into a bug report.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{APP_TITLE} diagnostics")?;
+        writeln!(f, "version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(
+            f,
+            "platform: {} {} ({})",
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            std::env::consts::FAMILY
+        )?;
+        writeln!(
+            f,
+            "build: {}",
+            if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+        )?;
+        writeln!(f, "puzzle type: {}", self.puzzle_type)?;
+        writeln!(f, "seed: {}", self.seed)?;
+        if let Some(rng_session_seed) = &self.rng_session_seed {
+            writeln!(f, "rng session seed: {rng_session_seed}")?;
+        }
+        if let Some(givens) = &self.givens {
+            writeln!(f, "givens:")?;
+            for line in givens.lines() {
+                writeln!(f, "  {line}")?;
+            }
+        }
+        if let Some(progress) = &self.progress {
+            writeln!(f, "progress: {progress}")?;
+        }
+        if !self.moves.is_empty() {
+            writeln!(f, "moves:")?;
+            for ((row, col), val) in &self.moves {
+                match val {
+                    Some(val) => writeln!(f, "  r{}c{} = {val}", row + 1, col + 1)?,
+                    None => writeln!(f, "  r{}c{} erased", row + 1, col + 1)?,
+                }
+            }
+        }
+        for (name, state) in &self.states {
+            writeln!(f, "{name}: {state}")?;
+        }
+        match (self.fps, self.frame_time_ms) {
+            (Some(fps), Some(frame_time)) => {
+                writeln!(f, "frame time: {frame_time:.2}ms ({fps:.1} fps)")?;
+            }
+            _ => writeln!(f, "frame time: unavailable")?,
+        }
+        if !self.span_timings.is_empty() {
+            writeln!(f, "span timings:")?;
+            for (name, timing) in &self.span_timings {
+                writeln!(f, "  {name}: {timing}")?;
+            }
+        }
+        writeln!(f, "recent logs:")?;
+        for line in &self.logs {
+            writeln!(f, "  {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything a diagnostics report is collected from.
+#[derive(SystemParam)]
+struct DiagnosticsInputs<'w> {
+    puzzle_settings: Res<'w, PuzzleSettings>,
+    game: Option<Res<'w, ClassicGameState>>,
+    app_state: Res<'w, State<AppState>>,
+    menu_state: Res<'w, State<MenuState>>,
+    game_state: Res<'w, State<GameState>>,
+    nav_state: Res<'w, State<NavState>>,
+    diagnostics: Res<'w, DiagnosticsStore>,
+    recent_logs: Res<'w, RecentLogs>,
+    span_timings: Option<Res<'w, SpanTimings>>,
+    rng_service: Option<Res<'w, RngService>>,
+}
+
+impl DiagnosticsInputs<'_> {
+    fn report(&self) -> DiagnosticsReport {
+        let smoothed = |path| {
+            self.diagnostics
+                .get(path)
+                .and_then(bevy::diagnostic::Diagnostic::smoothed)
+        };
+        DiagnosticsReport {
+            puzzle_type: self.puzzle_settings.puzzle_type.to_string(),
+            seed: self.puzzle_settings.seed.clone(),
+            rng_session_seed: self
+                .rng_service
+                .as_ref()
+                .map(|rng_service| rng_service.session_seed().to_string()),
+            givens: self
+                .game
+                .as_ref()
+                .map(|game| game.grid.givens().to_string()),
+            progress: self.game.as_ref().map(|game| game.grid.progress_string()),
+            moves: self
+                .game
+                .as_ref()
+                .map_or_else(Vec::new, |game| game.moves.clone()),
+            states: vec![
+                ("app state", format!("{:?}", self.app_state.get())),
+                ("menu state", format!("{:?}", self.menu_state.get())),
+                ("game state", format!("{:?}", self.game_state.get())),
+                ("nav state", format!("{:?}", self.nav_state.get())),
+            ],
+            fps: smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+            frame_time_ms: smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            span_timings: self
+                .span_timings
+                .as_ref()
+                .map_or_else(Vec::new, |span_timings| span_timings.timings()),
+            logs: self.recent_logs.lines(),
+        }
+    }
+}
+
+// Copy the report with Ctrl+Shift+D, or save it to a file with Ctrl+Alt+Shift+D
+fn diagnostics_export_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    inputs: DiagnosticsInputs,
+    mut clipboard_resource: ResMut<ClipboardResource>,
+) {
+    let control_keys = [
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ];
+    if !(keys.just_pressed(KeyCode::KeyD)
+        && keys.any_pressed(control_keys)
+        && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]))
+    {
+        return;
+    }
+
+    let report = inputs.report().to_string();
+    if keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        match export_file(DIAGNOSTICS_FILE_NAME, &report) {
+            Ok(path) => info!("Saved the diagnostics report to {path}"),
+            Err(err) => warn!("Couldn't save the diagnostics report: {err}"),
+        }
+    } else {
+        clipboard_resource.copy(report);
+        info!("Copied diagnostics report to the clipboard");
+    }
+}
+
+#[cfg(debug_assertions)]
+fn diagnostics_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let save = match args {
+        [] | ["copy"] => false,
+        ["save"] => true,
+        _ => return Err("usage: diagnostics [copy|save]".into()),
+    };
+    let mut inputs = bevy::ecs::system::SystemState::<DiagnosticsInputs>::new(world);
+    let report = inputs.get(world).report().to_string();
+    if save {
+        let path = export_file(DIAGNOSTICS_FILE_NAME, &report)?;
+        Ok(format!("Saved the diagnostics report to {path}"))
+    } else {
+        world.resource_mut::<ClipboardResource>().copy(report);
+        Ok("Copied the diagnostics report to the clipboard".into())
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component, plugins::nav::NavState, puzzles::arrow::ArrowPuzzle, PuzzleSettings,
+};
+
+use super::{cache::PuzzleCache, GameState, GameSystems, PuzzleType};
+
+pub fn arrow_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing(PuzzleType::Arrow)), arrow_setup)
+        .add_systems(
+            OnExit(GameState::Playing(PuzzleType::Arrow)),
+            despawn_component::<ArrowContainer>,
+        )
+        .add_systems(
+            Update,
+            arrow_action_system
+                .in_set(GameSystems::ApplyEdits)
+                .run_if(in_state(GameState::Playing(PuzzleType::Arrow))),
+        );
+}
+
+#[derive(Component)]
+struct ArrowContainer;
+
+// Generate and spawn the board
+fn arrow_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+) {
+    nav_state.set(NavState::Pause);
+    info!("Setting up arrow puzzle!");
+    info!("Seed is {:}", puzzle_settings.seed);
+    let (puzzle, _solution) = puzzle_cache.get_or_generate(&puzzle_settings, || {
+        let puzzle = ArrowPuzzle::from_seed(puzzle_settings.seed.clone());
+        let solution = puzzle.solution().unwrap();
+        (puzzle, solution)
+    });
+    info!("Finished with {} clues:", puzzle.num_clues());
+    info!("{puzzle:}");
+}
+
+fn arrow_action_system() {}
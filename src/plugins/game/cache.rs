@@ -0,0 +1,111 @@
+use std::{any::Any, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    grids::classic::GridTransform,
+    puzzles::{classic::ClassicRules, hint::Technique},
+    utility::{lru_cache::LruCache, share_code::GENERATOR_VERSION},
+    PuzzleSettings,
+};
+
+use super::PuzzleType;
+
+/// How many generated puzzles are kept in memory.
+const PUZZLE_CACHE_CAPACITY: usize = 8;
+
+pub fn puzzle_cache_plugin(app: &mut App) {
+    app.init_resource::<PuzzleCache>();
+}
+
+/// Everything that determines which puzzle gets generated.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PuzzleCacheKey {
+    pub seed: String,
+    pub puzzle_type: PuzzleType,
+    pub rules: ClassicRules,
+    pub practice: Option<Technique>,
+    pub remix: Option<GridTransform>,
+    pub challenge: u8,
+}
+
+impl From<&PuzzleSettings> for PuzzleCacheKey {
+    fn from(settings: &PuzzleSettings) -> Self {
+        Self {
+            seed: settings.seed.clone(),
+            puzzle_type: settings.puzzle_type,
+            rules: settings.rules,
+            practice: settings.practice,
+            remix: settings.remix,
+            challenge: settings.challenge,
+        }
+    }
+}
+
+struct PuzzleCacheEntry {
+    /// The `GENERATOR_VERSION` the puzzle was generated with
+    version: u8,
+    generated: Arc<dyn Any + Send + Sync>,
+}
+
+/// Keeps recently generated puzzles (and their solutions) so that starting the same seed again
+/// doesn't redo the generation.
+#[derive(Resource)]
+pub struct PuzzleCache {
+    entries: LruCache<PuzzleCacheKey, PuzzleCacheEntry>,
+}
+
+impl Default for PuzzleCache {
+    fn default() -> Self {
+        Self {
+            entries: LruCache::new(PUZZLE_CACHE_CAPACITY),
+        }
+    }
+}
+
+impl PuzzleCache {
+    /// Returns the cached puzzle for some settings, or generates and caches it. Each puzzle type
+    /// stores its own type of value, usually the puzzle along with its solution.
+    pub fn get_or_generate<P, F>(&mut self, settings: &PuzzleSettings, generate: F) -> P
+    where
+        P: Clone + Send + Sync + 'static,
+        F: FnOnce() -> P,
+    {
+        let key = PuzzleCacheKey::from(settings);
+        // Drop anything generated by another version before looking up the entry
+        self.entries
+            .retain(|_, entry| entry.version == GENERATOR_VERSION);
+        if let Some(generated) = self
+            .entries
+            .get(&key)
+            .and_then(|entry| entry.generated.downcast_ref::<P>())
+        {
+            debug!("Using cached {} puzzle", settings.puzzle_type);
+            return generated.clone();
+        }
+
+        let generated = generate();
+        self.insert(settings, generated.clone());
+        generated
+    }
+
+    /// Caches a puzzle for some settings, so that starting them plays it instead of generating
+    /// one, e.g. for a puzzle that's written out by hand.
+    pub fn insert<P>(&mut self, settings: &PuzzleSettings, generated: P)
+    where
+        P: Send + Sync + 'static,
+    {
+        self.entries.insert(
+            PuzzleCacheKey::from(settings),
+            PuzzleCacheEntry {
+                version: GENERATOR_VERSION,
+                generated: Arc::new(generated),
+            },
+        );
+    }
+
+    /// Removes every cached puzzle.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -1,10 +1,55 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::{
-    despawn_component, plugins::nav::NavState, puzzles::classic::ClassicPuzzle, PuzzleSettings,
+    despawn_component,
+    grids::{
+        classic::ClassicGrid,
+        classic_state::{ClassicGridState, GhostPlacement, NakedPair, PencilMark},
+    },
+    plugins::{
+        common::{clipboard::ClipboardResource, theme::style::StyleSheet},
+        nav::NavState,
+    },
+    puzzles::{
+        classic::ClassicPuzzle,
+        conflict::{classic_conflicts, Conflict},
+        hint::{grade, Region},
+        progress::completed_units,
+    },
+    remove_resource,
+    utility::{bitset::BitSet16, share_code::ShareCode, week::unix_secs_now},
+    PuzzleSettings,
 };
 
-use super::{GameState, PuzzleType};
+use super::{
+    cache::PuzzleCache,
+    classic_grid_rules,
+    completion::SolvedPuzzle,
+    gameplay::{milestone, GameplayEvent},
+    hint::{hint_limit, hint_text, HintPress, HintProgress, HintSettings, HintStats},
+    input::{ArmedDigit, DigitFirstSettings, InputAction},
+    jump::{BoardCellKinds, SelectedCell},
+    layout::LayoutSettings,
+    marathon::Marathon,
+    outcome::{FailReason, GameOutcome},
+    panel::{
+        board_and_side_panel_bundle, digit_counts_text, hint_button_text, progress_text,
+        DigitCountsText, HintButtonText, ProgressText, ShareDialogOpenButton,
+    },
+    playable_classic_grid_puzzle,
+    practice::PracticeCompletions,
+    progress::UnitCompleted,
+    sandbox::Sandbox,
+    save_slots::{save_dialog_bundle, PendingRestore, SaveSlot, SaveSlotDialog, SaveSlots},
+    shake::EntryRejected,
+    share::{share_dialog_bundle, ShareContents, ShareDialog},
+    solve_history::{SolveHistory, SolveMove, SolveOutcome, SolveRecord},
+    timer::{GameTimer, BLITZ_DURATION},
+    tutorial::ActiveTutorial,
+    GameState, GameSystems, PuzzleType,
+};
 
 pub fn classic_plugin(app: &mut App) {
     app.add_systems(
@@ -13,28 +58,1194 @@ pub fn classic_plugin(app: &mut App) {
     )
     .add_systems(
         OnExit(GameState::Playing(PuzzleType::Classic)),
-        despawn_component::<ClassicContainer>,
+        (
+            despawn_component::<ClassicContainer>,
+            remove_resource::<ClassicGameState>,
+            remove_resource::<ClassicSandbox>,
+            remove_resource::<GameTimer>,
+            remove_resource::<HintProgress>,
+            remove_resource::<CompletedUnits>,
+            remove_resource::<ClassicConflicts>,
+            remove_resource::<ClassicGhostHighlights>,
+            remove_resource::<ClassicPairHighlights>,
+            remove_resource::<BoardCellKinds>,
+        ),
     )
     .add_systems(
         Update,
-        (classic_game_action_system).run_if(in_state(GameState::Playing(PuzzleType::Classic))),
+        (
+            (
+                classic_sandbox_system.before(classic_game_action_system),
+                classic_game_action_system,
+                copy_board_system,
+                hint_system,
+                check_system,
+                share_dialog_open_system,
+                save_dialog_open_system,
+            )
+                .in_set(GameSystems::ApplyEdits),
+            (
+                classic_outcome_system,
+                (solved_system, solve_history_system).after(classic_outcome_system),
+                unit_completion_system,
+                milestone_system,
+                conflict_system,
+                ghost_highlight_system,
+                pair_highlight_system,
+            )
+                .in_set(GameSystems::DeriveState),
+            (
+                digit_counts_system,
+                progress_text_system,
+                hint_button_text_system,
+            )
+                .in_set(GameSystems::Render),
+        )
+            .run_if(in_state(GameState::Playing(PuzzleType::Classic))),
     );
+    #[cfg(debug_assertions)]
+    {
+        app.add_systems(
+            Update,
+            console_commands::solution_count_system
+                .run_if(resource_exists::<console_commands::PendingSolutionCount>),
+        )
+        .add_systems(
+            OnExit(GameState::Playing(PuzzleType::Classic)),
+            remove_resource::<console_commands::ScrubbedEdits>,
+        );
+        for command in console_commands::CLASSIC_CONSOLE_COMMANDS {
+            crate::plugins::console::register_console_command(app, command);
+        }
+    }
 }
 
 #[derive(Component)]
 struct ClassicContainer;
 
+/// How much time checking the entries adds to the game.
+const CHECK_PENALTY: Duration = Duration::from_secs(10);
+
+/// The rows, columns, and boxes that were already filled in correctly.
+#[derive(Resource)]
+struct CompletedUnits(Vec<Region>);
+
+/// How the number armed in digit-first mode fits in each cell, if one is armed and the assist
+/// level highlights them.
+#[derive(Resource, Default)]
+struct ClassicGhostHighlights(Option<(u8, [[GhostPlacement; 9]; 9])>);
+
+/// The naked pairs in the center marks, if the assist level highlights them, along with the marks
+/// they were found in so that they're only looked for again when the marks change.
+#[derive(Resource, Default, PartialEq)]
+struct ClassicPairHighlights {
+    marks: [[BitSet16; 9]; 9],
+    pairs: Vec<NakedPair>,
+}
+
+/// The rules the filled cells break, including the variant's, for highlighting errors.
+#[derive(Resource, Default)]
+struct ClassicConflicts(Vec<Conflict>);
+
+/// The grid being played, which rejects entries in given cells, and its solution for checking
+/// entries.
+#[derive(Resource)]
+pub struct ClassicGameState {
+    pub grid: ClassicGridState,
+    pub solution: ClassicGrid,
+    /// The entries made so far, for replaying the solve
+    pub moves: Vec<SolveMove>,
+    /// How many times the entries have been checked
+    pub checks: u32,
+}
+
+/// The puzzle as it was when the sandbox was opened, which it goes back to if the sandbox's
+/// entries are discarded.
+struct ClassicSandboxSnapshot {
+    grid: ClassicGridState,
+    moves: usize,
+    checks: u32,
+}
+
+/// The snapshot taken when the sandbox was opened, while it's open.
+#[derive(Resource, Default)]
+struct ClassicSandbox(Option<ClassicSandboxSnapshot>);
+
 // Generate and spawn the board
 fn classic_puzzle_setup(
     mut nav_state: ResMut<NextState<NavState>>,
     puzzle_settings: Res<PuzzleSettings>,
+    mut pending_restore: ResMut<PendingRestore>,
+    layout_settings: Res<LayoutSettings>,
+    style_sheet: Res<StyleSheet>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+    mut commands: Commands,
 ) {
     nav_state.set(NavState::Pause);
-    println!("Setting up classic puzzle!");
-    println!("Seed is {:}", puzzle_settings.seed);
-    let puzzle = ClassicPuzzle::from_seed(puzzle_settings.seed.clone());
-    println!("Finished:");
-    println!("{puzzle:}");
+    info!("Setting up classic puzzle!");
+    info!("Seed is {:}", puzzle_settings.seed);
+    let (puzzle, solution) = puzzle_cache.get_or_generate(&puzzle_settings, || {
+        playable_classic_grid_puzzle(&puzzle_settings)
+    });
+    info!("Finished:");
+    info!("{puzzle:}");
+    let mut grid = ClassicGridState::from(*puzzle.grid());
+    let restored = pending_restore.take_for(&puzzle_settings);
+    if let Some(slot) = &restored {
+        if let Err(err) = grid.apply_progress(&slot.progress) {
+            warn!("Couldn't pick up the saved game: {err}");
+        }
+        grid.set_mistakes(slot.mistakes);
+        commands.insert_resource(SelectedCell(slot.selected));
+    }
+    match restored.as_ref().and_then(|slot| slot.marks.as_ref()) {
+        Some(marks) => grid.apply_marks(marks),
+        None if puzzle_settings.fill_candidates
+            || puzzle_settings.assist_level.assists().auto_candidates =>
+        {
+            grid.fill_candidates();
+        }
+        None => {}
+    }
+    commands.insert_resource(BoardCellKinds(std::array::from_fn(|row| {
+        std::array::from_fn(|col| grid.cell_kind((row as u8, col as u8)))
+    })));
+    commands.insert_resource(CompletedUnits(completed_units(grid.grid(), &solution)));
+    commands.insert_resource(ClassicConflicts::default());
+    commands.init_resource::<ClassicGhostHighlights>();
+    commands.init_resource::<ClassicPairHighlights>();
+    commands.init_resource::<ClassicSandbox>();
+    commands.insert_resource(ClassicGameState {
+        grid,
+        solution,
+        moves: Vec::new(),
+        checks: restored.as_ref().map_or(0, |slot| slot.checks),
+    });
+    commands.spawn((
+        ClassicContainer,
+        board_and_side_panel_bundle((), &puzzle_settings.seed, &layout_settings, &style_sheet),
+    ));
+    let hint_limit = hint_limit(grade(&puzzle).difficulty);
+    commands.insert_resource(match &restored {
+        Some(slot) => HintProgress::resumed(hint_limit, slot.hints),
+        None => HintProgress::limited(hint_limit),
+    });
+    commands.insert_resource(match restored {
+        Some(slot) => GameTimer::resumed(slot.elapsed),
+        None if puzzle_settings.blitz => GameTimer::countdown(BLITZ_DURATION),
+        None => GameTimer::default(),
+    });
+}
+
+// Enter or erase numbers in the selected cell, or in digit-first mode, enter the armed number in
+// each cell that's selected. Entries in given cells are rejected, as are numbers that clash with a
+// peer in strict mode. Pencil marks are toggled in the selected cell, and sweeps change the whole
+// board in one step.
+#[allow(clippy::too_many_arguments)]
+fn classic_game_action_system(
+    mut input_actions: EventReader<InputAction>,
+    selected_cell: Res<SelectedCell>,
+    digit_first_settings: Res<DigitFirstSettings>,
+    armed_digit: Res<ArmedDigit>,
+    game_state: Option<ResMut<ClassicGameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut entry_rejected_events: EventWriter<EntryRejected>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let Some(mut game_state) = game_state else {
+        return;
+    };
+    let ClassicGameState {
+        grid,
+        solution,
+        moves,
+        ..
+    } = &mut *game_state;
+    for action in input_actions.read() {
+        match *action {
+            InputAction::ClearMarks => grid.clear_marks(),
+            InputAction::FillMarks => grid.fill_candidates(),
+            InputAction::RemoveMarks(val) => grid.remove_marks(val),
+            InputAction::ToggleMark(kind, val) => {
+                if let Some(position) = selected_cell.0 {
+                    if let Err(err) = grid.toggle_mark(position, kind, val) {
+                        info!("{err}");
+                        entry_rejected_events.write(EntryRejected(position));
+                    }
+                }
+            }
+            _ => {}
+        }
+        let (position, val) = match (*action, digit_first_settings.enabled) {
+            (InputAction::Digit(val), false) => (selected_cell.0, Some(val)),
+            (InputAction::Select(position), true) if armed_digit.0.is_some() => {
+                (Some(position), armed_digit.0)
+            }
+            (InputAction::Erase, _) => (selected_cell.0, None),
+            _ => continue,
+        };
+        let Some(position) = position else {
+            continue;
+        };
+        let result = match val {
+            Some(val) => {
+                let entered = if puzzle_settings.strict_entry {
+                    grid.enter_strict(position, val, solution)
+                } else {
+                    grid.enter(position, val, solution)
+                };
+                entered.map(|correct| {
+                    if correct && puzzle_settings.assist_level.assists().smart_erase {
+                        grid.erase_peer_marks(position, val);
+                    }
+                    Some(correct)
+                })
+            }
+            None => grid.set(position, None).map(|()| None),
+        };
+        match result {
+            Ok(correct) => {
+                moves.push((position, val));
+                gameplay_events.write(GameplayEvent::Placed {
+                    position,
+                    val,
+                    correct,
+                });
+            }
+            Err(err) => {
+                info!("{err}");
+                entry_rejected_events.write(EntryRejected(position));
+            }
+        }
+    }
+}
+
+// Open the sandbox with a snapshot of the puzzle, and close it by keeping its entries or going back
+// to the snapshot
+fn classic_sandbox_system(
+    mut input_actions: EventReader<InputAction>,
+    game_state: Option<ResMut<ClassicGameState>>,
+    classic_sandbox: Option<ResMut<ClassicSandbox>>,
+    mut sandbox: ResMut<Sandbox>,
+) {
+    let (Some(mut game_state), Some(mut classic_sandbox)) = (game_state, classic_sandbox) else {
+        return;
+    };
+    for action in input_actions.read() {
+        match (action, classic_sandbox.0.take()) {
+            (InputAction::OpenSandbox, None) => {
+                classic_sandbox.0 = Some(ClassicSandboxSnapshot {
+                    grid: game_state.grid,
+                    moves: game_state.moves.len(),
+                    checks: game_state.checks,
+                });
+            }
+            (InputAction::KeepSandbox, Some(_)) => {}
+            (InputAction::DiscardSandbox, Some(snapshot)) => {
+                game_state.grid = snapshot.grid;
+                game_state.moves.truncate(snapshot.moves);
+                game_state.checks = snapshot.checks;
+            }
+            (_, snapshot) => classic_sandbox.0 = snapshot,
+        }
+    }
+    sandbox.set_if_neq(Sandbox {
+        open: classic_sandbox.0.is_some(),
+    });
+}
+
+// End the puzzle once the grid matches the solution, the player has made too many mistakes (if the
+// mistake limit is on and the sandbox is closed), or a blitz game's countdown runs out
+fn classic_outcome_system(
+    game_state: Option<Res<ClassicGameState>>,
+    game_timer: Option<Res<GameTimer>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    sandbox: Res<Sandbox>,
+    mut outcomes: EventWriter<GameOutcome>,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    let score = game_state.grid.correct_entries(&game_state.solution);
+    if game_state.is_changed() && *game_state.grid.grid() == game_state.solution {
+        let time = game_timer.map_or(Duration::ZERO, |game_timer| game_timer.elapsed());
+        outcomes.write(GameOutcome::Completed { time, score });
+    } else if game_state.is_changed()
+        && !sandbox.open
+        && puzzle_settings
+            .mistake_limit
+            .is_some_and(|mistake_limit| game_state.grid.mistakes() >= mistake_limit)
+    {
+        info!("The solution was:");
+        info!("{:}", game_state.solution);
+        outcomes.write(GameOutcome::Failed {
+            reason: FailReason::TooManyMistakes,
+        });
+    } else if game_timer
+        .is_some_and(|game_timer| game_timer.is_changed() && game_timer.is_finished())
+    {
+        outcomes.write(GameOutcome::Failed {
+            reason: FailReason::TimeUp { score },
+        });
+    }
+}
+
+// Show the completion screen once the puzzle is completed, counting it if it was a practice
+// puzzle, or show the split between the puzzles of a marathon
+#[allow(clippy::too_many_arguments)]
+fn solved_system(
+    game_state: Option<Res<ClassicGameState>>,
+    hint_progress: Option<Res<HintProgress>>,
+    marathon: Option<ResMut<Marathon>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut practice_completions: ResMut<PracticeCompletions>,
+    mut outcomes: EventReader<GameOutcome>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    let Some(time) = outcomes.read().find_map(|outcome| match outcome {
+        GameOutcome::Completed { time, .. } => Some(*time),
+        _ => None,
+    }) else {
+        return;
+    };
+    info!("Solved!");
+    if let Some(technique) = puzzle_settings.practice {
+        practice_completions.complete(technique);
+    }
+    if let Some(mut marathon) = marathon {
+        marathon.splits.push(time);
+        next_game_state.set(GameState::MarathonSplit);
+    } else {
+        commands.insert_resource(SolvedPuzzle {
+            time,
+            givens: game_state.grid.givens(),
+            grid: game_state.solution,
+            hints: hint_progress
+                .map_or_else(HintStats::default, |hint_progress| hint_progress.stats()),
+        });
+        next_game_state.set(GameState::Solved);
+    }
+}
+
+// Add the puzzle to the solve history once it's completed or failed, unless it's the tutorial's
+#[allow(clippy::too_many_arguments)]
+fn solve_history_system(
+    game_state: Option<Res<ClassicGameState>>,
+    hint_progress: Option<Res<HintProgress>>,
+    game_timer: Option<Res<GameTimer>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    active_tutorial: Res<ActiveTutorial>,
+    mut outcomes: EventReader<GameOutcome>,
+    mut solve_history: ResMut<SolveHistory>,
+) {
+    let Some(game_state) = game_state.filter(|_| active_tutorial.0.is_none()) else {
+        return;
+    };
+    let elapsed = game_timer.map_or(Duration::ZERO, |game_timer| game_timer.elapsed());
+    let Some((time, outcome)) = outcomes.read().find_map(|outcome| match *outcome {
+        GameOutcome::Completed { time, .. } => Some((time, SolveOutcome::Solved)),
+        GameOutcome::Failed { reason } => Some((elapsed, reason.into())),
+        GameOutcome::Abandoned => None,
+    }) else {
+        return;
+    };
+    let givens = game_state.grid.givens();
+    let grade_report = grade(&ClassicPuzzle::from_grid_with_rules(
+        givens,
+        puzzle_settings.rules,
+    ));
+    solve_history.record(SolveRecord {
+        puzzle_type: puzzle_settings.puzzle_type,
+        seed: puzzle_settings.seed.clone(),
+        rules: puzzle_settings.rules,
+        remix: puzzle_settings.remix,
+        givens: givens.to_string(),
+        solution: game_state.solution.to_string(),
+        time,
+        hints: hint_progress
+            .as_ref()
+            .map_or(0, |hint_progress| hint_progress.stats().regions),
+        hints_exhausted: hint_progress.is_some_and(|hint_progress| hint_progress.exhausted()),
+        checks: game_state.checks,
+        mistakes: game_state.grid.mistakes(),
+        difficulty: grade_report.difficulty,
+        moves: game_state.moves.clone(),
+        solved_at: unix_secs_now(),
+        assist_level: puzzle_settings.assist_level,
+        blitz: puzzle_settings.blitz,
+        outcome,
+    });
+}
+
+// Copy the board to the clipboard
+fn copy_board_system(
+    game_state: Option<Res<ClassicGameState>>,
+    mut input_actions: EventReader<InputAction>,
+    mut clipboard_resource: ResMut<ClipboardResource>,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    for _ in input_actions
+        .read()
+        .filter(|action| **action == InputAction::Copy)
+    {
+        clipboard_resource.copy(game_state.grid.grid().to_string());
+        info!("Copied the board to the clipboard");
+    }
+}
+
+// Reveal the next stage of a hint when one is asked for (if hints are on)
+fn hint_system(
+    game_state: Option<Res<ClassicGameState>>,
+    hint_progress: Option<ResMut<HintProgress>>,
+    hint_settings: Res<HintSettings>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut input_actions: EventReader<InputAction>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let (Some(game_state), Some(mut hint_progress)) = (game_state, hint_progress) else {
+        return;
+    };
+    let pressed = input_actions
+        .read()
+        .any(|action| *action == InputAction::Hint);
+    if !pressed || !puzzle_settings.assist_level.assists().hints {
+        return;
+    }
+    match hint_progress.press(game_state.grid.grid(), &hint_settings) {
+        HintPress::Shown(stage, hint) => {
+            info!("Hint: {}", hint_text(stage, &hint));
+            gameplay_events.write(GameplayEvent::HintShown);
+        }
+        HintPress::CoolingDown(remaining) => {
+            info!("Next hint in {} seconds", remaining.as_secs() + 1);
+        }
+        HintPress::NoHint => info!("No hint found"),
+        HintPress::Exhausted => info!("There are no hints left for this puzzle"),
+    }
+}
+
+// Tell the player whether their entries are right so far, without saying which ones are wrong.
+// Each check adds a penalty to the time.
+fn check_system(
+    game_state: Option<ResMut<ClassicGameState>>,
+    game_timer: Option<ResMut<GameTimer>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut input_actions: EventReader<InputAction>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let Some(mut game_state) = game_state else {
+        return;
+    };
+    let pressed = input_actions
+        .read()
+        .any(|action| *action == InputAction::Check);
+    if !pressed || !puzzle_settings.assist_level.assists().checks {
+        return;
+    }
+    game_state.checks += 1;
+    gameplay_events.write(GameplayEvent::Checked);
+    if let Some(mut game_timer) = game_timer {
+        game_timer.add_penalty(CHECK_PENALTY);
+    }
+    match game_state.grid.wrong_entries(&game_state.solution) {
+        0 => info!("All correct so far"),
+        1 => info!("1 cell is wrong"),
+        wrong => info!("{wrong} cells are wrong"),
+    }
+}
+
+// Keep the side panel's digit counters up to date with the grid
+fn digit_counts_system(
+    game_state: Option<Res<ClassicGameState>>,
+    mut digit_counts_text_query: Query<(&mut Text, Ref<DigitCountsText>)>,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    for (mut text, digit_counts) in &mut digit_counts_text_query {
+        if game_state.is_changed() || digit_counts.is_added() {
+            text.0 = digit_counts_text(game_state.grid.grid());
+        }
+    }
+}
+
+fn progress_text_system(
+    game_state: Option<Res<ClassicGameState>>,
+    mut progress_text_query: Query<(&mut Text, Ref<ProgressText>)>,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    for (mut text, progress) in &mut progress_text_query {
+        if game_state.is_changed() || progress.is_added() {
+            text.0 = progress_text(game_state.grid.grid());
+        }
+    }
+}
+
+// Show how many hints are left on the hint button
+fn hint_button_text_system(
+    hint_progress: Option<Res<HintProgress>>,
+    mut hint_button_text_query: Query<(&mut Text, Ref<HintButtonText>)>,
+) {
+    let Some(hint_progress) = hint_progress else {
+        return;
+    };
+    for (mut text, hint_button_text_ref) in &mut hint_button_text_query {
+        if hint_progress.is_changed() || hint_button_text_ref.is_added() {
+            text.0 = hint_button_text(hint_progress.remaining());
+        }
+    }
 }
 
-fn classic_game_action_system() {}
+// Announce the rows, columns, and boxes that have just been filled in correctly
+fn unit_completion_system(
+    game_state: Option<Res<ClassicGameState>>,
+    completed_units_resource: Option<ResMut<CompletedUnits>>,
+    mut unit_completed_events: EventWriter<UnitCompleted>,
+) {
+    let (Some(game_state), Some(mut completed)) = (game_state, completed_units_resource) else {
+        return;
+    };
+    if !game_state.is_changed() {
+        return;
+    }
+    let now_completed = completed_units(game_state.grid.grid(), &game_state.solution);
+    for &unit in now_completed
+        .iter()
+        .filter(|unit| !completed.0.contains(unit))
+    {
+        unit_completed_events.write(UnitCompleted(unit));
+    }
+    completed.0 = now_completed;
+}
+
+// Publish the classic milestones as the grid changes: halfway, once half of the cells that weren't
+// given are filled in correctly
+fn milestone_system(
+    game_state: Option<Res<ClassicGameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut halfway: Local<bool>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    if game_state.is_added() {
+        *halfway = false;
+    }
+    if !game_state.is_changed() || *halfway {
+        return;
+    }
+    let to_fill = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .filter(|&position| !game_state.grid.is_given(position))
+        .count();
+    if game_state.grid.correct_entries(&game_state.solution) * 2 >= to_fill {
+        *halfway = true;
+        gameplay_events.write(milestone(&puzzle_settings, "halfway"));
+    }
+}
+
+// Point out where the armed number can go as it's armed or the grid changes (if the assist level
+// highlights them): the cells where it's the only candidate left, and how many cells can't take it
+fn ghost_highlight_system(
+    game_state: Option<Res<ClassicGameState>>,
+    armed_digit: Res<ArmedDigit>,
+    puzzle_settings: Res<PuzzleSettings>,
+    ghost_highlights: Option<ResMut<ClassicGhostHighlights>>,
+) {
+    let (Some(game_state), Some(mut ghost_highlights)) = (game_state, ghost_highlights) else {
+        return;
+    };
+    if !game_state.is_changed() && !armed_digit.is_changed() {
+        return;
+    }
+    let Some(val) = armed_digit
+        .0
+        .filter(|_| puzzle_settings.assist_level.assists().ghost_highlighting)
+    else {
+        ghost_highlights.0 = None;
+        return;
+    };
+    let mut placements = [[GhostPlacement::Illegal; 9]; 9];
+    for (row, row_placements) in (0..9).zip(&mut placements) {
+        for (col, placement) in (0..9).zip(row_placements) {
+            *placement = game_state.grid.ghost_placement((row, col), val);
+        }
+    }
+    let cells = |kind| {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(move |&(row, col)| placements[row as usize][col as usize] == kind)
+    };
+    let only: Vec<String> = cells(GhostPlacement::OnlyCandidate)
+        .map(|(row, col)| format!("r{}c{}", row + 1, col + 1))
+        .collect();
+    let illegal = cells(GhostPlacement::Illegal).count();
+    info!("{val} can't go in {illegal} cells");
+    if !only.is_empty() {
+        info!("{val} is the only candidate left in {}", only.join(", "));
+    }
+    ghost_highlights.0 = Some((val, placements));
+}
+
+// Point out the naked pairs in the center marks as the marks change (if the assist level highlights
+// them), announcing the ones that weren't there before
+fn pair_highlight_system(
+    game_state: Option<Res<ClassicGameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    pair_highlights: Option<ResMut<ClassicPairHighlights>>,
+) {
+    let (Some(game_state), Some(mut pair_highlights)) = (game_state, pair_highlights) else {
+        return;
+    };
+    if !game_state.is_changed() {
+        return;
+    }
+    if !puzzle_settings.assist_level.assists().pair_highlighting {
+        pair_highlights.set_if_neq(ClassicPairHighlights::default());
+        return;
+    }
+    let grid = &game_state.grid;
+    let marks = std::array::from_fn(|row| {
+        std::array::from_fn(|col| {
+            let position = (row as u8, col as u8);
+            if grid.grid().get_by_row_col(position).is_some() {
+                BitSet16::default()
+            } else {
+                grid.marks(position, PencilMark::Center)
+            }
+        })
+    });
+    if pair_highlights.marks == marks {
+        return;
+    }
+    let pairs = grid.naked_pairs();
+    for pair in pairs
+        .iter()
+        .filter(|pair| !pair_highlights.pairs.contains(pair))
+    {
+        let [(row, col), (other_row, other_col)] = pair.cells;
+        info!(
+            "{} and {} can only go in r{}c{} and r{}c{} in {}",
+            pair.vals[0],
+            pair.vals[1],
+            row + 1,
+            col + 1,
+            other_row + 1,
+            other_col + 1,
+            pair.region
+        );
+    }
+    *pair_highlights = ClassicPairHighlights { marks, pairs };
+}
+
+// Point out the rules the player's entries break, if the assist level highlights errors
+fn conflict_system(
+    game_state: Option<Res<ClassicGameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    conflicts: Option<ResMut<ClassicConflicts>>,
+) {
+    let (Some(game_state), Some(mut conflicts)) = (game_state, conflicts) else {
+        return;
+    };
+    if !game_state.is_changed() || !puzzle_settings.assist_level.assists().error_highlighting {
+        return;
+    }
+    let now_conflicting =
+        classic_conflicts(game_state.grid.grid(), classic_grid_rules(&puzzle_settings));
+    for conflict in now_conflicting
+        .iter()
+        .filter(|conflict| !conflicts.0.contains(conflict))
+    {
+        info!("Conflict: {conflict}");
+    }
+    conflicts.0 = now_conflicting;
+}
+
+fn share_dialog_open_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ShareDialogOpenButton>)>,
+    game_state: Option<Res<ClassicGameState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    container_query: Query<Entity, With<ClassicContainer>>,
+    dialog_query: Query<(), With<ShareDialog>>,
+    mut commands: Commands,
+) {
+    let Some(game_state) = game_state else {
+        return;
+    };
+    let pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !pressed || !dialog_query.is_empty() {
+        return;
+    }
+    let contents = ShareContents {
+        seed: puzzle_settings.seed.clone(),
+        share_code: ShareCode::new(puzzle_settings.seed.clone()).to_string(),
+        board: Some(game_state.grid.grid().to_string()),
+        progress: Some(game_state.grid.progress_string()),
+    };
+    if let Some(container) = container_query.iter().next() {
+        commands
+            .entity(container)
+            .with_child(share_dialog_bundle(contents));
+    }
+}
+
+// Open the save slot picker with the game as it is now, including the selected cell. Blitz and
+// marathon games can't be picked up again, so they aren't saved.
+#[allow(clippy::too_many_arguments)]
+fn save_dialog_open_system(
+    mut input_actions: EventReader<InputAction>,
+    game_state: Option<Res<ClassicGameState>>,
+    game_timer: Option<Res<GameTimer>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    selected_cell: Res<SelectedCell>,
+    hint_progress: Option<Res<HintProgress>>,
+    save_slots: Res<SaveSlots>,
+    container_query: Query<Entity, With<ClassicContainer>>,
+    dialog_query: Query<(), With<SaveSlotDialog>>,
+    mut commands: Commands,
+) {
+    let (Some(game_state), Some(game_timer)) = (game_state, game_timer) else {
+        return;
+    };
+    let pressed = input_actions
+        .read()
+        .any(|action| *action == InputAction::Save);
+    if !pressed || !dialog_query.is_empty() {
+        return;
+    }
+    if puzzle_settings.blitz || puzzle_settings.marathon.is_some() {
+        info!("Blitz and marathon games can't be saved");
+        return;
+    }
+    let ClassicGameState {
+        grid,
+        solution,
+        checks,
+        ..
+    } = &*game_state;
+    let open = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .filter(|position| !grid.is_given(*position))
+        .count();
+    let filled = grid.correct_entries(solution) + grid.wrong_entries(solution);
+    let slot = SaveSlot {
+        puzzle_type: puzzle_settings.puzzle_type,
+        seed: puzzle_settings.seed.clone(),
+        rules: puzzle_settings.rules,
+        remix: puzzle_settings.remix,
+        practice: puzzle_settings.practice,
+        challenge: puzzle_settings.challenge,
+        difficulty: grade(&ClassicPuzzle::from_grid_with_rules(
+            grid.givens(),
+            puzzle_settings.rules,
+        ))
+        .difficulty,
+        progress: grid.progress_string(),
+        percent: (filled * 100 / open.max(1)) as u8,
+        elapsed: game_timer.elapsed(),
+        saved_at: unix_secs_now(),
+        selected: selected_cell.0,
+        assist_level: puzzle_settings.assist_level,
+        mistake_limit: puzzle_settings.mistake_limit,
+        strict_entry: puzzle_settings.strict_entry,
+        fill_candidates: puzzle_settings.fill_candidates,
+        mistakes: grid.mistakes(),
+        checks: *checks,
+        hints: hint_progress
+            .map(|hint_progress| hint_progress.stats())
+            .unwrap_or_default(),
+        marks: Some(grid.marks_string()),
+    };
+    if let Some(container) = container_query.iter().next() {
+        commands
+            .entity(container)
+            .with_child(save_dialog_bundle(&save_slots, slot));
+    }
+}
+
+/// Console commands for starting and playing classic grid puzzles in debug builds.
+#[cfg(debug_assertions)]
+mod console_commands {
+    use bevy::prelude::*;
+    use rand::seq::SliceRandom;
+
+    use crate::{
+        plugins::{
+            common::{
+                rng::RngService,
+                solver::{SolverJobFinished, SolverJobId, SolverJobs},
+            },
+            console::{ConsoleCommand, ConsolePrint},
+            game::{classic_grid_rules, input::InputAction, solve_history::SolveMove},
+            nav::{Navigator, Screen},
+        },
+        puzzles::solver_job::{SolverRequest, SolverResponse},
+        AppState, PuzzleSettings,
+    };
+
+    use super::ClassicGameState;
+
+    /// The stream `fill-random` picks its cells with.
+    const FILL_RANDOM_STREAM: &str = "fill-random";
+
+    /// Counting stops here, since puzzles with many solutions would take too long to count.
+    const SOLUTION_COUNT_BOUND: usize = 1000;
+
+    /// How many entries `edits` lists around the current one, so they fit in the console.
+    const EDITS_SHOWN: usize = 8;
+
+    pub(super) const CLASSIC_CONSOLE_COMMANDS: [ConsoleCommand; 10] = [
+        ConsoleCommand {
+            name: "seed",
+            usage: "seed <seed>",
+            help: "Start a puzzle with a seed and the chosen settings",
+            run: seed_command,
+        },
+        ConsoleCommand {
+            name: "solve",
+            usage: "solve",
+            help: "Fill in the solution",
+            run: solve_command,
+        },
+        ConsoleCommand {
+            name: "fill-random",
+            usage: "fill-random [count]",
+            help: "Fill in some random empty cells (10 by default)",
+            run: fill_random_command,
+        },
+        ConsoleCommand {
+            name: "count-solutions",
+            usage: "count-solutions",
+            help: "Count the solutions of the grid being played",
+            run: count_solutions_command,
+        },
+        ConsoleCommand {
+            name: "clear-marks",
+            usage: "clear-marks",
+            help: "Remove every pencil mark",
+            run: clear_marks_command,
+        },
+        ConsoleCommand {
+            name: "fill-marks",
+            usage: "fill-marks",
+            help: "Fill in the candidates of every empty cell as pencil marks",
+            run: fill_marks_command,
+        },
+        ConsoleCommand {
+            name: "remove-marks",
+            usage: "remove-marks <number>",
+            help: "Remove a number's pencil marks from every cell",
+            run: remove_marks_command,
+        },
+        ConsoleCommand {
+            name: "edits",
+            usage: "edits",
+            help: "List the entries made so far, including the ones scrubbed back over",
+            run: edits_command,
+        },
+        ConsoleCommand {
+            name: "scrub",
+            usage: "scrub <back|forward> [count]",
+            help: "Undo or redo entries to see the board as it was (1 by default)",
+            run: scrub_command,
+        },
+        ConsoleCommand {
+            name: "dump",
+            usage: "dump",
+            help: "Print the grid being played as a grid string",
+            run: dump_command,
+        },
+    ];
+
+    fn game_state(world: &mut World) -> Result<Mut<'_, ClassicGameState>, String> {
+        world
+            .get_resource_mut::<ClassicGameState>()
+            .ok_or_else(|| "there's no classic grid puzzle being played".to_string())
+    }
+
+    fn seed_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+        if args.is_empty() {
+            return Err("usage: seed <seed>".into());
+        }
+        let seed = args.join(" ");
+        match world.resource::<State<AppState>>().get() {
+            AppState::Menu => {}
+            AppState::Game => return Err("leave the puzzle before starting another".into()),
+            AppState::Loading => return Err("wait for the app to load".into()),
+        }
+        let mut puzzle_settings = world.resource_mut::<PuzzleSettings>();
+        puzzle_settings.seed = seed.clone();
+        puzzle_settings.remix = None;
+        world.resource_mut::<Navigator>().push(Screen::Game);
+        Ok(format!("Starting the puzzle with seed {seed}"))
+    }
+
+    /// Fills in a cell, recording the entry as a move like the player's entries so that the
+    /// console's entries can be scrubbed over too.
+    fn console_entry(
+        game_state: &mut ClassicGameState,
+        position: (u8, u8),
+        val: Option<u8>,
+    ) -> Result<(), String> {
+        if game_state.grid.grid().get_by_row_col(position) == val {
+            return Ok(());
+        }
+        game_state
+            .grid
+            .set(position, val)
+            .map_err(|err| err.to_string())?;
+        game_state.moves.push((position, val));
+        Ok(())
+    }
+
+    fn solve_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        let mut game_state = game_state(world)?;
+        for row in 0..9 {
+            for col in 0..9 {
+                if !game_state.grid.is_given((row, col)) {
+                    let val = game_state.solution.get_by_row_col((row, col));
+                    console_entry(&mut game_state, (row, col), val)?;
+                }
+            }
+        }
+        Ok("Solved the grid".into())
+    }
+
+    fn fill_random_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+        let count = match args {
+            [] => 10,
+            [count] => count
+                .parse()
+                .map_err(|_| format!("{count} isn't a number of cells"))?,
+            _ => return Err("usage: fill-random [count]".into()),
+        };
+        let grid = *game_state(world)?.grid.grid();
+        let mut empty: Vec<(u8, u8)> = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&position| grid.get_by_row_col(position).is_none())
+            .collect();
+        empty.shuffle(
+            world
+                .resource_mut::<RngService>()
+                .stream(FILL_RANDOM_STREAM),
+        );
+        empty.truncate(count);
+        let mut game_state = game_state(world)?;
+        for &position in &empty {
+            let val = game_state.solution.get_by_row_col(position);
+            console_entry(&mut game_state, position, val)?;
+        }
+        Ok(format!("Filled {} cells", empty.len()))
+    }
+
+    /// Sends a pencil mark sweep the way the keypad does, so it's made like any other action.
+    fn send_sweep(world: &mut World, action: InputAction) -> Result<(), String> {
+        game_state(world)?;
+        world.send_event(action);
+        Ok(())
+    }
+
+    fn clear_marks_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        send_sweep(world, InputAction::ClearMarks)?;
+        Ok("Cleared the pencil marks".into())
+    }
+
+    fn fill_marks_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        send_sweep(world, InputAction::FillMarks)?;
+        Ok("Filled in the candidates".into())
+    }
+
+    fn remove_marks_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+        let [number] = args else {
+            return Err("usage: remove-marks <number>".into());
+        };
+        let val = number
+            .parse()
+            .ok()
+            .filter(|val| (1..=9).contains(val))
+            .ok_or_else(|| format!("{number} isn't a number from 1 to 9"))?;
+        send_sweep(world, InputAction::RemoveMarks(val))?;
+        Ok(format!("Removed the {val} pencil marks"))
+    }
+
+    fn count_solutions_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        let rules = classic_grid_rules(world.resource::<PuzzleSettings>());
+        let grid = game_state(world)?.grid.grid().to_string();
+        let id = world
+            .resource_mut::<SolverJobs>()
+            .submit(SolverRequest::CountSolutions {
+                grid,
+                rules,
+                bound: SOLUTION_COUNT_BOUND,
+            });
+        world.insert_resource(PendingSolutionCount(id));
+        Ok("Counting the solutions...".into())
+    }
+
+    /// The entries `scrub back` undid, latest last, so `scrub forward` can make them again. They're
+    /// dropped once another entry is made, since they no longer follow on from the board.
+    #[derive(Resource, Default)]
+    pub(super) struct ScrubbedEdits {
+        ahead: Vec<SolveMove>,
+        /// How many entries had been made when the last one was scrubbed over
+        at: usize,
+    }
+
+    fn edit_text(((row, col), val): SolveMove) -> String {
+        match val {
+            Some(val) => format!("r{}c{} = {val}", row + 1, col + 1),
+            None => format!("r{}c{} erased", row + 1, col + 1),
+        }
+    }
+
+    /// Returns the entries scrubbed back over, unless another entry was made since.
+    fn scrubbed_edits(world: &mut World, made: usize) -> Mut<'_, ScrubbedEdits> {
+        let mut scrubbed_edits = world.get_resource_or_init::<ScrubbedEdits>();
+        if scrubbed_edits.at != made {
+            scrubbed_edits.ahead.clear();
+            scrubbed_edits.at = made;
+        }
+        scrubbed_edits
+    }
+
+    fn edits_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        let moves = game_state(world)?.moves.clone();
+        let ahead = scrubbed_edits(world, moves.len()).ahead.clone();
+        let made = moves.len();
+        let edits: Vec<SolveMove> = moves.into_iter().chain(ahead.into_iter().rev()).collect();
+        if edits.is_empty() {
+            return Ok("No entries have been made".into());
+        }
+        let start = made.saturating_sub(EDITS_SHOWN / 2);
+        let end = (start + EDITS_SHOWN).min(edits.len());
+        let mut lines = vec![format!(
+            "{made} of {} entries made, showing {}-{end}",
+            edits.len(),
+            start + 1
+        )];
+        for (index, &edit) in edits.iter().enumerate().take(end).skip(start) {
+            let marker = if index + 1 == made { '>' } else { ' ' };
+            let undone = if index < made { "" } else { " (scrubbed)" };
+            lines.push(format!(
+                "{marker}{:>4}. {}{undone}",
+                index + 1,
+                edit_text(edit)
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn scrub_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+        let (forward, count) = match args {
+            [direction] => (*direction, 1),
+            [direction, count] => (
+                *direction,
+                count
+                    .parse()
+                    .map_err(|_| format!("{count} isn't a number of entries"))?,
+            ),
+            _ => return Err("usage: scrub <back|forward> [count]".into()),
+        };
+        let forward = match forward {
+            "back" => false,
+            "forward" => true,
+            _ => return Err("usage: scrub <back|forward> [count]".into()),
+        };
+        let game = game_state(world)?;
+        // Scrubbing back puts each cell back to its value before the move, which is only known if
+        // every entry on the board was recorded as a move (unlike a picked-up saved game's)
+        let mut replayed = game.grid.givens();
+        for &(position, val) in &game.moves {
+            replayed.set(position, val);
+        }
+        if !forward && replayed != *game.grid.grid() {
+            return Err(
+                "the board has entries that weren't made as moves, so it can't be scrubbed back"
+                    .into(),
+            );
+        }
+        let made = game.moves.len();
+        let mut ahead = std::mem::take(&mut scrubbed_edits(world, made).ahead);
+        let mut game_state = game_state(world)?;
+        let ClassicGameState { grid, moves, .. } = &mut *game_state;
+        let mut scrubbed = 0;
+        while scrubbed < count {
+            if forward {
+                let Some((position, val)) = ahead.pop() else {
+                    break;
+                };
+                grid.set(position, val).map_err(|err| err.to_string())?;
+                moves.push((position, val));
+            } else {
+                let Some((position, val)) = moves.pop() else {
+                    break;
+                };
+                let before = moves
+                    .iter()
+                    .rev()
+                    .find(|(earlier, _)| *earlier == position)
+                    .and_then(|(_, val)| *val);
+                grid.set(position, before).map_err(|err| err.to_string())?;
+                ahead.push((position, val));
+            }
+            scrubbed += 1;
+        }
+        let made = moves.len();
+        let mut scrubbed_edits = world.resource_mut::<ScrubbedEdits>();
+        scrubbed_edits.ahead = ahead;
+        scrubbed_edits.at = made;
+        let direction = if forward { "forward" } else { "back" };
+        Ok(format!(
+            "Scrubbed {direction} {scrubbed} entries, to {made} of {}",
+            made + scrubbed_edits.ahead.len()
+        ))
+    }
+
+    fn dump_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+        Ok(game_state(world)?
+            .grid
+            .grid()
+            .to_string()
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.')
+            .collect())
+    }
+
+    /// The job counting the solutions for `count-solutions`.
+    #[derive(Resource)]
+    pub(super) struct PendingSolutionCount(SolverJobId);
+
+    // Print the count to the console once the job has finished
+    pub(super) fn solution_count_system(
+        pending_solution_count: Res<PendingSolutionCount>,
+        mut finished_events: EventReader<SolverJobFinished>,
+        mut console_print_events: EventWriter<ConsolePrint>,
+        mut commands: Commands,
+    ) {
+        for SolverJobFinished { id, response } in finished_events.read() {
+            if *id != pending_solution_count.0 {
+                continue;
+            }
+            console_print_events.write(ConsolePrint(match response {
+                SolverResponse::SolutionCount(count) if *count == SOLUTION_COUNT_BOUND => {
+                    format!("The grid has at least {count} solutions")
+                }
+                SolverResponse::SolutionCount(count) => format!("The grid has {count} solutions"),
+                SolverResponse::Failed(err) => format!("Couldn't count the solutions: {err}"),
+                SolverResponse::Hint(_) => continue,
+            }));
+            commands.remove_resource::<PendingSolutionCount>();
+        }
+    }
+}
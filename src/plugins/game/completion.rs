@@ -0,0 +1,314 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component,
+    grids::classic::ClassicGrid,
+    plugins::{
+        common::{
+            bundles::mini_board::mini_board_bundle,
+            clipboard::ClipboardResource,
+            rng::{RngService, REMIX_STREAM, SIMILAR_STREAM},
+            theme::{
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    puzzles::{classic::ClassicPuzzle, hint::grade},
+    remove_resource, AppState, PuzzleSettings,
+};
+
+/// How much bigger than the usual mini board the finished board is drawn.
+const COMPLETION_BOARD_SCALE: f32 = 1.5;
+
+use super::{
+    cache::PuzzleCache,
+    classic_grid_rules,
+    hint::HintStats,
+    pregen::PregenPool,
+    solve_breakdown::{
+        hints_text, solve_breakdown_bundle, techniques_text, SolveBreakdown, SolveTimeline,
+    },
+    timer::{clock_text, precise_clock_text, TimerDisplaySettings, TimerPrecision},
+    variant::VariantInfo,
+    GameState, PuzzleType,
+};
+
+pub fn completion_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Solved), completion_setup)
+        .add_systems(
+            OnExit(GameState::Solved),
+            despawn_component::<CompletionContainer>,
+        )
+        .add_systems(OnExit(AppState::Game), remove_resource::<SolvedPuzzle>)
+        .add_systems(
+            Update,
+            completion_button_system.run_if(in_state(GameState::Solved)),
+        );
+}
+
+/// The puzzle that was just solved, for the completion screen.
+#[derive(Resource)]
+pub struct SolvedPuzzle {
+    pub time: Duration,
+    /// The puzzle's grid, for finding puzzles like it
+    pub givens: ClassicGrid,
+    /// The finished board
+    pub grid: ClassicGrid,
+    /// The stages of hints that were shown
+    pub hints: HintStats,
+}
+
+#[derive(Component)]
+#[require(Node, FocusScope)]
+struct CompletionContainer;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum CompletionButton {
+    /// Plays an equivalent puzzle that looks different
+    Remix,
+    /// Plays a harder puzzle with the same solution
+    Challenge,
+    /// Plays a new puzzle that grades about the same
+    MoreLikeThis,
+    /// Copies a summary of the solve to share
+    CopySummary(String),
+    Done,
+}
+
+/// Returns the solve as text to share, e.g.:
+/// ```text
+/// Solved a Classic sudoku in 5:32
+/// Thirds: 2:10 / 1:50 / 1:32
+/// Longest pause: 0:45
+/// Cells per minute: ▃▅█▆▂▁
+/// Techniques needed: Hidden single x12, Naked single x30
+/// Hints used: none
+/// ```
+fn summary_text(
+    puzzle_type: PuzzleType,
+    time: Duration,
+    breakdown: &SolveBreakdown,
+    techniques: &str,
+    hints: &str,
+) -> String {
+    let [first, second, last] = breakdown.thirds.map(clock_text);
+    [
+        format!("Solved a {puzzle_type} sudoku in {}", clock_text(time)),
+        format!("Thirds: {first} / {second} / {last}"),
+        format!("Longest pause: {}", clock_text(breakdown.longest_pause)),
+        format!("Cells per minute: {}", breakdown.sparkline_text()),
+        format!("Techniques needed: {techniques}"),
+        format!("Hints used: {hints}"),
+    ]
+    .join("\n")
+}
+
+fn completion_setup(
+    solved_puzzle: Option<Res<SolvedPuzzle>>,
+    solve_timeline: Res<SolveTimeline>,
+    puzzle_settings: Res<PuzzleSettings>,
+    timer_display_settings: Res<TimerDisplaySettings>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    // The time is shown to the second at least, and to the hundredth for speedruns
+    let precision = timer_display_settings
+        .precision
+        .max(TimerPrecision::Seconds);
+    let (time, grid, techniques, hints) = solved_puzzle.map_or_else(
+        || {
+            (
+                Duration::ZERO,
+                ClassicGrid::default(),
+                String::new(),
+                String::new(),
+            )
+        },
+        |solved_puzzle| {
+            let grade_report = grade(&ClassicPuzzle::from_grid_with_rules(
+                solved_puzzle.givens,
+                classic_grid_rules(&puzzle_settings),
+            ));
+            (
+                solved_puzzle.time,
+                solved_puzzle.grid,
+                techniques_text(&grade_report),
+                hints_text(solved_puzzle.hints),
+            )
+        },
+    );
+    let time_text = format!("Solved in {}", precise_clock_text(time, precision));
+    let breakdown = SolveBreakdown::new(solve_timeline.solved_at(), time);
+    let summary = summary_text(
+        puzzle_settings.puzzle_type,
+        time,
+        &breakdown,
+        &techniques,
+        &hints,
+    );
+    let button_bundle = |button: CompletionButton, label: &str, display: Display| {
+        (
+            button,
+            Node {
+                display,
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(style_sheet.button_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    // Only puzzles on a classic grid can be transformed, and only classic ones made harder
+    let remix_display = if VariantInfo::of(puzzle_settings.puzzle_type).classic_grid {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    let challenge_display = if puzzle_settings.puzzle_type == PuzzleType::Classic {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    commands.spawn((
+        CompletionContainer,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(style_sheet.gap),
+            ..default()
+        },
+        children![
+            (
+                Text::new("Solved!"),
+                TextFont::from_font_size(style_sheet.heading_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            ),
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(style_sheet.gap),
+                    ..default()
+                },
+                children![
+                    mini_board_bundle(&grid, COMPLETION_BOARD_SCALE),
+                    solve_breakdown_bundle(
+                        &breakdown,
+                        techniques,
+                        hints,
+                        style_sheet.body_font_size * 0.75
+                    ),
+                ],
+            ),
+            (
+                Text::new(time_text),
+                TextFont::from_font_size(style_sheet.body_font_size),
+                ThemedTextColor,
+            ),
+            button_bundle(CompletionButton::Remix, "Remix", remix_display),
+            button_bundle(
+                CompletionButton::Challenge,
+                "Play again harder",
+                challenge_display
+            ),
+            button_bundle(
+                CompletionButton::MoreLikeThis,
+                "More like this",
+                remix_display
+            ),
+            button_bundle(
+                CompletionButton::CopySummary(summary),
+                "Copy summary",
+                Display::Flex
+            ),
+            button_bundle(CompletionButton::Done, "Done", Display::Flex),
+        ],
+    ));
+}
+
+// Remix the puzzle with the same rules (and so the same difficulty), make it harder while keeping
+// its solution, play a new one that grades like it, copy a summary of the solve, or go back to the
+// menus
+#[allow(clippy::too_many_arguments)]
+fn completion_button_system(
+    interaction_query: Query<(&Interaction, &CompletionButton), Changed<Interaction>>,
+    solved_puzzle: Option<Res<SolvedPuzzle>>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut rng_service: ResMut<RngService>,
+    mut pregen_pool: ResMut<PregenPool>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut navigator: ResMut<Navigator>,
+    mut clipboard_resource: ResMut<ClipboardResource>,
+) {
+    for (_, button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        match button {
+            CompletionButton::Remix => {
+                let rules = classic_grid_rules(&puzzle_settings);
+                puzzle_settings.remix =
+                    Some(rules.random_transform(rng_service.stream(REMIX_STREAM)));
+                next_game_state.set(GameState::Playing(puzzle_settings.puzzle_type));
+            }
+            CompletionButton::Challenge => {
+                puzzle_settings.challenge = puzzle_settings.challenge.saturating_add(1);
+                next_game_state.set(GameState::Playing(puzzle_settings.puzzle_type));
+            }
+            CompletionButton::MoreLikeThis => {
+                let Some(solved_puzzle) = &solved_puzzle else {
+                    continue;
+                };
+                let report = grade(&ClassicPuzzle::from_grid_with_rules(
+                    solved_puzzle.givens,
+                    classic_grid_rules(&puzzle_settings),
+                ));
+                let Some(seed) = pregen_pool.take_similar(
+                    &puzzle_settings,
+                    &report,
+                    rng_service.stream(SIMILAR_STREAM),
+                    &mut puzzle_cache,
+                ) else {
+                    warn!("Couldn't find a puzzle like this one");
+                    continue;
+                };
+                puzzle_settings.seed = seed;
+                puzzle_settings.practice = None;
+                puzzle_settings.remix = None;
+                puzzle_settings.challenge = 0;
+                next_game_state.set(GameState::Playing(puzzle_settings.puzzle_type));
+            }
+            CompletionButton::CopySummary(summary) => {
+                clipboard_resource.copy(summary.clone());
+                info!("Copied the summary");
+            }
+            CompletionButton::Done => navigator.replace(Screen::Menu(MenuState::Home)),
+        }
+    }
+}
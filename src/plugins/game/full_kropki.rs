@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::despawn_component;
 
-use super::{GameState, PuzzleType};
+use super::{GameState, GameSystems, PuzzleType};
 
 pub fn full_kropki_plugin(app: &mut App) {
     app.add_systems(
@@ -15,7 +15,9 @@ pub fn full_kropki_plugin(app: &mut App) {
     )
     .add_systems(
         Update,
-        (full_kropki_action_system).run_if(in_state(GameState::Playing(PuzzleType::FullKropki))),
+        full_kropki_action_system
+            .in_set(GameSystems::ApplyEdits)
+            .run_if(in_state(GameState::Playing(PuzzleType::FullKropki))),
     );
 }
 
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::{puzzles::hint::Region, PuzzleSettings};
+
+use super::{
+    outcome::GameOutcome, progress::UnitCompleted, shake::EntryRejected, GameSystems, PuzzleType,
+};
+
+pub fn gameplay_event_plugin(app: &mut App) {
+    app.add_event::<GameplayEvent>().add_systems(
+        Update,
+        gameplay_forward_system.in_set(GameSystems::DeriveState),
+    );
+}
+
+/// Something that happened while a puzzle was played. Puzzle types publish these as they're
+/// played, so achievements, stats, sounds, and animations can react to any puzzle type without it
+/// knowing about them. Rejected entries, completed units, and outcomes are forwarded here from
+/// their own events, so puzzle types only need to publish the rest.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameplayEvent {
+    /// A number was entered in a cell, or the cell was erased if `val` is `None`. Whether it
+    /// matches the solution is included when the puzzle type knows.
+    Placed {
+        position: (u8, u8),
+        val: Option<u8>,
+        correct: Option<bool>,
+    },
+    /// An entry was refused, e.g. because the cell is given
+    Rejected { position: (u8, u8) },
+    /// A hint was shown
+    HintShown,
+    /// The entries were checked
+    Checked,
+    /// A row, column, box, or a variant's extra region was filled in correctly
+    UnitCompleted(Region),
+    /// The puzzle ended, one way or another
+    Ended(GameOutcome),
+    /// A milestone that a puzzle type defines for itself, named by that puzzle type
+    Milestone {
+        puzzle_type: PuzzleType,
+        name: &'static str,
+    },
+}
+
+// Publish the rejected entries, completed units, and outcomes that puzzle types already send
+fn gameplay_forward_system(
+    mut entry_rejected_events: EventReader<EntryRejected>,
+    mut unit_completed_events: EventReader<UnitCompleted>,
+    mut outcomes: EventReader<GameOutcome>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    gameplay_events.write_batch(entry_rejected_events.read().map(|EntryRejected(position)| {
+        GameplayEvent::Rejected {
+            position: *position,
+        }
+    }));
+    gameplay_events.write_batch(
+        unit_completed_events
+            .read()
+            .map(|UnitCompleted(region)| GameplayEvent::UnitCompleted(*region)),
+    );
+    gameplay_events.write_batch(outcomes.read().copied().map(GameplayEvent::Ended));
+}
+
+/// Returns a milestone for the puzzle type being played, for puzzle types to publish.
+#[must_use]
+pub fn milestone(puzzle_settings: &PuzzleSettings, name: &'static str) -> GameplayEvent {
+    GameplayEvent::Milestone {
+        puzzle_type: puzzle_settings.puzzle_type,
+        name,
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{plugins::common::storage, AppState};
+
+use super::{gameplay::GameplayEvent, input::InputAction, outcome::GameOutcome, GameSystems};
+
+/// The name whether haptic feedback is on is saved under.
+const HAPTICS_SETTINGS_STORAGE_NAME: &str = "haptics settings";
+
+/// Buzzes gamepads and, on the web, phones when an entry is wrong or refused and when a puzzle is
+/// completed, while the player has turned it on with V. Nothing happens on devices that can't.
+pub fn haptics_plugin(app: &mut App) {
+    app.insert_resource(HapticsSettings::load())
+        .insert_resource(HapticsSupport::detect())
+        .add_systems(
+            Update,
+            (
+                haptics_toggle_system.in_set(GameSystems::ApplyEdits),
+                haptic_feedback_system
+                    .in_set(GameSystems::Render)
+                    .run_if(|settings: Res<HapticsSettings>| settings.enabled),
+            )
+                .run_if(in_state(AppState::Game)),
+        );
+}
+
+/// Whether haptic feedback is on. This is off until the player turns it on, and is kept between
+/// sessions.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HapticsSettings {
+    pub enabled: bool,
+}
+
+impl HapticsSettings {
+    fn load() -> Self {
+        storage::load(HAPTICS_SETTINGS_STORAGE_NAME).unwrap_or_default()
+    }
+}
+
+/// Whether the device itself can vibrate, which is only known on the web. Gamepads are checked
+/// for as they're connected instead.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HapticsSupport {
+    pub vibration: bool,
+}
+
+impl HapticsSupport {
+    #[cfg(target_family = "wasm")]
+    fn detect() -> Self {
+        // Browsers without the vibration API (e.g. Safari) leave `navigator.vibrate` out
+        let vibration = web_sys::window().is_some_and(|window| {
+            js_sys::Reflect::has(&window.navigator(), &js_sys::JsString::from("vibrate"))
+                .unwrap_or(false)
+        });
+        Self { vibration }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn detect() -> Self {
+        Self::default()
+    }
+}
+
+/// What the player is told by feel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HapticCue {
+    /// An entry was wrong or refused
+    InvalidEntry,
+    Completed,
+}
+
+impl HapticCue {
+    /// Returns how strongly and for how long gamepads rumble.
+    fn rumble(self) -> (GamepadRumbleIntensity, Duration) {
+        match self {
+            HapticCue::InvalidEntry => (
+                GamepadRumbleIntensity::strong_motor(0.6),
+                Duration::from_millis(120),
+            ),
+            HapticCue::Completed => (
+                GamepadRumbleIntensity::weak_motor(0.8),
+                Duration::from_millis(600),
+            ),
+        }
+    }
+
+    /// Returns how the device vibrates on the web, in milliseconds of alternating vibration and
+    /// stillness.
+    #[cfg_attr(not(target_family = "wasm"), allow(dead_code))]
+    fn vibration_pattern(self) -> &'static [u32] {
+        match self {
+            HapticCue::InvalidEntry => &[80],
+            HapticCue::Completed => &[100, 60, 100, 60, 200],
+        }
+    }
+}
+
+/// Returns the cue for a gameplay event, if it has one.
+fn haptic_cue(event: &GameplayEvent) -> Option<HapticCue> {
+    match event {
+        GameplayEvent::Placed {
+            correct: Some(false),
+            ..
+        }
+        | GameplayEvent::Rejected { .. } => Some(HapticCue::InvalidEntry),
+        GameplayEvent::Ended(GameOutcome::Completed { .. }) => Some(HapticCue::Completed),
+        _ => None,
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn vibrate(cue: HapticCue) {
+    let pattern: js_sys::Array = cue
+        .vibration_pattern()
+        .iter()
+        .map(|millis| js_sys::Number::from(*millis))
+        .collect();
+    if let Some(window) = web_sys::window() {
+        window.navigator().vibrate_with_pattern(&pattern);
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn vibrate(_cue: HapticCue) {}
+
+// Turn haptic feedback on and off, saying so when there's nothing to feel it with
+fn haptics_toggle_system(
+    mut input_actions: EventReader<InputAction>,
+    haptics_support: Res<HapticsSupport>,
+    gamepad_query: Query<(), With<Gamepad>>,
+    mut haptics_settings: ResMut<HapticsSettings>,
+) {
+    for _ in input_actions
+        .read()
+        .filter(|action| **action == InputAction::ToggleHaptics)
+    {
+        haptics_settings.enabled = !haptics_settings.enabled;
+        storage::save(HAPTICS_SETTINGS_STORAGE_NAME, &*haptics_settings);
+        let state = if haptics_settings.enabled {
+            "on"
+        } else {
+            "off"
+        };
+        if haptics_settings.enabled && !haptics_support.vibration && gamepad_query.is_empty() {
+            info!("Haptic feedback is {state}, but there's no gamepad or vibration to feel it");
+        } else {
+            info!("Haptic feedback is {state}");
+        }
+    }
+}
+
+// Buzz every connected gamepad, and the device on the web, for the last cue of the frame
+fn haptic_feedback_system(
+    mut gameplay_events: EventReader<GameplayEvent>,
+    haptics_support: Res<HapticsSupport>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+    mut rumble_requests: EventWriter<GamepadRumbleRequest>,
+) {
+    let Some(cue) = gameplay_events.read().filter_map(haptic_cue).last() else {
+        return;
+    };
+    let (intensity, duration) = cue.rumble();
+    for gamepad in &gamepad_query {
+        rumble_requests.write(GamepadRumbleRequest::Stop { gamepad });
+        rumble_requests.write(GamepadRumbleRequest::Add {
+            duration,
+            intensity,
+            gamepad,
+        });
+    }
+    if haptics_support.vibration {
+        vibrate(cue);
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::classic::ClassicGrid,
+    puzzles::hint::{find_hint, Hint, Technique},
+};
+
+use super::GameSystems;
+
+pub fn game_hint_plugin(app: &mut App) {
+    app.init_resource::<HintSettings>().add_systems(
+        Update,
+        hint_cooldown_system
+            .in_set(GameSystems::DeriveState)
+            .run_if(resource_exists::<HintProgress>),
+    );
+}
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HintSettings {
+    /// How long the player has to wait between hint presses
+    pub cooldown: Duration,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Returns how many hints a puzzle of a difficulty allows, or `None` if there's no limit. Puzzles
+/// that only need hidden singles get as many as the player wants, harder ones get a few, and ones
+/// the hint techniques can't solve get none.
+#[must_use]
+pub fn hint_limit(difficulty: Option<Technique>) -> Option<u32> {
+    match difficulty {
+        Some(Technique::HiddenSingle) => None,
+        Some(Technique::NakedSingle) => Some(3),
+        None => Some(0),
+    }
+}
+
+/// How much of a hint has been shown. Each press of the hint button reveals the next stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintStage {
+    /// The row, column, box, or cell to look at
+    Region,
+    /// The technique that finds the number
+    Technique,
+    /// The number and the cell it goes in
+    Digit,
+}
+
+/// Returns what a stage of a hint tells the player.
+#[must_use]
+pub fn hint_text(stage: HintStage, hint: &Hint) -> String {
+    match stage {
+        HintStage::Region => format!("Look at {}", hint.region),
+        HintStage::Technique => hint.technique.to_string(),
+        HintStage::Digit => {
+            let (row, col) = hint.position;
+            format!("{} goes in r{}c{}", hint.val, row + 1, col + 1)
+        }
+    }
+}
+
+/// How many times each stage of a hint has been shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintStats {
+    pub regions: u32,
+    pub techniques: u32,
+    pub digits: u32,
+}
+
+/// The result of pressing the hint button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintPress {
+    /// The next stage of the current hint was revealed
+    Shown(HintStage, Hint),
+    /// The player has to wait this long before the next press
+    CoolingDown(Duration),
+    /// No technique can find another number
+    NoHint,
+    /// Every hint the puzzle allows has been used
+    Exhausted,
+}
+
+/// The hint being revealed in the current game.
+#[derive(Resource, Default)]
+pub struct HintProgress {
+    current: Option<(HintStage, Hint)>,
+    cooldown: Option<Timer>,
+    stats: HintStats,
+    /// How many hints can be started in the game (unlimited if `None`)
+    limit: Option<u32>,
+}
+
+impl HintProgress {
+    /// Starts a game that allows `limit` hints, or any number if it's `None`.
+    #[must_use]
+    pub fn limited(limit: Option<u32>) -> Self {
+        Self { limit, ..default() }
+    }
+
+    /// Picks up a game that allows `limit` hints after the hints in `stats` have been shown.
+    #[must_use]
+    pub fn resumed(limit: Option<u32>, stats: HintStats) -> Self {
+        Self {
+            stats,
+            limit,
+            ..default()
+        }
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> HintStats {
+        self.stats
+    }
+
+    /// Returns how many more hints can be started, or `None` if there's no limit.
+    #[must_use]
+    pub fn remaining(&self) -> Option<u32> {
+        self.limit
+            .map(|limit| limit.saturating_sub(self.stats.regions))
+    }
+
+    /// Returns true if the game had a limit on hints and they've all been used.
+    #[must_use]
+    pub fn exhausted(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+
+    /// Reveals the next stage of a hint for the grid, starting a new hint once the last one has
+    /// been fully revealed or its cell has been filled. The hint being revealed can always be
+    /// finished, but no new one is started once the limit has been reached.
+    pub fn press(&mut self, grid: &ClassicGrid, settings: &HintSettings) -> HintPress {
+        if let Some(cooldown) = self.cooldown.as_ref().filter(|timer| !timer.finished()) {
+            return HintPress::CoolingDown(cooldown.remaining());
+        }
+        let next = match self.current {
+            Some((HintStage::Region, hint)) if grid.get_by_row_col(hint.position).is_none() => {
+                Some((HintStage::Technique, hint))
+            }
+            Some((HintStage::Technique, hint)) if grid.get_by_row_col(hint.position).is_none() => {
+                Some((HintStage::Digit, hint))
+            }
+            _ if self.exhausted() => return HintPress::Exhausted,
+            _ => find_hint(grid).map(|hint| (HintStage::Region, hint)),
+        };
+        self.current = next;
+        let Some((stage, hint)) = next else {
+            return HintPress::NoHint;
+        };
+        match stage {
+            HintStage::Region => self.stats.regions += 1,
+            HintStage::Technique => self.stats.techniques += 1,
+            HintStage::Digit => self.stats.digits += 1,
+        }
+        self.cooldown = Some(Timer::new(settings.cooldown, TimerMode::Once));
+        HintPress::Shown(stage, hint)
+    }
+}
+
+fn hint_cooldown_system(time: Res<Time>, mut hint_progress: ResMut<HintProgress>) {
+    if let Some(cooldown) = &mut hint_progress.cooldown {
+        cooldown.tick(time.delta());
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::{despawn_component, plugins::nav::NavState, PuzzleSettings};
+
+use super::{cache::PuzzleCache, playable_classic_grid_puzzle, GameState, GameSystems, PuzzleType};
+
+pub fn hyper_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing(PuzzleType::Hyper)), hyper_setup)
+        .add_systems(
+            OnExit(GameState::Playing(PuzzleType::Hyper)),
+            despawn_component::<HyperContainer>,
+        )
+        .add_systems(
+            Update,
+            hyper_action_system
+                .in_set(GameSystems::ApplyEdits)
+                .run_if(in_state(GameState::Playing(PuzzleType::Hyper))),
+        );
+}
+
+#[derive(Component)]
+struct HyperContainer;
+
+// Generate and spawn the board
+fn hyper_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+) {
+    nav_state.set(NavState::Pause);
+    info!("Setting up hyper puzzle!");
+    info!("Seed is {:}", puzzle_settings.seed);
+    let (puzzle, _solution) = puzzle_cache.get_or_generate(&puzzle_settings, || {
+        playable_classic_grid_puzzle(&puzzle_settings)
+    });
+    info!("Finished with {} clues:", puzzle.num_clues());
+    info!("{puzzle:}");
+}
+
+fn hyper_action_system() {}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    despawn_component,
+    plugins::common::theme::{
+        node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+        text::{ThemedFontWeight, ThemedTextColor},
+    },
+    AppState, PuzzleSettings,
+};
+
+use super::{
+    input::{ActionButton, InputAction},
+    timer::{GameTimer, PausesGameTimer},
+    GameState, GameSystems,
+};
+
+pub fn idle_nudge_plugin(app: &mut App) {
+    app.init_resource::<IdleNudgeSettings>()
+        .init_resource::<IdleTime>()
+        .add_systems(
+            Update,
+            (idle_time_system, idle_nudge_dismiss_system)
+                .chain()
+                .in_set(GameSystems::DeriveState)
+                .run_if(in_state(AppState::Game).and(resource_exists::<GameTimer>)),
+        )
+        .add_systems(
+            OnExit(AppState::Game),
+            (idle_time_reset_system, despawn_component::<IdleNudge>),
+        );
+}
+
+/// Whether a nudge is shown after the player hasn't done anything for a while, and how long that
+/// is. The timer is paused while the nudge is shown.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdleNudgeSettings {
+    pub enabled: bool,
+    pub after: Duration,
+}
+
+impl Default for IdleNudgeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// How long the game has been played since the player last did anything.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct IdleTime(Duration);
+
+/// The nudge shown to an idle player, which offers a hint or a break.
+#[derive(Component)]
+#[require(Node, PausesGameTimer)]
+struct IdleNudge;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct IdleNudgeButton;
+
+/// Closes the nudge without doing anything else.
+#[derive(Component)]
+struct IdleNudgeDismissButton;
+
+fn idle_nudge_bundle(offer_hint: bool) -> impl Bundle {
+    let button_bundle = |label: &str| {
+        (
+            IdleNudgeButton,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(20.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    let message = if offer_hint {
+        "Still there? The timer is paused. Want a hint, or a break?"
+    } else {
+        "Still there? The timer is paused, so take a break if you need one."
+    };
+
+    (
+        IdleNudge,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            align_self: AlignSelf::Center,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        GlobalZIndex(10),
+        Children::spawn((
+            Spawn((
+                Text::new(message),
+                TextFont::from_font_size(16.0),
+                ThemedTextColor,
+            )),
+            SpawnIter(
+                offer_hint
+                    .then(|| (ActionButton(InputAction::Hint), button_bundle("Hint")))
+                    .into_iter(),
+            ),
+            Spawn((IdleNudgeDismissButton, button_bundle("Keep going"))),
+        )),
+    )
+}
+
+// Count how long the player has been idle, and show the nudge once it's been long enough. Time
+// only counts while the timer runs, so dialogs and the nudge itself don't add to it.
+#[allow(clippy::too_many_arguments)]
+fn idle_time_system(
+    time: Res<Time>,
+    settings: Res<IdleNudgeSettings>,
+    puzzle_settings: Res<PuzzleSettings>,
+    game_state: Res<State<GameState>>,
+    game_timer: Res<GameTimer>,
+    nudge_query: Query<(), With<IdleNudge>>,
+    mut input_actions: EventReader<InputAction>,
+    mut idle_time: ResMut<IdleTime>,
+    mut commands: Commands,
+) {
+    if input_actions.read().count() > 0 {
+        idle_time.0 = Duration::ZERO;
+        return;
+    }
+    let playing = matches!(game_state.get(), GameState::Playing(_));
+    if !settings.enabled || !playing || game_timer.is_paused() || !nudge_query.is_empty() {
+        return;
+    }
+    idle_time.0 += time.delta();
+    if idle_time.0 >= settings.after {
+        idle_time.0 = Duration::ZERO;
+        commands.spawn(idle_nudge_bundle(
+            puzzle_settings.assist_level.assists().hints,
+        ));
+    }
+}
+
+// Close the nudge when its button is pressed or the player does anything else
+fn idle_nudge_dismiss_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<IdleNudgeDismissButton>)>,
+    nudge_query: Query<Entity, With<IdleNudge>>,
+    mut input_actions: EventReader<InputAction>,
+    mut commands: Commands,
+) {
+    let dismiss_pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !dismiss_pressed && input_actions.read().count() == 0 {
+        return;
+    }
+    for nudge in &nudge_query {
+        commands.entity(nudge).despawn();
+    }
+}
+
+fn idle_time_reset_system(mut idle_time: ResMut<IdleTime>) {
+    *idle_time = IdleTime::default();
+}
@@ -0,0 +1,450 @@
+use std::time::Duration;
+
+use bevy::{
+    input::{
+        gamepad::{Gamepad, GamepadButton},
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    grids::{classic_state::PencilMark, coordinate::CoordinateInput},
+    plugins::{
+        common::{bundles::text_input::TextInputContainer, theme::focus::FocusedEntity},
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    AppState,
+};
+
+use super::{jump::CellJumpSettings, GameSystems};
+
+pub fn input_action_plugin(app: &mut App) {
+    app.add_event::<InputAction>()
+        .init_resource::<KeyBindings>()
+        .init_resource::<DigitFirstSettings>()
+        .init_resource::<ArmedDigit>()
+        .add_systems(
+            Update,
+            (
+                keyboard_action_system,
+                gamepad_action_system,
+                action_button_system,
+            )
+                .in_set(GameSystems::Input)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(
+            Update,
+            (pause_action_system, digit_first_system)
+                .in_set(GameSystems::ApplyEdits)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(OnExit(AppState::Game), armed_digit_reset_system);
+}
+
+/// Something the player asked the game to do. The keyboard, gamepads, and on-screen buttons
+/// (which are also how touches arrive) all send these, so gameplay systems read them instead of
+/// the devices.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputAction {
+    /// Enter a number in the selected cell
+    Digit(u8),
+    /// Clear the selected cell
+    Erase,
+    /// Select the cell at a (row, col)
+    Select((u8, u8)),
+    /// Move the selection by some rows and columns
+    Move {
+        rows: i8,
+        cols: i8,
+    },
+    Hint,
+    /// Tell whether the entries so far are right, without showing which ones aren't
+    Check,
+    Copy,
+    Pause,
+    /// Switch between entering numbers in the selected cell and picking a number to place first
+    ToggleDigitFirst,
+    /// Remove every pencil mark from the board
+    ClearMarks,
+    /// Replace the pencil marks of every empty cell with the numbers it can still take
+    FillMarks,
+    /// Remove a number's pencil marks from the whole board
+    RemoveMarks(u8),
+    /// Add or remove a pencil mark of a kind in the selected cell
+    ToggleMark(PencilMark, u8),
+    /// Print the board on paper, in black and white (on the web)
+    Print,
+    /// Start trying out entries that can be thrown away, from a snapshot of the puzzle
+    OpenSandbox,
+    /// Keep the entries tried out in the sandbox and close it
+    KeepSandbox,
+    /// Go back to the snapshot taken when the sandbox was opened
+    DiscardSandbox,
+    /// Show or hide the stats of the game being played
+    ToggleSessionStats,
+    /// Save the game being played in one of the save slots
+    Save,
+    /// Turn haptic feedback on or off
+    ToggleHaptics,
+}
+
+impl InputAction {
+    /// Returns the action sent instead when an on-screen button for this action is held down:
+    /// holding a number sweeps its pencil marks off the board, and holding erase clears them all.
+    #[must_use]
+    pub fn long_press(self) -> Option<InputAction> {
+        match self {
+            InputAction::Digit(val) => Some(InputAction::RemoveMarks(val)),
+            InputAction::Erase => Some(InputAction::ClearMarks),
+            _ => None,
+        }
+    }
+}
+
+/// How long an on-screen button has to be held down to send its long-press action.
+const LONG_PRESS: Duration = Duration::from_millis(500);
+
+/// Whether numbers are picked before cells. In digit-first mode, a number is armed and then
+/// entered in each cell that's selected, instead of being entered in the selected cell. This is
+/// kept between games.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DigitFirstSettings {
+    pub enabled: bool,
+}
+
+/// The number that's entered in each cell selected in digit-first mode, if one is armed.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArmedDigit(pub Option<u8>);
+
+/// Sends its action when pressed, like the keys of an on-screen keypad.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Button)]
+pub struct ActionButton(pub InputAction);
+
+/// The actions bound to physical keys. Numbers and cell coordinates follow the keyboard layout
+/// instead, since they're typed as characters. A key that types part of a coordinate doesn't send
+/// its action, so the defaults stay off the row labels (A to I) and R.
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings(pub Vec<(KeyCode, InputAction)>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(vec![
+            (KeyCode::ArrowUp, InputAction::Move { rows: -1, cols: 0 }),
+            (KeyCode::ArrowDown, InputAction::Move { rows: 1, cols: 0 }),
+            (KeyCode::ArrowLeft, InputAction::Move { rows: 0, cols: -1 }),
+            (KeyCode::ArrowRight, InputAction::Move { rows: 0, cols: 1 }),
+            (KeyCode::Backspace, InputAction::Erase),
+            (KeyCode::Delete, InputAction::Erase),
+            (KeyCode::Slash, InputAction::Hint),
+            (KeyCode::KeyK, InputAction::Check),
+            (KeyCode::KeyP, InputAction::Pause),
+            (KeyCode::KeyN, InputAction::ToggleDigitFirst),
+            (KeyCode::KeyM, InputAction::FillMarks),
+            (KeyCode::KeyT, InputAction::OpenSandbox),
+            (KeyCode::KeyO, InputAction::ToggleSessionStats),
+            (KeyCode::KeyS, InputAction::Save),
+            (KeyCode::KeyV, InputAction::ToggleHaptics),
+        ])
+    }
+}
+
+impl KeyBindings {
+    /// Returns the action bound to a key, if any.
+    #[must_use]
+    pub fn get(&self, key_code: KeyCode) -> Option<InputAction> {
+        self.0
+            .iter()
+            .find(|(bound_key, _)| *bound_key == key_code)
+            .map(|(_, action)| *action)
+    }
+}
+
+const CONTROL_KEYS: [KeyCode; 4] = [
+    KeyCode::SuperLeft,
+    KeyCode::SuperRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+];
+
+const SHIFT_KEYS: [KeyCode; 2] = [KeyCode::ShiftLeft, KeyCode::ShiftRight];
+
+const NUMPAD_KEYS: [KeyCode; 11] = [
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadDecimal,
+];
+
+/// Returns the action for a number pad key. With Num Lock on, its numbers are entered right away
+/// instead of being read as part of a coordinate, and 0 erases. With Num Lock off, its arrows move
+/// the selection and Ins and Del erase.
+fn numpad_action(key_code: KeyCode, logical_key: &Key) -> Option<InputAction> {
+    if !NUMPAD_KEYS.contains(&key_code) {
+        return None;
+    }
+    match logical_key {
+        Key::Character(input) => match input.chars().next()?.to_digit(10)? {
+            0 => Some(InputAction::Erase),
+            #[allow(clippy::cast_possible_truncation)]
+            digit => Some(InputAction::Digit(digit as u8)),
+        },
+        Key::ArrowUp => Some(InputAction::Move { rows: -1, cols: 0 }),
+        Key::ArrowDown => Some(InputAction::Move { rows: 1, cols: 0 }),
+        Key::ArrowLeft => Some(InputAction::Move { rows: 0, cols: -1 }),
+        Key::ArrowRight => Some(InputAction::Move { rows: 0, cols: 1 }),
+        Key::Insert | Key::Delete => Some(InputAction::Erase),
+        _ => None,
+    }
+}
+
+/// Returns the number of a number key, on the number row or the number pad. Pencil marks go by the
+/// key instead of what it types, since Shift changes what the number row types.
+fn mark_key_val(key_code: KeyCode) -> Option<u8> {
+    let val = match key_code {
+        KeyCode::Digit1 | KeyCode::Numpad1 => 1,
+        KeyCode::Digit2 | KeyCode::Numpad2 => 2,
+        KeyCode::Digit3 | KeyCode::Numpad3 => 3,
+        KeyCode::Digit4 | KeyCode::Numpad4 => 4,
+        KeyCode::Digit5 | KeyCode::Numpad5 => 5,
+        KeyCode::Digit6 | KeyCode::Numpad6 => 6,
+        KeyCode::Digit7 | KeyCode::Numpad7 => 7,
+        KeyCode::Digit8 | KeyCode::Numpad8 => 8,
+        KeyCode::Digit9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    };
+    Some(val)
+}
+
+// Turn key presses into actions. Typed coordinates select their cell, and numbers that aren't
+// part of a coordinate (or are typed on the number pad) are entered. Numbers typed with Shift held
+// toggle corner marks, and with Ctrl or Super held, center marks. Nothing is sent while a text
+// input has focus, and only the copy shortcut counts while Ctrl or Super is held.
+#[allow(clippy::too_many_arguments)]
+fn keyboard_action_system(
+    key_bindings: Res<KeyBindings>,
+    cell_jump_settings: Res<CellJumpSettings>,
+    focused_entity: Res<FocusedEntity>,
+    text_input_query: Query<(), With<TextInputContainer>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+    mut coordinate_input: Local<CoordinateInput>,
+    mut input_actions: EventWriter<InputAction>,
+) {
+    let typing_elsewhere = focused_entity
+        .current
+        .is_some_and(|entity| text_input_query.contains(entity));
+    if typing_elsewhere {
+        keyboard_input_events.clear();
+        coordinate_input.clear();
+        return;
+    }
+    let shortcut = keys.any_pressed(CONTROL_KEYS);
+    let mark_kind = if shortcut {
+        Some(PencilMark::Center)
+    } else if keys.any_pressed(SHIFT_KEYS) {
+        Some(PencilMark::Corner)
+    } else {
+        None
+    };
+    for keyboard_input_event in keyboard_input_events.read() {
+        if keyboard_input_event.state == ButtonState::Released {
+            continue;
+        }
+        if let (Some(kind), Some(val)) = (mark_kind, mark_key_val(keyboard_input_event.key_code)) {
+            coordinate_input.clear();
+            input_actions.write(InputAction::ToggleMark(kind, val));
+            continue;
+        }
+        if shortcut {
+            coordinate_input.clear();
+            if matches!(&keyboard_input_event.logical_key, Key::Character(input) if input == "c") {
+                input_actions.write(InputAction::Copy);
+            }
+            continue;
+        }
+        if let Some(action) = numpad_action(
+            keyboard_input_event.key_code,
+            &keyboard_input_event.logical_key,
+        ) {
+            coordinate_input.clear();
+            input_actions.write(action);
+            continue;
+        }
+        let mut typing_coordinate = false;
+        if let Key::Character(input) = &keyboard_input_event.logical_key {
+            for c in input.chars() {
+                let position = if cell_jump_settings.enabled {
+                    coordinate_input.push(c)
+                } else {
+                    None
+                };
+                if let Some(position) = position {
+                    typing_coordinate = true;
+                    input_actions.write(InputAction::Select(position));
+                } else if coordinate_input.pending().is_empty() {
+                    if let Some(digit) = c.to_digit(10).filter(|&digit| digit != 0) {
+                        #[allow(clippy::cast_possible_truncation)]
+                        input_actions.write(InputAction::Digit(digit as u8));
+                    }
+                } else {
+                    typing_coordinate = true;
+                }
+            }
+        }
+        // Keys that start or continue a coordinate are typing it, so "h5" doesn't also send H's
+        // action
+        if !typing_coordinate {
+            if let Some(action) = key_bindings.get(keyboard_input_event.key_code) {
+                input_actions.write(action);
+            }
+        }
+        match &keyboard_input_event.logical_key {
+            Key::Copy => {
+                input_actions.write(InputAction::Copy);
+            }
+            Key::Escape => coordinate_input.clear(),
+            _ => {}
+        }
+    }
+}
+
+// Turn gamepad buttons into actions: the D-pad moves the selection, and the face buttons erase,
+// hint, and pause
+fn gamepad_action_system(
+    gamepad_query: Query<&Gamepad>,
+    mut input_actions: EventWriter<InputAction>,
+) {
+    let bindings = [
+        (
+            GamepadButton::DPadUp,
+            InputAction::Move { rows: -1, cols: 0 },
+        ),
+        (
+            GamepadButton::DPadDown,
+            InputAction::Move { rows: 1, cols: 0 },
+        ),
+        (
+            GamepadButton::DPadLeft,
+            InputAction::Move { rows: 0, cols: -1 },
+        ),
+        (
+            GamepadButton::DPadRight,
+            InputAction::Move { rows: 0, cols: 1 },
+        ),
+        (GamepadButton::West, InputAction::Erase),
+        (GamepadButton::North, InputAction::Hint),
+        (GamepadButton::Start, InputAction::Pause),
+    ];
+    for gamepad in &gamepad_query {
+        for (button, action) in bindings {
+            if gamepad.just_pressed(button) {
+                input_actions.write(action);
+            }
+        }
+    }
+}
+
+/// The on-screen button being held down that has a long-press action, and for how long.
+#[derive(Default)]
+struct HeldActionButton {
+    entity: Option<Entity>,
+    held: Duration,
+    /// Whether the long-press action was sent, so the button's own action isn't sent on release
+    long_pressed: bool,
+}
+
+// Send the actions of on-screen buttons as they're pressed. Buttons with a long-press action send
+// theirs once held long enough, and otherwise send their own action when released.
+fn action_button_system(
+    time: Res<Time>,
+    interaction_query: Query<(Entity, &Interaction, &ActionButton)>,
+    mut held_button: Local<HeldActionButton>,
+    mut input_actions: EventWriter<InputAction>,
+) {
+    for (entity, interaction, action_button) in &interaction_query {
+        let long_press = action_button.0.long_press();
+        let held = held_button.entity == Some(entity);
+        match (interaction, long_press) {
+            (Interaction::Pressed, None) if !held => {
+                held_button.entity = Some(entity);
+                input_actions.write(action_button.0);
+            }
+            (Interaction::Pressed, Some(_)) if !held => {
+                *held_button = HeldActionButton {
+                    entity: Some(entity),
+                    ..default()
+                };
+            }
+            (Interaction::Pressed, Some(long_press)) => {
+                held_button.held += time.delta();
+                if held_button.held >= LONG_PRESS && !held_button.long_pressed {
+                    held_button.long_pressed = true;
+                    input_actions.write(long_press);
+                }
+            }
+            (Interaction::Hovered | Interaction::None, long_press) if held => {
+                if long_press.is_some() && !held_button.long_pressed {
+                    input_actions.write(action_button.0);
+                }
+                *held_button = HeldActionButton::default();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Pausing leaves the game for the menus, like the pause button
+fn pause_action_system(
+    mut input_actions: EventReader<InputAction>,
+    mut navigator: ResMut<Navigator>,
+) {
+    if input_actions
+        .read()
+        .any(|action| *action == InputAction::Pause)
+    {
+        navigator.replace(Screen::Menu(MenuState::Home));
+    }
+}
+
+// Switch digit-first mode on and off, and arm the numbers pressed while it's on. Pressing the
+// armed number again disarms it.
+fn digit_first_system(
+    mut input_actions: EventReader<InputAction>,
+    mut digit_first_settings: ResMut<DigitFirstSettings>,
+    mut armed_digit: ResMut<ArmedDigit>,
+) {
+    for action in input_actions.read() {
+        match *action {
+            InputAction::ToggleDigitFirst => {
+                digit_first_settings.enabled = !digit_first_settings.enabled;
+                armed_digit.0 = None;
+                let mode = if digit_first_settings.enabled {
+                    "on"
+                } else {
+                    "off"
+                };
+                info!("Digit-first mode is {mode}");
+            }
+            InputAction::Digit(val) if digit_first_settings.enabled => {
+                armed_digit.0 = (armed_digit.0 != Some(val)).then_some(val);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn armed_digit_reset_system(mut armed_digit: ResMut<ArmedDigit>) {
+    *armed_digit = ArmedDigit::default();
+}
@@ -0,0 +1,577 @@
+use bevy::{
+    asset::AssetPlugin,
+    ecs::schedule::ExecutorKind,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState, InputPlugin,
+    },
+    log::{
+        tracing,
+        tracing_subscriber::{layer::SubscriberExt, Registry},
+    },
+    prelude::*,
+    state::app::StatesPlugin,
+};
+
+use crate::{
+    grids::classic_state::{ClassicGridState, PencilMark},
+    plugins::{
+        common::{
+            bundles::text_input::TextInputContainer,
+            clipboard::ClipboardResource,
+            rng::RngService,
+            theme::{focus::FocusedEntity, style::StyleSheet, PrintMode},
+        },
+        console::ConsoleCommands,
+        diagnostics::{diagnostics_log_layer, diagnostics_plugin},
+        loading::LoadingAssets,
+        menu::MenuState,
+        nav::{NavState, Navigator},
+    },
+    AppState, PuzzleSettings,
+};
+
+use super::{
+    classic::ClassicGameState,
+    game_plugin,
+    hint::{HintProgress, HintStats},
+    input::{DigitFirstSettings, InputAction, KeyBindings},
+    jump::SelectedCell,
+    panel::{digit_counts_text, progress_text, DigitCountsText, ProgressText},
+    sandbox::Sandbox,
+    save_slots::{PendingRestore, SaveSlot, SaveSlotDialog},
+    session_stats::SessionStatsSettings,
+    AssistLevel,
+};
+
+/// The keys the driver types, along with what they type.
+const KEYS: [(KeyCode, Option<&str>); 14] = [
+    (KeyCode::KeyR, Some("r")),
+    (KeyCode::KeyC, Some("c")),
+    (KeyCode::KeyD, Some("d")),
+    (KeyCode::KeyH, Some("h")),
+    (KeyCode::KeyI, Some("i")),
+    (KeyCode::KeyX, Some("x")),
+    (KeyCode::Digit0, Some("0")),
+    (KeyCode::Digit1, Some("1")),
+    (KeyCode::Digit4, Some("4")),
+    (KeyCode::Digit7, Some("7")),
+    (KeyCode::Digit9, Some("9")),
+    (KeyCode::Escape, None),
+    (KeyCode::Tab, None),
+    (KeyCode::ControlLeft, None),
+];
+
+const STEPS: usize = 300;
+
+/// Returns an app with the game plugin and the resources it uses from the rest of the app,
+/// playing a classic puzzle.
+fn game_app() -> App {
+    game_app_restoring(None)
+}
+
+/// Returns an app like `game_app`, which picks up a saved game if there is one.
+fn game_app_restoring(slot: Option<&SaveSlot>) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        InputPlugin,
+        AssetPlugin::default(),
+    ))
+    .init_state::<AppState>()
+    .init_state::<NavState>()
+    .insert_resource(PuzzleSettings {
+        seed: "input fuzz".into(),
+        assist_level: AssistLevel::Full,
+        ..default()
+    })
+    .init_resource::<FocusedEntity>()
+    .init_resource::<StyleSheet>()
+    .init_resource::<PrintMode>()
+    .init_resource::<ClipboardResource>()
+    .init_resource::<RngService>()
+    .init_resource::<Navigator>()
+    .init_resource::<LoadingAssets>()
+    .add_plugins(game_plugin);
+    if let Some(slot) = slot {
+        app.world_mut()
+            .resource_scope(|world, mut puzzle_settings: Mut<PuzzleSettings>| {
+                world
+                    .resource_mut::<PendingRestore>()
+                    .start(slot, &mut puzzle_settings);
+            });
+    }
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Game);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert!(
+        app.world().contains_resource::<ClassicGameState>(),
+        "the classic game didn't start"
+    );
+    app
+}
+
+fn keyboard_input(
+    key_code: KeyCode,
+    text: Option<&str>,
+    state: ButtonState,
+    window: Entity,
+) -> KeyboardInput {
+    let logical_key = match (key_code, text) {
+        (_, Some(text)) => Key::Character(text.into()),
+        (KeyCode::Escape, None) => Key::Escape,
+        (KeyCode::Tab, None) => Key::Tab,
+        _ => Key::Control,
+    };
+    KeyboardInput {
+        key_code,
+        logical_key,
+        state,
+        text: text
+            .filter(|_| state == ButtonState::Pressed)
+            .map(Into::into),
+        repeat: false,
+        window,
+    }
+}
+
+/// Does one random thing: presses or releases a key, presses a button, or moves focus.
+fn random_input(rng: &mut fastrand::Rng, app: &mut App, window: Entity, held: &mut Vec<usize>) {
+    let world = app.world_mut();
+    match rng.u8(0..10) {
+        0..=5 => {
+            let key = rng.usize(0..KEYS.len());
+            let (key_code, text) = KEYS[key];
+            let state = if held.contains(&key) {
+                held.retain(|&held_key| held_key != key);
+                ButtonState::Released
+            } else {
+                held.push(key);
+                ButtonState::Pressed
+            };
+            world.send_event(keyboard_input(key_code, text, state, window));
+        }
+        6..=7 => {
+            let buttons: Vec<Entity> = world
+                .query_filtered::<Entity, With<Button>>()
+                .iter(world)
+                .collect();
+            if let Some(&button) = rng.choice(buttons.iter()) {
+                world.entity_mut(button).insert(Interaction::Pressed);
+            }
+        }
+        8 => {
+            let text_inputs: Vec<Entity> = world
+                .query_filtered::<Entity, With<TextInputContainer>>()
+                .iter(world)
+                .collect();
+            let entity = rng.choice(text_inputs.iter()).copied();
+            world.resource_mut::<FocusedEntity>().set(entity);
+        }
+        _ => world.resource_mut::<FocusedEntity>().set(None),
+    }
+}
+
+/// Checks what should hold no matter what was pressed.
+fn check_invariants(app: &mut App, start: &ClassicGridState) {
+    let world = app.world_mut();
+    assert_eq!(*world.resource::<State<AppState>>().get(), AppState::Game);
+
+    let game_state = world.resource::<ClassicGameState>();
+    let grid = game_state.grid;
+    for row in 0..9 {
+        for col in 0..9 {
+            let position = (row, col);
+            assert_eq!(grid.is_given(position), start.is_given(position));
+            if start.is_given(position) {
+                assert_eq!(
+                    grid.grid().get_by_row_col(position),
+                    start.grid().get_by_row_col(position),
+                    "the given at r{}c{} changed",
+                    row + 1,
+                    col + 1
+                );
+            }
+        }
+    }
+
+    let expected_counts = digit_counts_text(grid.grid());
+    for text in world
+        .query_filtered::<&Text, With<DigitCountsText>>()
+        .iter(world)
+    {
+        assert_eq!(text.0, expected_counts);
+    }
+    let expected_progress = progress_text(grid.grid());
+    for text in world
+        .query_filtered::<&Text, With<ProgressText>>()
+        .iter(world)
+    {
+        assert_eq!(text.0, expected_progress);
+    }
+
+    if let Some((row, col)) = world.resource::<SelectedCell>().0 {
+        assert!(row < 9 && col < 9);
+    }
+}
+
+/// Feeds random keyboard input, button presses, and focus changes into the game plugin without a
+/// window, checking that nothing panics and that the game's state stays consistent.
+#[test]
+fn random_input_keeps_the_game_consistent() {
+    for seed in 0..4 {
+        let mut app = game_app();
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().spawn(TextInputContainer {
+            placeholder_text: String::new(),
+            is_empty: true,
+        });
+        let start = app.world().resource::<ClassicGameState>().grid;
+
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut held = Vec::new();
+        for _ in 0..STEPS {
+            for _ in 0..rng.usize(1..4) {
+                random_input(&mut rng, &mut app, window, &mut held);
+            }
+            app.update();
+            // Let go of pressed buttons, like a click does
+            let world = app.world_mut();
+            for mut interaction in world.query::<&mut Interaction>().iter_mut(world) {
+                interaction.set_if_neq(Interaction::None);
+            }
+            check_invariants(&mut app, &start);
+        }
+    }
+}
+
+/// Sends actions straight to the game, the way any input device would, and checks that they select
+/// cells and enter and erase numbers.
+#[test]
+fn input_actions_enter_numbers() {
+    let mut app = game_app();
+    let game_state = app.world().resource::<ClassicGameState>();
+    let (grid, solution) = (game_state.grid, game_state.solution);
+    let cells = (0..9).flat_map(|row| (0..9).map(move |col| (row, col)));
+    let given = cells
+        .clone()
+        .find(|&position| grid.is_given(position))
+        .unwrap();
+    let empty = cells
+        .clone()
+        .find(|&position| !grid.is_given(position))
+        .unwrap();
+    let val = solution.get_by_row_col(empty).unwrap();
+    let cell_val = |app: &App, position| {
+        app.world()
+            .resource::<ClassicGameState>()
+            .grid
+            .grid()
+            .get_by_row_col(position)
+    };
+
+    for action in [InputAction::Select(empty), InputAction::Digit(val)] {
+        app.world_mut().send_event(action);
+    }
+    app.update();
+    assert_eq!(app.world().resource::<SelectedCell>().0, Some(empty));
+    assert_eq!(cell_val(&app, empty), Some(val));
+
+    app.world_mut().send_event(InputAction::Erase);
+    app.update();
+    assert_eq!(cell_val(&app, empty), None);
+
+    // Givens can't be changed, and moving stays on the board
+    for action in [
+        InputAction::Select(given),
+        InputAction::Erase,
+        InputAction::Move { rows: -9, cols: 9 },
+    ] {
+        app.world_mut().send_event(action);
+    }
+    app.update();
+    assert_eq!(cell_val(&app, given), grid.grid().get_by_row_col(given));
+    assert_eq!(app.world().resource::<SelectedCell>().0, Some((0, 8)));
+
+    // In digit-first mode, numbers are armed and then entered in the cells that are selected
+    for action in [InputAction::ToggleDigitFirst, InputAction::Digit(val)] {
+        app.world_mut().send_event(action);
+    }
+    app.update();
+    assert_eq!(cell_val(&app, (0, 8)), grid.grid().get_by_row_col((0, 8)));
+    app.world_mut().send_event(InputAction::Select(empty));
+    app.update();
+    assert_eq!(cell_val(&app, empty), Some(val));
+}
+
+/// Saves a game part of the way through and picks it up in a new app, and checks that the game's
+/// mode, entries, marks, and counts come back with it.
+#[test]
+fn saved_games_pick_up_where_they_left_off() {
+    let mut app = game_app();
+    {
+        let mut puzzle_settings = app.world_mut().resource_mut::<PuzzleSettings>();
+        puzzle_settings.mistake_limit = Some(5);
+        puzzle_settings.strict_entry = true;
+    }
+    let mut game_state = app.world_mut().resource_mut::<ClassicGameState>();
+    let empty = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .find(|&position| !game_state.grid.is_given(position))
+        .unwrap();
+    let val = game_state.solution.get_by_row_col(empty).unwrap();
+    game_state.grid.set(empty, Some(val)).unwrap();
+    game_state
+        .grid
+        .toggle_mark(empty, PencilMark::Corner, 1)
+        .unwrap();
+    game_state.grid.set_mistakes(2);
+    game_state.checks = 3;
+    let hints = HintStats {
+        regions: 1,
+        techniques: 1,
+        digits: 0,
+    };
+    app.world_mut()
+        .insert_resource(HintProgress::resumed(None, hints));
+    for action in [InputAction::Select(empty), InputAction::Save] {
+        app.world_mut().send_event(action);
+    }
+    app.update();
+    let slot = app
+        .world_mut()
+        .query::<&SaveSlotDialog>()
+        .single(app.world())
+        .unwrap()
+        .0
+        .clone()
+        .expect("the game wasn't saved");
+
+    let restored = game_app_restoring(Some(&slot));
+    let puzzle_settings = restored.world().resource::<PuzzleSettings>();
+    assert_eq!(puzzle_settings.assist_level, AssistLevel::Full);
+    assert_eq!(puzzle_settings.mistake_limit, Some(5));
+    assert!(puzzle_settings.strict_entry);
+    let (saved_state, restored_state) = (
+        app.world().resource::<ClassicGameState>(),
+        restored.world().resource::<ClassicGameState>(),
+    );
+    assert_eq!(restored_state.grid, saved_state.grid);
+    assert_eq!(restored_state.grid.mistakes(), 2);
+    assert_eq!(restored_state.checks, 3);
+    assert_eq!(restored.world().resource::<HintProgress>().stats(), hints);
+    assert_eq!(restored.world().resource::<SelectedCell>().0, Some(empty));
+}
+
+/// Fills in cells and solves the grid from the console, and checks that scrubbing back undoes them
+/// like the player's entries.
+#[cfg(debug_assertions)]
+#[test]
+fn console_entries_can_be_scrubbed_back() {
+    let mut app = game_app();
+    let start = app.world().resource::<ClassicGameState>().grid;
+    let run = |app: &mut App, line: &[&str]| {
+        let run = app
+            .world()
+            .resource::<ConsoleCommands>()
+            .get(line[0])
+            .unwrap()
+            .run;
+        run(app.world_mut(), &line[1..])
+    };
+
+    run(&mut app, &["fill-random", "3"]).unwrap();
+    run(&mut app, &["solve"]).unwrap();
+    let game_state = app.world().resource::<ClassicGameState>();
+    assert_eq!(*game_state.grid.grid(), game_state.solution);
+    let made = game_state.moves.len();
+    run(&mut app, &["scrub", "back", &made.to_string()]).unwrap();
+    assert_eq!(
+        app.world().resource::<ClassicGameState>().grid.grid(),
+        start.grid()
+    );
+
+    // Entries that weren't made as moves can't be scrubbed back over
+    let mut game_state = app.world_mut().resource_mut::<ClassicGameState>();
+    let empty = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .find(|&position| !game_state.grid.is_given(position))
+        .unwrap();
+    let val = game_state.solution.get_by_row_col(empty);
+    game_state.grid.set(empty, val).unwrap();
+    assert!(run(&mut app, &["scrub", "back"]).is_err());
+}
+
+/// Types coordinates whose row labels were bound to actions, and checks that they only select
+/// their cells.
+#[test]
+fn typed_coordinates_dont_send_bound_actions() {
+    let mut app = game_app();
+    app.world_mut()
+        .resource_mut::<KeyBindings>()
+        .0
+        .push((KeyCode::KeyH, InputAction::Hint));
+    // The puzzle may not allow any hints, which would hide one being asked for
+    app.insert_resource(HintProgress::default());
+    let window = app.world_mut().spawn_empty().id();
+    let type_text = |app: &mut App, text: &str| {
+        for c in text.chars() {
+            let key_code = match c {
+                'b' => KeyCode::KeyB,
+                'f' => KeyCode::KeyF,
+                'h' => KeyCode::KeyH,
+                'i' => KeyCode::KeyI,
+                '2' => KeyCode::Digit2,
+                '5' => KeyCode::Digit5,
+                '7' => KeyCode::Digit7,
+                _ => unreachable!(),
+            };
+            let text = c.to_string();
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world_mut()
+                    .send_event(keyboard_input(key_code, Some(&text), state, window));
+            }
+        }
+        app.update();
+    };
+
+    type_text(&mut app, "h5");
+    assert_eq!(app.world().resource::<SelectedCell>().0, Some((7, 4)));
+    assert_eq!(
+        app.world().resource::<HintProgress>().stats(),
+        HintStats::default()
+    );
+
+    for (text, position) in [("b5", (1, 4)), ("f2", (5, 1)), ("i7", (8, 6))] {
+        type_text(&mut app, text);
+        assert_eq!(app.world().resource::<SelectedCell>().0, Some(position));
+    }
+    assert!(!app.world().resource::<Sandbox>().open);
+    assert_eq!(
+        *app.world().resource::<DigitFirstSettings>(),
+        DigitFirstSettings::default()
+    );
+    assert!(!app.world().resource::<SessionStatsSettings>().shown);
+}
+
+/// Types numbers with Shift and Ctrl held, and checks that they toggle pencil marks in the
+/// selected cell instead of entering numbers.
+#[test]
+fn modified_numbers_toggle_marks() {
+    let mut app = game_app();
+    let empty = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .find(|&position| {
+            !app.world()
+                .resource::<ClassicGameState>()
+                .grid
+                .is_given(position)
+        })
+        .unwrap();
+    app.world_mut().send_event(InputAction::Select(empty));
+    app.update();
+    // The assists may have filled in center marks already
+    let mut center_marks = app
+        .world()
+        .resource::<ClassicGameState>()
+        .grid
+        .marks(empty, PencilMark::Center);
+    if center_marks.has(3) {
+        center_marks.remove(3);
+    } else {
+        center_marks.insert(3);
+    }
+    let window = app.world_mut().spawn_empty().id();
+    let type_with = |app: &mut App, modifier: KeyCode, key_code: KeyCode, text: &str| {
+        let events = [
+            keyboard_input(modifier, None, ButtonState::Pressed, window),
+            keyboard_input(key_code, Some(text), ButtonState::Pressed, window),
+            keyboard_input(key_code, Some(text), ButtonState::Released, window),
+            keyboard_input(modifier, None, ButtonState::Released, window),
+        ];
+        // The modifier has to be held down before the number is typed
+        for event in events {
+            app.world_mut().send_event(event);
+            app.update();
+        }
+    };
+
+    type_with(&mut app, KeyCode::ShiftLeft, KeyCode::Digit5, "%");
+    type_with(&mut app, KeyCode::ControlLeft, KeyCode::Digit3, "3");
+    type_with(&mut app, KeyCode::ShiftRight, KeyCode::Numpad7, "7");
+    let grid = &app.world().resource::<ClassicGameState>().grid;
+    assert_eq!(grid.grid().get_by_row_col(empty), None);
+    assert_eq!(
+        grid.marks(empty, PencilMark::Corner)
+            .iter()
+            .collect::<Vec<_>>(),
+        vec![5, 7]
+    );
+    assert_eq!(grid.marks(empty, PencilMark::Center), center_marks);
+
+    type_with(&mut app, KeyCode::ShiftLeft, KeyCode::Digit5, "%");
+    let grid = &app.world().resource::<ClassicGameState>().grid;
+    assert_eq!(
+        grid.marks(empty, PencilMark::Corner)
+            .iter()
+            .collect::<Vec<_>>(),
+        vec![7]
+    );
+}
+
+/// Selects a cell and exports a diagnostics report, and checks that the message about the
+/// selection made it into the report's recent logs, along with the game's grid and entries.
+#[test]
+fn gameplay_messages_reach_the_diagnostics_report() {
+    let mut app = game_app();
+    app.init_state::<MenuState>()
+        .add_plugins(diagnostics_plugin);
+    // The log layer is only installed for this thread, so the game's systems have to run on it
+    app.edit_schedule(Update, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    let layer = diagnostics_log_layer(&mut app).unwrap();
+    let window = app.world_mut().spawn_empty().id();
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        app.world_mut().send_event(InputAction::Select((2, 6)));
+        app.update();
+        for (key_code, text) in [
+            (KeyCode::ControlLeft, None),
+            (KeyCode::ShiftLeft, None),
+            (KeyCode::KeyD, Some("d")),
+        ] {
+            app.world_mut().send_event(keyboard_input(
+                key_code,
+                text,
+                ButtonState::Pressed,
+                window,
+            ));
+        }
+        app.update();
+    });
+
+    let mut report = String::new();
+    app.world_mut()
+        .resource_mut::<ClipboardResource>()
+        .native_paste(&mut report);
+    let logs = report
+        .split_once("recent logs:")
+        .expect("the report wasn't copied")
+        .1;
+    assert!(logs.contains("Selected r3c7"), "{report}");
+    let progress = app
+        .world()
+        .resource::<ClassicGameState>()
+        .grid
+        .progress_string();
+    assert!(report.contains("givens:\n"), "{report}");
+    assert!(
+        report.contains(&format!("progress: {progress}\n")),
+        "{report}"
+    );
+}
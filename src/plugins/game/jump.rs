@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{grids::classic_state::PuzzleCellKind, AppState};
+
+use super::{input::InputAction, GameSystems};
+
+pub fn cell_jump_plugin(app: &mut App) {
+    app.init_resource::<CellJumpSettings>()
+        .init_resource::<SelectedCell>()
+        .add_systems(
+            Update,
+            cell_jump_system
+                .in_set(GameSystems::ApplyEdits)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(OnExit(AppState::Game), selected_cell_reset_system);
+}
+
+/// Whether typing a coordinate ("r4c7", or the edge labels like "D7") selects that cell, and
+/// whether moving the selection skips over given cells.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellJumpSettings {
+    pub enabled: bool,
+    pub skip_givens: bool,
+}
+
+impl Default for CellJumpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            skip_givens: false,
+        }
+    }
+}
+
+/// Which cells of the board being played were given, indexed by [row][col]. Puzzle plugins keep
+/// this while their puzzle is played so that selecting and styling cells can tell givens apart.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoardCellKinds(pub [[PuzzleCellKind; 9]; 9]);
+
+impl BoardCellKinds {
+    #[must_use]
+    pub fn get(&self, (row, col): (u8, u8)) -> PuzzleCellKind {
+        self.0[row as usize][col as usize]
+    }
+}
+
+/// The (row, col) of the cell the player is working on, if any.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SelectedCell(pub Option<(u8, u8)>);
+
+/// Returns where moving from a cell by some rows and columns ends up, staying on the board.
+fn step((row, col): (u8, u8), rows: i8, cols: i8) -> (u8, u8) {
+    (
+        row.saturating_add_signed(rows).min(8),
+        col.saturating_add_signed(cols).min(8),
+    )
+}
+
+// Select the cell whose coordinate was typed, or move the selection. Moving without a selection
+// starts from the center. If givens are skipped, moving keeps going the same way past them, and
+// stays put if only givens are left before the edge of the board.
+fn cell_jump_system(
+    cell_jump_settings: Res<CellJumpSettings>,
+    cell_kinds: Option<Res<BoardCellKinds>>,
+    mut input_actions: EventReader<InputAction>,
+    mut selected_cell: ResMut<SelectedCell>,
+) {
+    let skipped = |position| {
+        cell_jump_settings.skip_givens
+            && cell_kinds
+                .as_ref()
+                .is_some_and(|cell_kinds| cell_kinds.get(position) == PuzzleCellKind::Given)
+    };
+    for action in input_actions.read() {
+        let position = match *action {
+            InputAction::Select(position) => position,
+            InputAction::Move { rows, cols } => {
+                let start = selected_cell.0.unwrap_or((4, 4));
+                let mut position = step(start, rows, cols);
+                while skipped(position) && step(position, rows, cols) != position {
+                    position = step(position, rows, cols);
+                }
+                if skipped(position) {
+                    start
+                } else {
+                    position
+                }
+            }
+            _ => continue,
+        };
+        selected_cell.0 = Some(position);
+        info!("Selected r{}c{}", position.0 + 1, position.1 + 1);
+    }
+}
+
+fn selected_cell_reset_system(mut selected_cell: ResMut<SelectedCell>) {
+    *selected_cell = SelectedCell::default();
+}
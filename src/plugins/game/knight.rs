@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::despawn_component;
 
-use super::{GameState, PuzzleType};
+use super::{GameState, GameSystems, PuzzleType};
 
 pub fn knight_plugin(app: &mut App) {
     app.add_systems(
@@ -15,7 +15,9 @@ pub fn knight_plugin(app: &mut App) {
     )
     .add_systems(
         Update,
-        (knight_action_system).run_if(in_state(GameState::Playing(PuzzleType::Knight))),
+        knight_action_system
+            .in_set(GameSystems::ApplyEdits)
+            .run_if(in_state(GameState::Playing(PuzzleType::Knight))),
     );
 }
 
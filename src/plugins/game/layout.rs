@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+pub fn game_layout_plugin(app: &mut App) {
+    app.init_resource::<LayoutSettings>();
+}
+
+/// How the game screen is arranged around the board.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayoutSettings {
+    /// Put the keypad and side panels on the left of the board instead of the right
+    pub left_handed: bool,
+    /// Label the rows (A-I) and columns (1-9) along the edges of the board
+    pub show_coordinates: bool,
+    /// Split an on-screen keypad on either side of the board, with 1-5 on the left and 6-9 on the
+    /// right, so both thumbs can reach it on a tablet
+    pub split_keypad: bool,
+}
+
+impl LayoutSettings {
+    /// Returns the direction to lay out the board followed by its panels.
+    #[must_use]
+    pub fn panel_direction(&self) -> FlexDirection {
+        if self.left_handed {
+            FlexDirection::RowReverse
+        } else {
+            FlexDirection::Row
+        }
+    }
+}
+
+/// Returns the label drawn next to a row when coordinates are shown.
+#[must_use]
+pub fn row_label(row: u8) -> char {
+    char::from(b'A' + row)
+}
+
+/// Returns the label drawn above a column when coordinates are shown.
+#[must_use]
+pub fn col_label(col: u8) -> char {
+    char::from(b'1' + col)
+}
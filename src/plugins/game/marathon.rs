@@ -0,0 +1,289 @@
+use std::time::Duration;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    despawn_component,
+    plugins::{
+        common::{
+            storage,
+            theme::{
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    remove_resource, AppState, PuzzleSettings,
+};
+
+use super::{timer::clock_text, GameState, PuzzleType};
+
+/// How many puzzles a marathon has.
+pub const MARATHON_LENGTH: u8 = 5;
+
+/// How many finished marathons are kept in the history.
+const MARATHON_HISTORY_SIZE: usize = 20;
+
+/// The name the marathon history is saved under.
+const MARATHON_HISTORY_STORAGE_NAME: &str = "marathon history";
+
+pub fn marathon_plugin(app: &mut App) {
+    app.insert_resource(MarathonHistory::load())
+        .add_systems(OnEnter(AppState::Game), marathon_setup)
+        .add_systems(OnExit(AppState::Game), remove_resource::<Marathon>)
+        .add_systems(OnEnter(GameState::MarathonSplit), marathon_split_setup)
+        .add_systems(
+            OnExit(GameState::MarathonSplit),
+            despawn_component::<MarathonSplitContainer>,
+        )
+        .add_systems(
+            Update,
+            marathon_continue_system.run_if(in_state(GameState::MarathonSplit)),
+        );
+}
+
+/// A marathon being played, which times each of its puzzles. Every puzzle's seed comes from the
+/// marathon's seed, so that a marathon can be replayed with it.
+#[derive(Resource)]
+pub struct Marathon {
+    pub length: u8,
+    pub seed: String,
+    /// How long each solved puzzle took, in order
+    pub splits: Vec<Duration>,
+}
+
+impl Marathon {
+    /// Returns the seed of the puzzle at an index of a marathon.
+    #[must_use]
+    pub fn puzzle_seed(seed: &str, index: usize) -> String {
+        format!("{seed} #{}", index + 1)
+    }
+
+    /// Returns how long the solved puzzles took altogether.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.splits.iter().sum()
+    }
+
+    /// Returns true once every puzzle has been solved.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.splits.len() >= usize::from(self.length)
+    }
+}
+
+/// A finished marathon.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarathonResult {
+    pub puzzle_type: PuzzleType,
+    pub seed: String,
+    pub splits: Vec<Duration>,
+}
+
+impl MarathonResult {
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.splits.iter().sum()
+    }
+}
+
+/// The finished marathons, most recent last, which are saved between sessions.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct MarathonHistory {
+    results: Vec<MarathonResult>,
+}
+
+impl MarathonHistory {
+    fn load() -> Self {
+        storage::load(MARATHON_HISTORY_STORAGE_NAME).unwrap_or_default()
+    }
+
+    /// Adds a finished marathon, dropping the oldest once the history is full.
+    pub fn record(&mut self, result: MarathonResult) {
+        self.results.push(result);
+        let overflow = self.results.len().saturating_sub(MARATHON_HISTORY_SIZE);
+        self.results.drain(..overflow);
+        storage::save(MARATHON_HISTORY_STORAGE_NAME, self);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &MarathonResult> {
+        self.results.iter()
+    }
+
+    /// Returns the fastest total time of the marathons of a puzzle type and length.
+    #[must_use]
+    pub fn best_total(&self, puzzle_type: PuzzleType, length: usize) -> Option<Duration> {
+        self.results
+            .iter()
+            .filter(|result| result.puzzle_type == puzzle_type && result.splits.len() == length)
+            .map(MarathonResult::total)
+            .min()
+    }
+}
+
+#[derive(Component)]
+#[require(Node, FocusScope)]
+struct MarathonSplitContainer;
+
+/// Starts the next puzzle, or goes back to the menus after the last one.
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct MarathonContinueButton;
+
+// Start a marathon if one was chosen, beginning with its first puzzle
+fn marathon_setup(mut puzzle_settings: ResMut<PuzzleSettings>, mut commands: Commands) {
+    let Some(length) = puzzle_settings.marathon else {
+        return;
+    };
+    let seed = std::mem::take(&mut puzzle_settings.seed);
+    puzzle_settings.seed = Marathon::puzzle_seed(&seed, 0);
+    commands.insert_resource(Marathon {
+        length,
+        seed,
+        splits: Vec::new(),
+    });
+}
+
+// Show the splits so far between puzzles, recording the marathon once it's finished
+fn marathon_split_setup(
+    marathon: Res<Marathon>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut marathon_history: ResMut<MarathonHistory>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    let finished = marathon.is_finished();
+    let best_before =
+        marathon_history.best_total(puzzle_settings.puzzle_type, marathon.splits.len());
+    if finished {
+        marathon_history.record(MarathonResult {
+            puzzle_type: puzzle_settings.puzzle_type,
+            seed: marathon.seed.clone(),
+            splits: marathon.splits.clone(),
+        });
+    }
+
+    let text_bundle = |text: String, font_size: f32| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(font_size),
+            ThemedTextColor,
+        )
+    };
+    let title = if finished {
+        "Marathon finished!".to_string()
+    } else {
+        format!(
+            "Puzzle {} of {} solved",
+            marathon.splits.len(),
+            marathon.length
+        )
+    };
+    let title_bundle = (
+        Text::new(title),
+        TextFont::from_font_size(style_sheet.heading_font_size),
+        ThemedFontWeight::Bold,
+        ThemedTextColor,
+    );
+    // Each split along with the time of the marathon at that point
+    let split_bundles: Vec<_> = marathon
+        .splits
+        .iter()
+        .scan(Duration::ZERO, |total, &split| {
+            *total += split;
+            Some(*total)
+        })
+        .zip(&marathon.splits)
+        .enumerate()
+        .map(|(index, (total, &split))| {
+            text_bundle(
+                format!(
+                    "Puzzle {}: {} ({})",
+                    index + 1,
+                    clock_text(split),
+                    clock_text(total)
+                ),
+                style_sheet.body_font_size,
+            )
+        })
+        .collect();
+    let total = marathon.total();
+    let total_text = match best_before {
+        Some(best) if finished && total < best => {
+            format!("Total: {} (a new best!)", clock_text(total))
+        }
+        Some(best) if finished => {
+            format!("Total: {} (best {})", clock_text(total), clock_text(best))
+        }
+        _ => format!("Total: {}", clock_text(total)),
+    };
+    let continue_button_bundle = (
+        MarathonContinueButton,
+        Node {
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+            ..default()
+        },
+        children![(
+            Text::new(if finished { "Done" } else { "Next puzzle" }),
+            TextFont::from_font_size(style_sheet.button_font_size),
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )],
+    );
+
+    commands.spawn((
+        MarathonSplitContainer,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(style_sheet.gap),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(title_bundle),
+            SpawnIter(split_bundles.into_iter()),
+            Spawn(text_bundle(total_text, style_sheet.button_font_size)),
+            Spawn(continue_button_bundle),
+        )),
+    ));
+}
+
+// Start the next puzzle of the marathon with the button or Enter, or leave once it's finished
+fn marathon_continue_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MarathonContinueButton>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    marathon: Res<Marathon>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut navigator: ResMut<Navigator>,
+) {
+    let pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !pressed && !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    if marathon.is_finished() {
+        navigator.replace(Screen::Menu(MenuState::Home));
+    } else {
+        puzzle_settings.seed = Marathon::puzzle_seed(&marathon.seed, marathon.splits.len());
+        next_game_state.set(GameState::Playing(puzzle_settings.puzzle_type));
+    }
+}
@@ -1,17 +1,77 @@
 use bevy::prelude::*;
 use indoc::indoc;
 use num_enum::TryFromPrimitive;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
-use crate::{AppState, PuzzleSettings};
+use crate::{
+    grids::classic::ClassicGrid,
+    plugins::common::solver,
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        error::PuzzleError,
+        hint::{challenge_puzzle, practice_puzzle},
+    },
+    AppState, PuzzleSettings,
+};
+use variant::{Overlay, VariantInfo};
 
-mod classic;
+#[cfg(debug_assertions)]
+mod arrow;
+pub mod cache;
+pub mod classic;
+pub mod completion;
 #[cfg(debug_assertions)]
 mod full_kropki;
+pub mod gameplay;
+pub mod haptics;
+pub mod hint;
+#[cfg(debug_assertions)]
+mod hyper;
+pub mod idle;
+pub mod input;
+#[cfg(test)]
+mod input_fuzz;
+pub mod jump;
 #[cfg(debug_assertions)]
 mod knight;
+pub mod layout;
+pub mod marathon;
+pub mod outcome;
+pub mod overlay;
+pub mod pace;
+pub mod panel;
+pub mod practice;
+pub mod pregen;
+pub mod print;
+pub mod progress;
+#[cfg(debug_assertions)]
+mod samurai;
+pub mod sandbox;
+pub mod save_slots;
+pub mod session_stats;
+pub mod shake;
+pub mod share;
+pub mod solve_breakdown;
+pub mod solve_history;
+pub mod timer;
+pub mod tutorial;
+pub mod variant;
 
-#[derive(Default, EnumIter, Display, TryFromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(
+    Default,
+    EnumIter,
+    Display,
+    TryFromPrimitive,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 #[repr(usize)]
 pub enum PuzzleType {
     #[default]
@@ -21,6 +81,12 @@ pub enum PuzzleType {
     #[cfg(debug_assertions)]
     #[strum(to_string = "Full Kropki")]
     FullKropki,
+    #[cfg(debug_assertions)]
+    Samurai,
+    #[cfg(debug_assertions)]
+    Hyper,
+    #[cfg(debug_assertions)]
+    Arrow,
 }
 
 impl PuzzleType {
@@ -41,30 +107,250 @@ impl PuzzleType {
                  • a black dot means one of the numbers is twice the value of the other
                	 • a white dot means the numbers are consecutive
             "},
+            #[cfg(debug_assertions)]
+            PuzzleType::Samurai => indoc! {"
+                Five overlapping 9x9 grids. Classic rules apply to each grid, and the corner boxes \
+                of the center grid are shared with the four outer grids.
+            "},
+            #[cfg(debug_assertions)]
+            PuzzleType::Hyper => indoc! {"
+                Classic rules plus four extra shaded 3x3 windows that must also each contain all \
+                digits 1-9.
+            "},
+            #[cfg(debug_assertions)]
+            PuzzleType::Arrow => indoc! {"
+                Classic rules plus arrows: the digit in each circle must equal the sum of the \
+                digits along its arrow. Digits may repeat along an arrow.
+            "},
         }
         .into()
     }
+
+    /// Returns the name used for the puzzle type in links, like `full-kropki`.
+    #[must_use]
+    pub fn slug(&self) -> String {
+        self.to_string().to_lowercase().replace(' ', "-")
+    }
+}
+
+/// Presets for how much the game helps while solving.
+#[derive(
+    Default,
+    EnumIter,
+    Display,
+    TryFromPrimitive,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[repr(usize)]
+pub enum AssistLevel {
+    #[default]
+    Off,
+    Light,
+    Full,
+}
+
+/// The individual assists that an `AssistLevel` turns on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Assists {
+    /// Fill in the center marks of every empty cell
+    pub auto_candidates: bool,
+    /// Remove a number's pencil marks from a cell's neighbors when it is entered
+    pub smart_erase: bool,
+    /// Highlight entries that don't match the solution
+    pub error_highlighting: bool,
+    /// Allow asking for hints
+    pub hints: bool,
+    /// Allow checking whether the entries so far are right, for a time penalty
+    pub checks: bool,
+    /// Point out where the number armed in digit-first mode can and can't go
+    pub ghost_highlighting: bool,
+    /// Point out pairs of cells in a unit whose center marks are the same two numbers
+    pub pair_highlighting: bool,
+}
+
+impl AssistLevel {
+    #[must_use]
+    pub fn assists(&self) -> Assists {
+        match self {
+            AssistLevel::Off => Assists::default(),
+            AssistLevel::Light => Assists {
+                smart_erase: true,
+                hints: true,
+                checks: true,
+                ..Assists::default()
+            },
+            AssistLevel::Full => Assists {
+                auto_candidates: true,
+                smart_erase: true,
+                error_highlighting: true,
+                hints: true,
+                checks: true,
+                ghost_highlighting: true,
+                pair_highlighting: true,
+            },
+        }
+    }
+}
+
+/// The stages of a frame of play, which run in this order so that each stage sees what the ones
+/// before it did in the same frame instead of the next one.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameSystems {
+    /// Turning device input into `InputAction`s
+    Input,
+    /// Applying actions to the game, like entering numbers and moving the selection
+    ApplyEdits,
+    /// Working out what follows from the edits, like conflicts, completed units, and whether the
+    /// puzzle is solved
+    DeriveState,
+    /// Updating the screen to match
+    Render,
 }
 
 #[derive(Default, Debug, Clone, Hash, PartialEq, Eq, States)]
 pub enum GameState {
     Playing(PuzzleType),
+    /// Between the puzzles of a marathon, showing the times so far
+    MarathonSplit,
+    /// Showing the time of the puzzle that was just solved
+    Solved,
     #[default]
     Disabled,
 }
 
 pub fn game_plugin(app: &mut App) {
     app.init_state::<GameState>()
+        .configure_sets(
+            Update,
+            (
+                GameSystems::Input,
+                GameSystems::ApplyEdits,
+                GameSystems::DeriveState,
+                GameSystems::Render,
+            )
+                .chain(),
+        )
         .add_systems(OnEnter(AppState::Game), game_setup)
         .add_plugins((
+            cache::puzzle_cache_plugin,
+            completion::completion_plugin,
+            variant::variant_registry_plugin,
+            timer::game_timer_plugin,
+            hint::game_hint_plugin,
+            input::input_action_plugin,
+            jump::cell_jump_plugin,
+            layout::game_layout_plugin,
+            marathon::marathon_plugin,
+            panel::side_panel_plugin,
+            practice::practice_plugin,
+            pregen::pregen_plugin,
+            progress::progress_plugin,
+            shake::shake_plugin,
+            share::share_dialog_plugin,
+        ))
+        .add_plugins((
+            gameplay::gameplay_event_plugin,
+            idle::idle_nudge_plugin,
+            outcome::game_outcome_plugin,
+            overlay::board_overlay_plugin,
+            pace::pace_plugin,
+            print::print_plugin,
+            sandbox::sandbox_plugin,
+            solver::solver_plugin,
+            solve_history::solve_history_plugin,
             classic::classic_plugin,
             #[cfg(debug_assertions)]
             full_kropki::full_kropki_plugin,
             #[cfg(debug_assertions)]
             knight::knight_plugin,
+            #[cfg(debug_assertions)]
+            samurai::samurai_plugin,
+            #[cfg(debug_assertions)]
+            hyper::hyper_plugin,
+            #[cfg(debug_assertions)]
+            arrow::arrow_plugin,
+        ))
+        .add_plugins((
+            session_stats::session_stats_plugin,
+            save_slots::save_slots_plugin,
+            tutorial::tutorial_plugin,
+            solve_breakdown::solve_breakdown_plugin,
+            haptics::haptics_plugin,
         ));
 }
 
+/// Returns the rules a puzzle on a classic grid is generated with, which include the rules its
+/// variant adds to the chosen ones.
+#[must_use]
+pub fn classic_grid_rules(settings: &PuzzleSettings) -> ClassicRules {
+    let variant = VariantInfo::of(settings.puzzle_type);
+    ClassicRules {
+        hyper_windows: settings.rules.hyper_windows || variant.overlays.contains(&Overlay::Windows),
+        ..settings.rules
+    }
+}
+
+/// Generates the puzzle and its solution for puzzle types that are played on a classic grid.
+/// Returns `None` for other puzzle types, and an error if a practice puzzle couldn't be found.
+pub fn generate_classic_grid_puzzle(
+    settings: &PuzzleSettings,
+) -> Result<Option<(ClassicPuzzle, ClassicGrid)>, PuzzleError> {
+    if !VariantInfo::of(settings.puzzle_type).classic_grid {
+        return Ok(None);
+    }
+    let rules = classic_grid_rules(settings);
+    let mut puzzle = match settings.practice {
+        Some(technique) if settings.puzzle_type == PuzzleType::Classic => {
+            practice_puzzle(technique, &settings.seed)?
+        }
+        _ => ClassicPuzzle::from_seed_with_rules(settings.seed.clone(), rules),
+    };
+    let solution = puzzle
+        .solution()
+        .ok_or(PuzzleError::NotWellPosed { solutions: 0 })?;
+    if settings.puzzle_type == PuzzleType::Classic {
+        for level in 1..=settings.challenge {
+            let seed = format!("{}-challenge-{level}", settings.seed);
+            let Some(harder) = challenge_puzzle(&puzzle, &solution, &seed) else {
+                break;
+            };
+            puzzle = harder;
+        }
+    }
+    Ok(Some(match settings.remix {
+        Some(transform) => (
+            ClassicPuzzle::from_grid_with_rules(transform.apply(puzzle.grid()), rules),
+            transform.apply(&solution),
+        ),
+        None => (puzzle, solution),
+    }))
+}
+
+/// Generates the puzzle and its solution for a puzzle type that's played on a classic grid, for
+/// starting it. If the puzzle couldn't be generated, the error is printed and a regular puzzle is
+/// generated from the same seed instead.
+#[must_use]
+pub fn playable_classic_grid_puzzle(settings: &PuzzleSettings) -> (ClassicPuzzle, ClassicGrid) {
+    let generated = generate_classic_grid_puzzle(settings).or_else(|err| {
+        warn!("Couldn't generate the puzzle, so a regular one is played instead: {err}");
+        generate_classic_grid_puzzle(&PuzzleSettings {
+            practice: None,
+            ..settings.clone()
+        })
+    });
+    match generated {
+        Ok(Some(generated)) => generated,
+        _ => panic!("{} isn't played on a classic grid", settings.puzzle_type),
+    }
+}
+
 fn game_setup(
     mut next_game_state: ResMut<NextState<GameState>>,
     puzzle_settings: ResMut<PuzzleSettings>,
@@ -0,0 +1,97 @@
+use std::{fmt::Display, time::Duration};
+
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    plugins::{
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    AppState,
+};
+
+use super::{GameState, PuzzleType};
+
+/// Sends `GameOutcome::Abandoned` when the game is left while a puzzle is unfinished, and goes
+/// back to the menus when a puzzle is failed. Puzzle plugins send the other outcomes.
+pub fn game_outcome_plugin(app: &mut App) {
+    app.add_event::<GameOutcome>()
+        .init_resource::<PuzzleEnded>()
+        .add_systems(OnExit(AppState::Game), game_abandoned_system)
+        .add_systems(PostUpdate, game_outcome_system);
+    for puzzle_type in PuzzleType::iter() {
+        app.add_systems(
+            OnEnter(GameState::Playing(puzzle_type)),
+            puzzle_ended_reset_system,
+        );
+    }
+}
+
+/// Why a puzzle was failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailReason {
+    /// The mistake limit was reached
+    TooManyMistakes,
+    /// A blitz game's countdown ran out, scoring a point for each correct entry
+    TimeUp { score: usize },
+}
+
+impl Display for FailReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailReason::TooManyMistakes => write!(f, "Too many mistakes!"),
+            FailReason::TimeUp { score } => write!(f, "Time's up! You scored {score} points."),
+        }
+    }
+}
+
+/// How the puzzle being played ended. Everything that happens at the end of a puzzle (recording
+/// the solve, showing the completion screen, going back to the menus) reads these instead of
+/// checking the puzzle itself.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    /// The puzzle was solved, scoring a point for each cell the player filled.
+    Completed {
+        time: Duration,
+        score: usize,
+    },
+    Failed {
+        reason: FailReason,
+    },
+    /// The game was left before the puzzle ended
+    Abandoned,
+}
+
+/// Whether the puzzle being played has had an outcome.
+#[derive(Resource, Default)]
+struct PuzzleEnded(bool);
+
+fn puzzle_ended_reset_system(mut puzzle_ended: ResMut<PuzzleEnded>) {
+    puzzle_ended.0 = false;
+}
+
+// Note which puzzles have ended, and leave failed puzzles for the menus
+fn game_outcome_system(
+    mut outcomes: EventReader<GameOutcome>,
+    mut puzzle_ended: ResMut<PuzzleEnded>,
+    mut navigator: ResMut<Navigator>,
+) {
+    for outcome in outcomes.read() {
+        puzzle_ended.0 = true;
+        if let GameOutcome::Failed { reason } = outcome {
+            info!("{reason}");
+            navigator.replace(Screen::Menu(MenuState::Home));
+        }
+    }
+}
+
+fn game_abandoned_system(
+    game_state: Res<State<GameState>>,
+    puzzle_ended: Res<PuzzleEnded>,
+    mut outcomes: EventWriter<GameOutcome>,
+) {
+    if matches!(game_state.get(), GameState::Playing(_)) && !puzzle_ended.0 {
+        outcomes.write(GameOutcome::Abandoned);
+    }
+}
@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::UiSystem};
+
+use crate::{
+    plugins::common::theme::{
+        color::{ThemeColorRole, ThemedColor},
+        node::ThemedBorderColor,
+    },
+    puzzles::arrow::Arrow,
+};
+
+/// How wide a Kropki dot is, as a fraction of a cell's width.
+const DOT_SIZE: f32 = 0.3;
+
+/// How thick cage edges and arrow segments are, as a fraction of a cell's width.
+const LINE_WIDTH: f32 = 0.06;
+
+/// How far a cage edge is drawn inside its cell's side, as a fraction of a cell's width, so that
+/// the edges of neighboring cages don't touch.
+const CAGE_INSET: f32 = 0.1;
+
+/// How many marks an arrow segment is drawn with. Segments are drawn as a row of square marks,
+/// since UI nodes can't be rotated to follow the diagonal ones.
+const ARROW_SEGMENT_MARKS: usize = 6;
+
+/// Positions the decorations of every `BoardOverlay` over its board's cells, again whenever the
+/// cells are laid out, e.g. after the window is resized.
+pub fn board_overlay_plugin(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        board_overlay_layout_system.after(UiSystem::Layout),
+    );
+}
+
+/// A cell of a board that overlays can be drawn over, at a (row, col).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardCell(pub (u8, u8));
+
+/// A side of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CellSide {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// Something drawn across cell boundaries by a variant, anchored to cells by their (row, col).
+/// The decorations are drawn with the theme's text color, except for white dots, which are
+/// drawn with its background color.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(Node, Pickable::IGNORE)]
+pub enum OverlayDecoration {
+    /// A Kropki dot on the boundary between two neighboring cells, which is filled (black) or not
+    /// (white)
+    Dot {
+        cells: ((u8, u8), (u8, u8)),
+        filled: bool,
+    },
+    /// One side of a cell on the outline of a killer cage
+    CageEdge { cell: (u8, u8), side: CellSide },
+    /// Part of an arrow or thermometer, from the center of a cell to the center of another
+    ArrowSegment { from: (u8, u8), to: (u8, u8) },
+}
+
+/// The layer that draws a board's decorations. It covers the board it's a child of, and its
+/// decorations are positioned over the `BoardCell`s below that board.
+#[derive(Component, Default)]
+#[require(Node, Pickable::IGNORE)]
+pub struct BoardOverlay;
+
+/// A mark of an arrow segment, positioned along the segment by its index.
+#[derive(Component)]
+#[require(Node, Pickable::IGNORE)]
+struct ArrowSegmentMark(usize);
+
+/// Returns the segments of arrows, from each arrow's circle along its path.
+#[must_use]
+pub fn arrow_decorations(arrows: &[Arrow]) -> Vec<OverlayDecoration> {
+    arrows
+        .iter()
+        .flat_map(|arrow| {
+            std::iter::once(arrow.circle)
+                .chain(arrow.path.iter().copied())
+                .collect::<Vec<_>>()
+                .windows(2)
+                .map(|pair| OverlayDecoration::ArrowSegment {
+                    from: pair[0],
+                    to: pair[1],
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns a layer drawing decorations over a board. Spawn it as the last child of the board so
+/// that it's drawn over the cells.
+#[must_use]
+pub fn board_overlay_bundle(
+    decorations: impl IntoIterator<Item = OverlayDecoration>,
+) -> impl Bundle {
+    let decoration_bundles: Vec<_> = decorations
+        .into_iter()
+        .map(|decoration| {
+            let marks = match decoration {
+                OverlayDecoration::ArrowSegment { .. } => ARROW_SEGMENT_MARKS,
+                _ => 0,
+            };
+            let (role, border) = match decoration {
+                OverlayDecoration::Dot { filled: false, .. } => (ThemeColorRole::Background, 1.0),
+                _ => (ThemeColorRole::Text, 0.0),
+            };
+            let mark_bundles = (0..marks).map(|index| {
+                (
+                    ArrowSegmentMark(index),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    ThemedColor(ThemeColorRole::Text),
+                )
+            });
+            (
+                decoration,
+                Node {
+                    position_type: PositionType::Absolute,
+                    border: UiRect::all(Val::Px(border)),
+                    ..default()
+                },
+                BorderRadius::MAX,
+                BorderColor::default(),
+                ThemedBorderColor,
+                ThemedColor(role),
+                Children::spawn(SpawnIter(mark_bundles.collect::<Vec<_>>().into_iter())),
+            )
+        })
+        .collect();
+
+    (
+        BoardOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        ZIndex(1),
+        Children::spawn(SpawnIter(decoration_bundles.into_iter())),
+    )
+}
+
+/// Where a cell is in its overlay, in logical pixels from the overlay's top left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellRect {
+    center: Vec2,
+    size: f32,
+}
+
+/// Returns where a decoration is drawn, as the center and size of its node in logical pixels from
+/// the overlay's top left corner, or `None` if a cell it's anchored to hasn't been laid out. Arrow
+/// segments are empty nodes at the center of their first cell, with their marks drawn out from
+/// there by `arrow_mark_rect`.
+fn decoration_rect(
+    decoration: OverlayDecoration,
+    cells: &HashMap<(u8, u8), CellRect>,
+) -> Option<(Vec2, Vec2)> {
+    match decoration {
+        OverlayDecoration::Dot {
+            cells: (first, second),
+            ..
+        } => {
+            let (first, second) = (cells.get(&first)?, cells.get(&second)?);
+            let size = Vec2::splat(first.size * DOT_SIZE);
+            Some(((first.center + second.center) / 2.0, size))
+        }
+        OverlayDecoration::CageEdge { cell, side } => {
+            let cell = cells.get(&cell)?;
+            let offset = cell.size / 2.0 - cell.size * CAGE_INSET;
+            let length = cell.size - 2.0 * cell.size * CAGE_INSET;
+            let width = cell.size * LINE_WIDTH;
+            let (center, size) = match side {
+                CellSide::Top => (Vec2::new(0.0, -offset), Vec2::new(length, width)),
+                CellSide::Bottom => (Vec2::new(0.0, offset), Vec2::new(length, width)),
+                CellSide::Left => (Vec2::new(-offset, 0.0), Vec2::new(width, length)),
+                CellSide::Right => (Vec2::new(offset, 0.0), Vec2::new(width, length)),
+            };
+            Some((cell.center + center, size))
+        }
+        OverlayDecoration::ArrowSegment { from, to } => {
+            cells.get(&to)?;
+            Some((cells.get(&from)?.center, Vec2::ZERO))
+        }
+    }
+}
+
+/// Returns where a mark of an arrow segment is drawn, as the center and size of its node in logical
+/// pixels from the center of the segment's first cell. The marks are spread evenly between the two
+/// centers.
+fn arrow_mark_rect(from: CellRect, to: CellRect, index: usize) -> (Vec2, Vec2) {
+    let along = (index as f32 + 0.5) / ARROW_SEGMENT_MARKS as f32;
+    let width = from.size * LINE_WIDTH;
+    ((to.center - from.center) * along, Vec2::splat(width * 1.5))
+}
+
+/// Sets a node to a rectangle, given by its center and size in logical pixels.
+fn place(node: &mut Node, center: Vec2, size: Vec2) {
+    let left = Val::Px(center.x - size.x / 2.0);
+    let top = Val::Px(center.y - size.y / 2.0);
+    let (width, height) = (Val::Px(size.x), Val::Px(size.y));
+    // Only touch the node when it moves, so that the layout isn't redone every frame
+    if (node.left, node.top, node.width, node.height) != (left, top, width, height) {
+        node.left = left;
+        node.top = top;
+        node.width = width;
+        node.height = height;
+    }
+}
+
+// Position the decorations over their cells once the cells have been laid out
+#[allow(clippy::type_complexity)]
+fn board_overlay_layout_system(
+    overlay_query: Query<
+        (&ComputedNode, &GlobalTransform, &ChildOf, &Children),
+        With<BoardOverlay>,
+    >,
+    changed_cell_query: Query<(), (With<BoardCell>, Changed<ComputedNode>)>,
+    added_overlay_query: Query<(), Added<BoardOverlay>>,
+    cell_query: Query<(Entity, &BoardCell, &ComputedNode, &GlobalTransform)>,
+    parent_query: Query<&ChildOf>,
+    mut decoration_query: Query<(&OverlayDecoration, &mut Node, Option<&Children>)>,
+    mut mark_query: Query<(&ArrowSegmentMark, &mut Node), Without<OverlayDecoration>>,
+) {
+    if changed_cell_query.is_empty() && added_overlay_query.is_empty() {
+        return;
+    }
+    for (overlay_node, overlay_transform, board, decorations) in &overlay_query {
+        // Layout positions are in physical pixels, while node offsets are in logical ones
+        let inverse_scale_factor = overlay_node.inverse_scale_factor();
+        let overlay_top_left =
+            overlay_transform.translation().truncate() - overlay_node.size() / 2.0;
+        let cells: HashMap<(u8, u8), CellRect> = cell_query
+            .iter()
+            .filter(|(cell, ..)| {
+                parent_query
+                    .iter_ancestors(*cell)
+                    .any(|ancestor| ancestor == board.parent())
+            })
+            .map(|(_, BoardCell(position), cell_node, cell_transform)| {
+                let center = cell_transform.translation().truncate() - overlay_top_left;
+                let rect = CellRect {
+                    center: center * inverse_scale_factor,
+                    size: cell_node.size().x * inverse_scale_factor,
+                };
+                (*position, rect)
+            })
+            .collect();
+
+        for &decoration_entity in decorations {
+            let Ok((decoration, mut node, marks)) = decoration_query.get_mut(decoration_entity)
+            else {
+                continue;
+            };
+            let Some((center, size)) = decoration_rect(*decoration, &cells) else {
+                continue;
+            };
+            place(&mut node, center, size);
+            let OverlayDecoration::ArrowSegment { from, to } = *decoration else {
+                continue;
+            };
+            let (Some(from), Some(to)) = (cells.get(&from), cells.get(&to)) else {
+                continue;
+            };
+            for &mark in marks.into_iter().flatten() {
+                let Ok((ArrowSegmentMark(index), mut mark_node)) = mark_query.get_mut(mark) else {
+                    continue;
+                };
+                let (center, size) = arrow_mark_rect(*from, *to, *index);
+                place(&mut mark_node, center, size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the cells of a row of a board with 20 pixel cells, starting at the top left corner.
+    fn cells() -> HashMap<(u8, u8), CellRect> {
+        (0..9)
+            .map(|col| {
+                let rect = CellRect {
+                    center: Vec2::new(10.0 + 20.0 * f32::from(col), 10.0),
+                    size: 20.0,
+                };
+                ((0, col), rect)
+            })
+            .collect()
+    }
+
+    fn assert_rect_eq(rect: Option<(Vec2, Vec2)>, center: Vec2, size: Vec2) {
+        let (actual_center, actual_size) = rect.unwrap();
+        assert!(
+            actual_center.abs_diff_eq(center, 1e-4) && actual_size.abs_diff_eq(size, 1e-4),
+            "{actual_center} {actual_size} isn't {center} {size}"
+        );
+    }
+
+    #[test]
+    fn test_dot_between_cells() {
+        let dot = OverlayDecoration::Dot {
+            cells: ((0, 1), (0, 2)),
+            filled: true,
+        };
+        assert_rect_eq(
+            decoration_rect(dot, &cells()),
+            Vec2::new(40.0, 10.0),
+            Vec2::splat(6.0),
+        );
+        // Cells that haven't been laid out have nowhere to draw
+        let dot = OverlayDecoration::Dot {
+            cells: ((0, 8), (1, 8)),
+            filled: false,
+        };
+        assert_eq!(decoration_rect(dot, &cells()), None);
+    }
+
+    #[test]
+    fn test_cage_edges_are_inset() {
+        let edge =
+            |side| decoration_rect(OverlayDecoration::CageEdge { cell: (0, 0), side }, &cells());
+        assert_rect_eq(
+            edge(CellSide::Top),
+            Vec2::new(10.0, 2.0),
+            Vec2::new(16.0, 1.2),
+        );
+        assert_rect_eq(
+            edge(CellSide::Bottom),
+            Vec2::new(10.0, 18.0),
+            Vec2::new(16.0, 1.2),
+        );
+        assert_rect_eq(
+            edge(CellSide::Left),
+            Vec2::new(2.0, 10.0),
+            Vec2::new(1.2, 16.0),
+        );
+        assert_rect_eq(
+            edge(CellSide::Right),
+            Vec2::new(18.0, 10.0),
+            Vec2::new(1.2, 16.0),
+        );
+    }
+
+    #[test]
+    fn test_arrow_segment_marks() {
+        let cells = cells();
+        let segment = OverlayDecoration::ArrowSegment {
+            from: (0, 0),
+            to: (0, 3),
+        };
+        assert_rect_eq(
+            decoration_rect(segment, &cells),
+            Vec2::new(10.0, 10.0),
+            Vec2::ZERO,
+        );
+        let (from, to) = (cells[&(0, 0)], cells[&(0, 3)]);
+        let marks: Vec<_> = (0..ARROW_SEGMENT_MARKS)
+            .map(|index| arrow_mark_rect(from, to, index))
+            .collect();
+        // The marks are spread evenly from the first center to the second, without reaching either
+        assert_rect_eq(Some(marks[0]), Vec2::new(5.0, 0.0), Vec2::splat(1.8));
+        assert_rect_eq(Some(marks[5]), Vec2::new(55.0, 0.0), Vec2::splat(1.8));
+    }
+
+    #[test]
+    fn test_arrow_decorations() {
+        let arrow = Arrow {
+            circle: (0, 0),
+            path: vec![(0, 1), (1, 2)],
+        };
+        assert_eq!(
+            arrow_decorations(&[arrow]),
+            vec![
+                OverlayDecoration::ArrowSegment {
+                    from: (0, 0),
+                    to: (0, 1),
+                },
+                OverlayDecoration::ArrowSegment {
+                    from: (0, 1),
+                    to: (1, 2),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    plugins::common::theme::color::{ThemeColorRole, ThemedColor},
+    PuzzleSettings,
+};
+
+use super::{solve_history::SolveHistory, timer::GameTimer, GameSystems, PuzzleType};
+
+pub fn pace_plugin(app: &mut App) {
+    app.init_resource::<PaceSettings>().add_systems(
+        Update,
+        pace_bar_system
+            .in_set(GameSystems::Render)
+            .run_if(resource_exists::<GameTimer>),
+    );
+}
+
+/// What the pace bar under the timer shows, for speed solvers trying to beat their best time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaceIndicator {
+    #[default]
+    Off,
+    /// Fills up and starts over every period, like a silent metronome
+    Tick(Duration),
+    /// Fills up as the time nears the best time for the puzzle type, and turns red once it's
+    /// passed
+    PersonalBest,
+}
+
+/// The pace indicator chosen for each puzzle type. Puzzle types that aren't listed don't show one.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PaceSettings(pub HashMap<PuzzleType, PaceIndicator>);
+
+impl PaceSettings {
+    #[must_use]
+    pub fn get(&self, puzzle_type: PuzzleType) -> PaceIndicator {
+        self.0.get(&puzzle_type).copied().unwrap_or_default()
+    }
+}
+
+/// The track of the pace bar, which is hidden when there's no pace to show.
+#[derive(Component)]
+#[require(Node)]
+struct PaceBar;
+
+#[derive(Component)]
+#[require(Node)]
+struct PaceBarFill;
+
+/// Returns the pace bar. The side panel shows it under the timer.
+#[must_use]
+pub fn pace_bar_bundle() -> impl Bundle {
+    (
+        PaceBar,
+        Node {
+            display: Display::None,
+            width: Val::Percent(100.0),
+            height: Val::Px(4.0),
+            margin: UiRect::vertical(Val::Px(4.0)),
+            ..default()
+        },
+        ThemedColor(ThemeColorRole::Surface),
+        children![(
+            PaceBarFill,
+            Node {
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            ThemedColor(ThemeColorRole::Accent),
+        )],
+    )
+}
+
+/// Returns how full the pace bar is, from 0 to 1, and whether the best time has been passed. Time
+/// doesn't count towards a best time during blitz games, so they only tick.
+fn pace(
+    indicator: PaceIndicator,
+    game_timer: &GameTimer,
+    best: Option<Duration>,
+) -> Option<(f32, bool)> {
+    let elapsed = game_timer.elapsed();
+    match indicator {
+        PaceIndicator::Off => None,
+        PaceIndicator::Tick(period) if !period.is_zero() => {
+            let into_period = elapsed.as_secs_f32() % period.as_secs_f32();
+            Some((into_period / period.as_secs_f32(), false))
+        }
+        PaceIndicator::Tick(_) => None,
+        PaceIndicator::PersonalBest => {
+            let best = best.filter(|best| !best.is_zero() && game_timer.remaining().is_none())?;
+            Some((
+                (elapsed.as_secs_f32() / best.as_secs_f32()).min(1.0),
+                elapsed > best,
+            ))
+        }
+    }
+}
+
+// Fill the pace bar, and show it only when the puzzle type has a pace to show
+fn pace_bar_system(
+    pace_settings: Res<PaceSettings>,
+    puzzle_settings: Res<PuzzleSettings>,
+    solve_history: Res<SolveHistory>,
+    game_timer: Res<GameTimer>,
+    mut bar_query: Query<&mut Node, (With<PaceBar>, Without<PaceBarFill>)>,
+    mut fill_query: Query<(&mut Node, &mut ThemedColor), With<PaceBarFill>>,
+) {
+    let puzzle_type = puzzle_settings.puzzle_type;
+    let indicator = pace_settings.get(puzzle_type);
+    let best = (indicator == PaceIndicator::PersonalBest)
+        .then(|| solve_history.fastest_time(puzzle_type, puzzle_settings.assist_level))
+        .flatten();
+    let shown = pace(indicator, &game_timer, best);
+    let display = if shown.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in &mut bar_query {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    let Some((filled, behind)) = shown else {
+        return;
+    };
+    let role = if behind {
+        ThemeColorRole::Danger
+    } else {
+        ThemeColorRole::Accent
+    };
+    for (mut node, mut color) in &mut fill_query {
+        node.width = Val::Percent(filled * 100.0);
+        if color.0 != role {
+            color.0 = role;
+        }
+    }
+}
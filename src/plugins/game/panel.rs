@@ -0,0 +1,448 @@
+use std::time::Duration;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    grids::classic::ClassicGrid,
+    plugins::common::theme::{
+        focus::FocusScope,
+        icon::{icon_bundle, Icon, IconId},
+        node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+        style::StyleSheet,
+        text::{ThemedFontWeight, ThemedTextColor},
+        PrintMode,
+    },
+};
+
+use super::{
+    input::{ActionButton, InputAction},
+    layout::LayoutSettings,
+    marathon::Marathon,
+    pace::pace_bar_bundle,
+    progress::UnitCompletedText,
+    shake::BoardShake,
+    timer::{write_clock_text, GameTimer, TimerDisplaySettings},
+    GameSystems,
+};
+
+pub fn side_panel_plugin(app: &mut App) {
+    app.init_resource::<SidePanelSettings>().add_systems(
+        Update,
+        (
+            side_panel_toggle_system,
+            side_panel_collapse_system,
+            split_keypad_system,
+            print_view_system.run_if(resource_changed::<PrintMode>),
+            timer_text_system.run_if(resource_exists::<GameTimer>),
+        )
+            .in_set(GameSystems::Render),
+    );
+}
+
+/// Whether the side panel is collapsed. This is kept between games.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SidePanelSettings {
+    pub collapsed: bool,
+}
+
+/// The panel next to the board holding the timer, digit counters, hint button, and seed
+#[derive(Component)]
+#[require(ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect)]
+struct SidePanel;
+
+/// The contents of the side panel, which are hidden when it's collapsed
+#[derive(Component)]
+struct SidePanelBody;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect,
+    FocusScope
+)]
+struct PanelButton;
+
+#[derive(Component)]
+struct SidePanelToggle;
+
+#[derive(Component)]
+struct SidePanelToggleIcon;
+
+#[derive(Component)]
+struct TimerText;
+
+/// The text listing how many of each digit are left to place. The puzzle's plugin keeps it up to
+/// date with `digit_counts_text`.
+#[derive(Component)]
+pub struct DigitCountsText;
+
+/// The text showing how many cells are filled in. The puzzle's plugin keeps it up to date with
+/// `progress_text`.
+#[derive(Component)]
+pub struct ProgressText;
+
+/// One side of the split keypad, which is only shown when `LayoutSettings::split_keypad` is on.
+#[derive(Component)]
+struct SplitKeypadHalf;
+
+/// Asks for the next stage of a hint when pressed.
+#[derive(Component)]
+pub struct HintButton;
+
+/// The hint button's label, which the puzzle's plugin keeps up to date with `hint_button_text`.
+#[derive(Component)]
+pub struct HintButtonText;
+
+/// Opens the share dialog when pressed.
+#[derive(Component)]
+pub struct ShareDialogOpenButton;
+
+/// Returns the spot for the board, which grows to fill the space the side panel doesn't use, with
+/// the halves of the split keypad on either side of it, followed by the side panel.
+pub fn board_and_side_panel_bundle(
+    board: impl Bundle,
+    seed: &str,
+    layout_settings: &LayoutSettings,
+    style_sheet: &StyleSheet,
+) -> impl Bundle {
+    let body_font_size = style_sheet.body_font_size;
+    let button_node = Node {
+        justify_content: JustifyContent::Center,
+        padding: UiRect::all(Val::Px(5.0)),
+        margin: UiRect::top(Val::Px(10.0)),
+        ..default()
+    };
+    let button_text = |text: &str| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(body_font_size),
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )
+    };
+
+    let toggle_bundle = (
+        PanelButton,
+        SidePanelToggle,
+        Node {
+            align_self: AlignSelf::End,
+            padding: UiRect::axes(Val::Px(style_sheet.button_padding), Val::Px(4.0)),
+            ..default()
+        },
+        children![(SidePanelToggleIcon, icon_bundle(IconId::ArrowLeft, 30.0))],
+    );
+
+    let body_bundle = (
+        SidePanelBody,
+        Node {
+            flex_direction: FlexDirection::Column,
+            width: Val::Px(style_sheet.side_panel_width),
+            ..default()
+        },
+        children![
+            (
+                TimerText,
+                Text::default(),
+                TextFont::from_font_size(style_sheet.heading_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            ),
+            pace_bar_bundle(),
+            (
+                ProgressText,
+                Text::default(),
+                TextFont::from_font_size(body_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            ),
+            (
+                UnitCompletedText::default(),
+                Text::default(),
+                TextFont::from_font_size(body_font_size),
+                ThemedTextColor,
+            ),
+            (
+                DigitCountsText,
+                Text::default(),
+                TextFont::from_font_size(body_font_size),
+                ThemedTextColor,
+                Node {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                },
+            ),
+            (
+                PanelButton,
+                HintButton,
+                ActionButton(InputAction::Hint),
+                button_node.clone(),
+                children![(HintButtonText, button_text("Hint"))],
+            ),
+            (
+                PanelButton,
+                ActionButton(InputAction::Check),
+                button_node.clone(),
+                children![button_text("Check")],
+            ),
+            (
+                PanelButton,
+                ActionButton(InputAction::OpenSandbox),
+                button_node.clone(),
+                children![button_text("What if?")],
+            ),
+            (
+                Text::new(format!("Seed: {seed}")),
+                TextFont::from_font_size(body_font_size),
+                ThemedTextColor,
+                Node {
+                    margin: UiRect::top(Val::Px(style_sheet.gap)),
+                    ..default()
+                },
+            ),
+            (
+                PanelButton,
+                ShareDialogOpenButton,
+                button_node.clone(),
+                children![button_text("Share...")],
+            ),
+            (
+                PanelButton,
+                ActionButton(InputAction::Save),
+                button_node.clone(),
+                children![button_text("Save...")],
+            ),
+            // Only browsers can print
+            (
+                PanelButton,
+                ActionButton(InputAction::Print),
+                Node {
+                    display: if cfg!(target_family = "wasm") {
+                        Display::Flex
+                    } else {
+                        Display::None
+                    },
+                    ..button_node
+                },
+                children![button_text("Print")],
+            ),
+        ],
+    );
+
+    let keypad_key_bundle = |action: InputAction, label: String| {
+        (
+            PanelButton,
+            ActionButton(action),
+            Node {
+                justify_content: JustifyContent::Center,
+                width: Val::Px(style_sheet.heading_font_size * 1.5),
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(style_sheet.heading_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    let keypad_half_bundle = |keys: Vec<(InputAction, String)>| {
+        (
+            SplitKeypadHalf,
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(style_sheet.gap / 2.0),
+                margin: UiRect::horizontal(Val::Px(style_sheet.gap)),
+                display: Display::None,
+                ..default()
+            },
+            Children::spawn(SpawnIter(
+                keys.into_iter()
+                    .map(|(action, label)| keypad_key_bundle(action, label))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+        )
+    };
+    let digit_key = |val: u8| (InputAction::Digit(val), val.to_string());
+    let left_keys = (1..=5).map(digit_key).collect();
+    let right_keys = (6..=9)
+        .map(digit_key)
+        .chain([(InputAction::Erase, "X".to_string())])
+        .collect();
+
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: layout_settings.panel_direction(),
+            ..default()
+        },
+        children![
+            (
+                Node {
+                    flex_grow: 1.0,
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                children![
+                    keypad_half_bundle(left_keys),
+                    (
+                        BoardShake::default(),
+                        Node {
+                            flex_grow: 1.0,
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        board,
+                    ),
+                    keypad_half_bundle(right_keys),
+                ],
+            ),
+            (
+                SidePanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                    margin: UiRect::all(Val::Px(style_sheet.gap)),
+                    ..default()
+                },
+                children![toggle_bundle, body_bundle],
+            )
+        ],
+    )
+}
+
+/// Returns the text for `DigitCountsText`, listing how many of each digit are left to place.
+#[must_use]
+pub fn digit_counts_text(grid: &ClassicGrid) -> String {
+    let mut counts = [0; 9];
+    for val in grid.iter_all().flatten() {
+        counts[*val as usize - 1] += 1;
+    }
+    (1..=9)
+        .map(|val| {
+            let separator = if val % 3 == 0 { "\n" } else { "   " };
+            format!(
+                "{val}: {}{separator}",
+                9usize.saturating_sub(counts[val - 1])
+            )
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// Returns the text for `HintButtonText`, with how many hints are left if there's a limit.
+#[must_use]
+pub fn hint_button_text(remaining: Option<u32>) -> String {
+    match remaining {
+        None => "Hint".into(),
+        Some(remaining) => format!("Hint ({remaining} left)"),
+    }
+}
+
+/// Returns the text for `ProgressText`, e.g. "47/81" cells filled in (givens included).
+#[must_use]
+pub fn progress_text(grid: &ClassicGrid) -> String {
+    format!("{}/81", grid.iter_all().flatten().count())
+}
+
+// Show the split keypad next to the board only while it's turned on
+fn split_keypad_system(
+    layout_settings: Res<LayoutSettings>,
+    mut keypad_query: Query<(&mut Node, Ref<SplitKeypadHalf>)>,
+) {
+    for (mut node, keypad_half) in &mut keypad_query {
+        if layout_settings.is_changed() || keypad_half.is_added() {
+            node.display = if layout_settings.split_keypad {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+}
+
+fn side_panel_toggle_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SidePanelToggle>)>,
+    mut side_panel_settings: ResMut<SidePanelSettings>,
+) {
+    for _ in interaction_query
+        .iter()
+        .filter(|interaction| **interaction == Interaction::Pressed)
+    {
+        side_panel_settings.collapsed = !side_panel_settings.collapsed;
+    }
+}
+
+// Hide the body of the panel while it's collapsed, so the board can use the space
+fn side_panel_collapse_system(
+    side_panel_settings: Res<SidePanelSettings>,
+    layout_settings: Res<LayoutSettings>,
+    mut body_query: Query<(&mut Node, Ref<SidePanelBody>)>,
+    mut icon_query: Query<(&mut Icon, Ref<SidePanelToggleIcon>)>,
+) {
+    let settings_changed = side_panel_settings.is_changed() || layout_settings.is_changed();
+    for (mut node, body) in &mut body_query {
+        if settings_changed || body.is_added() {
+            node.display = if side_panel_settings.collapsed {
+                Display::None
+            } else {
+                Display::Flex
+            };
+        }
+    }
+    // The arrow points the way the panel will move
+    let opens_left = side_panel_settings.collapsed != layout_settings.left_handed;
+    for (mut icon, toggle_icon) in &mut icon_query {
+        if settings_changed || toggle_icon.is_added() {
+            icon.0 = if opens_left {
+                IconId::ArrowLeft
+            } else {
+                IconId::ArrowRight
+            };
+        }
+    }
+}
+
+// Show the game's time, or the time of the whole marathon so far during one. The text is written
+// into the same strings every frame, so a clock showing hundredths doesn't allocate as it ticks.
+fn timer_text_system(
+    game_timer: Res<GameTimer>,
+    marathon: Option<Res<Marathon>>,
+    timer_display_settings: Res<TimerDisplaySettings>,
+    mut timer_text: Local<String>,
+    mut timer_text_query: Query<&mut Text, With<TimerText>>,
+) {
+    let time = game_timer.remaining().unwrap_or_else(|| {
+        game_timer.elapsed() + marathon.map_or(Duration::ZERO, |marathon| marathon.total())
+    });
+    write_clock_text(&mut timer_text, time, timer_display_settings.precision);
+    for mut text in &mut timer_text_query {
+        if text.0 != *timer_text {
+            text.0.clear();
+            text.0.push_str(&timer_text);
+        }
+    }
+}
+
+// Hide the side panel and the keypad while the board is printed, leaving just the board
+#[allow(clippy::type_complexity)]
+fn print_view_system(
+    print_mode: Res<PrintMode>,
+    mut visibility_query: Query<&mut Visibility, Or<(With<SidePanel>, With<SplitKeypadHalf>)>>,
+) {
+    let visibility = if print_mode.0 {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut node_visibility in &mut visibility_query {
+        node_visibility.set_if_neq(visibility);
+    }
+}
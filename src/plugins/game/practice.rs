@@ -0,0 +1,24 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::puzzles::hint::Technique;
+
+pub fn practice_plugin(app: &mut App) {
+    app.init_resource::<PracticeCompletions>();
+}
+
+/// How many practice puzzles have been solved for each technique.
+#[derive(Resource, Default)]
+pub struct PracticeCompletions(HashMap<Technique, u32>);
+
+impl PracticeCompletions {
+    #[must_use]
+    pub fn get(&self, technique: Technique) -> u32 {
+        self.0.get(&technique).copied().unwrap_or(0)
+    }
+
+    pub fn complete(&mut self, technique: Technique) {
+        *self.0.entry(technique).or_default() += 1;
+    }
+}
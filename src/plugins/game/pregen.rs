@@ -0,0 +1,298 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::classic::ClassicGrid,
+    plugins::common::{
+        rng::{RngService, PREGEN_STREAM},
+        storage,
+    },
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        hint::{grade, grade_distance, similar_grades, GradeReport},
+    },
+    utility::{seed::SeedRng, share_code::GENERATOR_VERSION},
+    AppState, PuzzleSettings,
+};
+
+use super::{
+    cache::PuzzleCache,
+    classic_grid_rules, generate_classic_grid_puzzle,
+    variant::{VariantInfo, VariantRegistry},
+    PuzzleType,
+};
+
+/// How many puzzles are kept ready for each kind of puzzle.
+const POOL_SIZE: usize = 3;
+
+/// How many new seeds `take_similar` tries when none of the pregenerated puzzles are similar enough.
+const SIMILAR_ATTEMPTS: usize = 8;
+
+/// The name the pools are saved under.
+const PREGEN_STORAGE_NAME: &str = "pregenerated puzzles";
+
+pub fn pregen_plugin(app: &mut App) {
+    app.init_resource::<PregenPool>()
+        .add_systems(OnEnter(AppState::Menu), pregen_load_system)
+        .add_systems(Update, pregen_fill_system.run_if(in_state(AppState::Menu)))
+        // Leave the cores to the game while it's being played
+        .add_systems(OnExit(AppState::Menu), pregen_cancel_system);
+}
+
+/// The kind of puzzle a pool holds. Puzzle types don't have difficulties yet, so the chosen rules
+/// are what tell pools of the same type apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct PregenKey {
+    puzzle_type: PuzzleType,
+    rules: ClassicRules,
+}
+
+impl PregenKey {
+    fn settings(self, seed: String) -> PuzzleSettings {
+        PuzzleSettings {
+            puzzle_type: self.puzzle_type,
+            seed,
+            rules: self.rules,
+            ..default()
+        }
+    }
+}
+
+/// A generated puzzle, stored as grid strings so that it can be saved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PregeneratedPuzzle {
+    seed: String,
+    givens: String,
+    solution: String,
+}
+
+impl PregeneratedPuzzle {
+    /// Rebuilds the puzzle and its solution, or returns `None` if the saved grids can't be read or
+    /// don't agree.
+    fn restore(&self, settings: &PuzzleSettings) -> Option<(ClassicPuzzle, ClassicGrid)> {
+        let givens: ClassicGrid = self.givens.parse().ok()?;
+        let solution: ClassicGrid = self.solution.parse().ok()?;
+        let consistent = (0..9).all(|row| {
+            (0..9).all(|col| {
+                let given = givens.get_by_row_col((row, col));
+                let solved = solution.get_by_row_col((row, col));
+                solved.is_some() && (given.is_none() || given == solved)
+            })
+        });
+        consistent.then(|| {
+            (
+                ClassicPuzzle::from_grid_with_rules(givens, classic_grid_rules(settings)),
+                solution,
+            )
+        })
+    }
+}
+
+/// What's saved between sessions.
+#[derive(Serialize, Deserialize)]
+struct PregenStorage {
+    /// The `GENERATOR_VERSION` the puzzles were generated with. Pools saved with the crate version
+    /// here instead can't be read, and are generated again.
+    version: u8,
+    pools: Vec<(PregenKey, Vec<PregeneratedPuzzle>)>,
+}
+
+/// Puzzles generated ahead of time while the player is on the menus, so that starting a puzzle
+/// without a seed doesn't wait for the generator. One puzzle is generated at a time.
+#[derive(Resource, Default)]
+pub struct PregenPool {
+    pools: Vec<(PregenKey, VecDeque<PregeneratedPuzzle>)>,
+    task: Option<(PregenKey, Task<Option<PregeneratedPuzzle>>)>,
+    loaded: bool,
+}
+
+impl PregenPool {
+    fn pool_mut(&mut self, key: PregenKey) -> &mut VecDeque<PregeneratedPuzzle> {
+        let index = match self.pools.iter().position(|(pool_key, _)| *pool_key == key) {
+            Some(index) => index,
+            None => {
+                self.pools.push((key, VecDeque::new()));
+                self.pools.len() - 1
+            }
+        };
+        &mut self.pools[index].1
+    }
+
+    /// Takes a pregenerated puzzle for some settings (ignoring their seed) and puts it in the cache
+    /// so that it starts right away, returning its seed. Settings without a pool get one, so that
+    /// the next puzzle like them is ready in time.
+    pub fn take(&mut self, settings: &PuzzleSettings, cache: &mut PuzzleCache) -> Option<String> {
+        // Marathon puzzles get their seeds from the marathon's seed
+        if settings.practice.is_some()
+            || settings.marathon.is_some()
+            || !VariantInfo::of(settings.puzzle_type).classic_grid
+        {
+            return None;
+        }
+        let key = PregenKey {
+            puzzle_type: settings.puzzle_type,
+            rules: settings.rules,
+        };
+        let pregenerated = self.pool_mut(key).pop_front();
+        self.save();
+        let pregenerated = pregenerated?;
+        let settings = PuzzleSettings {
+            seed: pregenerated.seed.clone(),
+            ..settings.clone()
+        };
+        let generated = pregenerated.restore(&settings)?;
+        cache.get_or_generate(&settings, || generated);
+        Some(pregenerated.seed)
+    }
+
+    /// Finds a puzzle for some settings (ignoring their seed) that grades like `report`, puts it in
+    /// the cache so that it starts right away, and returns its seed. The pregenerated puzzles are
+    /// graded first, then puzzles from new seeds until one is similar enough. If none is, the
+    /// closest one with the same hardest technique is used. Returns `None` if no puzzle found needs
+    /// the same hardest technique, or the settings' puzzles aren't on a classic grid.
+    pub fn take_similar(
+        &mut self,
+        settings: &PuzzleSettings,
+        report: &GradeReport,
+        rng: &mut impl SeedRng,
+        cache: &mut PuzzleCache,
+    ) -> Option<String> {
+        if !VariantInfo::of(settings.puzzle_type).classic_grid {
+            return None;
+        }
+        let key = PregenKey {
+            puzzle_type: settings.puzzle_type,
+            rules: settings.rules,
+        };
+        let with_seed = |seed: String| PuzzleSettings {
+            seed,
+            practice: None,
+            remix: None,
+            challenge: 0,
+            ..settings.clone()
+        };
+        let mut pool = std::mem::take(self.pool_mut(key));
+        let pregenerated = pool.iter().enumerate().filter_map(|(index, pregenerated)| {
+            let settings = with_seed(pregenerated.seed.clone());
+            let generated = pregenerated.restore(&settings)?;
+            Some((Some(index), settings, generated))
+        });
+        let fresh = (0..SIMILAR_ATTEMPTS).filter_map(|_| {
+            let settings = with_seed(rng.gen_seed());
+            let generated = generate_classic_grid_puzzle(&settings).ok().flatten()?;
+            Some((None, settings, generated))
+        });
+        let mut closest = None;
+        let mut closest_distance = usize::MAX;
+        for (index, settings, generated) in pregenerated.chain(fresh) {
+            let candidate = grade(&generated.0);
+            let Some(distance) = grade_distance(report, &candidate) else {
+                continue;
+            };
+            if distance < closest_distance {
+                closest_distance = distance;
+                closest = Some((index, settings, generated));
+            }
+            if similar_grades(report, &candidate) {
+                break;
+            }
+        }
+        if let Some((Some(index), ..)) = closest {
+            pool.remove(index);
+        }
+        *self.pool_mut(key) = pool;
+        self.save();
+        let (_, settings, generated) = closest?;
+        cache.get_or_generate(&settings, || generated);
+        Some(settings.seed)
+    }
+
+    fn save(&self) {
+        storage::save(
+            PREGEN_STORAGE_NAME,
+            &PregenStorage {
+                version: GENERATOR_VERSION,
+                pools: self
+                    .pools
+                    .iter()
+                    .map(|(key, pool)| (*key, pool.iter().cloned().collect()))
+                    .collect(),
+            },
+        );
+    }
+}
+
+// Load the saved pools the first time the menus are shown, and make a pool for every puzzle type
+// that can be generated
+fn pregen_load_system(mut pregen_pool: ResMut<PregenPool>, variant_registry: Res<VariantRegistry>) {
+    if pregen_pool.loaded {
+        return;
+    }
+    pregen_pool.loaded = true;
+    // Puzzles from another version of the generators wouldn't match their seeds
+    if let Some(saved) = storage::load::<PregenStorage>(PREGEN_STORAGE_NAME)
+        .filter(|saved| saved.version == GENERATOR_VERSION)
+    {
+        pregen_pool.pools = saved
+            .pools
+            .into_iter()
+            .map(|(key, pool)| (key, pool.into_iter().take(POOL_SIZE).collect()))
+            .collect();
+    }
+    for variant in variant_registry
+        .iter()
+        .filter(|variant| variant.generator && variant.classic_grid)
+    {
+        pregen_pool.pool_mut(PregenKey {
+            puzzle_type: variant.puzzle_type,
+            rules: ClassicRules::default(),
+        });
+    }
+}
+
+// Collect the puzzle being generated, then start on the emptiest pool that isn't full
+fn pregen_fill_system(mut pregen_pool: ResMut<PregenPool>, mut rng_service: ResMut<RngService>) {
+    if let Some((key, task)) = pregen_pool.task.as_mut() {
+        let key = *key;
+        let Some(generated) = block_on(future::poll_once(task)) else {
+            return;
+        };
+        pregen_pool.task = None;
+        if let Some(generated) = generated {
+            pregen_pool.pool_mut(key).push_back(generated);
+            pregen_pool.save();
+        }
+    }
+
+    let Some(key) = pregen_pool
+        .pools
+        .iter()
+        .filter(|(_, pool)| pool.len() < POOL_SIZE)
+        .min_by_key(|(_, pool)| pool.len())
+        .map(|(key, _)| *key)
+    else {
+        return;
+    };
+    let seed = rng_service.stream(PREGEN_STREAM).gen_seed();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let (puzzle, solution) = generate_classic_grid_puzzle(&key.settings(seed.clone()))
+            .ok()
+            .flatten()?;
+        Some(PregeneratedPuzzle {
+            seed,
+            givens: puzzle.grid().to_string(),
+            solution: solution.to_string(),
+        })
+    });
+    pregen_pool.task = Some((key, task));
+}
+
+fn pregen_cancel_system(mut pregen_pool: ResMut<PregenPool>) {
+    // Dropping the task cancels it
+    pregen_pool.task = None;
+}
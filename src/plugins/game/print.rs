@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use crate::{plugins::common::theme::PrintMode, AppState};
+
+use super::{input::InputAction, GameSystems};
+
+/// How many frames are drawn in black and white before printing, so the page shows the print view.
+const PRINT_VIEW_FRAMES: u8 = 3;
+
+pub fn print_plugin(app: &mut App) {
+    app.init_resource::<PendingPrint>()
+        .add_systems(
+            Update,
+            (print_action_system, print_system)
+                .chain()
+                .in_set(GameSystems::ApplyEdits)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(OnExit(AppState::Game), print_reset_system);
+}
+
+/// The frames left to draw in black and white before the page is printed, while waiting to print.
+#[derive(Resource, Default)]
+struct PendingPrint(Option<u8>);
+
+// Switch to the print view when printing is asked for
+fn print_action_system(
+    mut input_actions: EventReader<InputAction>,
+    mut pending_print: ResMut<PendingPrint>,
+    mut print_mode: ResMut<PrintMode>,
+) {
+    let pressed = input_actions
+        .read()
+        .any(|action| *action == InputAction::Print);
+    if pressed && pending_print.0.is_none() {
+        print_mode.0 = true;
+        pending_print.0 = Some(PRINT_VIEW_FRAMES);
+    }
+}
+
+// Print the page once the print view has been drawn, then go back to the interactive view. The
+// browser's print dialog blocks until it's closed, so the view is restored after printing.
+fn print_system(mut pending_print: ResMut<PendingPrint>, mut print_mode: ResMut<PrintMode>) {
+    let Some(frames) = pending_print.0 else {
+        return;
+    };
+    if frames > 0 {
+        pending_print.0 = Some(frames - 1);
+        return;
+    }
+    print_page();
+    pending_print.0 = None;
+    print_mode.0 = false;
+}
+
+#[cfg(target_family = "wasm")]
+fn print_page() {
+    if let Some(Err(err)) = web_sys::window().map(|window| window.print()) {
+        warn!("Couldn't print the page: {err:?}");
+    }
+}
+
+/// Only the web can print, which is the only place the print button is shown.
+#[cfg(not(target_family = "wasm"))]
+fn print_page() {
+    info!("Printing is only available on the web");
+}
+
+fn print_reset_system(mut pending_print: ResMut<PendingPrint>, mut print_mode: ResMut<PrintMode>) {
+    pending_print.0 = None;
+    print_mode.set_if_neq(PrintMode(false));
+}
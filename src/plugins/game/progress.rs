@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::puzzles::hint::Region;
+
+use super::GameSystems;
+
+pub fn progress_plugin(app: &mut App) {
+    app.init_resource::<ProgressSettings>()
+        .add_event::<UnitCompleted>()
+        .add_systems(
+            Update,
+            (unit_completed_effect_system, unit_completed_fade_system)
+                .chain()
+                .in_set(GameSystems::Render),
+        );
+}
+
+/// How long the message for a completed unit takes to fade out.
+const UNIT_COMPLETED_FADE: Duration = Duration::from_millis(1500);
+
+/// Whether completing a row, column, or box shows a brief message. This is kept between games.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgressSettings {
+    pub unit_effects: bool,
+}
+
+impl Default for ProgressSettings {
+    fn default() -> Self {
+        Self { unit_effects: true }
+    }
+}
+
+/// Sent by a puzzle's plugin when a row, column, or box is filled in correctly.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnitCompleted(pub Region);
+
+/// The text that briefly names the last unit that was completed.
+#[derive(Component, Default)]
+pub struct UnitCompletedText {
+    fade: Option<Timer>,
+}
+
+fn unit_completed_effect_system(
+    progress_settings: Res<ProgressSettings>,
+    mut unit_completed_events: EventReader<UnitCompleted>,
+    mut unit_completed_text_query: Query<(&mut Text, &mut TextColor, &mut UnitCompletedText)>,
+) {
+    let Some(UnitCompleted(region)) = unit_completed_events.read().last().copied() else {
+        return;
+    };
+    if !progress_settings.unit_effects {
+        return;
+    }
+    for (mut text, mut text_color, mut unit_completed_text) in &mut unit_completed_text_query {
+        text.0 = format!("Completed {region}!");
+        text_color.0.set_alpha(1.0);
+        unit_completed_text.fade = Some(Timer::new(UNIT_COMPLETED_FADE, TimerMode::Once));
+    }
+}
+
+fn unit_completed_fade_system(
+    time: Res<Time>,
+    mut unit_completed_text_query: Query<(&mut Text, &mut TextColor, &mut UnitCompletedText)>,
+) {
+    for (mut text, mut text_color, mut unit_completed_text) in &mut unit_completed_text_query {
+        let Some(fade) = unit_completed_text.fade.as_mut() else {
+            continue;
+        };
+        fade.tick(time.delta());
+        text_color.0.set_alpha(fade.fraction_remaining());
+        if fade.finished() {
+            text.0.clear();
+            unit_completed_text.fade = None;
+        }
+    }
+}
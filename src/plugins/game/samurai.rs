@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component, plugins::nav::NavState, puzzles::samurai::SamuraiPuzzle, PuzzleSettings,
+};
+
+use super::{cache::PuzzleCache, GameState, GameSystems, PuzzleType};
+
+pub fn samurai_plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(GameState::Playing(PuzzleType::Samurai)),
+        samurai_setup,
+    )
+    .add_systems(
+        OnExit(GameState::Playing(PuzzleType::Samurai)),
+        despawn_component::<SamuraiContainer>,
+    )
+    .add_systems(
+        Update,
+        samurai_action_system
+            .in_set(GameSystems::ApplyEdits)
+            .run_if(in_state(GameState::Playing(PuzzleType::Samurai))),
+    );
+}
+
+#[derive(Component)]
+struct SamuraiContainer;
+
+// Generate and spawn the board
+fn samurai_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+) {
+    nav_state.set(NavState::Pause);
+    info!("Setting up samurai puzzle!");
+    info!("Seed is {:}", puzzle_settings.seed);
+    let (puzzle, _solution) = puzzle_cache.get_or_generate(&puzzle_settings, || {
+        let puzzle = SamuraiPuzzle::from_seed(puzzle_settings.seed.clone());
+        let solution = puzzle.solution().unwrap();
+        (puzzle, solution)
+    });
+    info!("Finished with {} clues:", puzzle.num_clues());
+    info!("{puzzle:}");
+}
+
+fn samurai_action_system() {}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component,
+    plugins::common::theme::{
+        node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+        text::{ThemedFontWeight, ThemedTextColor},
+    },
+    AppState,
+};
+
+use super::{
+    input::{ActionButton, InputAction},
+    GameSystems,
+};
+
+pub fn sandbox_plugin(app: &mut App) {
+    app.init_resource::<Sandbox>()
+        .add_systems(
+            Update,
+            sandbox_banner_system
+                .in_set(GameSystems::Render)
+                .run_if(resource_changed::<Sandbox>),
+        )
+        .add_systems(
+            OnExit(AppState::Game),
+            (sandbox_reset_system, despawn_component::<SandboxBanner>),
+        );
+}
+
+/// Whether the player is trying out entries that can be thrown away. The puzzle's plugin takes a
+/// snapshot of the puzzle when the sandbox opens, and goes back to it if the entries are
+/// discarded.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sandbox {
+    pub open: bool,
+}
+
+/// Shown over the board while the sandbox is open, with buttons to keep or discard its entries.
+#[derive(Component)]
+#[require(Node)]
+struct SandboxBanner;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct SandboxBannerButton;
+
+fn sandbox_banner_bundle() -> impl Bundle {
+    let button_bundle = |action: InputAction, label: &str| {
+        (
+            SandboxBannerButton,
+            ActionButton(action),
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(20.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+
+    (
+        SandboxBanner,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            align_self: AlignSelf::Center,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        GlobalZIndex(10),
+        children![
+            (
+                Text::new("What if? These entries can be thrown away"),
+                TextFont::from_font_size(16.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            ),
+            button_bundle(InputAction::KeepSandbox, "Keep"),
+            button_bundle(InputAction::DiscardSandbox, "Discard"),
+        ],
+    )
+}
+
+// Show the banner while the sandbox is open
+fn sandbox_banner_system(
+    sandbox: Res<Sandbox>,
+    banner_query: Query<Entity, With<SandboxBanner>>,
+    mut commands: Commands,
+) {
+    match (sandbox.open, banner_query.iter().next()) {
+        (true, None) => {
+            commands.spawn(sandbox_banner_bundle());
+        }
+        (false, Some(banner)) => commands.entity(banner).despawn(),
+        _ => {}
+    }
+}
+
+fn sandbox_reset_system(mut sandbox: ResMut<Sandbox>) {
+    sandbox.set_if_neq(Sandbox::default());
+}
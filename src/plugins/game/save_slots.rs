@@ -0,0 +1,339 @@
+use std::time::Duration;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::classic::GridTransform,
+    plugins::{
+        common::{
+            storage,
+            theme::{
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        nav::{Navigator, Screen},
+    },
+    puzzles::{classic::ClassicRules, hint::Technique},
+    utility::week::unix_secs_now,
+    PuzzleSettings,
+};
+
+use super::{
+    hint::HintStats,
+    solve_history::difficulty_text,
+    timer::{clock_text, PausesGameTimer},
+    AssistLevel, PuzzleType,
+};
+
+/// How many games can be saved at once.
+pub const SAVE_SLOT_COUNT: usize = 3;
+
+/// The name the save slots are saved under.
+const SAVE_SLOTS_STORAGE_NAME: &str = "save slots";
+
+pub fn save_slots_plugin(app: &mut App) {
+    app.insert_resource(SaveSlots::load())
+        .init_resource::<PendingRestore>()
+        .add_systems(Update, save_slot_dialog_system);
+}
+
+/// A game saved part of the way through, with what's needed to pick it up again and to tell it
+/// apart from the other slots.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveSlot {
+    pub puzzle_type: PuzzleType,
+    pub seed: String,
+    pub rules: ClassicRules,
+    pub remix: Option<GridTransform>,
+    pub practice: Option<Technique>,
+    pub challenge: u8,
+    /// The hardest technique the puzzle needs (`None` if the hint techniques can't solve it)
+    pub difficulty: Option<Technique>,
+    /// The player's entries, as a progress string
+    pub progress: String,
+    /// How much of the puzzle had been filled in, from 0 to 100
+    pub percent: u8,
+    pub elapsed: Duration,
+    /// When the game was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+    /// The cell that was selected, which is selected again when the game is picked up
+    #[serde(default)]
+    pub selected: Option<(u8, u8)>,
+    #[serde(default)]
+    pub assist_level: AssistLevel,
+    #[serde(default)]
+    pub mistake_limit: Option<u8>,
+    #[serde(default)]
+    pub strict_entry: bool,
+    #[serde(default)]
+    pub fill_candidates: bool,
+    /// How many entries didn't match the solution
+    #[serde(default)]
+    pub mistakes: u8,
+    /// How many times the entries had been checked
+    #[serde(default)]
+    pub checks: u32,
+    /// How many times each stage of a hint had been shown
+    #[serde(default)]
+    pub hints: HintStats,
+    /// The pencil marks, as a marks string (`None` for games saved before marks were kept)
+    #[serde(default)]
+    pub marks: Option<String>,
+}
+
+impl SaveSlot {
+    /// Returns the settings for picking the game up again.
+    #[must_use]
+    pub fn settings(&self) -> PuzzleSettings {
+        PuzzleSettings {
+            puzzle_type: self.puzzle_type,
+            seed: self.seed.clone(),
+            rules: self.rules,
+            remix: self.remix,
+            practice: self.practice,
+            challenge: self.challenge,
+            assist_level: self.assist_level,
+            mistake_limit: self.mistake_limit,
+            strict_entry: self.strict_entry,
+            fill_candidates: self.fill_candidates,
+            ..default()
+        }
+    }
+
+    /// Returns the slot's metadata on one line, e.g. "Classic, Naked single - 45% - 12:34 - saved
+    /// 3h ago".
+    #[must_use]
+    pub fn summary(&self, now: u64) -> String {
+        format!(
+            "{}, {} - {}% - {} - saved {}",
+            self.puzzle_type,
+            difficulty_text(self.difficulty),
+            self.percent,
+            clock_text(self.elapsed),
+            saved_ago_text(now.saturating_sub(self.saved_at))
+        )
+    }
+}
+
+/// Returns how long ago something was saved, in the largest whole unit, e.g. "3h ago".
+fn saved_ago_text(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".into(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86_400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// The saved games, by slot, which are kept between sessions.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct SaveSlots(Vec<Option<SaveSlot>>);
+
+impl SaveSlots {
+    fn load() -> Self {
+        let mut save_slots: Self = storage::load(SAVE_SLOTS_STORAGE_NAME).unwrap_or_default();
+        save_slots.0.resize(SAVE_SLOT_COUNT, None);
+        save_slots
+    }
+
+    /// Saves a game in a slot, replacing the game saved there before.
+    pub fn store(&mut self, index: usize, slot: SaveSlot) {
+        if let Some(stored) = self.0.get_mut(index) {
+            *stored = Some(slot);
+            storage::save(SAVE_SLOTS_STORAGE_NAME, self);
+        }
+    }
+
+    /// Returns the game saved in a slot, if there is one.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&SaveSlot> {
+        self.0.get(index)?.as_ref()
+    }
+
+    /// Returns the slots that have games saved in them, along with their indices.
+    pub fn filled(&self) -> impl Iterator<Item = (usize, &SaveSlot)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.as_ref()?)))
+    }
+}
+
+/// The saved game to pick up when the next puzzle is set up, which must be started with the
+/// slot's settings.
+#[derive(Resource, Default)]
+pub struct PendingRestore(pub Option<SaveSlot>);
+
+impl PendingRestore {
+    /// Sets up a saved game to be picked up. Push the game screen afterwards to start it.
+    pub fn start(&mut self, slot: &SaveSlot, puzzle_settings: &mut PuzzleSettings) {
+        *puzzle_settings = slot.settings();
+        self.0 = Some(slot.clone());
+    }
+
+    /// Returns the saved game to pick up if it's for the puzzle being set up.
+    pub fn take_for(&mut self, puzzle_settings: &PuzzleSettings) -> Option<SaveSlot> {
+        self.0.take().filter(|slot| {
+            slot.puzzle_type == puzzle_settings.puzzle_type && slot.seed == puzzle_settings.seed
+        })
+    }
+}
+
+/// The dialog for picking a save slot. It saves the game being played when it has one, and
+/// otherwise picks up the game saved in the slot.
+#[derive(Component)]
+#[require(Node, PausesGameTimer)]
+pub struct SaveSlotDialog(pub(super) Option<SaveSlot>);
+
+#[derive(Component, Clone, Copy)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum SaveSlotButton {
+    Slot(usize),
+    Cancel,
+}
+
+/// Returns the dialog for saving a game in one of the slots, which shows what each slot holds.
+/// Spawn it as a child of the screen's root so that it's removed along with the screen.
+#[must_use]
+pub fn save_dialog_bundle(save_slots: &SaveSlots, slot: SaveSlot) -> impl Bundle {
+    let now = unix_secs_now();
+    let labels = save_slots
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let summary = slot
+                .as_ref()
+                .map_or_else(|| "Empty".into(), |slot| slot.summary(now));
+            (index, format!("Slot {}: {summary}", index + 1))
+        })
+        .collect();
+    slot_dialog_bundle("Save to a slot", labels, Some(slot))
+}
+
+/// Returns the dialog for picking which saved game to continue. Spawn it as a child of the
+/// screen's root so that it's removed along with the screen.
+#[must_use]
+pub fn continue_dialog_bundle(save_slots: &SaveSlots) -> impl Bundle {
+    let now = unix_secs_now();
+    let labels = save_slots
+        .filled()
+        .map(|(index, slot)| (index, format!("Slot {}: {}", index + 1, slot.summary(now))))
+        .collect();
+    slot_dialog_bundle("Continue", labels, None)
+}
+
+fn slot_dialog_bundle(
+    heading: &str,
+    labels: Vec<(usize, String)>,
+    slot: Option<SaveSlot>,
+) -> impl Bundle {
+    let button_bundle = |button: SaveSlotButton, label: String| {
+        (
+            button,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(18.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    let slot_button_bundles = labels
+        .into_iter()
+        .map(move |(index, label)| button_bundle(SaveSlotButton::Slot(index), label));
+
+    let dialog_bundle = (
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        FocusScope::trap(),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(20.0)),
+            width: Val::Px(480.0),
+            ..default()
+        },
+        Children::spawn((
+            Spawn((
+                Text::new(heading),
+                TextFont::from_font_size(36.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )),
+            SpawnIter(slot_button_bundles),
+            Spawn(button_bundle(SaveSlotButton::Cancel, "Cancel".into())),
+        )),
+    );
+
+    (
+        SaveSlotDialog(slot),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.5)),
+        GlobalZIndex(10),
+        children![dialog_bundle],
+    )
+}
+
+// Save the game in the slot that's picked, or start the game saved in it
+fn save_slot_dialog_system(
+    interaction_query: Query<(&Interaction, &SaveSlotButton), Changed<Interaction>>,
+    dialog_query: Query<(Entity, &SaveSlotDialog)>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut pending_restore: ResMut<PendingRestore>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut navigator: ResMut<Navigator>,
+    mut commands: Commands,
+) {
+    let Some(button) = interaction_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button)
+    else {
+        return;
+    };
+    let Some((dialog, SaveSlotDialog(slot))) = dialog_query.iter().next() else {
+        return;
+    };
+    commands.entity(dialog).despawn();
+    let SaveSlotButton::Slot(index) = button else {
+        return;
+    };
+    match slot {
+        Some(slot) => {
+            save_slots.store(index, slot.clone());
+            info!("Saved the game to slot {}", index + 1);
+        }
+        None => {
+            if let Some(slot) = save_slots.get(index) {
+                pending_restore.start(slot, &mut puzzle_settings);
+                navigator.push(Screen::Game);
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashSet, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component,
+    grids::classic_state::PuzzleCellKind,
+    plugins::common::theme::{
+        node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+        text::{ThemedFontWeight, ThemedTextColor},
+    },
+    AppState, PuzzleSettings,
+};
+
+use super::{
+    gameplay::GameplayEvent,
+    input::InputAction,
+    jump::BoardCellKinds,
+    solve_history::SolveHistory,
+    timer::{clock_text, GameTimer},
+    GameSystems,
+};
+
+pub fn session_stats_plugin(app: &mut App) {
+    app.init_resource::<SessionStatsSettings>()
+        .init_resource::<SessionStats>()
+        .add_systems(
+            Update,
+            (
+                session_stats_toggle_system.in_set(GameSystems::ApplyEdits),
+                (session_stats_system, session_stats_overlay_system)
+                    .chain()
+                    .in_set(GameSystems::Render),
+            )
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(
+            OnExit(AppState::Game),
+            (
+                session_stats_reset_system,
+                despawn_component::<SessionStatsOverlay>,
+            ),
+        );
+}
+
+/// Whether the stats of the game being played are shown over the board. This is kept between
+/// games.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionStatsSettings {
+    pub shown: bool,
+}
+
+/// What the player has done in the game being played, counted from the gameplay events.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// The cells the player has filled in, whether or not they're right
+    filled: HashSet<(u8, u8)>,
+    /// Wrong and rejected entries
+    errors: u32,
+    hints: u32,
+}
+
+impl SessionStats {
+    /// Returns the average time taken to fill a cell so far, if any have been filled.
+    #[must_use]
+    pub fn time_per_cell(&self, elapsed: Duration) -> Option<Duration> {
+        let filled = u32::try_from(self.filled.len())
+            .ok()
+            .filter(|filled| *filled > 0)?;
+        Some(elapsed / filled)
+    }
+}
+
+/// The stats shown over the board while `SessionStatsSettings::shown` is on.
+#[derive(Component)]
+#[require(Node)]
+struct SessionStatsOverlay;
+
+#[derive(Component)]
+struct SessionStatsText;
+
+fn session_stats_overlay_bundle() -> impl Bundle {
+    (
+        SessionStatsOverlay,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            right: Val::Px(20.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        GlobalZIndex(10),
+        children![(
+            SessionStatsText,
+            Text::default(),
+            TextFont::from_font_size(16.0),
+            ThemedFontWeight::Regular,
+            ThemedTextColor,
+        )],
+    )
+}
+
+/// Returns how the projected time compares to the best time, e.g. "Pace: 4:10 (0:50 ahead of
+/// best)".
+fn pace_text(projected: Duration, best: Option<Duration>) -> String {
+    match best {
+        Some(best) if projected <= best => format!(
+            "Pace: {} ({} ahead of best)",
+            clock_text(projected),
+            clock_text(best - projected)
+        ),
+        Some(best) => format!(
+            "Pace: {} ({} behind best)",
+            clock_text(projected),
+            clock_text(projected - best)
+        ),
+        None => format!("Pace: {}", clock_text(projected)),
+    }
+}
+
+fn session_stats_toggle_system(
+    mut input_actions: EventReader<InputAction>,
+    mut session_stats_settings: ResMut<SessionStatsSettings>,
+) {
+    for _ in input_actions
+        .read()
+        .filter(|action| **action == InputAction::ToggleSessionStats)
+    {
+        session_stats_settings.shown = !session_stats_settings.shown;
+    }
+}
+
+// Count the entries, errors, and hints as they're published
+fn session_stats_system(
+    mut gameplay_events: EventReader<GameplayEvent>,
+    mut session_stats: ResMut<SessionStats>,
+) {
+    for event in gameplay_events.read() {
+        match *event {
+            GameplayEvent::Placed {
+                position,
+                val,
+                correct,
+            } => {
+                if val.is_some() {
+                    session_stats.filled.insert(position);
+                } else {
+                    session_stats.filled.remove(&position);
+                }
+                if correct == Some(false) {
+                    session_stats.errors += 1;
+                }
+            }
+            GameplayEvent::Rejected { .. } => session_stats.errors += 1,
+            GameplayEvent::HintShown => session_stats.hints += 1,
+            _ => {}
+        }
+    }
+}
+
+// Show the overlay while it's turned on, and keep its stats up to date with the timer
+#[allow(clippy::too_many_arguments)]
+fn session_stats_overlay_system(
+    session_stats_settings: Res<SessionStatsSettings>,
+    session_stats: Res<SessionStats>,
+    game_timer: Option<Res<GameTimer>>,
+    cell_kinds: Option<Res<BoardCellKinds>>,
+    puzzle_settings: Res<PuzzleSettings>,
+    solve_history: Res<SolveHistory>,
+    overlay_query: Query<Entity, With<SessionStatsOverlay>>,
+    mut text_query: Query<&mut Text, With<SessionStatsText>>,
+    mut commands: Commands,
+) {
+    match (session_stats_settings.shown, overlay_query.iter().next()) {
+        (true, None) => {
+            commands.spawn(session_stats_overlay_bundle());
+        }
+        (false, Some(overlay)) => commands.entity(overlay).despawn(),
+        _ => {}
+    }
+    if !session_stats_settings.shown {
+        return;
+    }
+    let elapsed = game_timer.map_or(Duration::ZERO, |game_timer| game_timer.elapsed());
+    let mut lines = vec![
+        format!("Cells filled: {}", session_stats.filled.len()),
+        format!("Errors: {}", session_stats.errors),
+        format!("Hints used: {}", session_stats.hints),
+    ];
+    let time_per_cell = session_stats.time_per_cell(elapsed);
+    lines.push(time_per_cell.map_or_else(
+        || "Time per cell: -".into(),
+        |time_per_cell| format!("Time per cell: {:.1}s", time_per_cell.as_secs_f32()),
+    ));
+    // The pace is the time the puzzle would take if every open cell took the average so far
+    let open = cell_kinds.map(|cell_kinds| {
+        cell_kinds
+            .0
+            .iter()
+            .flatten()
+            .filter(|kind| **kind == PuzzleCellKind::Open)
+            .count() as u32
+    });
+    if let (Some(time_per_cell), Some(open)) = (time_per_cell, open) {
+        let best =
+            solve_history.fastest_time(puzzle_settings.puzzle_type, puzzle_settings.assist_level);
+        lines.push(pace_text(time_per_cell * open, best));
+    }
+    let stats_text = lines.join("\n");
+    for mut text in &mut text_query {
+        if text.0 != stats_text {
+            text.0.clone_from(&stats_text);
+        }
+    }
+}
+
+fn session_stats_reset_system(mut session_stats: ResMut<SessionStats>) {
+    *session_stats = SessionStats::default();
+}
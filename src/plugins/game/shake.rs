@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::GameSystems;
+
+pub fn shake_plugin(app: &mut App) {
+    app.add_event::<EntryRejected>().add_systems(
+        Update,
+        (entry_rejected_shake_system, board_shake_system)
+            .chain()
+            .in_set(GameSystems::Render),
+    );
+}
+
+/// How long the board shakes after an entry is rejected.
+const SHAKE_DURATION: Duration = Duration::from_millis(300);
+
+/// How far the board moves to each side while shaking, in pixels.
+const SHAKE_AMPLITUDE: f32 = 8.0;
+
+/// How many times the board moves back and forth while shaking.
+const SHAKE_SWINGS: f32 = 3.0;
+
+/// Sent by a puzzle's plugin when an entry in a cell is rejected, e.g. because it clashes with a
+/// peer in strict mode.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntryRejected(pub (u8, u8));
+
+/// The spot for the board, which shakes when an entry is rejected.
+#[derive(Component, Default)]
+pub struct BoardShake {
+    shake: Option<Timer>,
+}
+
+fn entry_rejected_shake_system(
+    mut entry_rejected_events: EventReader<EntryRejected>,
+    mut board_shake_query: Query<&mut BoardShake>,
+) {
+    if entry_rejected_events.read().last().is_none() {
+        return;
+    }
+    for mut board_shake in &mut board_shake_query {
+        board_shake.shake = Some(Timer::new(SHAKE_DURATION, TimerMode::Once));
+    }
+}
+
+// Swing the board from side to side, settling as the shake runs out
+fn board_shake_system(time: Res<Time>, mut board_shake_query: Query<(&mut Node, &mut BoardShake)>) {
+    for (mut node, mut board_shake) in &mut board_shake_query {
+        let Some(shake) = board_shake.shake.as_mut() else {
+            continue;
+        };
+        shake.tick(time.delta());
+        if shake.finished() {
+            node.left = Val::Auto;
+            board_shake.shake = None;
+            continue;
+        }
+        let swing = (shake.fraction() * SHAKE_SWINGS * std::f32::consts::TAU).sin();
+        node.left = Val::Px(SHAKE_AMPLITUDE * swing * shake.fraction_remaining());
+    }
+}
@@ -0,0 +1,308 @@
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::plugins::common::{
+    clipboard::ClipboardResource,
+    theme::{
+        focus::{FocusScope, FocusedEntity},
+        icon::{icon_bundle, Icon, IconId},
+        node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
+        text::{ThemedFontWeight, ThemedTextColor},
+    },
+};
+
+use super::timer::PausesGameTimer;
+
+pub fn share_dialog_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            share_copy_system,
+            #[cfg(not(target_family = "wasm"))]
+            share_save_image_system,
+            share_close_system,
+        ),
+    );
+}
+
+/// What the share dialog offers to copy. Puzzles without a board or progress string leave them
+/// out.
+pub struct ShareContents {
+    pub seed: String,
+    pub share_code: String,
+    pub board: Option<String>,
+    pub progress: Option<String>,
+}
+
+/// The share dialog, which covers the game screen and pauses the timer while it's open.
+#[derive(Component)]
+#[require(Node, PausesGameTimer)]
+pub struct ShareDialog;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct ShareDialogButton;
+
+/// Copies its text to the clipboard when pressed, and says what was copied.
+#[derive(Component)]
+struct ShareCopyButton {
+    name: &'static str,
+    text: String,
+}
+
+/// Shows whether its copy button's text is on the clipboard.
+#[derive(Component)]
+struct ShareCopyIcon;
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(Component)]
+struct ShareSaveImageButton {
+    path: String,
+}
+
+#[derive(Component)]
+struct ShareCloseButton;
+
+/// Says what the last action did.
+#[derive(Component)]
+struct ShareStatusText;
+
+/// Returns the share dialog. Spawn it as a child of the game screen's root so that it's removed
+/// along with the screen.
+#[must_use]
+pub fn share_dialog_bundle(contents: ShareContents) -> impl Bundle {
+    let ShareContents {
+        seed,
+        share_code,
+        board,
+        progress,
+    } = contents;
+    let button_bundle = |label: &str| {
+        (
+            ShareDialogButton,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(20.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+
+    let mut copy_buttons = vec![
+        (
+            "Copy seed",
+            ShareCopyButton {
+                name: "the seed",
+                text: seed.clone(),
+            },
+        ),
+        (
+            "Copy share code",
+            ShareCopyButton {
+                name: "the share code",
+                text: share_code.clone(),
+            },
+        ),
+    ];
+    if let Some(board) = board {
+        copy_buttons.push((
+            "Copy board",
+            ShareCopyButton {
+                name: "the board",
+                text: board,
+            },
+        ));
+    }
+    if let Some(progress) = progress {
+        copy_buttons.push((
+            "Copy progress",
+            ShareCopyButton {
+                name: "your progress",
+                text: format!("{share_code} {progress}"),
+            },
+        ));
+    }
+    let copy_button_bundles = copy_buttons.into_iter().map(|(label, copy_button)| {
+        (
+            copy_button,
+            ShareDialogButton,
+            Node {
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            children![
+                (
+                    Text::new(label),
+                    TextFont::from_font_size(20.0),
+                    ThemedFontWeight::Bold,
+                    ThemedTextColor,
+                ),
+                (ShareCopyIcon, icon_bundle(IconId::Copy, 20.0)),
+            ],
+        )
+    });
+
+    // Screenshots can't be saved to disk on the web
+    #[cfg(not(target_family = "wasm"))]
+    let save_image_bundles = {
+        let file_stem: String = seed
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        Some((
+            ShareSaveImageButton {
+                path: format!("sudoku-{file_stem}.png"),
+            },
+            button_bundle("Save image"),
+        ))
+    };
+    #[cfg(target_family = "wasm")]
+    let save_image_bundles: Option<()> = None;
+
+    let dialog_bundle = (
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        FocusScope::trap(),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(20.0)),
+            width: Val::Px(320.0),
+            ..default()
+        },
+        Children::spawn((
+            Spawn((
+                Text::new("Share"),
+                TextFont::from_font_size(36.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )),
+            SpawnIter(copy_button_bundles),
+            SpawnIter(save_image_bundles.into_iter()),
+            Spawn((
+                ShareStatusText,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                ThemedTextColor,
+            )),
+            Spawn((ShareCloseButton, button_bundle("Close"))),
+        )),
+    );
+
+    (
+        ShareDialog,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.5)),
+        GlobalZIndex(10),
+        children![dialog_bundle],
+    )
+}
+
+fn set_status(status_text_query: &mut Query<&mut Text, With<ShareStatusText>>, status: String) {
+    for mut text in status_text_query {
+        text.0.clone_from(&status);
+    }
+}
+
+// Copy a button's text, and check it off in place of any button copied before it
+fn share_copy_system(
+    interaction_query: Query<(&Interaction, &ShareCopyButton, &Children), Changed<Interaction>>,
+    mut clipboard_resource: ResMut<ClipboardResource>,
+    mut status_text_query: Query<&mut Text, With<ShareStatusText>>,
+    mut copy_icon_query: Query<(Entity, &mut Icon), With<ShareCopyIcon>>,
+) {
+    for (_, copy_button, children) in interaction_query
+        .iter()
+        .filter(|(interaction, _, _)| **interaction == Interaction::Pressed)
+    {
+        clipboard_resource.copy(copy_button.text.clone());
+        for (entity, mut icon) in &mut copy_icon_query {
+            icon.set_if_neq(Icon(if children.contains(&entity) {
+                IconId::Check
+            } else {
+                IconId::Copy
+            }));
+        }
+        set_status(
+            &mut status_text_query,
+            format!("Copied {} to the clipboard", copy_button.name),
+        );
+    }
+}
+
+// Save a screenshot of the game without the dialog in front of it
+#[cfg(not(target_family = "wasm"))]
+fn share_save_image_system(
+    interaction_query: Query<(&Interaction, &ShareSaveImageButton), Changed<Interaction>>,
+    mut dialog_query: Query<&mut Visibility, With<ShareDialog>>,
+    mut status_text_query: Query<&mut Text, With<ShareStatusText>>,
+    mut commands: Commands,
+) {
+    use bevy::render::view::screenshot::{save_to_disk, Screenshot, ScreenshotCaptured};
+
+    for (_, save_image_button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        for mut visibility in &mut dialog_query {
+            *visibility = Visibility::Hidden;
+        }
+        commands
+            .spawn(Screenshot::primary_window())
+            .observe(save_to_disk(save_image_button.path.clone()))
+            .observe(
+                |_: Trigger<ScreenshotCaptured>,
+                 mut dialog_query: Query<&mut Visibility, With<ShareDialog>>| {
+                    for mut visibility in &mut dialog_query {
+                        *visibility = Visibility::Inherited;
+                    }
+                },
+            );
+        set_status(
+            &mut status_text_query,
+            format!("Saved {}", save_image_button.path),
+        );
+    }
+}
+
+// Close the dialog with its button or Escape
+fn share_close_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ShareCloseButton>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    dialog_query: Query<Entity, With<ShareDialog>>,
+    mut focused_entity: ResMut<FocusedEntity>,
+    mut commands: Commands,
+) {
+    let close_pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !close_pressed && !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    for dialog in &dialog_query {
+        commands.entity(dialog).despawn();
+        focused_entity.set(None);
+    }
+}
@@ -0,0 +1,337 @@
+use std::{collections::HashSet, iter, time::Duration};
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    plugins::common::theme::{
+        color::{ThemeColorRole, ThemedColor},
+        text::{ThemedFontWeight, ThemedTextColor},
+    },
+    puzzles::hint::GradeReport,
+    AppState,
+};
+
+use super::{
+    gameplay::GameplayEvent,
+    hint::HintStats,
+    timer::{clock_text, GameTimer},
+    GameState, GameSystems,
+};
+
+/// How wide the bar of the slowest third is drawn, in pixels.
+const THIRD_BAR_WIDTH: f32 = 160.0;
+
+/// How tall the sparkline's bar for the busiest minute is drawn, in pixels.
+const SPARKLINE_HEIGHT: f32 = 32.0;
+
+/// The characters the sparkline is drawn with in the copied summary, from lowest to highest.
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub fn solve_breakdown_plugin(app: &mut App) {
+    app.init_resource::<SolveTimeline>()
+        .add_systems(
+            Update,
+            solve_timeline_system
+                .in_set(GameSystems::DeriveState)
+                .run_if(in_state(AppState::Game)),
+        )
+        // Each puzzle after a remix or a marathon split gets a timeline of its own
+        .add_systems(OnExit(GameState::Solved), solve_timeline_reset_system)
+        .add_systems(
+            OnExit(GameState::MarathonSplit),
+            solve_timeline_reset_system,
+        )
+        .add_systems(OnExit(AppState::Game), solve_timeline_reset_system);
+}
+
+/// When each cell of the puzzle being played was first filled in correctly, by the game timer.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveTimeline {
+    solved: HashSet<(u8, u8)>,
+    solved_at: Vec<Duration>,
+}
+
+impl SolveTimeline {
+    /// Returns the times the cells were solved at, in the order they were solved.
+    #[must_use]
+    pub fn solved_at(&self) -> &[Duration] {
+        &self.solved_at
+    }
+}
+
+/// How a solve went over time, for the completion screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveBreakdown {
+    /// How long each third of the cells took to solve
+    pub thirds: [Duration; 3],
+    /// The longest stretch without solving a cell
+    pub longest_pause: Duration,
+    /// How many cells were solved in each minute of the game
+    pub per_minute: Vec<u32>,
+}
+
+impl SolveBreakdown {
+    /// Breaks down a solve from when its cells were solved (in order) and how long it took.
+    #[must_use]
+    pub fn new(solved_at: &[Duration], total: Duration) -> Self {
+        // Each third ends when its last cell was solved, and the last one when the puzzle was
+        let third_end = |third: usize| match solved_at.len() * third / 3 {
+            _ if third == 3 => total,
+            0 => Duration::ZERO,
+            solved => solved_at[solved - 1],
+        };
+        let thirds = [0, 1, 2].map(|third| third_end(third + 1).saturating_sub(third_end(third)));
+
+        let longest_pause = iter::once(Duration::ZERO)
+            .chain(solved_at.iter().copied())
+            .chain(iter::once(total))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]))
+            .max()
+            .unwrap_or_default();
+
+        let minutes = total.as_secs().div_ceil(60).max(1) as usize;
+        let mut per_minute = vec![0; minutes];
+        for at in solved_at {
+            per_minute[(at.as_secs() / 60).min(minutes as u64 - 1) as usize] += 1;
+        }
+
+        Self {
+            thirds,
+            longest_pause,
+            per_minute,
+        }
+    }
+
+    /// Returns the cells solved per minute as a line of block characters, e.g. "▃▇█▅".
+    #[must_use]
+    pub fn sparkline_text(&self) -> String {
+        let busiest = self
+            .per_minute
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        self.per_minute
+            .iter()
+            .map(|count| {
+                let level =
+                    (*count as usize * (SPARKLINE_CHARS.len() - 1)).div_ceil(busiest as usize);
+                SPARKLINE_CHARS[level]
+            })
+            .collect()
+    }
+}
+
+/// Returns the techniques a puzzle needs and how many numbers each placed, e.g. "Hidden single
+/// x12, Naked single x30".
+#[must_use]
+pub fn techniques_text(grade_report: &GradeReport) -> String {
+    let mut techniques: Vec<_> = grade_report
+        .techniques
+        .iter()
+        .map(|(technique, count)| format!("{technique} x{count}"))
+        .collect();
+    if grade_report.backtracks_required > 0 {
+        techniques.push("trial and error".into());
+    }
+    if techniques.is_empty() {
+        "none".into()
+    } else {
+        techniques.join(", ")
+    }
+}
+
+/// Returns how many stages of hints were shown, e.g. "2 regions, 1 technique".
+#[must_use]
+pub fn hints_text(hint_stats: HintStats) -> String {
+    let stages: Vec<_> = [
+        (hint_stats.regions, "region", "regions"),
+        (hint_stats.techniques, "technique", "techniques"),
+        (hint_stats.digits, "number", "numbers"),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(count, one, many)| format!("{count} {}", if count == 1 { one } else { many }))
+    .collect();
+    if stages.is_empty() {
+        "none".into()
+    } else {
+        stages.join(", ")
+    }
+}
+
+/// Returns the breakdown drawn with bars: one for each third, scaled to the slowest, and a
+/// sparkline of the cells solved per minute, followed by the techniques needed and the hints used.
+#[must_use]
+pub fn solve_breakdown_bundle(
+    breakdown: &SolveBreakdown,
+    techniques: String,
+    hints: String,
+    font_size: f32,
+) -> impl Bundle {
+    let text_bundle = move |text: String| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(font_size),
+            ThemedTextColor,
+        )
+    };
+
+    let slowest = breakdown.thirds.iter().max().copied().unwrap_or_default();
+    let third_bundles = ["First", "Second", "Last"]
+        .into_iter()
+        .zip(breakdown.thirds)
+        .map(move |(name, time)| {
+            let fraction = if slowest.is_zero() {
+                0.0
+            } else {
+                time.as_secs_f32() / slowest.as_secs_f32()
+            };
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                },
+                children![
+                    (
+                        Node {
+                            width: Val::Px(140.0),
+                            ..default()
+                        },
+                        children![text_bundle(format!("{name} third: {}", clock_text(time)))],
+                    ),
+                    (
+                        Node {
+                            width: Val::Px(THIRD_BAR_WIDTH * fraction),
+                            height: Val::Px(10.0),
+                            ..default()
+                        },
+                        ThemedColor(ThemeColorRole::Accent),
+                    ),
+                ],
+            )
+        });
+
+    let busiest = breakdown
+        .per_minute
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let minute_bundles = breakdown
+        .per_minute
+        .iter()
+        .map(|count| {
+            (
+                Node {
+                    width: Val::Px(4.0),
+                    // Minutes without a cell solved still show a sliver, so the gaps read as time
+                    height: Val::Px((SPARKLINE_HEIGHT * *count as f32 / busiest as f32).max(1.0)),
+                    ..default()
+                },
+                ThemedColor(ThemeColorRole::Accent),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        Children::spawn((
+            SpawnIter(third_bundles),
+            Spawn(text_bundle(format!(
+                "Longest pause: {}",
+                clock_text(breakdown.longest_pause)
+            ))),
+            Spawn((
+                Text::new("Cells solved per minute"),
+                TextFont::from_font_size(font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )),
+            Spawn((
+                Node {
+                    align_items: AlignItems::End,
+                    column_gap: Val::Px(1.0),
+                    height: Val::Px(SPARKLINE_HEIGHT),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(minute_bundles.into_iter())),
+            )),
+            Spawn(text_bundle(format!("Techniques needed: {techniques}"))),
+            Spawn(text_bundle(format!("Hints used: {hints}"))),
+        )),
+    )
+}
+
+// Note when each cell is first solved, by the game timer so that paused time is left out
+fn solve_timeline_system(
+    mut gameplay_events: EventReader<GameplayEvent>,
+    game_timer: Option<Res<GameTimer>>,
+    mut solve_timeline: ResMut<SolveTimeline>,
+) {
+    let Some(game_timer) = game_timer else {
+        return;
+    };
+    for event in gameplay_events.read() {
+        if let GameplayEvent::Placed {
+            position,
+            correct: Some(true),
+            ..
+        } = *event
+        {
+            if solve_timeline.solved.insert(position) {
+                solve_timeline.solved_at.push(game_timer.elapsed());
+            }
+        }
+    }
+}
+
+fn solve_timeline_reset_system(mut solve_timeline: ResMut<SolveTimeline>) {
+    *solve_timeline = SolveTimeline::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_thirds_and_longest_pause() {
+        let solved_at = [
+            secs(10),
+            secs(20),
+            secs(30),
+            secs(100),
+            secs(110),
+            secs(115),
+        ];
+        let breakdown = SolveBreakdown::new(&solved_at, secs(120));
+        assert_eq!(breakdown.thirds, [secs(20), secs(80), secs(20)]);
+        assert_eq!(breakdown.longest_pause, secs(70));
+
+        // Nothing solved is one long pause
+        let breakdown = SolveBreakdown::new(&[], secs(90));
+        assert_eq!(breakdown.thirds, [secs(0), secs(0), secs(90)]);
+        assert_eq!(breakdown.longest_pause, secs(90));
+    }
+
+    #[test]
+    fn test_per_minute() {
+        let solved_at = [secs(5), secs(50), secs(70), secs(179), secs(180)];
+        let breakdown = SolveBreakdown::new(&solved_at, secs(180));
+        assert_eq!(breakdown.per_minute, vec![2, 1, 2]);
+        assert_eq!(breakdown.sparkline_text(), "█▅█");
+    }
+}
@@ -0,0 +1,662 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::classic::{ClassicGrid, GridTransform},
+    plugins::common::storage,
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        error::PuzzleError,
+        hint::{grade, Technique},
+        validation::{validate_classic_puzzle, ValidationError},
+    },
+    utility::week::{day_streak, unix_day, unix_secs_now},
+    PuzzleSettings,
+};
+
+use super::{outcome::FailReason, AssistLevel, PuzzleType};
+
+/// How many solves are kept in the history.
+const SOLVE_HISTORY_SIZE: usize = 50;
+
+/// The name the solve history is saved under.
+const SOLVE_HISTORY_STORAGE_NAME: &str = "solve history";
+
+/// The version of the saved solve history. Histories saved with an older version are regraded,
+/// and their totals rebuilt from the solves they kept, when they're loaded.
+const SOLVE_HISTORY_VERSION: u32 = 2;
+
+pub fn solve_history_plugin(app: &mut App) {
+    app.insert_resource(SolveHistory::load());
+    #[cfg(debug_assertions)]
+    crate::plugins::console::register_console_command(
+        app,
+        crate::plugins::console::ConsoleCommand {
+            name: "regrade-history",
+            usage: "regrade-history",
+            help: "Recompute the difficulty of every solve in the history",
+            run: regrade_history_command,
+        },
+    );
+}
+
+/// An entry made while solving: a cell and the number put in it, or `None` if it was cleared.
+pub type SolveMove = ((u8, u8), Option<u8>);
+
+/// A solved puzzle, with what's needed to show its board and play it again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SolveRecord {
+    pub puzzle_type: PuzzleType,
+    pub seed: String,
+    pub rules: ClassicRules,
+    pub remix: Option<GridTransform>,
+    /// The puzzle's grid, as a grid string
+    pub givens: String,
+    /// The finished board, as a grid string
+    pub solution: String,
+    pub time: Duration,
+    /// How many hints were asked for
+    pub hints: u32,
+    /// Whether every hint the puzzle's difficulty allowed was used
+    #[serde(default)]
+    pub hints_exhausted: bool,
+    /// How many times the entries were checked
+    #[serde(default)]
+    pub checks: u32,
+    pub mistakes: u8,
+    /// The hardest technique the puzzle needs (`None` if the hint techniques can't solve it)
+    #[serde(default)]
+    pub difficulty: Option<Technique>,
+    /// The entries made while solving, in order
+    pub moves: Vec<SolveMove>,
+    /// When the puzzle was solved, in seconds since the Unix epoch
+    #[serde(default)]
+    pub solved_at: u64,
+    /// How much the game helped (solves saved before this was kept count as unassisted)
+    #[serde(default)]
+    pub assist_level: AssistLevel,
+    /// Whether it was a blitz game, which is timed by its countdown
+    #[serde(default)]
+    pub blitz: bool,
+    #[serde(default)]
+    pub outcome: SolveOutcome,
+}
+
+/// How a game in the history ended.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolveOutcome {
+    #[default]
+    Solved,
+    /// The mistake limit was reached
+    TooManyMistakes,
+    /// A blitz game's countdown ran out, scoring a point for each correct entry
+    TimeUp { score: usize },
+}
+
+impl From<FailReason> for SolveOutcome {
+    fn from(reason: FailReason) -> Self {
+        match reason {
+            FailReason::TooManyMistakes => SolveOutcome::TooManyMistakes,
+            FailReason::TimeUp { score } => SolveOutcome::TimeUp { score },
+        }
+    }
+}
+
+/// Returns how a difficulty is shown, e.g. in the history.
+#[must_use]
+pub fn difficulty_text(difficulty: Option<Technique>) -> String {
+    difficulty.map_or_else(
+        || "beyond the hint techniques".to_string(),
+        |technique| technique.to_string(),
+    )
+}
+
+impl SolveRecord {
+    /// Returns the saved givens, or why they can't be read if the solve is damaged.
+    pub fn givens(&self) -> Result<ClassicGrid, PuzzleError> {
+        self.givens.parse()
+    }
+
+    /// Returns the saved solution, or why it can't be read if the solve is damaged.
+    pub fn solution(&self) -> Result<ClassicGrid, PuzzleError> {
+        self.solution.parse()
+    }
+
+    /// Checks that the saved givens and solution are a puzzle that can be shown and played, e.g.
+    /// after the saved history was edited or cut short.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_classic_puzzle(&self.givens, &self.solution, self.rules).map(|_| ())
+    }
+
+    /// Returns the settings for playing the same puzzle again.
+    #[must_use]
+    pub fn settings(&self) -> PuzzleSettings {
+        PuzzleSettings {
+            puzzle_type: self.puzzle_type,
+            seed: self.seed.clone(),
+            rules: self.rules,
+            remix: self.remix,
+            assist_level: self.assist_level,
+            ..default()
+        }
+    }
+
+    /// Returns how the game ended, e.g. "Failed (too many mistakes)".
+    #[must_use]
+    pub fn outcome_text(&self) -> String {
+        match self.outcome {
+            SolveOutcome::Solved if self.blitz => "Solved before the countdown ran out".into(),
+            SolveOutcome::Solved => "Solved".into(),
+            SolveOutcome::TooManyMistakes => "Failed (too many mistakes)".into(),
+            SolveOutcome::TimeUp { score } => format!("Time ran out ({score} points)"),
+        }
+    }
+
+    /// Returns true if the solve counts towards best times and stats: the puzzle was solved, and
+    /// not against a blitz countdown.
+    #[must_use]
+    pub fn is_timed_solve(&self) -> bool {
+        self.outcome == SolveOutcome::Solved && !self.blitz
+    }
+}
+
+/// Totals for the solves of a puzzle type and difficulty, for exporting the history.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryStats {
+    pub puzzle_type: PuzzleType,
+    pub difficulty: Option<Technique>,
+    #[serde(default)]
+    pub assist_level: AssistLevel,
+    pub solves: usize,
+    pub best_secs: f64,
+    pub average_secs: f64,
+    pub hints: u32,
+    /// How many of the solves used every hint they were allowed
+    #[serde(default)]
+    pub hints_exhausted: usize,
+    pub mistakes: u32,
+}
+
+/// The totals for the solves of a puzzle type, difficulty, and assist level.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GroupTotals {
+    puzzle_type: PuzzleType,
+    difficulty: Option<Technique>,
+    assist_level: AssistLevel,
+    solves: usize,
+    best: Duration,
+    total_time: Duration,
+    hints: u32,
+    hints_exhausted: usize,
+    mistakes: u32,
+}
+
+/// Best times, totals, and the daily streak over every solve recorded, which are kept apart from
+/// the solves so that they outlast the ones dropped from the history. Only solved games count
+/// towards the streak, and only those solved outside blitz towards the rest.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct SolveTotals {
+    /// In the order they were first solved
+    groups: Vec<GroupTotals>,
+    /// The days with a solve, in order, which is one entry for each day played
+    solve_days: Vec<u64>,
+}
+
+impl SolveTotals {
+    fn from_records<'a>(records: impl IntoIterator<Item = &'a SolveRecord>) -> Self {
+        let mut totals = Self::default();
+        for record in records {
+            totals.add(record);
+        }
+        totals
+    }
+
+    fn add(&mut self, record: &SolveRecord) {
+        if record.outcome == SolveOutcome::Solved {
+            let day = unix_day(record.solved_at);
+            if let Err(index) = self.solve_days.binary_search(&day) {
+                self.solve_days.insert(index, day);
+            }
+        }
+        if !record.is_timed_solve() {
+            return;
+        }
+        let group = self.groups.iter_mut().find(|group| {
+            group.puzzle_type == record.puzzle_type
+                && group.difficulty == record.difficulty
+                && group.assist_level == record.assist_level
+        });
+        match group {
+            Some(group) => {
+                group.solves += 1;
+                group.best = group.best.min(record.time);
+                group.total_time += record.time;
+                group.hints += record.hints;
+                group.hints_exhausted += usize::from(record.hints_exhausted);
+                group.mistakes += u32::from(record.mistakes);
+            }
+            None => self.groups.push(GroupTotals {
+                puzzle_type: record.puzzle_type,
+                difficulty: record.difficulty,
+                assist_level: record.assist_level,
+                solves: 1,
+                best: record.time,
+                total_time: record.time,
+                hints: record.hints,
+                hints_exhausted: usize::from(record.hints_exhausted),
+                mistakes: record.mistakes.into(),
+            }),
+        }
+    }
+}
+
+/// What the history is exported to and imported from as JSON.
+#[derive(Serialize, Deserialize)]
+struct HistoryExport {
+    stats: Vec<HistoryStats>,
+    solves: Vec<SolveRecord>,
+}
+
+/// Quotes a CSV field if it has a comma, quote, or line break in it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The solved puzzles, most recent last, which are saved between sessions.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct SolveHistory {
+    /// The `SOLVE_HISTORY_VERSION` the history was saved with (0 for histories saved before it
+    /// was)
+    #[serde(default)]
+    version: u32,
+    records: Vec<SolveRecord>,
+    /// The totals over every solve, including those dropped from `records`
+    #[serde(default)]
+    totals: SolveTotals,
+    /// The last solve that set a personal best, until it's been celebrated
+    #[serde(skip)]
+    new_best: Option<SolveRecord>,
+}
+
+impl SolveHistory {
+    fn load() -> Self {
+        let Some(mut solve_history) = storage::load::<Self>(SOLVE_HISTORY_STORAGE_NAME) else {
+            return Self {
+                version: SOLVE_HISTORY_VERSION,
+                ..default()
+            };
+        };
+        if solve_history.version < SOLVE_HISTORY_VERSION {
+            info!(
+                "Regrading the solve history saved with version {}",
+                solve_history.version
+            );
+            solve_history.regrade(|done, total| info!("Regraded {done} of {total} solves"));
+            solve_history.totals = SolveTotals::from_records(&solve_history.records);
+            solve_history.version = SOLVE_HISTORY_VERSION;
+            storage::save(SOLVE_HISTORY_STORAGE_NAME, &solve_history);
+        }
+        solve_history
+    }
+
+    /// Recomputes the difficulty of every solve from its givens, calling `progress` with how many
+    /// solves are done out of the total after each one. Returns how many difficulties changed. The
+    /// totals keep the difficulties the solves were recorded with.
+    pub fn regrade(&mut self, mut progress: impl FnMut(usize, usize)) -> usize {
+        let total = self.records.len();
+        let mut changed = 0;
+        for (index, record) in self.records.iter_mut().enumerate() {
+            // Damaged solves keep their difficulty until they're removed
+            if let Ok(givens) = record.givens() {
+                let puzzle = ClassicPuzzle::from_grid_with_rules(givens, record.rules);
+                let difficulty = grade(&puzzle).difficulty;
+                if difficulty != record.difficulty {
+                    record.difficulty = difficulty;
+                    changed += 1;
+                }
+            }
+            progress(index + 1, total);
+        }
+        changed
+    }
+
+    /// Adds a solve, dropping the oldest once the history is full. A solve that's faster than the
+    /// others of its puzzle type, difficulty, and assist level is kept for `take_new_best`.
+    pub fn record(&mut self, record: SolveRecord) {
+        let is_best = record.is_timed_solve()
+            && self
+                .best_time(record.puzzle_type, record.difficulty, record.assist_level)
+                .is_some_and(|best| record.time < best);
+        if is_best {
+            self.new_best = Some(record.clone());
+        }
+        self.totals.add(&record);
+        self.records.push(record);
+        let overflow = self.records.len().saturating_sub(SOLVE_HISTORY_SIZE);
+        self.records.drain(..overflow);
+        storage::save(SOLVE_HISTORY_STORAGE_NAME, self);
+    }
+
+    /// Removes the solve at an index (oldest first), if there is one. It stays in the totals.
+    pub fn remove(&mut self, index: usize) {
+        if index < self.records.len() {
+            self.records.remove(index);
+            storage::save(SOLVE_HISTORY_STORAGE_NAME, self);
+        }
+    }
+
+    /// Returns the totals for each puzzle type, difficulty, and assist level that has been solved,
+    /// in the order they were first solved. Failed and blitz games are left out.
+    #[must_use]
+    pub fn stats(&self) -> Vec<HistoryStats> {
+        self.totals
+            .groups
+            .iter()
+            .map(|group| HistoryStats {
+                puzzle_type: group.puzzle_type,
+                difficulty: group.difficulty,
+                assist_level: group.assist_level,
+                solves: group.solves,
+                best_secs: group.best.as_secs_f64(),
+                average_secs: group.total_time.as_secs_f64() / group.solves as f64,
+                hints: group.hints,
+                hints_exhausted: group.hints_exhausted,
+                mistakes: group.mistakes,
+            })
+            .collect()
+    }
+
+    /// Returns the history and its stats as JSON, which `import_json` can read back.
+    pub fn to_json(&self) -> Result<String, String> {
+        let export = HistoryExport {
+            stats: self.stats(),
+            solves: self.records.clone(),
+        };
+        serde_json::to_string_pretty(&export).map_err(|err| err.to_string())
+    }
+
+    /// Returns the solves as CSV, one row each, oldest first.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "puzzle_type,seed,difficulty,assist_level,blitz,outcome,time_secs,hints,hints_exhausted,checks,mistakes,solved_at,givens\n",
+        );
+        for record in &self.records {
+            // Damaged solves are exported without their givens
+            let givens: String = record.givens().map_or_else(
+                |_| String::new(),
+                |givens| {
+                    givens
+                        .iter_all()
+                        .map(|val| val.map_or('.', |val| char::from(b'0' + val)))
+                        .collect()
+                },
+            );
+            let row = [
+                record.puzzle_type.to_string(),
+                record.seed.clone(),
+                difficulty_text(record.difficulty),
+                record.assist_level.to_string(),
+                record.blitz.to_string(),
+                format!("{:?}", record.outcome),
+                format!("{:.3}", record.time.as_secs_f64()),
+                record.hints.to_string(),
+                record.hints_exhausted.to_string(),
+                record.checks.to_string(),
+                record.mistakes.to_string(),
+                record.solved_at.to_string(),
+                givens,
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Returns the stats as CSV, one row for each puzzle type and difficulty.
+    #[must_use]
+    pub fn stats_to_csv(&self) -> String {
+        let mut csv = String::from(
+            "puzzle_type,difficulty,assist_level,solves,best_secs,average_secs,hints,hints_exhausted,mistakes\n",
+        );
+        for stats in self.stats() {
+            let row = [
+                stats.puzzle_type.to_string(),
+                difficulty_text(stats.difficulty),
+                stats.assist_level.to_string(),
+                stats.solves.to_string(),
+                format!("{:.3}", stats.best_secs),
+                format!("{:.3}", stats.average_secs),
+                stats.hints.to_string(),
+                stats.hints_exhausted.to_string(),
+                stats.mistakes.to_string(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Adds the solves from a JSON export that aren't already in the history, keeping the most
+    /// recent ones once it's full. Solves that fail validation are skipped. Returns how many were
+    /// added.
+    pub fn import_json(&mut self, json: &str) -> Result<usize, String> {
+        let export: HistoryExport = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let total = self.records.len();
+        for record in export.solves {
+            let known = self.records.iter().any(|known| {
+                known.seed == record.seed
+                    && known.solved_at == record.solved_at
+                    && known.time == record.time
+            });
+            if !known && record.validate().is_ok() {
+                self.totals.add(&record);
+                self.records.push(record);
+            }
+        }
+        let added = self.records.len() - total;
+        if added > 0 {
+            self.records.sort_by_key(|record| record.solved_at);
+            let overflow = self.records.len().saturating_sub(SOLVE_HISTORY_SIZE);
+            self.records.drain(..overflow);
+            storage::save(SOLVE_HISTORY_STORAGE_NAME, self);
+        }
+        Ok(added)
+    }
+
+    /// Returns the index of each solve that fails validation, along with why.
+    #[must_use]
+    pub fn damaged(&self) -> Vec<(usize, ValidationError)> {
+        self.records
+            .iter()
+            .enumerate()
+            .filter_map(|(index, record)| record.validate().err().map(|error| (index, error)))
+            .collect()
+    }
+
+    /// Removes every solve that fails validation, returning how many were removed. They stay in the
+    /// totals.
+    pub fn remove_damaged(&mut self) -> usize {
+        let total = self.records.len();
+        self.records.retain(|record| record.validate().is_ok());
+        let removed = total - self.records.len();
+        if removed > 0 {
+            storage::save(SOLVE_HISTORY_STORAGE_NAME, self);
+        }
+        removed
+    }
+
+    /// Returns the fastest time of the solves of a puzzle type and difficulty at an assist level.
+    #[must_use]
+    pub fn best_time(
+        &self,
+        puzzle_type: PuzzleType,
+        difficulty: Option<Technique>,
+        assist_level: AssistLevel,
+    ) -> Option<Duration> {
+        self.totals
+            .groups
+            .iter()
+            .find(|group| {
+                group.puzzle_type == puzzle_type
+                    && group.difficulty == difficulty
+                    && group.assist_level == assist_level
+            })
+            .map(|group| group.best)
+    }
+
+    /// Returns the fastest time of the solves of a puzzle type at an assist level, whatever their
+    /// difficulty.
+    #[must_use]
+    pub fn fastest_time(
+        &self,
+        puzzle_type: PuzzleType,
+        assist_level: AssistLevel,
+    ) -> Option<Duration> {
+        self.totals
+            .groups
+            .iter()
+            .filter(|group| group.puzzle_type == puzzle_type && group.assist_level == assist_level)
+            .map(|group| group.best)
+            .min()
+    }
+
+    /// Returns the hardest difficulty of a puzzle type that has been solved at an assist level,
+    /// along with its best time.
+    #[must_use]
+    pub fn hardest_best_time(
+        &self,
+        puzzle_type: PuzzleType,
+        assist_level: AssistLevel,
+    ) -> Option<(Option<Technique>, Duration)> {
+        // Puzzles the hint techniques can't solve are the hardest
+        std::iter::once(None)
+            .chain(Technique::ALL.into_iter().rev().map(Some))
+            .find_map(|difficulty| {
+                self.best_time(puzzle_type, difficulty, assist_level)
+                    .map(|time| (difficulty, time))
+            })
+    }
+
+    /// Returns how many days in a row, up to today, have had a solve.
+    #[must_use]
+    pub fn daily_streak(&self) -> u32 {
+        day_streak(
+            self.totals.solve_days.iter().copied(),
+            unix_day(unix_secs_now()),
+        )
+    }
+
+    /// Returns the last solve that set a personal best, if it hasn't been taken yet.
+    pub fn take_new_best(&mut self) -> Option<SolveRecord> {
+        self.new_best.take()
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&SolveRecord> {
+        self.records.get(index)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &SolveRecord> + ExactSizeIterator {
+        self.records.iter()
+    }
+}
+
+#[cfg(debug_assertions)]
+fn regrade_history_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let mut solve_history = world.resource_mut::<SolveHistory>();
+    let mut lines = Vec::new();
+    let changed = solve_history.regrade(|done, total| {
+        if done % 10 == 0 || done == total {
+            lines.push(format!("Regraded {done} of {total} solves"));
+        }
+    });
+    storage::save(SOLVE_HISTORY_STORAGE_NAME, &*solve_history);
+    lines.push(format!("{changed} difficulties changed"));
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(time_secs: u64, solved_at: u64) -> SolveRecord {
+        SolveRecord {
+            puzzle_type: PuzzleType::Classic,
+            seed: format!("seed {solved_at}"),
+            rules: ClassicRules::default(),
+            remix: None,
+            givens: String::new(),
+            solution: String::new(),
+            time: Duration::from_secs(time_secs),
+            hints: 0,
+            hints_exhausted: false,
+            checks: 0,
+            mistakes: 0,
+            difficulty: Some(Technique::NakedSingle),
+            moves: Vec::new(),
+            solved_at,
+            assist_level: AssistLevel::Off,
+            blitz: false,
+            outcome: SolveOutcome::Solved,
+        }
+    }
+
+    #[test]
+    fn test_bests_outlast_dropped_solves() {
+        let mut solve_history = SolveHistory::default();
+        solve_history.record(record(60, 0));
+        for index in 0..SOLVE_HISTORY_SIZE as u64 {
+            solve_history.record(record(120, index + 1));
+        }
+        assert_eq!(solve_history.iter().len(), SOLVE_HISTORY_SIZE);
+        assert!(solve_history
+            .iter()
+            .all(|record| record.time.as_secs() == 120));
+
+        let best = solve_history.best_time(
+            PuzzleType::Classic,
+            Some(Technique::NakedSingle),
+            AssistLevel::Off,
+        );
+        assert_eq!(best, Some(Duration::from_secs(60)));
+        let stats = solve_history.stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].solves, SOLVE_HISTORY_SIZE + 1);
+    }
+
+    #[test]
+    fn test_bests_leave_out_blitz_failed_and_assisted_games() {
+        let mut solve_history = SolveHistory::default();
+        solve_history.record(record(300, 0));
+        solve_history.record(SolveRecord {
+            blitz: true,
+            ..record(30, 1)
+        });
+        solve_history.record(SolveRecord {
+            outcome: SolveOutcome::TooManyMistakes,
+            ..record(20, 2)
+        });
+        solve_history.record(SolveRecord {
+            assist_level: AssistLevel::Full,
+            ..record(10, 3)
+        });
+        assert_eq!(
+            solve_history.fastest_time(PuzzleType::Classic, AssistLevel::Off),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            solve_history.fastest_time(PuzzleType::Classic, AssistLevel::Full),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(solve_history.stats().len(), 2);
+    }
+}
@@ -0,0 +1,182 @@
+use std::{fmt::Write, time::Duration};
+
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::GameSystems;
+
+/// How long a blitz game lasts.
+pub const BLITZ_DURATION: Duration = Duration::from_secs(5 * 60);
+
+pub fn game_timer_plugin(app: &mut App) {
+    app.init_resource::<TimerDisplaySettings>().add_systems(
+        Update,
+        (game_timer_system, timer_pause_system)
+            .in_set(GameSystems::DeriveState)
+            .run_if(resource_exists::<GameTimer>),
+    );
+}
+
+/// How precisely the game's time is shown. Times are always kept (and saved in the solve history)
+/// to the nanosecond, whatever they're shown as. Ordered from the least precise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimerPrecision {
+    /// Whole minutes, for casual games that don't need a ticking clock
+    Minutes,
+    /// `mm:ss`
+    #[default]
+    Seconds,
+    /// `mm:ss.cc`, with hundredths of a second for speedruns
+    Centiseconds,
+}
+
+/// How the game's timer is shown. This is kept between games.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimerDisplaySettings {
+    pub precision: TimerPrecision,
+}
+
+/// Stops the game timer while it exists, like the dialogs and prompts that cover the board.
+#[derive(Component, Default)]
+pub struct PausesGameTimer;
+
+/// Times the game being played. Games normally count up, but blitz games count down.
+#[derive(Resource)]
+pub enum GameTimer {
+    Stopwatch(Stopwatch),
+    Countdown(Timer),
+}
+
+impl Default for GameTimer {
+    fn default() -> Self {
+        GameTimer::Stopwatch(Stopwatch::new())
+    }
+}
+
+impl GameTimer {
+    /// Creates a timer that counts down from `duration`.
+    #[must_use]
+    pub fn countdown(duration: Duration) -> Self {
+        GameTimer::Countdown(Timer::new(duration, TimerMode::Once))
+    }
+
+    /// Creates a timer that counts up from `elapsed`, for picking up a saved game.
+    #[must_use]
+    pub fn resumed(elapsed: Duration) -> Self {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.set_elapsed(elapsed);
+        GameTimer::Stopwatch(stopwatch)
+    }
+
+    /// Returns how long the game has been played.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            GameTimer::Stopwatch(stopwatch) => stopwatch.elapsed(),
+            GameTimer::Countdown(timer) => timer.elapsed(),
+        }
+    }
+
+    /// Returns the time left before a countdown runs out (`None` if this isn't a countdown).
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        match self {
+            GameTimer::Stopwatch(_) => None,
+            GameTimer::Countdown(timer) => Some(timer.remaining()),
+        }
+    }
+
+    /// Returns true if a countdown has run out.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GameTimer::Countdown(timer) if timer.finished())
+    }
+
+    /// Stops (or restarts) the timer, e.g. while a dialog covers the board.
+    pub fn set_paused(&mut self, paused: bool) {
+        match (self, paused) {
+            (GameTimer::Stopwatch(stopwatch), true) => stopwatch.pause(),
+            (GameTimer::Stopwatch(stopwatch), false) => stopwatch.unpause(),
+            (GameTimer::Countdown(timer), true) => timer.pause(),
+            (GameTimer::Countdown(timer), false) => timer.unpause(),
+        }
+    }
+
+    /// Returns true if the timer is stopped.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        match self {
+            GameTimer::Stopwatch(stopwatch) => stopwatch.is_paused(),
+            GameTimer::Countdown(timer) => timer.paused(),
+        }
+    }
+
+    /// Adds time to the game as a penalty, which takes it off of a countdown.
+    pub fn add_penalty(&mut self, penalty: Duration) {
+        match self {
+            GameTimer::Stopwatch(stopwatch) => stopwatch.set_elapsed(stopwatch.elapsed() + penalty),
+            GameTimer::Countdown(timer) => {
+                let elapsed = (timer.elapsed() + penalty).min(timer.duration());
+                timer.set_elapsed(elapsed);
+            }
+        }
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        match self {
+            GameTimer::Stopwatch(stopwatch) => {
+                stopwatch.tick(delta);
+            }
+            GameTimer::Countdown(timer) => {
+                timer.tick(delta);
+            }
+        }
+    }
+}
+
+/// Formats a time like a clock, as `mm:ss`.
+#[must_use]
+pub fn clock_text(time: Duration) -> String {
+    precise_clock_text(time, TimerPrecision::Seconds)
+}
+
+/// Formats a time as precisely as asked.
+#[must_use]
+pub fn precise_clock_text(time: Duration, precision: TimerPrecision) -> String {
+    let mut text = String::new();
+    write_clock_text(&mut text, time, precision);
+    text
+}
+
+/// Writes a time as precisely as asked into `text`, replacing what was there. Reusing the same
+/// string keeps a clock that's redrawn every frame from allocating.
+pub fn write_clock_text(text: &mut String, time: Duration, precision: TimerPrecision) {
+    let secs = time.as_secs();
+    text.clear();
+    // Writing to a string can't fail
+    let _ = match precision {
+        TimerPrecision::Minutes => write!(text, "{} min", secs / 60),
+        TimerPrecision::Seconds => write!(text, "{:02}:{:02}", secs / 60, secs % 60),
+        TimerPrecision::Centiseconds => write!(
+            text,
+            "{:02}:{:02}.{:02}",
+            secs / 60,
+            secs % 60,
+            time.subsec_millis() / 10
+        ),
+    };
+}
+
+fn game_timer_system(time: Res<Time>, mut game_timer: ResMut<GameTimer>) {
+    game_timer.tick(time.delta());
+}
+
+// Stop the clock while anything that pauses it is shown
+fn timer_pause_system(
+    pauses_query: Query<(), With<PausesGameTimer>>,
+    mut game_timer: ResMut<GameTimer>,
+) {
+    let paused = !pauses_query.is_empty();
+    if game_timer.is_paused() != paused {
+        game_timer.set_paused(paused);
+    }
+}
@@ -0,0 +1,427 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    despawn_component,
+    grids::classic::ClassicGrid,
+    plugins::{
+        common::{
+            storage,
+            theme::{
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        loading::LoadingAssets,
+        menu::MenuState,
+        nav::{Navigator, Screen},
+    },
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        validation::{validate_classic_puzzle, ValidationError},
+    },
+    AppState, PuzzleSettings,
+};
+
+use super::{
+    cache::PuzzleCache, gameplay::GameplayEvent, input::InputAction, jump::SelectedCell,
+    outcome::GameOutcome, GameSystems, PuzzleType,
+};
+
+/// The tutorial's path within the assets folder.
+const TUTORIAL_PATH: &str = "welcome.tutorial.ron";
+
+/// The seed the tutorial's puzzle is played under. The puzzle is written out in the tutorial
+/// instead of being generated from it.
+pub const TUTORIAL_SEED: &str = "tutorial";
+
+/// The name whether the tutorial has been seen is saved under.
+const TUTORIAL_STORAGE_NAME: &str = "tutorial";
+
+pub fn tutorial_plugin(app: &mut App) {
+    app.init_asset::<Tutorial>()
+        .init_asset_loader::<TutorialLoader>()
+        .insert_resource(TutorialProgress::load())
+        .init_resource::<ActiveTutorial>()
+        .add_event::<StartTutorial>()
+        .add_systems(Startup, tutorial_init_system)
+        .add_systems(OnEnter(MenuState::Home), tutorial_first_launch_system)
+        .add_systems(Update, tutorial_start_system)
+        .add_systems(
+            Update,
+            (
+                tutorial_step_system,
+                tutorial_skip_system,
+                tutorial_prompt_system,
+            )
+                .chain()
+                .in_set(GameSystems::Render)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(
+            OnExit(AppState::Game),
+            (tutorial_exit_system, despawn_component::<TutorialPrompt>),
+        );
+}
+
+/// A scripted walk through a small puzzle, loaded from `assets/welcome.tutorial.ron` so that the
+/// steps can be changed without changing the game.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct Tutorial {
+    /// The puzzle's grid, one row per line with `.` for empty cells
+    pub givens: String,
+    pub solution: String,
+    pub steps: Vec<TutorialStep>,
+}
+
+impl Tutorial {
+    /// Returns the puzzle and its solution, which are checked when the tutorial loads.
+    #[must_use]
+    pub fn puzzle(&self) -> (ClassicPuzzle, ClassicGrid) {
+        let (givens, solution) =
+            validate_classic_puzzle(&self.givens, &self.solution, ClassicRules::default())
+                .unwrap_or_default();
+        (
+            ClassicPuzzle::from_grid_with_rules(givens, ClassicRules::default()),
+            solution,
+        )
+    }
+}
+
+/// A prompt, shown until the player does what it asks.
+#[derive(Deserialize, Clone, Debug)]
+pub struct TutorialStep {
+    pub prompt: String,
+    pub goal: TutorialGoal,
+}
+
+/// What the player has to do to move on to the next step.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TutorialGoal {
+    /// Select the cell at a (row, col)
+    Select((u8, u8)),
+    /// Enter a number in the cell at a (row, col)
+    Enter { position: (u8, u8), val: u8 },
+    /// Fill in the notes of every empty cell
+    FillMarks,
+    /// Finish the puzzle
+    Solve,
+}
+
+#[derive(Debug)]
+pub enum TutorialLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Puzzle(ValidationError),
+}
+
+impl std::fmt::Display for TutorialLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TutorialLoaderError::Io(err) => write!(f, "Couldn't read the tutorial: {err}"),
+            TutorialLoaderError::Ron(err) => write!(f, "Couldn't parse the tutorial: {err}"),
+            TutorialLoaderError::Puzzle(err) => {
+                write!(f, "The tutorial's puzzle is broken: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TutorialLoaderError {}
+
+impl From<std::io::Error> for TutorialLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        TutorialLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for TutorialLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        TutorialLoaderError::Ron(err)
+    }
+}
+
+#[derive(Default)]
+struct TutorialLoader;
+
+impl AssetLoader for TutorialLoader {
+    type Asset = Tutorial;
+    type Settings = ();
+    type Error = TutorialLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Tutorial, TutorialLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let tutorial: Tutorial = ron::de::from_bytes(&bytes)?;
+        validate_classic_puzzle(
+            &tutorial.givens,
+            &tutorial.solution,
+            ClassicRules::default(),
+        )
+        .map_err(TutorialLoaderError::Puzzle)?;
+        Ok(tutorial)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tutorial.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct TutorialHandle(Handle<Tutorial>);
+
+/// Whether the tutorial has been played (or skipped), which is saved so that it's only offered
+/// on the first launch.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TutorialProgress {
+    pub seen: bool,
+}
+
+impl TutorialProgress {
+    fn load() -> Self {
+        storage::load(TUTORIAL_STORAGE_NAME).unwrap_or_default()
+    }
+}
+
+/// The step of the tutorial being played, while it's being played.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActiveTutorial(pub Option<usize>);
+
+/// Asks for the tutorial to be played, e.g. on the first launch or from the Learn screen.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct StartTutorial;
+
+/// The current step's prompt, shown under the board with a button to skip the rest.
+#[derive(Component)]
+#[require(Node)]
+struct TutorialPrompt;
+
+#[derive(Component)]
+struct TutorialPromptText;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct TutorialSkipButton;
+
+fn tutorial_prompt_bundle() -> impl Bundle {
+    (
+        TutorialPrompt,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            align_self: AlignSelf::Center,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            max_width: Val::Px(640.0),
+            ..default()
+        },
+        GlobalZIndex(10),
+        children![
+            (
+                TutorialPromptText,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                ThemedFontWeight::Regular,
+                ThemedTextColor,
+            ),
+            (
+                TutorialSkipButton,
+                Node {
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                children![(
+                    Text::new("Skip"),
+                    TextFont::from_font_size(20.0),
+                    ThemedFontWeight::Bold,
+                    ThemedTextColor,
+                )],
+            ),
+        ],
+    )
+}
+
+fn tutorial_init_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(TUTORIAL_PATH);
+    loading_assets.track(&handle);
+    commands.insert_resource(TutorialHandle(handle));
+}
+
+// Offer the tutorial the first time the home screen is shown
+fn tutorial_first_launch_system(
+    tutorial_progress: Res<TutorialProgress>,
+    mut start_tutorial_events: EventWriter<StartTutorial>,
+) {
+    if !tutorial_progress.seen {
+        start_tutorial_events.write(StartTutorial);
+    }
+}
+
+// Start the tutorial's puzzle from its first step
+fn tutorial_start_system(
+    mut start_tutorial_events: EventReader<StartTutorial>,
+    tutorial_handle: Option<Res<TutorialHandle>>,
+    tutorials: Res<Assets<Tutorial>>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut active_tutorial: ResMut<ActiveTutorial>,
+    mut navigator: ResMut<Navigator>,
+) {
+    if start_tutorial_events.read().count() == 0 || active_tutorial.0.is_some() {
+        return;
+    }
+    let Some(tutorial) = tutorial_handle.and_then(|handle| tutorials.get(&handle.0)) else {
+        warn!("Couldn't load the tutorial");
+        return;
+    };
+    *puzzle_settings = PuzzleSettings {
+        puzzle_type: PuzzleType::Classic,
+        seed: TUTORIAL_SEED.into(),
+        ..default()
+    };
+    puzzle_cache.insert(&puzzle_settings, tutorial.puzzle());
+    active_tutorial.0 = Some(0);
+    navigator.push(Screen::Game);
+}
+
+fn finish_tutorial(active_tutorial: &mut ActiveTutorial, tutorial_progress: &mut TutorialProgress) {
+    active_tutorial.0 = None;
+    if !tutorial_progress.seen {
+        tutorial_progress.seen = true;
+        storage::save(TUTORIAL_STORAGE_NAME, tutorial_progress);
+    }
+}
+
+// Move on to the next step once the player has done what the current one asks, and finish the
+// tutorial after the last one
+#[allow(clippy::too_many_arguments)]
+fn tutorial_step_system(
+    mut input_actions: EventReader<InputAction>,
+    mut gameplay_events: EventReader<GameplayEvent>,
+    selected_cell: Res<SelectedCell>,
+    tutorial_handle: Option<Res<TutorialHandle>>,
+    tutorials: Res<Assets<Tutorial>>,
+    mut active_tutorial: ResMut<ActiveTutorial>,
+    mut tutorial_progress: ResMut<TutorialProgress>,
+) {
+    let filled_marks = input_actions
+        .read()
+        .any(|action| *action == InputAction::FillMarks);
+    let gameplay_events: Vec<GameplayEvent> = gameplay_events.read().cloned().collect();
+    let Some(step) = active_tutorial.0 else {
+        return;
+    };
+    let Some(tutorial) = tutorial_handle.and_then(|handle| tutorials.get(&handle.0)) else {
+        return;
+    };
+    let Some(tutorial_step) = tutorial.steps.get(step) else {
+        finish_tutorial(&mut active_tutorial, &mut tutorial_progress);
+        return;
+    };
+    let met = match tutorial_step.goal {
+        TutorialGoal::Select(position) => selected_cell.0 == Some(position),
+        TutorialGoal::Enter { position, val } => gameplay_events.iter().any(|event| {
+            *event
+                == GameplayEvent::Placed {
+                    position,
+                    val: Some(val),
+                    correct: Some(true),
+                }
+        }),
+        TutorialGoal::FillMarks => filled_marks,
+        TutorialGoal::Solve => gameplay_events
+            .iter()
+            .any(|event| matches!(event, GameplayEvent::Ended(GameOutcome::Completed { .. }))),
+    };
+    if !met {
+        return;
+    }
+    if step + 1 < tutorial.steps.len() {
+        active_tutorial.0 = Some(step + 1);
+    } else {
+        finish_tutorial(&mut active_tutorial, &mut tutorial_progress);
+    }
+}
+
+fn tutorial_skip_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<TutorialSkipButton>)>,
+    mut active_tutorial: ResMut<ActiveTutorial>,
+    mut tutorial_progress: ResMut<TutorialProgress>,
+) {
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        finish_tutorial(&mut active_tutorial, &mut tutorial_progress);
+    }
+}
+
+// Show the current step's prompt
+fn tutorial_prompt_system(
+    active_tutorial: Res<ActiveTutorial>,
+    tutorial_handle: Option<Res<TutorialHandle>>,
+    tutorials: Res<Assets<Tutorial>>,
+    prompt_query: Query<Entity, With<TutorialPrompt>>,
+    mut text_query: Query<&mut Text, With<TutorialPromptText>>,
+    mut commands: Commands,
+) {
+    let step = active_tutorial.0.and_then(|step| {
+        let tutorial = tutorial_handle
+            .as_ref()
+            .and_then(|handle| tutorials.get(&handle.0))?;
+        Some((step, tutorial.steps.len(), tutorial.steps.get(step)?))
+    });
+    match (step, prompt_query.iter().next()) {
+        (Some(_), None) => {
+            commands.spawn(tutorial_prompt_bundle());
+        }
+        (None, Some(prompt)) => commands.entity(prompt).despawn(),
+        _ => {}
+    }
+
+    let Some((step, steps, tutorial_step)) = step else {
+        return;
+    };
+    let prompt_text = format!("Step {} of {steps}: {}", step + 1, tutorial_step.prompt);
+    for mut text in &mut text_query {
+        if text.0 != prompt_text {
+            text.0.clone_from(&prompt_text);
+        }
+    }
+}
+
+// Leaving the game ends the tutorial, which still counts as having seen it
+fn tutorial_exit_system(
+    mut active_tutorial: ResMut<ActiveTutorial>,
+    mut tutorial_progress: ResMut<TutorialProgress>,
+) {
+    if active_tutorial.0.is_some() {
+        finish_tutorial(&mut active_tutorial, &mut tutorial_progress);
+    }
+}
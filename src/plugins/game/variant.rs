@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use super::PuzzleType;
+
+pub fn variant_registry_plugin(app: &mut App) {
+    app.init_resource::<VariantRegistry>();
+}
+
+/// The clues drawn over a board besides the givens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Overlay {
+    KropkiDots,
+    Arrows,
+    /// Extra regions that must contain every digit, like the hyper windows
+    Windows,
+}
+
+/// An option that a puzzle type adds to the options step of the New Puzzle menu, on top of the
+/// modes and assists every puzzle type has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Display)]
+pub enum VariantOption {
+    /// Cells a king's move apart can't have the same number
+    #[strum(to_string = "Anti-king")]
+    AntiKing,
+    /// Orthogonally adjacent cells can't have consecutive numbers
+    #[strum(to_string = "Non-consecutive")]
+    NonConsecutive,
+}
+
+/// What a puzzle type supports. Menus and the game screen check these instead of matching on
+/// puzzle types, so adding a type only means describing it here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VariantInfo {
+    pub puzzle_type: PuzzleType,
+    /// The number of cells along each side of the boards it can be played on
+    pub board_sizes: &'static [u8],
+    pub overlays: &'static [Overlay],
+    /// Puzzles can be generated from a seed
+    pub generator: bool,
+    /// It's played on a single classic grid, which the preview, hints, and practice need
+    pub classic_grid: bool,
+    /// The options it adds to the New Puzzle menu, like the classic constraints
+    pub options: &'static [VariantOption],
+    /// A difficulty can be chosen. No puzzle type supports this yet.
+    pub difficulty: bool,
+}
+
+impl VariantInfo {
+    const CLASSIC: Self = Self {
+        puzzle_type: PuzzleType::Classic,
+        board_sizes: &[9],
+        overlays: &[],
+        generator: true,
+        classic_grid: true,
+        options: &[VariantOption::AntiKing, VariantOption::NonConsecutive],
+        difficulty: false,
+    };
+
+    /// Returns whether the puzzle type adds an option to the New Puzzle menu.
+    #[must_use]
+    pub fn has_option(&self, option: VariantOption) -> bool {
+        self.options.contains(&option)
+    }
+
+    #[must_use]
+    pub fn of(puzzle_type: PuzzleType) -> Self {
+        match puzzle_type {
+            PuzzleType::Classic => Self::CLASSIC,
+            #[cfg(debug_assertions)]
+            PuzzleType::Knight => Self {
+                puzzle_type,
+                generator: false,
+                classic_grid: false,
+                options: &[],
+                ..Self::CLASSIC
+            },
+            #[cfg(debug_assertions)]
+            PuzzleType::FullKropki => Self {
+                puzzle_type,
+                overlays: &[Overlay::KropkiDots],
+                generator: false,
+                classic_grid: false,
+                options: &[],
+                ..Self::CLASSIC
+            },
+            #[cfg(debug_assertions)]
+            PuzzleType::Samurai => Self {
+                puzzle_type,
+                board_sizes: &[21],
+                classic_grid: false,
+                options: &[],
+                ..Self::CLASSIC
+            },
+            #[cfg(debug_assertions)]
+            PuzzleType::Hyper => Self {
+                puzzle_type,
+                overlays: &[Overlay::Windows],
+                ..Self::CLASSIC
+            },
+            #[cfg(debug_assertions)]
+            PuzzleType::Arrow => Self {
+                puzzle_type,
+                overlays: &[Overlay::Arrows],
+                classic_grid: false,
+                options: &[],
+                ..Self::CLASSIC
+            },
+        }
+    }
+}
+
+/// Describes every puzzle type that's built in.
+#[derive(Resource, Clone, Debug)]
+pub struct VariantRegistry {
+    variants: Vec<VariantInfo>,
+}
+
+impl Default for VariantRegistry {
+    fn default() -> Self {
+        Self {
+            variants: PuzzleType::iter().map(VariantInfo::of).collect(),
+        }
+    }
+}
+
+impl VariantRegistry {
+    #[must_use]
+    pub fn get(&self, puzzle_type: PuzzleType) -> &VariantInfo {
+        self.variants
+            .iter()
+            .find(|variant| variant.puzzle_type == puzzle_type)
+            .expect("every puzzle type is registered")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &VariantInfo> {
+        self.variants.iter()
+    }
+}
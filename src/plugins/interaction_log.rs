@@ -0,0 +1,247 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ButtonState},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    plugins::{
+        common::{export::export_file, storage},
+        game::{input::ActionButton, jump::SelectedCell},
+        menu::MenuState,
+    },
+    AppState,
+};
+
+/// How many interactions are kept in the log. The oldest are dropped once it's full.
+const INTERACTION_LOG_SIZE: usize = 2000;
+
+/// How many interactions are recorded between saves of the log.
+const INTERACTION_LOG_SAVE_INTERVAL: usize = 50;
+
+/// The name the log is saved under, which is a file in the app's data folder on native builds.
+const INTERACTION_LOG_STORAGE_NAME: &str = "interaction log";
+
+/// The name whether the log is recording is saved under.
+const INTERACTION_LOG_SETTINGS_STORAGE_NAME: &str = "interaction log settings";
+
+/// Records how the board and menus are used, for deciding how to improve them, while the player
+/// has turned it on with Ctrl+Shift+L. Nothing identifying is kept (no seeds, entries, or times of
+/// day) and nothing is sent anywhere: the log is saved with the app's other data, and Ctrl+Shift+E
+/// exports it as JSON.
+pub fn interaction_log_plugin(app: &mut App) {
+    app.insert_resource(InteractionLogSettings::load())
+        .insert_resource(InteractionLog::load())
+        .add_systems(
+            Update,
+            (
+                interaction_log_shortcut_system,
+                (
+                    cell_focus_log_system.run_if(in_state(AppState::Game)),
+                    input_source_log_system.run_if(in_state(AppState::Game)),
+                    menu_timing_log_system,
+                )
+                    .run_if(|settings: Res<InteractionLogSettings>| settings.enabled),
+            )
+                .chain(),
+        )
+        .add_systems(OnExit(AppState::Game), interaction_log_save_system);
+}
+
+/// Whether interactions are being recorded. This is off until the player turns it on, and is kept
+/// between sessions.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractionLogSettings {
+    pub enabled: bool,
+}
+
+impl InteractionLogSettings {
+    fn load() -> Self {
+        storage::load(INTERACTION_LOG_SETTINGS_STORAGE_NAME).unwrap_or_default()
+    }
+}
+
+/// What was used to play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputSource {
+    /// The on-screen keypad and panel buttons, whether clicked or touched
+    Keypad,
+    Keyboard,
+    Gamepad,
+}
+
+/// Something the player did, without anything that could tell who they are or which puzzle they
+/// were playing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LoggedInteraction {
+    /// A cell of the board was selected, at a (row, col)
+    CellFocus((u8, u8)),
+    /// A key or button was pressed while playing
+    Input(InputSource),
+    /// A menu screen was left after being shown for this many seconds
+    MenuTime { menu: String, secs: f32 },
+}
+
+/// An interaction and when it happened, in seconds since the app started.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InteractionRecord {
+    pub at_secs: f32,
+    pub interaction: LoggedInteraction,
+}
+
+/// The recorded interactions, oldest first, which are saved between sessions.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct InteractionLog {
+    records: Vec<InteractionRecord>,
+    /// How many interactions were recorded since the log was last saved
+    #[serde(skip)]
+    unsaved: usize,
+}
+
+impl InteractionLog {
+    fn load() -> Self {
+        storage::load(INTERACTION_LOG_STORAGE_NAME).unwrap_or_default()
+    }
+
+    fn save(&mut self) {
+        storage::save(INTERACTION_LOG_STORAGE_NAME, self);
+        self.unsaved = 0;
+    }
+
+    /// Adds an interaction, dropping the oldest once the log is full, and saves the log every so
+    /// often.
+    pub fn record(&mut self, at_secs: f32, interaction: LoggedInteraction) {
+        self.records.push(InteractionRecord {
+            at_secs,
+            interaction,
+        });
+        let overflow = self.records.len().saturating_sub(INTERACTION_LOG_SIZE);
+        self.records.drain(..overflow);
+        self.unsaved += 1;
+        if self.unsaved >= INTERACTION_LOG_SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// Returns the log as JSON, for exporting it.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.records).map_err(|err| err.to_string())
+    }
+}
+
+// Turn recording on and off with Ctrl+Shift+L, and export the log with Ctrl+Shift+E
+fn interaction_log_shortcut_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<InteractionLogSettings>,
+    mut interaction_log: ResMut<InteractionLog>,
+) {
+    let control_keys = [
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ];
+    if !(keys.any_pressed(control_keys)
+        && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]))
+    {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyL) {
+        settings.enabled = !settings.enabled;
+        storage::save(INTERACTION_LOG_SETTINGS_STORAGE_NAME, &*settings);
+        if !settings.enabled {
+            interaction_log.save();
+        }
+        let state = if settings.enabled { "on" } else { "off" };
+        info!("Interaction logging is {state}");
+    }
+    if keys.just_pressed(KeyCode::KeyE) {
+        let exported = interaction_log
+            .to_json()
+            .and_then(|json| export_file("sudoku-interactions.json", &json));
+        match exported {
+            Ok(path) => info!("Exported the interaction log to {path}"),
+            Err(err) => warn!("Couldn't export the interaction log: {err}"),
+        }
+    }
+}
+
+fn cell_focus_log_system(
+    time: Res<Time<Real>>,
+    selected_cell: Res<SelectedCell>,
+    mut interaction_log: ResMut<InteractionLog>,
+) {
+    if !selected_cell.is_changed() {
+        return;
+    }
+    if let Some(position) = selected_cell.0 {
+        interaction_log.record(time.elapsed_secs(), LoggedInteraction::CellFocus(position));
+    }
+}
+
+// Count each key and button press while playing by what it came from
+fn input_source_log_system(
+    time: Res<Time<Real>>,
+    mut keyboard_inputs: EventReader<KeyboardInput>,
+    action_button_query: Query<&Interaction, (Changed<Interaction>, With<ActionButton>)>,
+    gamepad_query: Query<&Gamepad>,
+    mut interaction_log: ResMut<InteractionLog>,
+) {
+    let at_secs = time.elapsed_secs();
+    let keyboard_presses = keyboard_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed && !input.repeat)
+        .count();
+    let keypad_presses = action_button_query
+        .iter()
+        .filter(|interaction| **interaction == Interaction::Pressed)
+        .count();
+    let gamepad_presses = gamepad_query
+        .iter()
+        .map(|gamepad| gamepad.get_just_pressed().count())
+        .sum();
+    for (source, presses) in [
+        (InputSource::Keyboard, keyboard_presses),
+        (InputSource::Keypad, keypad_presses),
+        (InputSource::Gamepad, gamepad_presses),
+    ] {
+        for _ in 0..presses {
+            interaction_log.record(at_secs, LoggedInteraction::Input(source));
+        }
+    }
+}
+
+// Time each menu screen from when it's shown until it's left
+fn menu_timing_log_system(
+    time: Res<Time<Real>>,
+    menu_state: Res<State<MenuState>>,
+    mut shown: Local<Option<(MenuState, f32)>>,
+    mut interaction_log: ResMut<InteractionLog>,
+) {
+    let now = time.elapsed_secs();
+    let current = menu_state.get();
+    if shown.as_ref().is_some_and(|(menu, _)| menu == current) {
+        return;
+    }
+    if let Some((menu, since)) = shown.take() {
+        if menu != MenuState::Disabled {
+            interaction_log.record(
+                now,
+                LoggedInteraction::MenuTime {
+                    menu: format!("{menu:?}"),
+                    secs: now - since,
+                },
+            );
+        }
+    }
+    *shown = Some((current.clone(), now));
+}
+
+fn interaction_log_save_system(
+    settings: Res<InteractionLogSettings>,
+    mut interaction_log: ResMut<InteractionLog>,
+) {
+    if settings.enabled && interaction_log.unsaved > 0 {
+        interaction_log.save();
+    }
+}
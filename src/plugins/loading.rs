@@ -0,0 +1,92 @@
+use bevy::{asset::UntypedAssetId, prelude::*};
+
+use crate::{despawn_component, AppState, APP_TITLE};
+
+use super::{
+    menu::MenuState,
+    nav::{Navigator, Screen},
+};
+
+pub fn loading_plugin(app: &mut App) {
+    app.init_resource::<LoadingAssets>()
+        .add_systems(OnEnter(AppState::Loading), loading_setup)
+        .add_systems(
+            Update,
+            loading_progress_system.run_if(in_state(AppState::Loading)),
+        )
+        .add_systems(
+            OnExit(AppState::Loading),
+            despawn_component::<LoadingScreen>,
+        );
+}
+
+/// The assets that have to finish loading before leaving the splash screen. Plugins add their
+/// required assets (e.g. fonts) at startup.
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    ids: Vec<UntypedAssetId>,
+}
+
+impl LoadingAssets {
+    /// Waits for an asset (and its dependencies) before showing the menus.
+    pub fn track(&mut self, handle: impl Into<UntypedAssetId>) {
+        self.ids.push(handle.into());
+    }
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingProgressText;
+
+// The splash uses Bevy's built-in font, since the theme's fonts are what's loading
+fn loading_setup(mut commands: Commands) {
+    commands.spawn((
+        LoadingScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        children![
+            (Text::new(APP_TITLE), TextFont::from_font_size(80.0)),
+            (
+                LoadingProgressText,
+                Text::new("Loading..."),
+                TextFont::from_font_size(20.0),
+            )
+        ],
+    ));
+}
+
+// Show how many of the required assets are done, and go to the menus once they all are. Assets
+// that fail to load count as done so that the app doesn't get stuck on the splash screen.
+fn loading_progress_system(
+    asset_server: Res<AssetServer>,
+    loading_assets: Res<LoadingAssets>,
+    mut progress_text_query: Query<&mut Text, With<LoadingProgressText>>,
+    mut navigator: ResMut<Navigator>,
+) {
+    let done = loading_assets
+        .ids
+        .iter()
+        .filter(|&&id| {
+            asset_server.is_loaded_with_dependencies(id) || asset_server.load_state(id).is_failed()
+        })
+        .count();
+    let total = loading_assets.ids.len();
+    for mut text in &mut progress_text_query {
+        let progress = format!("Loading... {done}/{total}");
+        if text.0 != progress {
+            text.0 = progress;
+        }
+    }
+    if done == total {
+        navigator.replace(Screen::Menu(MenuState::Home));
+    }
+}
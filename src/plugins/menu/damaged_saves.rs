@@ -0,0 +1,179 @@
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    despawn_component,
+    plugins::{
+        common::theme::{
+            focus::FocusScope,
+            node::{
+                ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+            },
+            style::StyleSheet,
+            text::{ThemedFontWeight, ThemedTextColor},
+        },
+        game::solve_history::SolveHistory,
+        nav::{NavState, Navigator, Screen},
+    },
+};
+
+use super::MenuState;
+
+/// Shows why saved solves can't be loaded the first time the home menu is shown, and offers to
+/// discard them.
+pub fn damaged_saves_menu_plugin(app: &mut App) {
+    app.init_resource::<DamagedSavesReviewed>()
+        .add_systems(OnEnter(MenuState::Home), damaged_saves_check_system)
+        .add_systems(OnEnter(MenuState::DamagedSaves), damaged_saves_menu_setup)
+        .add_systems(
+            Update,
+            damaged_saves_button_system.run_if(in_state(MenuState::DamagedSaves)),
+        )
+        .add_systems(
+            OnExit(MenuState::DamagedSaves),
+            despawn_component::<DamagedSavesMenu>,
+        );
+}
+
+/// Whether the damaged solves have been shown this session, so that they're only brought up once.
+#[derive(Resource, Default)]
+struct DamagedSavesReviewed(bool);
+
+#[derive(Component)]
+#[require(Node, FocusScope)]
+struct DamagedSavesMenu;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum DamagedSavesButton {
+    /// Removes the damaged solves from the history
+    Discard,
+    /// Leaves the damaged solves in the history, to be shown again next session
+    Keep,
+}
+
+// Bring up the damaged solves if there are any that haven't been shown yet
+fn damaged_saves_check_system(
+    mut reviewed: ResMut<DamagedSavesReviewed>,
+    solve_history: Res<SolveHistory>,
+    mut navigator: ResMut<Navigator>,
+) {
+    if reviewed.0 {
+        return;
+    }
+    reviewed.0 = true;
+    let damaged = solve_history.damaged();
+    if damaged.is_empty() {
+        return;
+    }
+    warn!("{} saved solves failed validation", damaged.len());
+    navigator.push(Screen::Menu(MenuState::DamagedSaves));
+}
+
+fn damaged_saves_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    solve_history: Res<SolveHistory>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    nav_state.set(NavState::Back);
+
+    let damaged = solve_history.damaged();
+    let title_bundle = (
+        Text::new("Damaged saves"),
+        TextFont::from_font_size(style_sheet.heading_font_size),
+        Node {
+            margin: UiRect::all(Val::Px(40.0)),
+            ..default()
+        },
+        ThemedFontWeight::Bold,
+        ThemedTextColor,
+    );
+    let summary_bundle = (
+        Text::new(format!(
+            "{} saved solves are damaged, so their boards can't be shown or replayed.",
+            damaged.len()
+        )),
+        TextFont::from_font_size(style_sheet.body_font_size),
+        ThemedTextColor,
+    );
+    let error_bundles: Vec<_> = damaged
+        .into_iter()
+        .filter_map(|(index, error)| {
+            let record = solve_history.get(index)?;
+            Some((
+                Text::new(format!("{} ({}): {error}", record.puzzle_type, record.seed)),
+                TextFont::from_font_size(style_sheet.body_font_size),
+                ThemedTextColor,
+            ))
+        })
+        .collect();
+    let button_bundle = |button: DamagedSavesButton, label: &str| {
+        (
+            button,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(style_sheet.button_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    let buttons_bundle = (
+        Node {
+            column_gap: Val::Px(style_sheet.gap),
+            margin: UiRect::top(Val::Px(style_sheet.gap)),
+            ..default()
+        },
+        children![
+            button_bundle(DamagedSavesButton::Discard, "Discard damaged solves"),
+            button_bundle(DamagedSavesButton::Keep, "Keep them"),
+        ],
+    );
+
+    commands.spawn((
+        DamagedSavesMenu,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::top(Val::Px(80.0)),
+            row_gap: Val::Px(style_sheet.gap / 2.0),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(title_bundle),
+            Spawn(summary_bundle),
+            SpawnIter(error_bundles.into_iter()),
+            Spawn(buttons_bundle),
+        )),
+    ));
+}
+
+fn damaged_saves_button_system(
+    interaction_query: Query<(&Interaction, &DamagedSavesButton), Changed<Interaction>>,
+    mut solve_history: ResMut<SolveHistory>,
+    mut navigator: ResMut<Navigator>,
+) {
+    for (_, button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        if let DamagedSavesButton::Discard = button {
+            let removed = solve_history.remove_damaged();
+            info!("Discarded {removed} damaged solves");
+        }
+        navigator.back();
+    }
+}
@@ -1,17 +1,305 @@
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
 
-use crate::{despawn_component, plugins::nav::NavState};
+use crate::{
+    despawn_component,
+    plugins::{
+        common::{
+            export::export_file,
+            theme::{
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        game::{
+            marathon::{MarathonHistory, MARATHON_LENGTH},
+            solve_history::SolveHistory,
+            timer::clock_text,
+            PuzzleType,
+        },
+        nav::{NavState, Navigator, Screen},
+    },
+};
 
-use super::MenuState;
+use super::{solve_detail::SelectedSolve, MenuState};
+
+/// How many of the most recent marathons are listed.
+const RECENT_MARATHONS: usize = 5;
+
+/// How many of the most recent solves are listed.
+const RECENT_SOLVES: usize = 5;
 
 pub fn history_menu_plugin(app: &mut App) {
     app.add_systems(OnEnter(MenuState::History), history_menu_setup)
-        .add_systems(OnExit(MenuState::History), despawn_component::<HistoryMenu>);
+        .add_systems(OnExit(MenuState::History), despawn_component::<HistoryMenu>)
+        .add_systems(
+            Update,
+            (
+                solve_entry_button_system,
+                export_button_system,
+                history_drop_system,
+            )
+                .run_if(in_state(MenuState::History)),
+        );
 }
 
 #[derive(Component)]
 struct HistoryMenu;
 
-fn history_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut _commands: Commands) {
+/// Opens the details of the solve at an index of the history.
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct SolveEntryButton(usize);
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum ExportButton {
+    /// Saves the solves and stats as JSON, which can be dropped on this screen to import them
+    Json,
+    /// Saves the solves and the stats as two CSV files, for spreadsheets
+    Csv,
+}
+
+/// Tells the player where an export went, or what a dropped file added.
+#[derive(Component)]
+#[require(Text)]
+struct ExportStatusText;
+
+fn history_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    marathon_history: Res<MarathonHistory>,
+    solve_history: Res<SolveHistory>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
     nav_state.set(NavState::Back);
+
+    let heading_bundle = |text: &str| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(style_sheet.heading_font_size),
+            Node {
+                margin: UiRect::top(Val::Px(style_sheet.gap)),
+                ..default()
+            },
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )
+    };
+    let body_bundle = |text: String| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(style_sheet.body_font_size),
+            ThemedTextColor,
+        )
+    };
+
+    let finished = marathon_history.iter().count();
+    let best = marathon_history.best_total(PuzzleType::Classic, MARATHON_LENGTH.into());
+    let stat_bundles = [
+        body_bundle(format!("Marathons finished: {finished}")),
+        body_bundle(format!(
+            "Best classic marathon: {}",
+            best.map_or_else(|| "none yet".to_string(), clock_text)
+        )),
+    ];
+
+    // Each marathon's total, seed, and splits, most recent first
+    let marathon_bundles: Vec<_> = marathon_history
+        .iter()
+        .rev()
+        .take(RECENT_MARATHONS)
+        .map(|result| {
+            let splits: Vec<String> = result.splits.iter().copied().map(clock_text).collect();
+            body_bundle(format!(
+                "{} {} ({}): {}",
+                result.puzzle_type,
+                clock_text(result.total()),
+                result.seed,
+                splits.join(", ")
+            ))
+        })
+        .collect();
+    let empty_bundle = marathon_bundles
+        .is_empty()
+        .then(|| body_bundle("Finish a marathon to see its splits here".into()));
+
+    // Each solve's time and seed, most recent first, opening its details when pressed
+    let solve_bundles: Vec<_> = solve_history
+        .iter()
+        .enumerate()
+        .rev()
+        .take(RECENT_SOLVES)
+        .map(|(index, record)| {
+            (
+                SolveEntryButton(index),
+                Node {
+                    padding: UiRect::axes(Val::Px(style_sheet.button_padding), Val::Px(4.0)),
+                    ..default()
+                },
+                children![body_bundle(if record.is_timed_solve() {
+                    format!(
+                        "{} {} ({})",
+                        record.puzzle_type,
+                        clock_text(record.time),
+                        record.seed
+                    )
+                } else {
+                    format!(
+                        "{} {} - {} ({})",
+                        record.puzzle_type,
+                        clock_text(record.time),
+                        record.outcome_text(),
+                        record.seed
+                    )
+                })],
+            )
+        })
+        .collect();
+    let no_solves_bundle = solve_bundles
+        .is_empty()
+        .then(|| body_bundle("Solve a puzzle to see it here".into()));
+
+    let export_button_bundle = |button: ExportButton, label: &str| {
+        (
+            button,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(style_sheet.button_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+
+    commands.spawn((
+        HistoryMenu,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::top(Val::Px(80.0)),
+            row_gap: Val::Px(style_sheet.gap / 2.0),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(heading_bundle("Stats")),
+            SpawnIter(stat_bundles.into_iter()),
+            Spawn(heading_bundle("Marathons")),
+            SpawnIter(marathon_bundles.into_iter()),
+            SpawnIter(empty_bundle.into_iter()),
+            Spawn(heading_bundle("Solves")),
+            SpawnIter(solve_bundles.into_iter()),
+            SpawnIter(no_solves_bundle.into_iter()),
+            Spawn((
+                Node {
+                    column_gap: Val::Px(style_sheet.gap),
+                    margin: UiRect::top(Val::Px(style_sheet.gap)),
+                    ..default()
+                },
+                children![
+                    export_button_bundle(ExportButton::Json, "Export JSON"),
+                    export_button_bundle(ExportButton::Csv, "Export CSV"),
+                ],
+            )),
+            Spawn((
+                ExportStatusText,
+                TextFont::from_font_size(style_sheet.body_font_size),
+                ThemedTextColor,
+            )),
+        )),
+    ));
+}
+
+fn solve_entry_button_system(
+    interaction_query: Query<(&Interaction, &SolveEntryButton), Changed<Interaction>>,
+    mut navigator: ResMut<Navigator>,
+    mut commands: Commands,
+) {
+    for (_, solve_entry_button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        commands.insert_resource(SelectedSolve(solve_entry_button.0));
+        navigator.push(Screen::Menu(MenuState::SolveDetail));
+    }
+}
+
+fn export_button_system(
+    interaction_query: Query<(&Interaction, &ExportButton), Changed<Interaction>>,
+    solve_history: Res<SolveHistory>,
+    mut status_query: Query<&mut Text, With<ExportStatusText>>,
+) {
+    for (_, button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        let exported = match button {
+            ExportButton::Json => solve_history
+                .to_json()
+                .and_then(|json| export_file("sudoku-history.json", &json)),
+            ExportButton::Csv => export_file("sudoku-history.csv", &solve_history.to_csv())
+                .and_then(|solves| {
+                    let stats = export_file("sudoku-stats.csv", &solve_history.stats_to_csv())?;
+                    Ok(format!("{solves} and {stats}"))
+                }),
+        };
+        let status = match exported {
+            Ok(place) => format!("Saved to {place}"),
+            Err(err) => format!("Couldn't export, since {err}"),
+        };
+        for mut text in &mut status_query {
+            text.0.clone_from(&status);
+        }
+    }
+}
+
+// Import the solves from a JSON export dropped on the window
+fn history_drop_system(
+    mut file_drag_and_drop_events: EventReader<FileDragAndDrop>,
+    mut solve_history: ResMut<SolveHistory>,
+    mut status_query: Query<&mut Text, With<ExportStatusText>>,
+) {
+    for event in file_drag_and_drop_events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        if path_buf
+            .extension()
+            .and_then(|extension| extension.to_str())
+            != Some("json")
+        {
+            continue;
+        }
+        let imported = std::fs::read_to_string(path_buf)
+            .map_err(|err| err.to_string())
+            .and_then(|json| solve_history.import_json(&json));
+        let status = match imported {
+            Ok(added) => format!("Imported {added} solves (reopen History to list them)"),
+            Err(err) => format!("Couldn't import {}: {err}", path_buf.display()),
+        };
+        for mut text in &mut status_query {
+            text.0.clone_from(&status);
+        }
+    }
 }
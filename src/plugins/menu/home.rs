@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::{ecs::spawn::SpawnIter, prelude::*};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
@@ -5,24 +7,44 @@ use strum_macros::{Display, EnumIter};
 use crate::{
     despawn_component,
     plugins::{
-        common::theme::{
-            node::{
-                ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+        common::{
+            storage,
+            theme::{
+                ch::ChSize,
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
             },
-            text::{ThemedFontWeight, ThemedTextColor},
         },
-        nav::NavState,
+        game::{
+            save_slots::{continue_dialog_bundle, PendingRestore, SaveSlotDialog, SaveSlots},
+            solve_history::{difficulty_text, SolveHistory},
+            timer::clock_text,
+            AssistLevel, PuzzleType,
+        },
+        nav::{NavState, Navigator, Screen},
     },
-    AppState, APP_TITLE,
+    puzzles::classic::ClassicRules,
+    utility::week::{unix_secs_now, IsoWeek},
+    PuzzleSettings, APP_TITLE,
 };
 
-use super::{MenuState, PIXELS_PER_CH};
+use super::MenuState;
 
 pub fn home_menu_plugin(app: &mut App) {
     app.add_systems(OnEnter(MenuState::Home), home_menu_setup)
         .add_systems(
             Update,
-            (home_menu_action_system).run_if(in_state(MenuState::Home)),
+            (
+                home_menu_action_system,
+                weekly_challenge_text_system,
+                personal_bests_text_system,
+                new_best_fade_system,
+            )
+                .run_if(in_state(MenuState::Home)),
         )
         .add_systems(
             OnExit(MenuState::Home),
@@ -31,7 +53,7 @@ pub fn home_menu_plugin(app: &mut App) {
 }
 
 #[derive(Component)]
-#[require(Node)]
+#[require(Node, FocusScope)]
 struct HomeMenuContainer;
 
 #[derive(Component, EnumIter, Display)]
@@ -46,15 +68,135 @@ enum HomeMenuButton {
     Continue,
     #[strum(to_string = "New Puzzle")]
     NewPuzzle,
+    #[strum(to_string = "Weekly Challenge")]
+    WeeklyChallenge,
+    Practice,
+    Learn,
     History,
 }
 
-fn home_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut commands: Commands) {
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct WeeklyChallengeText;
+
+/// The best times and the daily streak, kept up to date with the solve history.
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct PersonalBestsText;
+
+/// Briefly celebrates a personal best set since the home screen was last shown.
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Bold, ThemedTextColor)]
+struct NewBestText {
+    fade: Timer,
+}
+
+/// How long the message for a new personal best takes to fade out.
+const NEW_BEST_FADE: Duration = Duration::from_secs(4);
+
+/// The variants that the weekly challenge cycles through, one per week.
+const WEEKLY_VARIANTS: [(&str, ClassicRules); 4] = [
+    (
+        "Classic",
+        ClassicRules {
+            hyper_windows: false,
+            anti_king: false,
+            non_consecutive: false,
+        },
+    ),
+    (
+        "Hyper",
+        ClassicRules {
+            hyper_windows: true,
+            anti_king: false,
+            non_consecutive: false,
+        },
+    ),
+    (
+        "Anti-king",
+        ClassicRules {
+            hyper_windows: false,
+            anti_king: true,
+            non_consecutive: false,
+        },
+    ),
+    (
+        "Non-consecutive",
+        ClassicRules {
+            hyper_windows: false,
+            anti_king: false,
+            non_consecutive: true,
+        },
+    ),
+];
+
+/// Returns the name of the week's variant and the settings for its puzzle. Everyone gets the same
+/// puzzle for the same week.
+fn weekly_challenge(week: IsoWeek) -> (&'static str, PuzzleSettings) {
+    let (name, rules) =
+        WEEKLY_VARIANTS[week.index().rem_euclid(WEEKLY_VARIANTS.len() as i64) as usize];
+    let settings = PuzzleSettings {
+        puzzle_type: PuzzleType::Classic,
+        seed: format!("weekly-{week}"),
+        rules,
+        ..default()
+    };
+    (name, settings)
+}
+
+/// Returns the text describing this week's challenge and when the next one starts.
+fn weekly_challenge_text() -> String {
+    let now = unix_secs_now();
+    let (name, _) = weekly_challenge(IsoWeek::from_unix_secs(now));
+    format!(
+        "This week: {name} - next in {}",
+        format_countdown(IsoWeek::until_next(now))
+    )
+}
+
+fn format_countdown(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    format!("{days}d {hours}h {minutes}m")
+}
+
+/// Returns the text listing the best time at the hardest difficulty solved of each puzzle type,
+/// with the assists last played with, followed by the daily streak.
+fn personal_bests_text(solve_history: &SolveHistory, assist_level: AssistLevel) -> String {
+    let streak = solve_history.daily_streak();
+    let assists = match assist_level {
+        AssistLevel::Off => String::new(),
+        _ => format!(", {assist_level} assists"),
+    };
+    PuzzleType::iter()
+        .filter_map(|puzzle_type| {
+            let (difficulty, time) = solve_history.hardest_best_time(puzzle_type, assist_level)?;
+            Some(format!(
+                "Best {puzzle_type} ({}{assists}): {}",
+                difficulty_text(difficulty),
+                clock_text(time)
+            ))
+        })
+        .chain((streak > 0).then(|| {
+            let days = if streak == 1 { "day" } else { "days" };
+            format!("Daily streak: {streak} {days}")
+        }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn home_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    mut solve_history: ResMut<SolveHistory>,
+    puzzle_settings: Res<PuzzleSettings>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
     nav_state.set(NavState::Hidden);
 
     let title_bundle = (
         Text::new(APP_TITLE),
-        TextFont::from_font_size(80.0),
+        TextFont::from_font_size(style_sheet.title_font_size),
         Node {
             margin: UiRect::all(Val::Px(50.0)),
             ..default()
@@ -63,12 +205,13 @@ fn home_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut commands: Com
         ThemedTextColor,
     );
 
-    let button_bundles = HomeMenuButton::iter().map(|home_menu_button| {
+    let menu_button_font_size = style_sheet.menu_button_font_size;
+    let button_bundles = HomeMenuButton::iter().map(move |home_menu_button| {
         let button_text_bundle = (
             ThemedFontWeight::Bold,
             ThemedTextColor,
             Text::new(home_menu_button.to_string()),
-            TextFont::from_font_size(40.0),
+            TextFont::from_font_size(menu_button_font_size),
         );
 
         (
@@ -77,13 +220,56 @@ fn home_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut commands: Com
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 padding: UiRect::vertical(Val::Px(5.0)),
-                width: Val::Px(32.0 * PIXELS_PER_CH),
+                ..default()
+            },
+            ChSize {
+                width: Some(32.0),
                 ..default()
             },
             children![button_text_bundle],
         )
     });
 
+    let weekly_challenge_text_bundle = (
+        WeeklyChallengeText,
+        Text::new(weekly_challenge_text()),
+        TextFont::from_font_size(style_sheet.body_font_size),
+    );
+
+    let personal_bests_text_bundle = (
+        PersonalBestsText,
+        Text::new(personal_bests_text(
+            &solve_history,
+            puzzle_settings.assist_level,
+        )),
+        TextFont::from_font_size(style_sheet.body_font_size),
+        TextLayout::new_with_justify(JustifyText::Center),
+    );
+
+    let new_best_bundle = solve_history.take_new_best().map(|record| {
+        (
+            NewBestText {
+                fade: Timer::new(NEW_BEST_FADE, TimerMode::Once),
+            },
+            Text::new(format!(
+                "New personal best: {} in {}!",
+                record.puzzle_type,
+                clock_text(record.time)
+            )),
+            TextFont::from_font_size(style_sheet.body_font_size),
+        )
+    });
+
+    // Warn that nothing will be kept when another instance of the app is already saving
+    let saves_locked_bundle = storage::saves_locked().then(|| {
+        (
+            Text::new("Another window of the game is open, so this one won't save anything"),
+            TextFont::from_font_size(style_sheet.body_font_size),
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )
+    });
+
     commands.spawn((
         HomeMenuContainer,
         Node {
@@ -92,33 +278,108 @@ fn home_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut commands: Com
             align_items: AlignItems::Center,
             justify_content: JustifyContent::Center,
             flex_direction: FlexDirection::Column,
-            row_gap: Val::Px(20.0),
+            row_gap: Val::Px(style_sheet.gap),
             ..default()
         },
-        Children::spawn((Spawn(title_bundle), SpawnIter(button_bundles))),
+        Children::spawn((
+            Spawn(title_bundle),
+            SpawnIter(button_bundles),
+            Spawn(weekly_challenge_text_bundle),
+            Spawn(personal_bests_text_bundle),
+            SpawnIter(new_best_bundle.into_iter()),
+            SpawnIter(saves_locked_bundle.into_iter()),
+        )),
     ));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn home_menu_action_system(
     interaction_query: Query<(&Interaction, &HomeMenuButton), Changed<Interaction>>,
-    mut menu_state: ResMut<NextState<MenuState>>,
-    mut app_state: ResMut<NextState<AppState>>,
+    mut navigator: ResMut<Navigator>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    save_slots: Res<SaveSlots>,
+    mut pending_restore: ResMut<PendingRestore>,
+    container_query: Query<Entity, With<HomeMenuContainer>>,
+    dialog_query: Query<(), With<SaveSlotDialog>>,
+    mut commands: Commands,
 ) {
     for (_, menu_button) in interaction_query
         .iter()
         .filter(|(interaction, _)| **interaction == Interaction::Pressed)
     {
         match menu_button {
-            HomeMenuButton::Continue => {
-                app_state.set(AppState::Game);
-                menu_state.set(MenuState::Disabled);
-            }
-            HomeMenuButton::History => {
-                menu_state.set(MenuState::History);
-            }
-            HomeMenuButton::NewPuzzle => {
-                menu_state.set(MenuState::NewPuzzle);
+            // Pick up a saved game, asking which one when there's more than one
+            HomeMenuButton::Continue => match save_slots.filled().count() {
+                0 => navigator.push(Screen::Game),
+                1 => {
+                    if let Some((_, slot)) = save_slots.filled().next() {
+                        pending_restore.start(slot, &mut puzzle_settings);
+                    }
+                    navigator.push(Screen::Game);
+                }
+                _ => {
+                    if let (Some(container), true) =
+                        (container_query.iter().next(), dialog_query.is_empty())
+                    {
+                        commands
+                            .entity(container)
+                            .with_child(continue_dialog_bundle(&save_slots));
+                    }
+                }
+            },
+            HomeMenuButton::History => navigator.push(Screen::Menu(MenuState::History)),
+            HomeMenuButton::NewPuzzle => navigator.push(Screen::Menu(MenuState::NewPuzzle)),
+            HomeMenuButton::Practice => navigator.push(Screen::Menu(MenuState::Practice)),
+            HomeMenuButton::Learn => navigator.push(Screen::Menu(MenuState::Learn)),
+            HomeMenuButton::WeeklyChallenge => {
+                (_, *puzzle_settings) = weekly_challenge(IsoWeek::now());
+                navigator.push(Screen::Game);
             }
         }
     }
 }
+
+// Keep the countdown to the next weekly challenge up to date
+fn weekly_challenge_text_system(mut text_query: Query<&mut Text, With<WeeklyChallengeText>>) {
+    let text = weekly_challenge_text();
+    for mut weekly_challenge_text in &mut text_query {
+        // Only write the text when it changes to avoid relayouts
+        if weekly_challenge_text.0 != text {
+            weekly_challenge_text.0.clone_from(&text);
+        }
+    }
+}
+
+fn personal_bests_text_system(
+    solve_history: Res<SolveHistory>,
+    puzzle_settings: Res<PuzzleSettings>,
+    mut text_query: Query<&mut Text, With<PersonalBestsText>>,
+) {
+    if !solve_history.is_changed() && !puzzle_settings.is_changed() {
+        return;
+    }
+    let text = personal_bests_text(&solve_history, puzzle_settings.assist_level);
+    for mut personal_bests_text in &mut text_query {
+        if personal_bests_text.0 != text {
+            personal_bests_text.0.clone_from(&text);
+        }
+    }
+}
+
+fn new_best_fade_system(
+    time: Res<Time>,
+    mut new_best_query: Query<(&mut TextColor, &mut Visibility, &mut NewBestText)>,
+) {
+    for (mut text_color, mut visibility, mut new_best_text) in &mut new_best_query {
+        if new_best_text.fade.finished() {
+            continue;
+        }
+        new_best_text.fade.tick(time.delta());
+        text_color
+            .0
+            .set_alpha(new_best_text.fade.fraction_remaining());
+        if new_best_text.fade.finished() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    color::Luminance,
+    image::{CompressedImageFormats, ImageSampler, ImageType},
+    prelude::*,
+};
+
+use crate::{
+    grids::ocr::{recognize_grid, GrayImage},
+    puzzles::{classic::ClassicRules, validation::validate_givens},
+};
+
+use super::MenuState;
+
+pub fn image_import_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        image_drop_system.run_if(not(in_state(MenuState::Disabled))),
+    );
+}
+
+/// Reads an image file as grayscale. Only the formats Bevy is built with can be read.
+fn load_gray_image(path: &std::path::Path) -> Result<GrayImage, String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .ok_or("the file has no extension")?;
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    let image = Image::from_buffer(
+        &bytes,
+        ImageType::Extension(extension),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::default(),
+    )
+    .map_err(|err| err.to_string())?;
+    let (width, height) = (image.width(), image.height());
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            image
+                .get_color_at(x, y)
+                .map(|color| (color.luminance() * 255.0) as u8)
+                .map_err(|err| err.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    GrayImage::new(width as usize, height as usize, pixels).ok_or("the image is empty".into())
+}
+
+// Recognize the givens in an image (a photo or screenshot) dropped on the window
+fn image_drop_system(mut file_drag_and_drop_events: EventReader<FileDragAndDrop>) {
+    for event in file_drag_and_drop_events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        // The history screen imports exported solve histories
+        if path_buf
+            .extension()
+            .and_then(|extension| extension.to_str())
+            == Some("json")
+        {
+            continue;
+        }
+        match load_gray_image(path_buf).map(|image| recognize_grid(&image)) {
+            Ok(Some(grid)) => {
+                info!("Recognized givens (check them before playing):");
+                info!("{grid}");
+                if let Err(err) = validate_givens(&grid, ClassicRules::default()) {
+                    info!("These givens can't be played, since {err}");
+                }
+            }
+            Ok(None) => info!("No grid was found in {}", path_buf.display()),
+            Err(err) => warn!("Couldn't read {}: {err}", path_buf.display()),
+        }
+    }
+}
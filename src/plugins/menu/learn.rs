@@ -0,0 +1,384 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::spawn::SpawnIter,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    despawn_component,
+    grids::classic::ClassicGrid,
+    plugins::{
+        common::{
+            bundles::mini_board::{mini_board_bundle, mini_board_cell_text, MiniBoardCell},
+            theme::{
+                color::{ThemeColorRole, ThemedColor},
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        game::{
+            hint::{hint_text, HintStage},
+            tutorial::StartTutorial,
+        },
+        loading::LoadingAssets,
+        nav::NavState,
+    },
+    puzzles::{
+        error::PuzzleError,
+        hint::{find_hint_using, Hint, Technique},
+    },
+};
+
+use super::MenuState;
+
+/// The glossary's path within the assets folder.
+const GLOSSARY_PATH: &str = "techniques.glossary.ron";
+
+/// How big the example boards are, relative to the mini boards' usual size.
+const EXAMPLE_BOARD_SCALE: f32 = 1.5;
+
+/// What an example's explanation says before its board is tapped.
+const EXAMPLE_PROMPT: &str = "Tap the board to see how it's used";
+
+pub fn learn_menu_plugin(app: &mut App) {
+    app.init_asset::<Glossary>()
+        .init_asset_loader::<GlossaryLoader>()
+        .add_systems(Startup, glossary_init_system)
+        .add_systems(OnEnter(MenuState::Learn), learn_menu_setup)
+        .add_systems(
+            Update,
+            (learn_example_system, learn_tutorial_system).run_if(in_state(MenuState::Learn)),
+        )
+        .add_systems(
+            OnExit(MenuState::Learn),
+            despawn_component::<LearnMenuContainer>,
+        );
+}
+
+/// The techniques the Learn screen explains, loaded from `assets/techniques.glossary.ron` so that
+/// techniques can be added without changing the screen.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug, Default)]
+pub struct Glossary {
+    pub entries: Vec<GlossaryEntry>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct GlossaryEntry {
+    pub technique: Technique,
+    pub summary: String,
+    /// A grid where the technique finds a number, one row per line with `.` for empty cells
+    pub example: String,
+}
+
+impl GlossaryEntry {
+    /// Returns the example grid, which is checked when the glossary loads.
+    #[must_use]
+    pub fn example_grid(&self) -> ClassicGrid {
+        self.example.parse().unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+pub enum GlossaryLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Example(Technique, PuzzleError),
+}
+
+impl std::fmt::Display for GlossaryLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlossaryLoaderError::Io(err) => write!(f, "Couldn't read the glossary: {err}"),
+            GlossaryLoaderError::Ron(err) => write!(f, "Couldn't parse the glossary: {err}"),
+            GlossaryLoaderError::Example(technique, err) => {
+                write!(f, "The glossary's example for {technique} is broken: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlossaryLoaderError {}
+
+impl From<std::io::Error> for GlossaryLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        GlossaryLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for GlossaryLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        GlossaryLoaderError::Ron(err)
+    }
+}
+
+#[derive(Default)]
+struct GlossaryLoader;
+
+impl AssetLoader for GlossaryLoader {
+    type Asset = Glossary;
+    type Settings = ();
+    type Error = GlossaryLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Glossary, GlossaryLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let glossary: Glossary = ron::de::from_bytes(&bytes)?;
+        for entry in &glossary.entries {
+            entry
+                .example
+                .parse::<ClassicGrid>()
+                .map_err(|err| GlossaryLoaderError::Example(entry.technique, err))?;
+        }
+        Ok(glossary)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["glossary.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct GlossaryHandle(Handle<Glossary>);
+
+fn glossary_init_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(GLOSSARY_PATH);
+    loading_assets.track(&handle);
+    commands.insert_resource(GlossaryHandle(handle));
+}
+
+#[derive(Component)]
+#[require(Node, FocusScope)]
+struct LearnMenuContainer;
+
+/// An entry's example board, which reveals the next stage of the technique's hint each time it's
+/// pressed, and starts over after the last one.
+#[derive(Component)]
+#[require(Button)]
+struct LearnExample {
+    entry: usize,
+    hint: Option<Hint>,
+    stage: Option<HintStage>,
+}
+
+/// Plays the first-launch tutorial again.
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct LearnTutorialButton;
+
+/// Explains the stage of the hint an entry's example is showing.
+#[derive(Component)]
+struct LearnExplanation(usize);
+
+fn learn_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    glossary_handle: Option<Res<GlossaryHandle>>,
+    glossaries: Res<Assets<Glossary>>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    nav_state.set(NavState::Back);
+
+    let title_bundle = (
+        Text::new("Learn"),
+        TextFont::from_font_size(style_sheet.heading_font_size),
+        Node {
+            margin: UiRect::all(Val::Px(40.0)),
+            ..default()
+        },
+        ThemedFontWeight::Bold,
+        ThemedTextColor,
+    );
+
+    let tutorial_button_bundle = (
+        LearnTutorialButton,
+        Node {
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+            ..default()
+        },
+        children![(
+            Text::new("Replay tutorial"),
+            TextFont::from_font_size(style_sheet.button_font_size),
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )],
+    );
+
+    let entries = glossary_handle
+        .and_then(|glossary_handle| glossaries.get(&glossary_handle.0))
+        .map(|glossary| glossary.entries.clone())
+        .unwrap_or_default();
+    let missing_bundle = entries.is_empty().then(|| {
+        (
+            Text::new("Couldn't load the techniques"),
+            TextFont::from_font_size(style_sheet.body_font_size),
+            ThemedTextColor,
+        )
+    });
+    let entry_bundles: Vec<_> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let grid = entry.example_grid();
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(style_sheet.gap / 2.0),
+                    max_width: Val::Px(600.0),
+                    ..default()
+                },
+                children![
+                    (
+                        Text::new(entry.technique.to_string()),
+                        TextFont::from_font_size(style_sheet.button_font_size),
+                        ThemedFontWeight::Bold,
+                        ThemedTextColor,
+                    ),
+                    (
+                        Text::new(entry.summary.clone()),
+                        TextFont::from_font_size(style_sheet.body_font_size),
+                        TextLayout::new_with_justify(JustifyText::Center),
+                        ThemedTextColor,
+                    ),
+                    (
+                        LearnExample {
+                            entry: index,
+                            hint: find_hint_using(&grid, &[entry.technique]),
+                            stage: None,
+                        },
+                        children![mini_board_bundle(&grid, EXAMPLE_BOARD_SCALE)],
+                    ),
+                    (
+                        LearnExplanation(index),
+                        Text::new(EXAMPLE_PROMPT),
+                        TextFont::from_font_size(style_sheet.body_font_size),
+                        ThemedTextColor,
+                    ),
+                ],
+            )
+        })
+        .collect();
+
+    commands.spawn((
+        LearnMenuContainer,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::vertical(Val::Px(80.0)),
+            row_gap: Val::Px(style_sheet.gap * 2.0),
+            overflow: Overflow::scroll_y(),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(title_bundle),
+            Spawn(tutorial_button_bundle),
+            SpawnIter(missing_bundle.into_iter()),
+            SpawnIter(entry_bundles.into_iter()),
+        )),
+    ));
+}
+
+// Reveal the next stage of an example's hint when its board is pressed: the region to look at is
+// highlighted, then the technique is named, then the number is filled in
+fn learn_example_system(
+    mut example_query: Query<(Entity, &Interaction, &mut LearnExample), Changed<Interaction>>,
+    mut explanation_query: Query<(&LearnExplanation, &mut Text), Without<MiniBoardCell>>,
+    mut cell_query: Query<(Entity, &MiniBoardCell, &mut Text), Without<LearnExplanation>>,
+    children_query: Query<&Children>,
+    glossary_handle: Option<Res<GlossaryHandle>>,
+    glossaries: Res<Assets<Glossary>>,
+    mut commands: Commands,
+) {
+    let Some(glossary) = glossary_handle.and_then(|handle| glossaries.get(&handle.0)) else {
+        return;
+    };
+    for (example_entity, _, mut example) in example_query
+        .iter_mut()
+        .filter(|(_, interaction, _)| **interaction == Interaction::Pressed)
+    {
+        let Some(entry) = glossary.entries.get(example.entry) else {
+            continue;
+        };
+        let grid = entry.example_grid();
+        example.stage = match example.stage {
+            None => Some(HintStage::Region),
+            Some(HintStage::Region) => Some(HintStage::Technique),
+            Some(HintStage::Technique) => Some(HintStage::Digit),
+            Some(HintStage::Digit) => None,
+        };
+        let explanation = match (example.stage, example.hint) {
+            (None, _) => EXAMPLE_PROMPT.to_string(),
+            (Some(stage), Some(hint)) => hint_text(stage, &hint),
+            (Some(_), None) => format!("This example doesn't need a {}", entry.technique),
+        };
+        for (LearnExplanation(index), mut text) in &mut explanation_query {
+            if *index == example.entry {
+                text.0.clone_from(&explanation);
+            }
+        }
+
+        let shown = example.hint.zip(example.stage);
+        for cell in children_query.iter_descendants(example_entity) {
+            let Ok((cell, MiniBoardCell(position), mut text)) = cell_query.get_mut(cell) else {
+                continue;
+            };
+            let placed = shown
+                .filter(|(hint, stage)| *stage == HintStage::Digit && hint.position == *position);
+            let highlighted =
+                shown.is_some_and(|(hint, _)| hint.region.cells().any(|cell| cell == *position));
+            text.0 = mini_board_cell_text(
+                grid.get_by_row_col(*position)
+                    .or(placed.map(|(hint, _)| hint.val)),
+            );
+            if placed.is_some() {
+                commands
+                    .entity(cell)
+                    .insert(ThemedColor(ThemeColorRole::Success));
+            } else if highlighted {
+                commands
+                    .entity(cell)
+                    .insert(ThemedColor(ThemeColorRole::Accent));
+            } else {
+                commands
+                    .entity(cell)
+                    .remove::<ThemedColor>()
+                    .insert(BackgroundColor(Color::NONE));
+            }
+        }
+    }
+}
+
+fn learn_tutorial_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<LearnTutorialButton>)>,
+    mut start_tutorial_events: EventWriter<StartTutorial>,
+) {
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        start_tutorial_events.write(StartTutorial);
+    }
+}
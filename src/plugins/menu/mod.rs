@@ -2,11 +2,17 @@ use bevy::prelude::*;
 
 use crate::AppState;
 
-use super::game::GameState;
+use super::{game::GameState, nav::Navigator};
 
+mod damaged_saves;
 mod history;
 mod home;
+#[cfg(not(target_family = "wasm"))]
+mod image_import;
+mod learn;
 mod new_puzzle;
+mod practice;
+mod solve_detail;
 
 pub fn menu_plugin(app: &mut App) {
     app.init_state::<MenuState>()
@@ -15,25 +21,37 @@ pub fn menu_plugin(app: &mut App) {
             home::home_menu_plugin,
             new_puzzle::new_puzzle_menu_plugin,
             history::history_menu_plugin,
+            practice::practice_menu_plugin,
+            learn::learn_menu_plugin,
+            solve_detail::solve_detail_menu_plugin,
+            damaged_saves::damaged_saves_menu_plugin,
         ));
+    #[cfg(not(target_family = "wasm"))]
+    app.add_plugins(image_import::image_import_plugin);
 }
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq, States)]
 pub enum MenuState {
-    #[default]
     Home,
     NewPuzzle,
     History,
+    Practice,
+    /// Explains the solving techniques with examples
+    Learn,
+    /// The details of a solve picked from the history
+    SolveDetail,
+    /// The saved solves that failed validation, with the option to discard them
+    DamagedSaves,
+    /// Not in the menus (loading or playing)
+    #[default]
     Disabled,
 }
 
-// Measured the width of the character "0" on my mac when it was 16px tall.
-pub const PIXELS_PER_CH: f32 = 10.5;
-
 fn menu_setup(
+    mut navigator: ResMut<Navigator>,
     mut game_state: ResMut<NextState<GameState>>,
     mut menu_state: ResMut<NextState<MenuState>>,
 ) {
     game_state.set(GameState::Disabled);
-    menu_state.set(MenuState::Home);
+    menu_state.set(navigator.take_entering_menu());
 }
@@ -1,48 +1,89 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::{spawn::SpawnIter, system::SystemParam},
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
 use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 use crate::{
     despawn_component,
+    grids::classic::ClassicGrid,
     plugins::{
         common::{
             bundles::{
                 dropdown::{self, dropdown_bundle, DropdownBundleOptions, DropdownContainer},
+                mini_board::mini_board_bundle,
                 text_input::{
                     text_input_bundle, text_input_plugin, TextInputBundleOptions,
                     TextInputContainer,
                 },
+                toggle::{toggle_bundle, toggle_plugin, ToggleBundleOptions, ToggleContainer},
             },
+            rng::{RngService, SEED_STREAM},
             theme::{
+                ch::ChSize,
+                focus::FocusScope,
                 node::{
                     ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
                 },
                 text::{ThemedFontWeight, ThemedTextColor},
             },
         },
-        game::PuzzleType,
-        nav::NavState,
+        game::{
+            cache::{PuzzleCache, PuzzleCacheKey},
+            generate_classic_grid_puzzle,
+            marathon::MARATHON_LENGTH,
+            overlay::board_overlay_bundle,
+            pregen::PregenPool,
+            timer::BLITZ_DURATION,
+            variant::{VariantInfo, VariantOption, VariantRegistry},
+            AssistLevel, PuzzleType,
+        },
+        nav::{NavState, Navigator, Screen},
+    },
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        error::PuzzleError,
     },
     utility::seed::SeedRng,
-    AppState, PuzzleSettings,
+    PuzzleSettings, DEFAULT_MISTAKE_LIMIT,
 };
 
-use super::{MenuState, PIXELS_PER_CH};
+#[cfg(debug_assertions)]
+use crate::{plugins::game::overlay::arrow_decorations, puzzles::arrow::ArrowPuzzle};
+
+use super::MenuState;
 
 pub fn new_puzzle_menu_plugin(app: &mut App) {
-    app.add_systems(OnEnter(MenuState::NewPuzzle), new_puzzle_menu_setup)
+    app.init_resource::<PuzzlePreview>()
+        .init_resource::<NewPuzzleStep>()
+        .add_systems(OnEnter(MenuState::NewPuzzle), new_puzzle_menu_setup)
         .add_systems(
             OnExit(MenuState::NewPuzzle),
-            despawn_component::<NewMenuContainer>,
+            (
+                despawn_component::<NewMenuContainer>,
+                preview_reset_system,
+                step_reset_system,
+            ),
         )
-        .add_plugins((dropdown::dropdown_plugin, text_input_plugin))
+        .add_plugins((dropdown::dropdown_plugin, text_input_plugin, toggle_plugin))
         .add_systems(
             Update,
-            (description_system, start_button_system).run_if(in_state(MenuState::NewPuzzle)),
+            (
+                description_system,
+                variant_options_system,
+                (step_button_system, step_page_system).chain(),
+                review_summary_system,
+                (preview_request_system, preview_poll_system).chain(),
+                start_button_system,
+            )
+                .run_if(in_state(MenuState::NewPuzzle)),
         );
 }
 
 #[derive(Component)]
-#[require(Node)]
+#[require(Node, FocusScope)]
 struct NewMenuContainer;
 
 #[derive(Component)]
@@ -55,6 +96,218 @@ struct PuzzleTypeDescriptionText;
 #[derive(Component)]
 struct SeedTextInput;
 
+/// The steps of the New Puzzle menu, which are shown one at a time so that the options of every
+/// puzzle type don't crowd a single page.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+enum NewPuzzleStep {
+    /// Choosing the puzzle type
+    #[default]
+    Type,
+    /// Choosing the selected puzzle type's options, along with the mode and assists
+    Options,
+    /// Entering a seed, checking the chosen settings and the preview, and starting
+    Review,
+}
+
+impl NewPuzzleStep {
+    fn title(self) -> &'static str {
+        match self {
+            NewPuzzleStep::Type => "Type",
+            NewPuzzleStep::Options => "Options",
+            NewPuzzleStep::Review => "Review",
+        }
+    }
+
+    fn previous(self) -> Option<Self> {
+        NewPuzzleStep::iter()
+            .take_while(|step| *step != self)
+            .last()
+    }
+
+    fn next(self) -> Option<Self> {
+        NewPuzzleStep::iter()
+            .skip_while(|step| *step != self)
+            .nth(1)
+    }
+}
+
+/// The page of a step, which is only shown during that step.
+#[derive(Component)]
+struct NewPuzzlePage(NewPuzzleStep);
+
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Bold, ThemedTextColor)]
+struct NewPuzzleStepText;
+
+/// Moves between the steps.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum StepButton {
+    Back,
+    Next,
+}
+
+/// The heading of the options the selected puzzle type adds, hidden if it doesn't add any.
+#[derive(Component)]
+struct VariantOptionsHeading;
+
+/// A toggle for an option that a puzzle type adds, only shown while that puzzle type is selected.
+#[derive(Component)]
+struct VariantOptionToggle(VariantOption);
+
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct ReviewSummaryText;
+
+#[derive(Component)]
+struct MistakeLimitToggle;
+
+#[derive(Component)]
+struct BlitzToggle;
+
+#[derive(Component)]
+struct MarathonToggle;
+
+#[derive(Component)]
+struct AssistLevelDropdown;
+
+#[derive(Component)]
+struct FillCandidatesToggle;
+
+#[derive(Component)]
+struct StrictEntryToggle;
+
+#[derive(Component)]
+#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
+struct PuzzlePreviewText;
+
+/// Holds the mini board of the generated preview, if there is one.
+#[derive(Component)]
+struct PuzzlePreviewBoard;
+
+/// The puzzle being generated in the background for the preview. Replacing the task drops (and
+/// cancels) the previous one.
+#[derive(Resource, Default)]
+#[allow(clippy::type_complexity)]
+struct PuzzlePreview {
+    settings: Option<PuzzleSettings>,
+    task: Option<Task<Result<Option<PreviewPuzzle>, PuzzleError>>>,
+}
+
+/// A puzzle generated for the preview, in the form its game plays it so that it can be cached for
+/// when the puzzle is started.
+enum PreviewPuzzle {
+    Classic(Box<ClassicPuzzle>, ClassicGrid),
+    #[cfg(debug_assertions)]
+    Arrow(Box<ArrowPuzzle>, ClassicGrid),
+}
+
+/// Generates the puzzle for the preview, or returns `None` if its type can't be previewed.
+fn generate_preview_puzzle(
+    settings: &PuzzleSettings,
+) -> Result<Option<PreviewPuzzle>, PuzzleError> {
+    #[cfg(debug_assertions)]
+    if settings.puzzle_type == PuzzleType::Arrow {
+        let puzzle = ArrowPuzzle::from_seed(settings.seed.clone());
+        return Ok(puzzle
+            .solution()
+            .map(|solution| PreviewPuzzle::Arrow(Box::new(puzzle), solution)));
+    }
+    Ok(generate_classic_grid_puzzle(settings)?
+        .map(|(puzzle, solution)| PreviewPuzzle::Classic(Box::new(puzzle), solution)))
+}
+
+/// The inputs that make up the puzzle settings.
+#[derive(SystemParam)]
+struct NewPuzzleInputs<'w, 's> {
+    dropdown_query: Query<'w, 's, &'static DropdownContainer, With<PuzzleTypeDropdown>>,
+    variant_option_query: Query<'w, 's, (&'static VariantOptionToggle, &'static ToggleContainer)>,
+    mistake_limit_query: Query<'w, 's, &'static ToggleContainer, With<MistakeLimitToggle>>,
+    blitz_query: Query<'w, 's, &'static ToggleContainer, With<BlitzToggle>>,
+    marathon_query: Query<'w, 's, &'static ToggleContainer, With<MarathonToggle>>,
+    assist_level_query: Query<'w, 's, &'static DropdownContainer, With<AssistLevelDropdown>>,
+    fill_candidates_query: Query<'w, 's, &'static ToggleContainer, With<FillCandidatesToggle>>,
+    strict_entry_query: Query<'w, 's, &'static ToggleContainer, With<StrictEntryToggle>>,
+    seed_container_query:
+        Query<'w, 's, (&'static Children, &'static TextInputContainer), With<SeedTextInput>>,
+    seed_text_query:
+        Query<'w, 's, &'static Text, (Without<PuzzlePreviewText>, Without<ReviewSummaryText>)>,
+    variant_registry: Res<'w, VariantRegistry>,
+}
+
+impl NewPuzzleInputs<'_, '_> {
+    /// Returns the entered seed, if any.
+    fn seed(&self) -> Option<String> {
+        let (seed_container_children, text_input_data) =
+            self.seed_container_query.single().unwrap();
+        if text_input_data.is_empty {
+            return None;
+        }
+        let seed_text = self
+            .seed_text_query
+            .get(seed_container_children[0])
+            .unwrap();
+        Some(seed_text.0.clone())
+    }
+
+    /// Returns the selected puzzle type.
+    fn puzzle_type(&self) -> PuzzleType {
+        PuzzleType::try_from(self.dropdown_query.single().unwrap().selected).unwrap()
+    }
+
+    /// Returns whether an option the selected puzzle type adds is on. Options it doesn't add are
+    /// always off.
+    fn variant_option(&self, option: VariantOption) -> bool {
+        self.variant_registry
+            .get(self.puzzle_type())
+            .has_option(option)
+            && self.variant_option_query.iter().any(
+                |(VariantOptionToggle(toggle_option), toggle)| {
+                    *toggle_option == option && toggle.on
+                },
+            )
+    }
+
+    /// Returns the puzzle settings chosen with the dropdown and toggles for a seed. Options the
+    /// puzzle type doesn't add are left out.
+    fn settings(&self, seed: String) -> PuzzleSettings {
+        let puzzle_type = self.puzzle_type();
+        let blitz = self.blitz_query.single().unwrap().on;
+        PuzzleSettings {
+            puzzle_type,
+            seed,
+            rules: ClassicRules {
+                anti_king: self.variant_option(VariantOption::AntiKing),
+                non_consecutive: self.variant_option(VariantOption::NonConsecutive),
+                ..ClassicRules::default()
+            },
+            mistake_limit: self
+                .mistake_limit_query
+                .single()
+                .unwrap()
+                .on
+                .then_some(DEFAULT_MISTAKE_LIMIT),
+            blitz,
+            assist_level: AssistLevel::try_from(self.assist_level_query.single().unwrap().selected)
+                .unwrap(),
+            fill_candidates: self.fill_candidates_query.single().unwrap().on,
+            practice: None,
+            // Blitz games are scored instead of timed, so they can't be strung together
+            marathon: (!blitz && self.marathon_query.single().unwrap().on)
+                .then_some(MARATHON_LENGTH),
+            remix: None,
+            challenge: 0,
+            strict_entry: self.strict_entry_query.single().unwrap().on,
+        }
+    }
+}
+
 #[derive(Component)]
 #[require(
     Button,
@@ -70,7 +323,10 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
 
     // Common node values
     let width = Val::Percent(96.0);
-    let max_width = Val::Px(65.0 * PIXELS_PER_CH);
+    let max_width = ChSize {
+        max_width: Some(65.0),
+        ..default()
+    };
     let margin = UiRect::bottom(Val::Px(20.0));
     let body_font_size = 20.0;
 
@@ -78,10 +334,10 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
         Text::new("New Puzzle"),
         TextFont::from_font_size(36.0),
         Node {
-            max_width,
             margin: UiRect::all(Val::Px(40.0)),
             ..default()
         },
+        max_width,
         ThemedFontWeight::Bold,
     );
 
@@ -89,10 +345,10 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
         TextFont::from_font_size(body_font_size),
         Node {
             width,
-            max_width,
             margin: UiRect::bottom(Val::Px(10.0)),
             ..default()
         },
+        max_width,
         ThemedFontWeight::Bold,
         ThemedTextColor,
     );
@@ -103,13 +359,13 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
 
     let type_dropdown_bundle = (
         PuzzleTypeDropdown,
+        max_width,
         dropdown_bundle(DropdownBundleOptions {
             selected: initial_selected_type as usize,
             options: PuzzleType::iter().map(|o| o.to_string()).collect(),
             text_font: TextFont::from_font_size(body_font_size),
             container_node: Node {
                 width,
-                max_width,
                 margin,
                 ..default()
             },
@@ -129,35 +385,274 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
         TextFont::from_font_size(body_font_size),
         Node {
             width,
-            max_width,
             height: Val::Vh(20.0),
             ..default()
         },
+        max_width,
+    );
+
+    let toggle_node = Node {
+        width,
+        margin: UiRect::bottom(Val::Px(10.0)),
+        padding: UiRect::all(Val::Px(5.0)),
+        ..default()
+    };
+
+    // Every puzzle type's options are spawned, and only the selected type's are shown
+    let initial_variant = VariantInfo::of(initial_selected_type);
+    let shown = |visible: bool| {
+        if visible {
+            Display::Flex
+        } else {
+            Display::None
+        }
+    };
+    let variant_option_toggle_bundles: Vec<_> = VariantOption::iter()
+        .map(|option| {
+            (
+                VariantOptionToggle(option),
+                max_width,
+                toggle_bundle(ToggleBundleOptions {
+                    text: option.to_string(),
+                    text_font: TextFont::from_font_size(body_font_size),
+                    container_node: Node {
+                        display: shown(initial_variant.has_option(option)),
+                        ..toggle_node.clone()
+                    },
+                    ..Default::default()
+                }),
+            )
+        })
+        .collect();
+
+    let variant_options_bundle = (
+        Node {
+            width: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            margin,
+            ..default()
+        },
+        Children::spawn((
+            Spawn((
+                VariantOptionsHeading,
+                Text::new("Constraints"),
+                TextFont::from_font_size(body_font_size),
+                Node {
+                    display: shown(!initial_variant.options.is_empty()),
+                    width,
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+                max_width,
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )),
+            SpawnIter(variant_option_toggle_bundles.into_iter()),
+        )),
+    );
+
+    let mode_heading_bundle = (Text::new("Mode"), base_heading_bundle.clone());
+
+    let mistake_limit_toggle_bundle = (
+        MistakeLimitToggle,
+        max_width,
+        toggle_bundle(ToggleBundleOptions {
+            text: format!("End after {DEFAULT_MISTAKE_LIMIT} mistakes"),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: toggle_node.clone(),
+            ..Default::default()
+        }),
+    );
+
+    let blitz_toggle_bundle = (
+        BlitzToggle,
+        max_width,
+        toggle_bundle(ToggleBundleOptions {
+            text: format!("Blitz ({} minutes)", BLITZ_DURATION.as_secs() / 60),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: Node {
+                margin,
+                ..toggle_node.clone()
+            },
+            ..Default::default()
+        }),
+    );
+
+    let marathon_toggle_bundle = (
+        MarathonToggle,
+        max_width,
+        toggle_bundle(ToggleBundleOptions {
+            text: format!("Marathon ({MARATHON_LENGTH} puzzles)"),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: Node {
+                margin,
+                ..toggle_node.clone()
+            },
+            ..Default::default()
+        }),
+    );
+
+    let assists_heading_bundle = (Text::new("Assists"), base_heading_bundle.clone());
+
+    let assist_level_dropdown_bundle = (
+        AssistLevelDropdown,
+        max_width,
+        dropdown_bundle(DropdownBundleOptions {
+            selected: AssistLevel::default() as usize,
+            options: AssistLevel::iter().map(|o| o.to_string()).collect(),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: Node {
+                width,
+                margin,
+                ..default()
+            },
+            button_node: Node {
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            ..Default::default()
+        }),
+    );
+
+    let fill_candidates_toggle_bundle = (
+        FillCandidatesToggle,
+        max_width,
+        toggle_bundle(ToggleBundleOptions {
+            text: "Fill in pencil marks at the start".into(),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: Node {
+                margin,
+                ..toggle_node.clone()
+            },
+            ..Default::default()
+        }),
     );
 
-    let seed_input_heading_bundle = (Text::new("Seed"), base_heading_bundle);
+    let strict_entry_toggle_bundle = (
+        StrictEntryToggle,
+        max_width,
+        toggle_bundle(ToggleBundleOptions {
+            text: "Block numbers already in the row, column, or box".into(),
+            text_font: TextFont::from_font_size(body_font_size),
+            container_node: Node {
+                margin,
+                ..toggle_node.clone()
+            },
+            ..Default::default()
+        }),
+    );
+
+    let page_node = Node {
+        width: Val::Percent(100.0),
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    };
+    let page_bundle = |step: NewPuzzleStep| {
+        let visible = step == NewPuzzleStep::default();
+        (
+            NewPuzzlePage(step),
+            Node {
+                display: shown(visible),
+                ..page_node.clone()
+            },
+            if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+        )
+    };
+
+    let type_page_bundle = (
+        page_bundle(NewPuzzleStep::Type),
+        children![
+            type_input_heading_bundle,
+            type_dropdown_bundle,
+            description_heading_bundle,
+            description_bundle,
+        ],
+    );
+
+    let options_page_bundle = (
+        page_bundle(NewPuzzleStep::Options),
+        children![
+            variant_options_bundle,
+            mode_heading_bundle,
+            mistake_limit_toggle_bundle,
+            blitz_toggle_bundle,
+            marathon_toggle_bundle,
+            assists_heading_bundle,
+            assist_level_dropdown_bundle,
+            fill_candidates_toggle_bundle,
+            strict_entry_toggle_bundle
+        ],
+    );
+
+    let seed_input_heading_bundle = (Text::new("Seed"), base_heading_bundle.clone());
 
     let seed_text_input_bundle = (
         SeedTextInput,
+        max_width,
         text_input_bundle(TextInputBundleOptions {
             placeholder_text: "Random...".into(),
             text_font: TextFont::from_font_size(body_font_size),
             container_node: Node {
-                margin: UiRect::bottom(Val::Px(40.0)),
+                margin,
                 padding: UiRect::horizontal(Val::Px(5.0)),
                 width,
-                max_width,
                 ..default()
             },
             ..Default::default()
         }),
     );
 
+    let summary_heading_bundle = (Text::new("Summary"), base_heading_bundle.clone());
+
+    let summary_bundle = (
+        ReviewSummaryText,
+        TextFont::from_font_size(body_font_size),
+        Node {
+            width,
+            margin,
+            ..default()
+        },
+        max_width,
+    );
+
+    let preview_heading_bundle = (Text::new("Preview"), base_heading_bundle);
+
+    let preview_bundle = (
+        Node {
+            width,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(5.0),
+            margin: UiRect::bottom(Val::Px(40.0)),
+            ..default()
+        },
+        max_width,
+        children![
+            (
+                PuzzlePreviewBoard,
+                Node {
+                    align_self: AlignSelf::Start,
+                    ..default()
+                },
+            ),
+            (
+                PuzzlePreviewText,
+                Text::new(PREVIEW_NO_SEED_TEXT),
+                TextFont::from_font_size(14.0),
+            ),
+        ],
+    );
+
     let start_button_bundle = (
         StartButton,
+        max_width,
         Node {
             width,
-            max_width,
             align_items: AlignItems::Center,
             justify_content: JustifyContent::Center,
             padding: UiRect::all(Val::Px(5.0)),
@@ -171,6 +666,52 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
         )],
     );
 
+    let review_page_bundle = (
+        page_bundle(NewPuzzleStep::Review),
+        children![
+            seed_input_heading_bundle,
+            seed_text_input_bundle,
+            summary_heading_bundle,
+            summary_bundle,
+            preview_heading_bundle,
+            preview_bundle,
+            start_button_bundle
+        ],
+    );
+
+    let step_button_bundle = |step_button: StepButton, label: &str| {
+        (
+            step_button,
+            Node {
+                display: shown(step_button == StepButton::Next),
+                padding: UiRect::axes(Val::Px(20.0), Val::Px(5.0)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(24.0),
+                ThemedFontWeight::Bold,
+                ThemedTextColor
+            )],
+        )
+    };
+
+    let step_buttons_bundle = (
+        Node {
+            width,
+            justify_content: JustifyContent::SpaceBetween,
+            margin: UiRect::bottom(Val::Px(40.0)),
+            ..default()
+        },
+        max_width,
+        children![
+            step_button_bundle(StepButton::Back, "Back"),
+            // Keeps Next on the right while Back is hidden
+            Node::default(),
+            step_button_bundle(StepButton::Next, "Next"),
+        ],
+    );
+
     commands.spawn((
         NewMenuContainer,
         Node {
@@ -184,17 +725,36 @@ fn new_puzzle_menu_setup(mut nav_state: ResMut<NextState<NavState>>, mut command
         },
         children![
             title_bundle,
-            type_input_heading_bundle,
-            type_dropdown_bundle,
-            description_heading_bundle,
-            description_bundle,
-            seed_input_heading_bundle,
-            seed_text_input_bundle,
-            start_button_bundle
+            (
+                NewPuzzleStepText,
+                Text::new(step_text(NewPuzzleStep::default())),
+                TextFont::from_font_size(body_font_size),
+                Node {
+                    margin,
+                    ..default()
+                },
+            ),
+            type_page_bundle,
+            options_page_bundle,
+            review_page_bundle,
+            step_buttons_bundle,
         ],
     ));
 }
 
+/// Returns which step is shown, like `Step 1 of 3: Type`.
+fn step_text(step: NewPuzzleStep) -> String {
+    let number = NewPuzzleStep::iter()
+        .position(|other| other == step)
+        .unwrap_or_default();
+    format!(
+        "Step {} of {}: {}",
+        number + 1,
+        NewPuzzleStep::iter().count(),
+        step.title()
+    )
+}
+
 fn description_system(
     dropdown_query: Query<
         &DropdownContainer,
@@ -210,34 +770,259 @@ fn description_system(
     }
 }
 
+// Show the options that the selected puzzle type adds, and hide the others
+#[allow(clippy::type_complexity)]
+fn variant_options_system(
+    dropdown_query: Query<
+        &DropdownContainer,
+        (Changed<DropdownContainer>, With<PuzzleTypeDropdown>),
+    >,
+    variant_registry: Res<VariantRegistry>,
+    mut heading_query: Query<&mut Node, With<VariantOptionsHeading>>,
+    mut toggle_query: Query<(&VariantOptionToggle, &mut Node), Without<VariantOptionsHeading>>,
+) {
+    let shown = |visible: bool| {
+        if visible {
+            Display::Flex
+        } else {
+            Display::None
+        }
+    };
+    for dropdown in dropdown_query.iter() {
+        let variant = variant_registry.get(PuzzleType::try_from(dropdown.selected).unwrap());
+        for mut heading_node in &mut heading_query {
+            heading_node.display = shown(!variant.options.is_empty());
+        }
+        for (VariantOptionToggle(option), mut toggle_node) in &mut toggle_query {
+            toggle_node.display = shown(variant.has_option(*option));
+        }
+    }
+}
+
+// Move to the previous or next step when its button is pressed
+fn step_button_system(
+    interaction_query: Query<(&Interaction, &StepButton), Changed<Interaction>>,
+    mut step: ResMut<NewPuzzleStep>,
+) {
+    for (_, step_button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        let moved_to = match step_button {
+            StepButton::Back => step.previous(),
+            StepButton::Next => step.next(),
+        };
+        if let Some(moved_to) = moved_to {
+            *step = moved_to;
+        }
+    }
+}
+
+// Show the current step's page, with the buttons that lead away from it
+fn step_page_system(
+    step: Res<NewPuzzleStep>,
+    mut page_query: Query<(&NewPuzzlePage, &mut Node, &mut Visibility)>,
+    mut step_button_query: Query<(&StepButton, &mut Node), Without<NewPuzzlePage>>,
+    mut step_text_query: Query<&mut Text, With<NewPuzzleStepText>>,
+) {
+    if !step.is_changed() {
+        return;
+    }
+    let shown = |visible: bool| {
+        if visible {
+            Display::Flex
+        } else {
+            Display::None
+        }
+    };
+    // Hidden pages are also made invisible so that their inputs can't be focused
+    for (NewPuzzlePage(page_step), mut node, mut visibility) in &mut page_query {
+        let visible = *page_step == *step;
+        node.display = shown(visible);
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for (step_button, mut node) in &mut step_button_query {
+        node.display = shown(match step_button {
+            StepButton::Back => step.previous().is_some(),
+            StepButton::Next => step.next().is_some(),
+        });
+    }
+    for mut text in &mut step_text_query {
+        text.0 = step_text(*step);
+    }
+}
+
+fn step_reset_system(mut step: ResMut<NewPuzzleStep>) {
+    *step = NewPuzzleStep::default();
+}
+
+// Describe the chosen settings on the review step
+fn review_summary_system(
+    step: Res<NewPuzzleStep>,
+    inputs: NewPuzzleInputs,
+    mut summary_text_query: Query<&mut Text, With<ReviewSummaryText>>,
+) {
+    if *step != NewPuzzleStep::Review {
+        return;
+    }
+    let summary = settings_summary(&inputs.settings(String::new()));
+    for mut summary_text in &mut summary_text_query {
+        if summary_text.0 != summary {
+            summary_text.0.clone_from(&summary);
+        }
+    }
+}
+
+/// Returns a line for each choice of the settings that differs from a plain puzzle of its type.
+fn settings_summary(settings: &PuzzleSettings) -> String {
+    let options: Vec<String> = [
+        (settings.rules.anti_king, VariantOption::AntiKing),
+        (
+            settings.rules.non_consecutive,
+            VariantOption::NonConsecutive,
+        ),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, option)| option.to_string())
+    .collect();
+    let mut lines = vec![if options.is_empty() {
+        settings.puzzle_type.to_string()
+    } else {
+        format!("{} ({})", settings.puzzle_type, options.join(", "))
+    }];
+    if let Some(mistake_limit) = settings.mistake_limit {
+        lines.push(format!("Ends after {mistake_limit} mistakes"));
+    }
+    if settings.blitz {
+        lines.push(format!("Blitz ({} minutes)", BLITZ_DURATION.as_secs() / 60));
+    }
+    if let Some(length) = settings.marathon {
+        lines.push(format!("Marathon ({length} puzzles)"));
+    }
+    lines.push(format!("Assists: {}", settings.assist_level));
+    if settings.fill_candidates {
+        lines.push("Pencil marks are filled in at the start".into());
+    }
+    if settings.strict_entry {
+        lines.push("Numbers already in the row, column, or box are blocked".into());
+    }
+    lines.join("\n")
+}
+
+const PREVIEW_NO_SEED_TEXT: &str = "Enter a seed to preview the puzzle.";
+
+// Start generating a preview whenever the settings change
+fn preview_request_system(
+    inputs: NewPuzzleInputs,
+    mut preview: ResMut<PuzzlePreview>,
+    mut preview_text_query: Query<&mut Text, With<PuzzlePreviewText>>,
+    preview_board_query: Query<Entity, With<PuzzlePreviewBoard>>,
+    mut commands: Commands,
+) {
+    let settings = inputs.seed().map(|seed| inputs.settings(seed));
+    let key = settings.as_ref().map(PuzzleCacheKey::from);
+    if preview.settings.as_ref().map(PuzzleCacheKey::from) == key {
+        return;
+    }
+
+    let mut preview_text = preview_text_query.single_mut().unwrap();
+    for preview_board in &preview_board_query {
+        commands.entity(preview_board).despawn_related::<Children>();
+    }
+    preview.task = settings.clone().map(|settings| {
+        preview_text.0 = "Generating...".into();
+        AsyncComputeTaskPool::get().spawn(async move { generate_preview_puzzle(&settings) })
+    });
+    if settings.is_none() {
+        preview_text.0 = PREVIEW_NO_SEED_TEXT.into();
+    }
+    preview.settings = settings;
+}
+
+// Show the preview once it has been generated, and cache it for when the puzzle is started
+fn preview_poll_system(
+    mut preview: ResMut<PuzzlePreview>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+    mut preview_text_query: Query<&mut Text, With<PuzzlePreviewText>>,
+    preview_board_query: Query<Entity, With<PuzzlePreviewBoard>>,
+    mut commands: Commands,
+) {
+    let Some(generated) = preview
+        .task
+        .as_mut()
+        .and_then(|task| block_on(future::poll_once(task)))
+    else {
+        return;
+    };
+    preview.task = None;
+
+    let mut preview_text = preview_text_query.single_mut().unwrap();
+    preview_text.0 = match (generated, &preview.settings) {
+        (Ok(Some(generated)), Some(settings)) => {
+            let (grid, clues, decorations) = match generated {
+                PreviewPuzzle::Classic(puzzle, solution) => {
+                    let (puzzle, _solution) =
+                        puzzle_cache.get_or_generate(settings, || (*puzzle, solution));
+                    (*puzzle.grid(), usize::from(puzzle.num_clues()), Vec::new())
+                }
+                #[cfg(debug_assertions)]
+                PreviewPuzzle::Arrow(puzzle, solution) => {
+                    let (puzzle, _solution): (ArrowPuzzle, ClassicGrid) =
+                        puzzle_cache.get_or_generate(settings, || (*puzzle, solution));
+                    let decorations = arrow_decorations(puzzle.arrows());
+                    (*puzzle.grid(), puzzle.num_clues(), decorations)
+                }
+            };
+            for preview_board in &preview_board_query {
+                let mut board = commands.spawn(mini_board_bundle(&grid, 1.0));
+                // The overlay is the board's last child, so that it's drawn over the cells
+                if !decorations.is_empty() {
+                    board.with_child(board_overlay_bundle(decorations.clone()));
+                }
+                let board = board.id();
+                commands.entity(preview_board).add_child(board);
+            }
+            format!("{clues} clues")
+        }
+        (Err(err), _) => format!("Couldn't generate this puzzle: {err}"),
+        _ => "No preview is available for this puzzle type.".into(),
+    };
+}
+
+fn preview_reset_system(mut preview: ResMut<PuzzlePreview>) {
+    *preview = PuzzlePreview::default();
+}
+
+#[allow(clippy::too_many_arguments)]
 fn start_button_system(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
-    dropdown_query: Query<&DropdownContainer, With<PuzzleTypeDropdown>>,
-    seed_container_query: Query<(&Children, &TextInputContainer), With<SeedTextInput>>,
-    seed_text_query: Query<&Text>,
+    inputs: NewPuzzleInputs,
+    mut pregen_pool: ResMut<PregenPool>,
+    mut puzzle_cache: ResMut<PuzzleCache>,
+    mut rng_service: ResMut<RngService>,
     mut puzzle_settings: ResMut<PuzzleSettings>,
-    mut next_menu_state: ResMut<NextState<MenuState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
+    mut navigator: ResMut<Navigator>,
 ) {
     for _ in interaction_query
         .iter()
         .filter(|interaction| **interaction == Interaction::Pressed)
     {
-        // Read the puzzle settings from the dropdown and the seed input
-        let dropdown_data = dropdown_query.single().unwrap();
-        let (seed_container_children, text_input_data) = seed_container_query.single().unwrap();
-        let seed_text = seed_text_query.get(seed_container_children[0]).unwrap();
-        // Set the PuzzleSettings resource
-        puzzle_settings.puzzle_type = PuzzleType::try_from(dropdown_data.selected).unwrap();
-        puzzle_settings.seed = if text_input_data.is_empty {
-            // Generate a random seed string if one was not provided
-            rand::rng().gen_seed()
-        } else {
-            // Otherwise use the provided value
-            seed_text.0.clone()
-        };
-        // Change states
-        next_menu_state.set(MenuState::Disabled);
-        next_app_state.set(AppState::Game);
+        let puzzle_type = inputs.puzzle_type();
+        if !inputs.variant_registry.get(puzzle_type).generator {
+            info!("{puzzle_type} puzzles can't be generated yet");
+            continue;
+        }
+        // Without a seed, play a pregenerated puzzle (or a new random seed if none are ready)
+        let seed = inputs
+            .seed()
+            .or_else(|| pregen_pool.take(&inputs.settings(String::new()), &mut puzzle_cache))
+            .unwrap_or_else(|| rng_service.stream(SEED_STREAM).gen_seed());
+        *puzzle_settings = inputs.settings(seed);
+        navigator.push(Screen::Game);
     }
 }
@@ -0,0 +1,136 @@
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    despawn_component,
+    plugins::{
+        common::{
+            rng::{RngService, SEED_STREAM},
+            theme::{
+                ch::ChSize,
+                focus::FocusScope,
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        game::{practice::PracticeCompletions, PuzzleType},
+        nav::{NavState, Navigator, Screen},
+    },
+    puzzles::hint::Technique,
+    utility::seed::SeedRng,
+    PuzzleSettings,
+};
+
+use super::MenuState;
+
+pub fn practice_menu_plugin(app: &mut App) {
+    app.add_systems(OnEnter(MenuState::Practice), practice_menu_setup)
+        .add_systems(
+            Update,
+            practice_button_system.run_if(in_state(MenuState::Practice)),
+        )
+        .add_systems(
+            OnExit(MenuState::Practice),
+            despawn_component::<PracticeMenuContainer>,
+        );
+}
+
+#[derive(Component)]
+#[require(Node, FocusScope)]
+struct PracticeMenuContainer;
+
+/// Starts a puzzle that needs the technique when pressed
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+struct PracticeButton(Technique);
+
+fn practice_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    practice_completions: Res<PracticeCompletions>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    nav_state.set(NavState::Back);
+
+    let title_bundle = (
+        Text::new("Practice"),
+        TextFont::from_font_size(style_sheet.heading_font_size),
+        Node {
+            margin: UiRect::all(Val::Px(40.0)),
+            ..default()
+        },
+        ThemedFontWeight::Bold,
+        ThemedTextColor,
+    );
+
+    let button_bundles = Technique::ALL.map(|technique| {
+        let completions = practice_completions.get(technique);
+        (
+            PracticeButton(technique),
+            Node {
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            ChSize {
+                width: Some(40.0),
+                ..default()
+            },
+            children![
+                (
+                    Text::new(technique.to_string()),
+                    TextFont::from_font_size(style_sheet.button_font_size),
+                    ThemedFontWeight::Bold,
+                    ThemedTextColor,
+                ),
+                (
+                    Text::new(format!("{completions} completed")),
+                    TextFont::from_font_size(style_sheet.body_font_size),
+                    ThemedTextColor,
+                )
+            ],
+        )
+    });
+
+    commands.spawn((
+        PracticeMenuContainer,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::top(Val::Px(80.0)),
+            row_gap: Val::Px(style_sheet.gap),
+            ..default()
+        },
+        Children::spawn((Spawn(title_bundle), SpawnIter(button_bundles.into_iter()))),
+    ));
+}
+
+fn practice_button_system(
+    interaction_query: Query<(&Interaction, &PracticeButton), Changed<Interaction>>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut rng_service: ResMut<RngService>,
+    mut navigator: ResMut<Navigator>,
+) {
+    for (_, practice_button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        *puzzle_settings = PuzzleSettings {
+            puzzle_type: PuzzleType::Classic,
+            seed: rng_service.stream(SEED_STREAM).gen_seed(),
+            practice: Some(practice_button.0),
+            ..default()
+        };
+        navigator.push(Screen::Game);
+    }
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    despawn_component,
+    grids::classic::ClassicGrid,
+    plugins::{
+        common::{
+            bundles::mini_board::{mini_board_bundle, mini_board_cell_text, MiniBoardCell},
+            theme::{
+                node::{
+                    ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+                },
+                style::StyleSheet,
+                text::{ThemedFontWeight, ThemedTextColor},
+            },
+        },
+        game::{
+            solve_history::{difficulty_text, SolveHistory, SolveMove},
+            timer::clock_text,
+        },
+        nav::{NavState, Navigator, Screen},
+    },
+    remove_resource, PuzzleSettings,
+};
+
+use super::MenuState;
+
+/// How long each entry of a replay is shown before the next one.
+const REPLAY_STEP: Duration = Duration::from_millis(250);
+
+/// How much bigger than the usual mini board the solve's board is drawn.
+const SOLVE_BOARD_SCALE: f32 = 1.5;
+
+pub fn solve_detail_menu_plugin(app: &mut App) {
+    app.add_systems(OnEnter(MenuState::SolveDetail), solve_detail_menu_setup)
+        .add_systems(
+            OnExit(MenuState::SolveDetail),
+            (
+                despawn_component::<SolveDetailMenu>,
+                remove_resource::<SolveReplay>,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                solve_detail_button_system,
+                solve_replay_system.run_if(resource_exists::<SolveReplay>),
+            )
+                .chain()
+                .run_if(in_state(MenuState::SolveDetail)),
+        );
+}
+
+/// The index of the solve in the history that the detail screen shows.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectedSolve(pub usize);
+
+/// A solve being replayed on the board, one entry at a time.
+#[derive(Resource)]
+struct SolveReplay {
+    grid: ClassicGrid,
+    moves: Vec<SolveMove>,
+    /// The index of the next entry to make, or `None` until the board shows the givens
+    next: Option<usize>,
+    step: Timer,
+}
+
+#[derive(Component)]
+struct SolveDetailMenu;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum SolveDetailButton {
+    /// Plays the same puzzle again
+    ReplaySeed,
+    /// Plays back the solve's entries on the board
+    ViewReplay,
+    Delete,
+}
+
+fn solve_detail_menu_setup(
+    mut nav_state: ResMut<NextState<NavState>>,
+    selected_solve: Option<Res<SelectedSolve>>,
+    solve_history: Res<SolveHistory>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    nav_state.set(NavState::Back);
+    let Some(record) = selected_solve.and_then(|selected| solve_history.get(selected.0)) else {
+        return;
+    };
+
+    let text_bundle = |text: String| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(style_sheet.body_font_size),
+            ThemedTextColor,
+        )
+    };
+    let button_bundle = |button: SolveDetailButton, label: &str| {
+        (
+            button,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(style_sheet.button_padding)),
+                ..default()
+            },
+            children![(
+                Text::new(label),
+                TextFont::from_font_size(style_sheet.button_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )],
+        )
+    };
+    let difficulty = difficulty_text(record.difficulty);
+
+    commands.spawn((
+        SolveDetailMenu,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::top(Val::Px(80.0)),
+            row_gap: Val::Px(style_sheet.gap / 2.0),
+            ..default()
+        },
+        children![
+            (
+                Text::new(format!("{} ({})", record.puzzle_type, record.seed)),
+                TextFont::from_font_size(style_sheet.heading_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            ),
+            // A damaged solve shows an empty board
+            mini_board_bundle(&record.solution().unwrap_or_default(), SOLVE_BOARD_SCALE),
+            text_bundle(record.outcome_text()),
+            text_bundle(format!("Time: {}", clock_text(record.time))),
+            text_bundle(if record.hints_exhausted {
+                format!("Hints: {} (all used)", record.hints)
+            } else {
+                format!("Hints: {}", record.hints)
+            }),
+            text_bundle(format!("Checks: {}", record.checks)),
+            text_bundle(format!("Mistakes: {}", record.mistakes)),
+            text_bundle(format!("Difficulty: {difficulty}")),
+            text_bundle(format!("Assists: {}", record.assist_level)),
+            (
+                Node {
+                    column_gap: Val::Px(style_sheet.gap),
+                    margin: UiRect::top(Val::Px(style_sheet.gap)),
+                    ..default()
+                },
+                children![
+                    button_bundle(SolveDetailButton::ReplaySeed, "Replay seed"),
+                    button_bundle(SolveDetailButton::ViewReplay, "View replay"),
+                    button_bundle(SolveDetailButton::Delete, "Delete"),
+                ],
+            ),
+        ],
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn solve_detail_button_system(
+    interaction_query: Query<(&Interaction, &SolveDetailButton), Changed<Interaction>>,
+    selected_solve: Option<Res<SelectedSolve>>,
+    mut solve_history: ResMut<SolveHistory>,
+    mut puzzle_settings: ResMut<PuzzleSettings>,
+    mut navigator: ResMut<Navigator>,
+    mut commands: Commands,
+) {
+    let Some(selected_solve) = selected_solve else {
+        return;
+    };
+    for (_, button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        let Some(record) = solve_history.get(selected_solve.0) else {
+            return;
+        };
+        match button {
+            SolveDetailButton::ReplaySeed => {
+                *puzzle_settings = record.settings();
+                navigator.push(Screen::Game);
+            }
+            SolveDetailButton::ViewReplay => {
+                let givens = match record.givens() {
+                    Ok(givens) => givens,
+                    Err(err) => {
+                        warn!("Couldn't replay the solve: {err}");
+                        continue;
+                    }
+                };
+                commands.insert_resource(SolveReplay {
+                    grid: givens,
+                    moves: record.moves.clone(),
+                    next: None,
+                    step: Timer::new(REPLAY_STEP, TimerMode::Repeating),
+                });
+            }
+            SolveDetailButton::Delete => {
+                solve_history.remove(selected_solve.0);
+                navigator.back();
+                return;
+            }
+        }
+    }
+}
+
+// Start the board from the givens, then make the solve's entries one step at a time
+fn solve_replay_system(
+    time: Res<Time>,
+    mut solve_replay: ResMut<SolveReplay>,
+    mut cell_query: Query<(&mut Text, &MiniBoardCell)>,
+) {
+    let Some(next) = solve_replay.next else {
+        for (mut text, MiniBoardCell(position)) in &mut cell_query {
+            text.0 = mini_board_cell_text(solve_replay.grid.get_by_row_col(*position));
+        }
+        solve_replay.next = Some(0);
+        return;
+    };
+    solve_replay.step.tick(time.delta());
+    let steps = solve_replay.step.times_finished_this_tick() as usize;
+    for index in next..next + steps {
+        let Some(&(position, val)) = solve_replay.moves.get(index) else {
+            break;
+        };
+        solve_replay.grid.set(position, val);
+        solve_replay.next = Some(index + 1);
+        for (mut text, _) in cell_query.iter_mut().filter(|(_, cell)| cell.0 == position) {
+            text.0 = mini_board_cell_text(val);
+        }
+    }
+}
@@ -1,38 +1,155 @@
-use bevy::prelude::*;
-use strum_macros::Display;
+use bevy::{ecs::system::SystemId, prelude::*};
 
 use crate::{plugins::menu::MenuState, AppState};
 
 use super::common::theme::{
+    focus::FocusScope,
+    icon::{icon_bundle, Icon, IconId},
     node::{ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect},
-    text::{ThemedFontWeight, ThemedTextColor},
 };
 
 pub fn nav_plugin(app: &mut App) {
     app.init_state::<NavState>()
+        .init_resource::<Navigator>()
         .add_systems(Startup, nav_setup)
         .add_systems(Update, nav_visibility_system)
         .add_systems(Update, nav_icon_system.run_if(state_changed::<NavState>))
-        .add_systems(Update, nav_button_action);
+        .add_systems(Update, (nav_button_action, nav_escape_system))
+        // Last, so that every request made during the frame has been made
+        .add_systems(Last, navigator_system);
 }
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, States, Display)]
+/// A screen of the app.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Screen {
+    /// The splash screen shown while the app loads, which can only be left
+    Loading,
+    Menu(MenuState),
+    /// Playing the puzzle in `PuzzleSettings`
+    Game,
+}
+
+/// How a navigation changes the screens that going back returns to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavKind {
+    /// Remember the screen being left
+    Push,
+    /// Leave the screen without remembering it
+    Replace,
+    /// Go back to the last screen remembered
+    Back,
+}
+
+/// A change of screen that has been asked for, which guards can let through, veto, or redirect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Navigation {
+    pub from: Screen,
+    pub to: Screen,
+    pub kind: NavKind,
+}
+
+/// What a guard decides about a navigation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NavDecision {
+    Allow,
+    /// Stay on the current screen. A guard that intercepts a navigation (e.g. to ask for
+    /// confirmation first) vetoes it and later continues it with `Navigator::resume`.
+    Veto,
+    /// Go to another screen instead
+    Redirect(Screen),
+}
+
+/// A navigation waiting to be made at the end of the frame.
+#[derive(Clone, Debug)]
+struct NavRequest {
+    kind: NavKind,
+    /// The screen to go to, or `None` to go back
+    to: Option<Screen>,
+    /// Whether the guards are asked before making it
+    guarded: bool,
+}
+
+/// Changes the screen being shown, keeping the menu screens visited before the current one so
+/// that going back returns to the previous screen instead of always going Home. Navigations are
+/// made at the end of the frame, after the guards added with `add_nav_guard` have allowed them.
+/// Only the last navigation asked for during a frame is made.
+#[derive(Resource, Default)]
+pub struct Navigator {
+    /// The previous menu screens, most recent last
+    history: Vec<MenuState>,
+    pending: Option<NavRequest>,
+    /// The menu screen to show once the menus are entered from another screen
+    entering_menu: Option<MenuState>,
+    guards: Vec<SystemId<In<Navigation>, NavDecision>>,
+}
+
+impl Navigator {
+    /// Goes to a screen, remembering the menu screen being left.
+    pub fn push(&mut self, screen: Screen) {
+        self.request(NavKind::Push, Some(screen), true);
+    }
+
+    /// Goes to a screen without remembering the one being left.
+    pub fn replace(&mut self, screen: Screen) {
+        self.request(NavKind::Replace, Some(screen), true);
+    }
+
+    /// Goes back to the last menu screen remembered (Home if there isn't one).
+    pub fn back(&mut self) {
+        self.request(NavKind::Back, None, true);
+    }
+
+    /// Makes a navigation without asking the guards again, for guards that intercepted it.
+    pub fn resume(&mut self, navigation: Navigation) {
+        self.request(navigation.kind, Some(navigation.to), false);
+    }
+
+    /// Returns the menu screen to show as the menus are entered, which is Home unless a
+    /// navigation asked for another one.
+    pub fn take_entering_menu(&mut self) -> MenuState {
+        self.entering_menu.take().unwrap_or(MenuState::Home)
+    }
+
+    fn request(&mut self, kind: NavKind, to: Option<Screen>, guarded: bool) {
+        self.pending = Some(NavRequest { kind, to, guarded });
+    }
+}
+
+/// Adds a guard that's asked about every navigation before it's made. The guards are asked in
+/// the order they were added, and a redirect changes the screen the later guards are asked about.
+pub fn add_nav_guard<M>(
+    app: &mut App,
+    guard: impl IntoSystem<In<Navigation>, NavDecision, M> + 'static,
+) {
+    let guard = app.world_mut().register_system(guard);
+    app.world_mut()
+        .resource_mut::<Navigator>()
+        .guards
+        .push(guard);
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, States)]
 pub enum NavState {
     #[default]
-    #[strum(to_string = " ")]
     Hidden,
-    #[strum(to_string = "🡠")]
     Back,
-    #[strum(to_string = "⏸")]
     Pause,
 }
 
-impl From<NavState> for String {
-    fn from(value: NavState) -> Self {
-        value.to_string()
+impl NavState {
+    /// Returns the nav button's icon, or `None` while the button is hidden.
+    #[must_use]
+    pub fn icon(&self) -> Option<IconId> {
+        match self {
+            NavState::Hidden => None,
+            NavState::Back => Some(IconId::Back),
+            NavState::Pause => Some(IconId::Pause),
+        }
     }
 }
 
+/// The back or pause button in the top left corner, which comes before every screen in the Tab
+/// order.
 #[derive(Component)]
 #[require(
     Button,
@@ -40,30 +157,16 @@ impl From<NavState> for String {
     ThemedBorderColor,
     ThemedBorderRadius,
     ThemedBorderRect,
-    Visibility
+    Visibility,
+    FocusScope = FocusScope::ordered(-1)
 )]
 struct NavButton;
 
 #[derive(Component)]
-#[require(Text, ThemedFontWeight::Regular, ThemedTextColor)]
 struct NavButtonIcon;
 
 fn nav_setup(mut commands: Commands) {
-    // TODO - use SVGs instead of text
-    let nav_button_icon_bundle = (
-        NavButtonIcon,
-        Text::new(NavState::default()),
-        TextFont::from_font_size(50.0),
-        ThemedFontWeight::Symbolic,
-        ThemedTextColor,
-        TextLayout::new_with_justify(JustifyText::Center),
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            margin: UiRect::top(Val::Px(4.0)),
-            ..default()
-        },
-    );
+    let nav_button_icon_bundle = (NavButtonIcon, icon_bundle(IconId::Back, 40.0));
 
     commands.spawn((
         NavButton,
@@ -73,6 +176,8 @@ fn nav_setup(mut commands: Commands) {
             top: Val::Px(20.0),
             width: Val::Px(80.0),
             height: Val::Px(60.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
             ..default()
         },
         Visibility::Visible,
@@ -81,31 +186,30 @@ fn nav_setup(mut commands: Commands) {
 }
 
 fn nav_icon_system(
-    mut nav_button_icon_query: Query<&mut Text, With<NavButtonIcon>>,
+    mut nav_button_icon_query: Query<&mut Icon, With<NavButtonIcon>>,
     nav_state: Res<State<NavState>>,
 ) {
-    let mut text = nav_button_icon_query.single_mut().unwrap();
-    text.0 = nav_state.get().to_string();
+    // Keep the last icon while the button is hidden
+    let Some(icon_id) = nav_state.get().icon() else {
+        return;
+    };
+    let mut icon = nav_button_icon_query.single_mut().unwrap();
+    icon.set_if_neq(Icon(icon_id));
 }
 
 fn nav_button_action(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<NavButton>)>,
     nav_state: Res<State<NavState>>,
-    mut menu_state: ResMut<NextState<MenuState>>,
-    mut app_state: ResMut<NextState<AppState>>,
+    mut navigator: ResMut<Navigator>,
 ) {
     for _ in interaction_query
         .iter()
         .filter(|interaction| **interaction == Interaction::Pressed)
     {
         match *nav_state.get() {
-            NavState::Back => {
-                menu_state.set(MenuState::Home);
-            }
+            NavState::Back => navigator.back(),
             NavState::Hidden => {}
-            NavState::Pause => {
-                app_state.set(AppState::Menu);
-            }
+            NavState::Pause => navigator.replace(Screen::Menu(MenuState::Home)),
         }
     }
 }
@@ -121,3 +225,88 @@ fn nav_visibility_system(
         Visibility::Visible
     };
 }
+
+// Escape goes back like the nav button
+fn nav_escape_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    nav_state: Res<State<NavState>>,
+    mut navigator: ResMut<Navigator>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) && *nav_state.get() == NavState::Back {
+        navigator.back();
+    }
+}
+
+/// Returns the screen being shown.
+fn current_screen(world: &World) -> Screen {
+    match world.resource::<State<AppState>>().get() {
+        AppState::Loading => Screen::Loading,
+        AppState::Menu => Screen::Menu(world.resource::<State<MenuState>>().get().clone()),
+        AppState::Game => Screen::Game,
+    }
+}
+
+// Ask the guards about the navigation asked for this frame, then change the states for it
+fn navigator_system(world: &mut World) {
+    let Some(request) = world.resource_mut::<Navigator>().pending.take() else {
+        return;
+    };
+    let navigator = world.resource::<Navigator>();
+    let to = request.to.unwrap_or_else(|| {
+        Screen::Menu(navigator.history.last().cloned().unwrap_or(MenuState::Home))
+    });
+    let guards = navigator.guards.clone();
+    let mut navigation = Navigation {
+        from: current_screen(world),
+        to,
+        kind: request.kind,
+    };
+    if request.guarded {
+        for guard in guards {
+            match world.run_system_with(guard, navigation.clone()) {
+                Ok(NavDecision::Allow) => {}
+                Ok(NavDecision::Veto) => return,
+                Ok(NavDecision::Redirect(screen)) => navigation.to = screen,
+                Err(err) => warn!("A navigation guard failed: {err}"),
+            }
+        }
+    }
+    if navigation.to == navigation.from {
+        return;
+    }
+
+    let mut navigator = world.resource_mut::<Navigator>();
+    match (navigation.kind, &navigation.from) {
+        (NavKind::Push, Screen::Menu(menu_state)) if *menu_state != MenuState::Disabled => {
+            navigator.history.push(menu_state.clone());
+        }
+        (NavKind::Back, _) => {
+            navigator.history.pop();
+        }
+        _ => {}
+    }
+    let entering_menus = navigation.from == Screen::Game || navigation.from == Screen::Loading;
+    match navigation.to {
+        Screen::Loading => warn!("The loading screen can't be navigated to"),
+        Screen::Menu(menu_state) if entering_menus => {
+            // Entering the menus would otherwise show Home
+            navigator.entering_menu = Some(menu_state);
+            world
+                .resource_mut::<NextState<AppState>>()
+                .set(AppState::Menu);
+        }
+        Screen::Menu(menu_state) => {
+            world.resource_mut::<NextState<MenuState>>().set(menu_state);
+        }
+        Screen::Game => {
+            // Leaving the menus starts over
+            navigator.history.clear();
+            world
+                .resource_mut::<NextState<MenuState>>()
+                .set(MenuState::Disabled);
+            world
+                .resource_mut::<NextState<AppState>>()
+                .set(AppState::Game);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::AssetPlugin,
+    input::InputPlugin,
+    prelude::*,
+    state::app::StatesPlugin,
+    window::{FileDragAndDrop, Ime},
+};
+
+use crate::{
+    plugins::{
+        common::{
+            clipboard::ClipboardResource,
+            rng::RngService,
+            theme::{focus::FocusedEntity, style::StyleSheet, PrintMode},
+        },
+        game::game_plugin,
+        loading::loading_plugin,
+        menu::{menu_plugin, MenuState},
+        nav::nav_plugin,
+    },
+    AppState, PuzzleSettings,
+};
+
+/// How long the app may take to get from launch to the home screen without rendering. This is
+/// far more than it needs, so that slow test machines don't fail it, while still catching work
+/// that shouldn't happen before the first paint (like generating puzzles).
+const STARTUP_BUDGET: Duration = Duration::from_secs(2);
+
+/// The most updates the app may take to reach the home screen.
+const MAX_STARTUP_UPDATES: usize = 10;
+
+/// Starts the app's plugins headless, along with the resources they use from the theme and the
+/// window, and checks how long (and how many updates) the home screen takes to show.
+#[test]
+fn home_screen_is_within_the_startup_budget() {
+    let start = Instant::now();
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        InputPlugin,
+        AssetPlugin::default(),
+    ))
+    .init_state::<AppState>()
+    .init_resource::<PuzzleSettings>()
+    .init_resource::<FocusedEntity>()
+    .init_resource::<StyleSheet>()
+    .init_resource::<PrintMode>()
+    .init_resource::<ClipboardResource>()
+    .init_resource::<RngService>()
+    .add_event::<FileDragAndDrop>()
+    .add_event::<Ime>()
+    .add_plugins((loading_plugin, menu_plugin, nav_plugin, game_plugin));
+
+    let mut updates = 0;
+    while *app.world().resource::<State<MenuState>>().get() != MenuState::Home {
+        assert!(
+            updates < MAX_STARTUP_UPDATES,
+            "the home screen didn't show within {MAX_STARTUP_UPDATES} updates"
+        );
+        app.update();
+        updates += 1;
+    }
+    // Build the home screen
+    app.update();
+
+    let elapsed = start.elapsed();
+    assert!(
+        elapsed <= STARTUP_BUDGET,
+        "the home screen took {elapsed:?} to show, over the {STARTUP_BUDGET:?} budget"
+    );
+}
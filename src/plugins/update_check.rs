@@ -0,0 +1,420 @@
+use bevy::{
+    ecs::spawn::SpawnIter,
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    despawn_component,
+    plugins::common::{
+        storage,
+        theme::{
+            node::{
+                ThemedBackgroundColor, ThemedBorderColor, ThemedBorderRadius, ThemedBorderRect,
+            },
+            style::StyleSheet,
+            text::{ThemedFontWeight, ThemedTextColor},
+        },
+    },
+    AppState,
+};
+
+/// The repo's latest release on GitHub, whose notes are the changelog for its version.
+const DEFAULT_RELEASE_URL: &str =
+    "https://api.github.com/repos/mrkjdy/sudoku_machine/releases/latest";
+
+const DISMISSED_UPDATE_STORAGE_NAME: &str = "dismissed update";
+
+/// The name whether to check for a newer version, and where, is saved under.
+const UPDATE_CHECK_SETTINGS_STORAGE_NAME: &str = "update check settings";
+
+/// Checks a release feed for a newer version, once per launch, and shows a notice on the menus when
+/// there is one. Nothing is fetched unless the player turns the check on with Ctrl+Shift+U, and the
+/// request carries nothing about the player.
+pub fn update_check_plugin(app: &mut App) {
+    app.insert_resource(UpdateCheckSettings::load())
+        .init_resource::<UpdateCheck>()
+        .add_systems(
+            Update,
+            (
+                update_check_shortcut_system,
+                (
+                    update_check_system,
+                    update_notice_system.run_if(in_state(AppState::Menu)),
+                    update_notice_button_system,
+                )
+                    .chain()
+                    .run_if(|settings: Res<UpdateCheckSettings>| settings.enabled),
+            )
+                .chain(),
+        )
+        .add_systems(
+            OnExit(AppState::Menu),
+            (
+                despawn_component::<UpdateNotice>,
+                despawn_component::<ChangelogDialog>,
+            ),
+        );
+    #[cfg(debug_assertions)]
+    crate::plugins::console::register_console_command(
+        app,
+        crate::plugins::console::ConsoleCommand {
+            name: "update-check",
+            usage: "update-check [on|off|url <url>|default-url]",
+            help: "Show or change whether and where to check for a newer version",
+            run: update_check_command,
+        },
+    );
+}
+
+/// Whether to check for a newer version, and where the release feed is. This is off until the
+/// player turns it on, and is kept between sessions, so the feed can also be changed in the saved
+/// settings.
+#[derive(Resource, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCheckSettings {
+    pub enabled: bool,
+    pub url: String,
+}
+
+impl UpdateCheckSettings {
+    fn load() -> Self {
+        storage::load(UPDATE_CHECK_SETTINGS_STORAGE_NAME).unwrap_or_default()
+    }
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: DEFAULT_RELEASE_URL.into(),
+        }
+    }
+}
+
+/// The latest release, read from JSON like `{ "version": "0.4.0", "changelog": "..." }`. GitHub's
+/// `tag_name` and `body` are read too, so the feed can be a repo's latest release.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ReleaseInfo {
+    #[serde(alias = "tag_name")]
+    pub version: String,
+    /// What changed, one change per line
+    #[serde(alias = "body", default)]
+    pub changelog: String,
+}
+
+impl ReleaseInfo {
+    /// Returns whether this release is newer than the running build. Versions are compared by
+    /// their numeric parts, so a feed with a malformed version is never newer.
+    #[must_use]
+    pub fn is_newer(&self) -> bool {
+        let parse = |version: &str| {
+            version
+                .trim_start_matches('v')
+                .split('.')
+                .map(str::parse::<u64>)
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+        };
+        match (parse(&self.version), parse(env!("CARGO_PKG_VERSION"))) {
+            (Some(latest), Some(current)) => latest > current,
+            _ => false,
+        }
+    }
+}
+
+/// The state of this launch's check.
+#[derive(Resource, Default)]
+enum UpdateCheck {
+    #[default]
+    NotStarted,
+    Fetching(Task<Result<ReleaseInfo, String>>),
+    /// The check finished. Holds the newer release, if there is one that hasn't been dismissed.
+    Done(Option<ReleaseInfo>),
+}
+
+/// The banner saying that a newer version is out.
+#[derive(Component)]
+#[require(Node)]
+struct UpdateNotice;
+
+/// Lists what changed in the newer version.
+#[derive(Component)]
+#[require(Node)]
+struct ChangelogDialog;
+
+#[derive(Component)]
+#[require(
+    Button,
+    ThemedBackgroundColor,
+    ThemedBorderColor,
+    ThemedBorderRadius,
+    ThemedBorderRect
+)]
+enum UpdateNoticeButton {
+    /// Opens the changelog dialog
+    WhatsNew,
+    /// Hides the notice until an even newer version is out
+    Dismiss,
+    /// Closes the changelog dialog
+    Close,
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn fetch_release(url: String) -> Result<String, String> {
+    ureq::get(&url)
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(target_family = "wasm")]
+async fn fetch_release(url: String) -> Result<String, String> {
+    use wasm_bindgen_futures::JsFuture;
+
+    let window = web_sys::window().ok_or("there's no page to fetch from")?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(&url))
+        .await
+        .map_err(|_| "the release feed couldn't be reached".to_string())?
+        .into();
+    if !response.ok() {
+        return Err(format!("the release feed returned {}", response.status()));
+    }
+    let text = response
+        .text()
+        .map_err(|_| "the release feed couldn't be read".to_string())?;
+    JsFuture::from(text)
+        .await
+        .ok()
+        .and_then(|text| text.as_string())
+        .ok_or("the release feed couldn't be read".into())
+}
+
+// Turn the check on and off with Ctrl+Shift+U
+fn update_check_shortcut_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<UpdateCheckSettings>,
+) {
+    let control_keys = [
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ];
+    if !(keys.just_pressed(KeyCode::KeyU)
+        && keys.any_pressed(control_keys)
+        && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]))
+    {
+        return;
+    }
+    settings.enabled = !settings.enabled;
+    storage::save(UPDATE_CHECK_SETTINGS_STORAGE_NAME, &*settings);
+    let state = if settings.enabled { "on" } else { "off" };
+    info!("Checking for a newer version is {state}");
+}
+
+/// Shows the update check's settings, or turns it on or off or points it at another release feed.
+/// Changing the feed checks it again.
+#[cfg(debug_assertions)]
+fn update_check_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let mut settings = world.resource_mut::<UpdateCheckSettings>();
+    match args {
+        [] => {}
+        ["on"] => settings.enabled = true,
+        ["off"] => settings.enabled = false,
+        ["url", url] => settings.url = (*url).into(),
+        ["default-url"] => settings.url = DEFAULT_RELEASE_URL.into(),
+        _ => return Err("usage: update-check [on|off|url <url>|default-url]".into()),
+    }
+    let settings = settings.clone();
+    if !args.is_empty() {
+        storage::save(UPDATE_CHECK_SETTINGS_STORAGE_NAME, &settings);
+    }
+    if matches!(args, ["url" | "default-url", ..]) {
+        *world.resource_mut::<UpdateCheck>() = UpdateCheck::NotStarted;
+    }
+    let state = if settings.enabled { "on" } else { "off" };
+    Ok(format!(
+        "Checking for a newer version is {state}, from {}",
+        settings.url
+    ))
+}
+
+// Start the check the first time it's turned on, then wait for the release feed
+fn update_check_system(settings: Res<UpdateCheckSettings>, mut update_check: ResMut<UpdateCheck>) {
+    match update_check.as_mut() {
+        UpdateCheck::NotStarted => {
+            let url = settings.url.clone();
+            *update_check = UpdateCheck::Fetching(IoTaskPool::get().spawn(async move {
+                let json = fetch_release(url).await?;
+                serde_json::from_str(&json).map_err(|err| err.to_string())
+            }));
+        }
+        UpdateCheck::Fetching(task) => {
+            let Some(fetched) = block_on(future::poll_once(task)) else {
+                return;
+            };
+            let newer = match fetched {
+                Ok(release) => {
+                    let dismissed = storage::load::<String>(DISMISSED_UPDATE_STORAGE_NAME);
+                    (release.is_newer() && dismissed.as_ref() != Some(&release.version))
+                        .then_some(release)
+                }
+                Err(err) => {
+                    warn!("Couldn't check for a newer version: {err}");
+                    None
+                }
+            };
+            *update_check = UpdateCheck::Done(newer);
+        }
+        UpdateCheck::Done(_) => {}
+    }
+}
+
+// Show the notice on the menus while there's a newer version that hasn't been dismissed
+fn update_notice_system(
+    update_check: Res<UpdateCheck>,
+    notice_query: Query<(), With<UpdateNotice>>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    let UpdateCheck::Done(Some(release)) = update_check.as_ref() else {
+        return;
+    };
+    if !notice_query.is_empty() {
+        return;
+    }
+    commands.spawn((
+        UpdateNotice,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            align_self: AlignSelf::Center,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        GlobalZIndex(10),
+        children![
+            (
+                Text::new(format!("Version {} is out", release.version)),
+                TextFont::from_font_size(style_sheet.body_font_size),
+                ThemedTextColor,
+            ),
+            update_notice_button_bundle(UpdateNoticeButton::WhatsNew, "What's new", &style_sheet),
+            update_notice_button_bundle(UpdateNoticeButton::Dismiss, "Dismiss", &style_sheet),
+        ],
+    ));
+}
+
+fn update_notice_button_bundle(
+    button: UpdateNoticeButton,
+    label: &str,
+    style_sheet: &StyleSheet,
+) -> impl Bundle {
+    (
+        button,
+        Node {
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        children![(
+            Text::new(label),
+            TextFont::from_font_size(style_sheet.body_font_size),
+            ThemedFontWeight::Bold,
+            ThemedTextColor,
+        )],
+    )
+}
+
+fn changelog_dialog_bundle(release: &ReleaseInfo, style_sheet: &StyleSheet) -> impl Bundle {
+    let change_bundles: Vec<_> = release
+        .changelog
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            (
+                Text::new(line),
+                TextFont::from_font_size(style_sheet.body_font_size),
+                ThemedTextColor,
+            )
+        })
+        .collect();
+    (
+        ChangelogDialog,
+        ThemedBackgroundColor,
+        ThemedBorderColor,
+        ThemedBorderRadius,
+        ThemedBorderRect,
+        Node {
+            position_type: PositionType::Absolute,
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(style_sheet.gap / 2.0),
+            max_width: Val::Percent(80.0),
+            padding: UiRect::all(Val::Px(style_sheet.gap)),
+            ..default()
+        },
+        GlobalZIndex(11),
+        Children::spawn((
+            Spawn((
+                Text::new(format!("What's new in {}", release.version)),
+                TextFont::from_font_size(style_sheet.heading_font_size),
+                ThemedFontWeight::Bold,
+                ThemedTextColor,
+            )),
+            SpawnIter(change_bundles.into_iter()),
+            Spawn(update_notice_button_bundle(
+                UpdateNoticeButton::Close,
+                "Close",
+                style_sheet,
+            )),
+        )),
+    )
+}
+
+fn update_notice_button_system(
+    interaction_query: Query<(&Interaction, &UpdateNoticeButton), Changed<Interaction>>,
+    mut update_check: ResMut<UpdateCheck>,
+    notice_query: Query<Entity, With<UpdateNotice>>,
+    dialog_query: Query<Entity, With<ChangelogDialog>>,
+    style_sheet: Res<StyleSheet>,
+    mut commands: Commands,
+) {
+    let UpdateCheck::Done(Some(release)) = update_check.as_ref() else {
+        return;
+    };
+    for (_, button) in interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        match button {
+            UpdateNoticeButton::WhatsNew => {
+                if dialog_query.is_empty() {
+                    commands.spawn(changelog_dialog_bundle(release, &style_sheet));
+                }
+            }
+            UpdateNoticeButton::Dismiss => {
+                storage::save(DISMISSED_UPDATE_STORAGE_NAME, &release.version);
+                for entity in notice_query.iter().chain(&dialog_query) {
+                    commands.entity(entity).despawn();
+                }
+                *update_check = UpdateCheck::Done(None);
+                return;
+            }
+            UpdateNoticeButton::Close => {
+                for entity in &dialog_query {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
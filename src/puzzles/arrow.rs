@@ -0,0 +1,521 @@
+use std::fmt::Display;
+
+use rand::{
+    seq::{IndexedRandom, SliceRandom},
+    Rng,
+};
+use rand_seeder::{SipHasher, SipRng};
+
+use crate::{
+    grids::classic::ClassicGrid, puzzles::classic::ClassicPuzzle, utility::element_set::ElementSet,
+};
+
+/// The total number of cells in a 9x9 board.
+const BOARD_SIZE: usize = 9 * 9;
+/// How many arrows the generator tries to place on a board.
+const ARROW_COUNT: usize = 8;
+/// How many random walks are attempted from each circle before moving on to another cell.
+const ARROW_WALK_ATTEMPTS: usize = 8;
+
+/// A circled cell whose value is the sum of the values along the arrow's path. The path starts
+/// next to the circle and each following cell is a king's move from the one before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Arrow {
+    pub circle: (u8, u8),
+    pub path: Vec<(u8, u8)>,
+}
+
+impl Display for Arrow {
+    /// Displays the arrow as `r1c1: r1c2 r2c3` using one-based rows and columns.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (circle_row, circle_col) = self.circle;
+        write!(f, "r{}c{}:", circle_row + 1, circle_col + 1)?;
+        for (row, col) in &self.path {
+            write!(f, " r{}c{}", row + 1, col + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct ArrowPuzzle {
+    /// The actual 9x9 grid
+    grid: ClassicGrid,
+    /// The remaining numbers that need to be placed for each row
+    row_sets: [ElementSet; 9],
+    /// The remaining numbers that need to be placed for each column
+    col_sets: [ElementSet; 9],
+    /// The remaining numbers that need to be placed for each 3x3 box
+    box_sets: [ElementSet; 9],
+    /// The arrows on the board. Arrows never share cells.
+    arrows: Vec<Arrow>,
+    /// The arrow that each cell is a part of (as the circle or along the path), if any
+    cell_arrows: Vec<Option<u8>>,
+}
+
+impl Default for ArrowPuzzle {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl ArrowPuzzle {
+    /// Creates a new, blank board with the given arrows
+    #[must_use]
+    pub fn new(arrows: Vec<Arrow>) -> Self {
+        let mut cell_arrows = vec![None; BOARD_SIZE];
+        for (arrow_index, arrow) in arrows.iter().enumerate() {
+            for &position in std::iter::once(&arrow.circle).chain(&arrow.path) {
+                cell_arrows[Self::cell(position)] = Some(arrow_index as u8);
+            }
+        }
+        Self {
+            grid: ClassicGrid::default(),
+            row_sets: [ElementSet::CLASSIC; 9],
+            col_sets: [ElementSet::CLASSIC; 9],
+            box_sets: [ElementSet::CLASSIC; 9],
+            arrows,
+            cell_arrows,
+        }
+    }
+
+    fn cell((row, col): (u8, u8)) -> usize {
+        row as usize * 9 + col as usize
+    }
+
+    fn position(cell: usize) -> (u8, u8) {
+        ((cell / 9) as u8, (cell % 9) as u8)
+    }
+
+    fn box_index((row, col): (u8, u8)) -> usize {
+        (row as usize / 3) * 3 + col as usize / 3
+    }
+
+    /// Returns the underlying grid.
+    #[must_use]
+    pub fn grid(&self) -> &ClassicGrid {
+        &self.grid
+    }
+
+    /// Returns the arrows on the board.
+    #[must_use]
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+
+    /// Sets a cell in the grid and removes the value from its row, column, and box sets
+    pub fn set(&mut self, position: (u8, u8), val: u8) {
+        self.row_sets[position.0 as usize].remove(val);
+        self.col_sets[position.1 as usize].remove(val);
+        self.box_sets[Self::box_index(position)].remove(val);
+        self.grid.set(position, Some(val));
+    }
+
+    /// Clears a cell in the grid and adds the value back to its row, column, and box sets
+    pub fn delete(&mut self, position: (u8, u8)) {
+        if let Some(val) = self.grid.get_by_row_col(position) {
+            self.row_sets[position.0 as usize].insert(val);
+            self.col_sets[position.1 as usize].insert(val);
+            self.box_sets[Self::box_index(position)].insert(val);
+            self.grid.set(position, None);
+        }
+    }
+
+    /// Returns true if placing `val` at `position` can still lead to the arrow's sum being met,
+    /// assuming every other empty cell on the arrow could take any value from 1 to 9.
+    fn arrow_allows(&self, arrow: &Arrow, position: (u8, u8), val: u8) -> bool {
+        let value_at = |p: (u8, u8)| {
+            if p == position {
+                Some(val)
+            } else {
+                self.grid.get_by_row_col(p)
+            }
+        };
+        let mut filled_sum = 0u8;
+        let mut empty_count = 0u8;
+        for &p in &arrow.path {
+            match value_at(p) {
+                Some(v) => filled_sum += v,
+                None => empty_count += 1,
+            }
+        }
+        let min_sum = filled_sum + empty_count;
+        match value_at(arrow.circle) {
+            Some(circle) => min_sum <= circle && circle <= filled_sum + empty_count * 9,
+            None => min_sum <= 9,
+        }
+    }
+
+    /// Gets the set of values that can still be placed in a cell.
+    fn get_element_set(&self, position: (u8, u8)) -> ElementSet {
+        let mut set = self.row_sets[position.0 as usize]
+            .intersection(&self.col_sets[position.1 as usize])
+            .intersection(&self.box_sets[Self::box_index(position)]);
+        if let Some(arrow_index) = self.cell_arrows[Self::cell(position)] {
+            let arrow = &self.arrows[arrow_index as usize];
+            let candidates = set;
+            for val in &candidates {
+                if !self.arrow_allows(arrow, position, val) {
+                    set.remove(val);
+                }
+            }
+        }
+        set
+    }
+
+    /// Returns the cells of a row, column, or box, numbered 0 to 26.
+    fn unit_cells(unit: usize) -> [(u8, u8); 9] {
+        let i = (unit % 9) as u8;
+        std::array::from_fn(|j| {
+            let j = j as u8;
+            match unit / 9 {
+                0 => (i, j),
+                1 => (j, i),
+                _ => ((i / 3) * 3 + j / 3, (i % 3) * 3 + j % 3),
+            }
+        })
+    }
+
+    /// Returns the numbers that still need to be placed in a row, column, or box.
+    fn unit_set(&self, unit: usize) -> ElementSet {
+        match unit / 9 {
+            0 => self.row_sets[unit % 9],
+            1 => self.col_sets[unit % 9],
+            _ => self.box_sets[unit % 9],
+        }
+    }
+
+    /// Repeatedly places naked singles (cells with one possibility) and hidden singles (digits with
+    /// one possible cell in a row, column, or box). Returns false if a contradiction is found.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut progress = false;
+
+            // Naked singles
+            for cell in 0..BOARD_SIZE {
+                let position = Self::position(cell);
+                if self.grid.get_by_row_col(position).is_some() {
+                    continue;
+                }
+                let set = self.get_element_set(position);
+                match set.len() {
+                    0 => return false,
+                    1 => {
+                        self.set(position, set.iter().next().unwrap());
+                        progress = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Hidden singles
+            for unit in 0..27 {
+                let cells = Self::unit_cells(unit);
+                for num in &self.unit_set(unit) {
+                    // An earlier hidden single in this unit may have already placed this number
+                    if !self.unit_set(unit).has(num) {
+                        continue;
+                    }
+                    let mut only_cell = None;
+                    let mut count = 0;
+                    for &position in &cells {
+                        if self.grid.get_by_row_col(position).is_none()
+                            && self.get_element_set(position).has(num)
+                        {
+                            only_cell = Some(position);
+                            count += 1;
+                        }
+                    }
+                    match (count, only_cell) {
+                        (0, _) => return false,
+                        (1, Some(position)) => {
+                            self.set(position, num);
+                            progress = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    /// Visit solutions recursively, propagating singles and then branching on the cell with the
+    /// fewest possibilities. Stops when the passed function returns false or all solutions have
+    /// been visited.
+    pub fn visit_solutions<F>(puzzle: ArrowPuzzle, mut visit: F)
+    where
+        F: FnMut(&ClassicGrid) -> bool,
+    {
+        fn dfs<F>(mut puzzle: ArrowPuzzle, visit: &mut F) -> bool
+        where
+            F: FnMut(&ClassicGrid) -> bool,
+        {
+            if !puzzle.propagate() {
+                return true;
+            }
+
+            // Find the empty cell with the fewest possibilities
+            let mut best: Option<((u8, u8), ElementSet)> = None;
+            for cell in 0..BOARD_SIZE {
+                let position = ArrowPuzzle::position(cell);
+                if puzzle.grid.get_by_row_col(position).is_some() {
+                    continue;
+                }
+                let set = puzzle.get_element_set(position);
+                if best.is_none_or(|(_, best_set)| set.len() < best_set.len()) {
+                    best = Some((position, set));
+                    if set.len() <= 2 {
+                        break;
+                    }
+                }
+            }
+
+            // No empty cells means the board is solved
+            let Some((position, possibilities)) = best else {
+                return visit(&puzzle.grid);
+            };
+
+            // Each branch works on its own copy, so nothing needs to be undone
+            for num in &possibilities {
+                let mut branch = puzzle.clone();
+                branch.set(position, num);
+                if !dfs(branch, visit) {
+                    return false;
+                }
+            }
+            true
+        }
+
+        dfs(puzzle, &mut visit);
+    }
+
+    /// Count solutions up to a maximum count.
+    #[must_use]
+    pub fn count_solutions_bounded(puzzle: ArrowPuzzle, max_count: usize) -> usize {
+        let mut count = 0;
+        if max_count == 0 {
+            return count;
+        }
+        Self::visit_solutions(puzzle, |_| {
+            count += 1;
+            count < max_count
+        });
+        count
+    }
+
+    /// Returns the first solution found for the puzzle, if it has one.
+    #[must_use]
+    pub fn solution(&self) -> Option<ClassicGrid> {
+        let mut solution = None;
+        Self::visit_solutions(self.clone(), |grid| {
+            solution = Some(*grid);
+            false
+        });
+        solution
+    }
+
+    /// Checks if the puzzle has exactly one solution.
+    #[must_use]
+    pub fn is_well_posed(&self) -> bool {
+        Self::count_solutions_bounded(self.clone(), 2) == 1
+    }
+
+    /// Draws arrows on a solved grid by picking circles at random and walking to adjacent unused
+    /// cells until the values along the path add up to the circle's value.
+    fn generate_arrows<T: Rng>(solution: &ClassicGrid, rng: &mut T) -> Vec<Arrow> {
+        let mut used = [false; BOARD_SIZE];
+        let mut circles: Vec<usize> = (0..BOARD_SIZE).collect();
+        circles.shuffle(rng);
+
+        let mut arrows = Vec::new();
+        for circle_cell in circles {
+            if arrows.len() == ARROW_COUNT {
+                break;
+            }
+            let circle = Self::position(circle_cell);
+            let circle_value = solution.get_by_row_col(circle).unwrap();
+            // Every path needs at least two cells
+            if used[circle_cell] || circle_value < 3 {
+                continue;
+            }
+
+            for _ in 0..ARROW_WALK_ATTEMPTS {
+                let mut path: Vec<(u8, u8)> = Vec::new();
+                let mut remaining = circle_value;
+                let mut current = circle;
+                while remaining > 0 {
+                    let next_steps: Vec<(u8, u8)> = ClassicPuzzle::iter_adjacent(current)
+                        .filter(|&p| {
+                            p != circle
+                                && !used[Self::cell(p)]
+                                && !path.contains(&p)
+                                && solution.get_by_row_col(p).unwrap() <= remaining
+                        })
+                        .collect();
+                    let Some(&next) = next_steps.choose(rng) else {
+                        break;
+                    };
+                    remaining -= solution.get_by_row_col(next).unwrap();
+                    path.push(next);
+                    current = next;
+                }
+                if remaining == 0 && path.len() >= 2 {
+                    for &p in std::iter::once(&circle).chain(&path) {
+                        used[Self::cell(p)] = true;
+                    }
+                    arrows.push(Arrow { circle, path });
+                    break;
+                }
+            }
+        }
+        arrows
+    }
+
+    /// Fills the board with random values and then draws arrows that agree with them.
+    pub fn fill_from_rng<T: Rng>(&mut self, rng: &mut T) {
+        let mut classic = ClassicPuzzle::new();
+        classic.fill_from_rng(rng);
+        let solution = *classic.grid();
+        let arrows = Self::generate_arrows(&solution, rng);
+        *self = Self::from_grid(solution, arrows);
+    }
+
+    /// Clears cells from the puzzle until it has exactly one solution.
+    pub fn minimize_from_rng<T: Rng>(&mut self, rng: &mut T) {
+        let mut filled_cells: Vec<((u8, u8), u8)> = (0..BOARD_SIZE)
+            .map(Self::position)
+            .filter_map(|position| {
+                self.grid
+                    .get_by_row_col(position)
+                    .map(|val| (position, val))
+            })
+            .collect();
+        filled_cells.shuffle(rng);
+
+        while let Some((position, val)) = filled_cells.pop() {
+            self.delete(position);
+            if !self.is_well_posed() {
+                self.set(position, val);
+            }
+        }
+    }
+
+    /// Creates and sets up a puzzle given some string seed
+    #[must_use]
+    pub fn from_seed(seed: String) -> Self {
+        let mut puzzle = ArrowPuzzle::default();
+
+        let mut rng: SipRng = SipHasher::from(seed).into_rng();
+
+        // Fill the board and draw the arrows
+        puzzle.fill_from_rng(&mut rng);
+
+        // Remove numbers
+        puzzle.minimize_from_rng(&mut rng);
+
+        puzzle
+    }
+
+    /// Creates a puzzle from a grid and the arrows drawn on it
+    #[must_use]
+    pub fn from_grid(grid: ClassicGrid, arrows: Vec<Arrow>) -> Self {
+        let mut puzzle = Self::new(arrows);
+        for cell in 0..BOARD_SIZE {
+            let position = Self::position(cell);
+            if let Some(val) = grid.get_by_row_col(position) {
+                puzzle.set(position, val);
+            }
+        }
+        puzzle
+    }
+
+    #[must_use]
+    pub fn num_clues(&self) -> usize {
+        self.grid.iter_all().filter(|cell| cell.is_some()).count()
+    }
+}
+
+impl Display for ArrowPuzzle {
+    /// Displays the grid followed by one line per arrow
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.grid.fmt(f)?;
+        for arrow in &self.arrows {
+            writeln!(f, "{arrow}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "test";
+
+    /// Checks that every arrow's path adds up to its circle.
+    fn assert_arrows_hold(grid: &ClassicGrid, arrows: &[Arrow]) {
+        for arrow in arrows {
+            let circle = grid.get_by_row_col(arrow.circle).unwrap();
+            let sum: u8 = arrow
+                .path
+                .iter()
+                .map(|&p| grid.get_by_row_col(p).unwrap())
+                .sum();
+            assert_eq!(circle, sum, "arrow {arrow} does not add up");
+        }
+    }
+
+    /// Test that generated arrows add up on the solved grid and never share cells.
+    #[test]
+    fn fill_from_rng_arrows_hold() {
+        let mut rng: SipRng = SipHasher::from(SEED).into_rng();
+        let mut puzzle = ArrowPuzzle::default();
+        puzzle.fill_from_rng(&mut rng);
+        assert!(!puzzle.arrows().is_empty());
+        assert_arrows_hold(puzzle.grid(), puzzle.arrows());
+        let arrow_cells: usize = puzzle.arrows().iter().map(|a| a.path.len() + 1).sum();
+        assert_eq!(
+            puzzle.cell_arrows.iter().filter(|a| a.is_some()).count(),
+            arrow_cells
+        );
+    }
+
+    /// Test that the arrow sums rule out candidates that can't add up.
+    #[test]
+    fn get_element_set_arrow() {
+        let arrow = Arrow {
+            circle: (0, 0),
+            path: vec![(1, 1), (2, 2)],
+        };
+        let mut puzzle = ArrowPuzzle::new(vec![arrow]);
+        puzzle.set((0, 0), 4);
+        // The path needs two values adding up to 4, so neither can be more than 3
+        let path_set = puzzle.get_element_set((1, 1));
+        assert_eq!(path_set.len(), 3);
+        assert!(!path_set.has(4));
+        puzzle.set((1, 1), 1);
+        let last_set = puzzle.get_element_set((2, 2));
+        assert_eq!(last_set.len(), 1);
+        assert!(last_set.has(3));
+    }
+
+    /// Test that generating from a seed produces a well-posed puzzle whose solution follows the
+    /// arrows.
+    #[test]
+    fn from_seed_well_posed() {
+        let puzzle = ArrowPuzzle::from_seed(SEED.into());
+        assert!(puzzle.num_clues() < BOARD_SIZE);
+        let arrows = puzzle.arrows().to_vec();
+        let mut solutions = Vec::new();
+        ArrowPuzzle::visit_solutions(puzzle, |grid| {
+            solutions.push(*grid);
+            true
+        });
+        assert_eq!(solutions.len(), 1);
+        assert_arrows_hold(&solutions[0], &arrows);
+    }
+}
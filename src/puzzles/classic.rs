@@ -4,17 +4,133 @@ use rand::{
     Rng,
 };
 use rand_seeder::{SipHasher, SipRng};
-use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
 
 use crate::{
-    grids::classic::ClassicGrid,
+    grids::classic::{ClassicGrid, GridTransform},
     utility::{element_set::ElementSet, priority_queue::ArrayPriorityQueue},
 };
 
+use super::{error::PuzzleError, validation::validate_givens};
+
 /// The total number of cells in a classic 9x9 Sudoku board.
 const BOARD_SIZE: usize = 9 * 9;
 /// The number of cells in a "group" (row, column, and box) without repeats.
 const GROUP_SIZE: usize = 9 + 8 + 4;
+/// The number of cells that share a group with a cell, not including the cell itself.
+const PEER_COUNT: usize = GROUP_SIZE - 1;
+/// The most cells, outside of a cell's group, that can share a hyper window with it.
+const HYPER_WINDOW_EXTRA_NEIGHBORS: usize = 4;
+/// The most cells, outside of a cell's group, that are a king's move away from it.
+const ANTI_KING_EXTRA_NEIGHBORS: usize = 4;
+/// The most cells that the optional rules can add to a cell's neighbors.
+const RULE_NEIGHBORS: usize = HYPER_WINDOW_EXTRA_NEIGHBORS + ANTI_KING_EXTRA_NEIGHBORS;
+/// The most cells that a placement can constrain, including the cell itself.
+const MAX_NEIGHBORS: usize = GROUP_SIZE + RULE_NEIGHBORS;
+
+/// The peers of every cell, in row, then column, then box order.
+static PEERS: [[CellIndex; PEER_COUNT]; BOARD_SIZE] = build_peers();
+
+/// Builds the table of cells that share a row, column, or box with each cell.
+const fn build_peers() -> [[CellIndex; PEER_COUNT]; BOARD_SIZE] {
+    let mut peers = [[0; PEER_COUNT]; BOARD_SIZE];
+    let mut cell = 0;
+    while cell < BOARD_SIZE {
+        let (row, col) = (cell / 9, cell % 9);
+        let (tl_box_row, tl_box_col) = ((row / 3) * 3, (col / 3) * 3);
+        let mut n = 0;
+        // Same row
+        let mut i = 0;
+        while i < 9 {
+            if i != col {
+                peers[cell][n] = (row * 9 + i) as CellIndex;
+                n += 1;
+            }
+            i += 1;
+        }
+        // Same column
+        i = 0;
+        while i < 9 {
+            if i != row {
+                peers[cell][n] = (i * 9 + col) as CellIndex;
+                n += 1;
+            }
+            i += 1;
+        }
+        // Same box, not already in the row or column
+        i = 0;
+        while i < 9 {
+            let (box_row, box_col) = (tl_box_row + i / 3, tl_box_col + i % 3);
+            if box_row != row && box_col != col {
+                peers[cell][n] = (box_row * 9 + box_col) as CellIndex;
+                n += 1;
+            }
+            i += 1;
+        }
+        cell += 1;
+    }
+    peers
+}
+
+/// Optional rules layered on top of the classic row, column, and box constraints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClassicRules {
+    /// Four extra shaded 3x3 windows (Hyper Sudoku) that must each contain all numbers 1 to 9
+    pub hyper_windows: bool,
+    /// Cells a king's move apart can't contain the same number
+    pub anti_king: bool,
+    /// Orthogonally adjacent cells can't contain consecutive numbers
+    pub non_consecutive: bool,
+}
+
+impl ClassicRules {
+    /// Returns true if any rule constrains pairs of cells outside of their groups.
+    fn has_pairwise(self) -> bool {
+        self.anti_king || self.non_consecutive
+    }
+
+    /// Returns a random transformation that keeps puzzles with these rules equivalent, so that a
+    /// transformed puzzle has the same (transformed) solution and needs the same techniques.
+    /// Turns and mirroring keep every rule. Moving bands and stacks would change which cells are
+    /// neighbors and where the hyper windows fall, and swapping numbers would change which are
+    /// consecutive, so those are limited by the rules.
+    pub fn random_transform<T: Rng>(self, rng: &mut T) -> GridTransform {
+        let mut transform = GridTransform {
+            quarter_turns: rng.random_range(0..4),
+            mirror: rng.random(),
+            ..GridTransform::default()
+        };
+        if self.non_consecutive {
+            // Reversing the numbers is the only other order that keeps consecutive numbers apart
+            if rng.random() {
+                transform.digits.reverse();
+            }
+        } else {
+            transform.digits.shuffle(rng);
+        }
+        if !self.has_pairwise() && !self.hyper_windows {
+            transform.band_order.shuffle(rng);
+            transform.stack_order.shuffle(rng);
+        }
+        transform
+    }
+}
+
+/// Counters describing how much work a solution search did. Unlike timings, these are
+/// deterministic, so tests and benchmarks can use them to catch algorithmic regressions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// The number of cells the search branched on
+    pub nodes_expanded: usize,
+    /// The number of values that left a neighbor without any possibilities, forcing the search to
+    /// back up
+    pub backtracks: usize,
+    /// The most cells branched on at once
+    pub max_depth: usize,
+    /// The number of values tried, each of which is propagated to the cell's neighbors
+    pub propagation_calls: usize,
+}
 
 #[derive(Clone)]
 pub struct ClassicPuzzle {
@@ -26,13 +142,28 @@ pub struct ClassicPuzzle {
     col_sets: [ElementSet; 9],
     /// The remaining numbers that need to be placed for each 3x3 box
     box_sets: [ElementSet; 9],
+    /// The remaining numbers that need to be placed for each hyper window (if enabled)
+    window_sets: [ElementSet; 4],
+    /// The optional rules in play
+    rules: ClassicRules,
     /// A priority queue for getting the next cell with the fewest possibilities
     empty_cell_queue: ArrayPriorityQueue<ElementSet, BOARD_SIZE>,
 }
 
-pub type CellCoords = (u8, u8, u8);
-pub type CellIndex = u8;
-pub type CellValue = Option<u8>;
+/// A move made with `ClassicPuzzle::apply`, which holds what's needed to undo it.
+#[must_use = "a move can only be undone with its token"]
+#[derive(Debug)]
+pub struct MoveToken {
+    cell_index: CellIndex,
+    /// The cell's possibilities before the move
+    old_set: ElementSet,
+    /// The neighbors' possibilities before the move
+    undo: ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS>,
+}
+
+pub(crate) type CellCoords = (u8, u8, u8);
+pub(crate) type CellIndex = u8;
+pub(crate) type CellValue = Option<u8>;
 
 impl Default for ClassicPuzzle {
     fn default() -> Self {
@@ -44,17 +175,31 @@ impl ClassicPuzzle {
     /// Creates a new, blank, classic 9x9 Sudoku board
     #[must_use]
     pub fn new() -> Self {
+        Self::with_rules(ClassicRules::default())
+    }
+
+    /// Creates a new, blank 9x9 Sudoku board with some optional rules
+    #[must_use]
+    pub fn with_rules(rules: ClassicRules) -> Self {
         Self {
             grid: ClassicGrid::default(),
             row_sets: std::array::from_fn(|_| ElementSet::CLASSIC),
             col_sets: std::array::from_fn(|_| ElementSet::CLASSIC),
             box_sets: std::array::from_fn(|_| ElementSet::CLASSIC),
+            window_sets: std::array::from_fn(|_| ElementSet::CLASSIC),
+            rules,
             empty_cell_queue: ArrayPriorityQueue::from_iter_unsafe(
                 (0..BOARD_SIZE).map(|k| (k, ElementSet::CLASSIC)),
             ),
         }
     }
 
+    /// Returns the underlying grid.
+    #[must_use]
+    pub fn grid(&self) -> &ClassicGrid {
+        &self.grid
+    }
+
     /// Calculates and returns the "cell index" for some row and column indexes (0 to 8)
     fn get_cell_index((row, col): (u8, u8)) -> CellIndex {
         row * 9 + col
@@ -70,27 +215,61 @@ impl ClassicPuzzle {
         (row / 3) * 3 + (col / 3)
     }
 
+    /// Calculates and returns the hyper window index for some row and column indexes, if the cell
+    /// is inside one of the four windows. The windows start at rows and columns 1 and 5.
+    fn get_window_index((row, col): (u8, u8)) -> Option<u8> {
+        let band = match row {
+            1..=3 => 0,
+            5..=7 => 1,
+            _ => return None,
+        };
+        let stack = match col {
+            1..=3 => 0,
+            5..=7 => 1,
+            _ => return None,
+        };
+        Some(band * 2 + stack)
+    }
+
+    /// Returns the hyper window index for some cell if hyper windows are enabled.
+    fn get_active_window_index(&self, (row, col): (u8, u8)) -> Option<u8> {
+        if self.rules.hyper_windows {
+            Self::get_window_index((row, col))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the optional rules in play.
+    #[must_use]
+    pub fn rules(&self) -> ClassicRules {
+        self.rules
+    }
+
     /// Calculates and returns the row, column, and box indexes for some "cell index" (0 to 80)
     #[must_use]
-    pub fn get_cell_coords(cell_index: CellIndex) -> CellCoords {
+    pub(crate) fn get_cell_coords(cell_index: CellIndex) -> CellCoords {
         let (row, col) = Self::get_row_col(cell_index);
         let box_index = Self::get_box_index((row, col));
         (row, col, box_index)
     }
 
     /// Sets a cell in the grid and removes the value from the corresponding sets
-    pub fn set(&mut self, (row, col, box_index): CellCoords, val: u8) {
+    pub(crate) fn set(&mut self, (row, col, box_index): CellCoords, val: u8) {
         debug_assert_eq!(box_index, Self::get_box_index((row, col)));
         // Update the sets
         self.row_sets[row as usize].remove(val);
         self.col_sets[col as usize].remove(val);
         self.box_sets[box_index as usize].remove(val);
+        if let Some(window_index) = self.get_active_window_index((row, col)) {
+            self.window_sets[window_index as usize].remove(val);
+        }
         // Set the value in the grid
         self.grid.set((row, col), Some(val));
     }
 
     /// Clears a cell in the grid and adds the value to the corresponding sets
-    pub fn delete(&mut self, (row, col, box_index): CellCoords) {
+    pub(crate) fn delete(&mut self, (row, col, box_index): CellCoords) {
         debug_assert_eq!(box_index, Self::get_box_index((row, col)));
         // Get the current value
         if let Some(value) = self.grid.get_by_row_col((row, col)) {
@@ -98,6 +277,9 @@ impl ClassicPuzzle {
             self.row_sets[row as usize].insert(value);
             self.col_sets[col as usize].insert(value);
             self.box_sets[box_index as usize].insert(value);
+            if let Some(window_index) = self.get_active_window_index((row, col)) {
+                self.window_sets[window_index as usize].insert(value);
+            }
             // Clear the value in the grid
             self.grid.set((row, col), None);
         }
@@ -106,9 +288,89 @@ impl ClassicPuzzle {
     /// Gets the "element set" for a given cell. An "element set" is a set of all possible values
     /// that can be placed in a cell, based on the empty cells in the "group" (row, column, or box).
     fn get_element_set(&self, (row, col, box_index): CellCoords) -> ElementSet {
-        self.row_sets[row as usize]
+        let group_set = self.row_sets[row as usize]
             .intersection(&self.col_sets[col as usize])
-            .intersection(&self.box_sets[box_index as usize])
+            .intersection(&self.box_sets[box_index as usize]);
+        let mut element_set = match self.get_active_window_index((row, col)) {
+            Some(window_index) => group_set.intersection(&self.window_sets[window_index as usize]),
+            None => group_set,
+        };
+
+        // Remove the values ruled out by filled cells under the pairwise rules
+        if self.rules.has_pairwise() {
+            for (adjacent_row, adjacent_col) in Self::iter_adjacent((row, col)) {
+                if let Some(val) = self.grid.get_by_row_col((adjacent_row, adjacent_col)) {
+                    let excluded =
+                        self.pairwise_excluded((adjacent_row, adjacent_col), (row, col), val);
+                    for num in &excluded {
+                        element_set.remove(num);
+                    }
+                }
+            }
+        }
+        element_set
+    }
+
+    /// Iterate over the 20 cells that share a row, column, or box with some cell, without
+    /// computing anything. Useful for highlighting a cell's neighbors.
+    pub fn iter_peers(position: (u8, u8)) -> impl Iterator<Item = (u8, u8)> {
+        PEERS[Self::get_cell_index(position) as usize]
+            .iter()
+            .map(|&peer| Self::get_row_col(peer))
+    }
+
+    /// Iterate over the (up to 8) cells that are a king's move away from some cell.
+    pub(crate) fn iter_adjacent((row, col): (u8, u8)) -> impl Iterator<Item = (u8, u8)> {
+        (-1i8..=1)
+            .flat_map(|d_row| (-1i8..=1).map(move |d_col| (d_row, d_col)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(move |(d_row, d_col)| {
+                let adjacent_row = row.checked_add_signed(d_row).filter(|&r| r < 9)?;
+                let adjacent_col = col.checked_add_signed(d_col).filter(|&c| c < 9)?;
+                Some((adjacent_row, adjacent_col))
+            })
+    }
+
+    /// Returns the values that the pairwise rules rule out for the cell at `to` when `val` is
+    /// placed in the adjacent cell at `from`. Values ruled out by shared groups are not included.
+    fn pairwise_excluded(&self, from: (u8, u8), to: (u8, u8), val: u8) -> ElementSet {
+        let mut excluded = ElementSet::default();
+        let is_diagonal = from.0 != to.0 && from.1 != to.1;
+        let is_adjacent = from.0.abs_diff(to.0) <= 1 && from.1.abs_diff(to.1) <= 1;
+        if !is_adjacent {
+            return excluded;
+        }
+        if is_diagonal && self.rules.anti_king {
+            excluded.insert(val);
+        }
+        if !is_diagonal && self.rules.non_consecutive {
+            if val > 1 {
+                excluded.insert(val - 1);
+            }
+            if val < 9 {
+                excluded.insert(val + 1);
+            }
+        }
+        excluded
+    }
+
+    /// Returns the values that can no longer be placed in the cell at `to` when `val` is placed in
+    /// the cell at `from`.
+    fn excluded_values(
+        &self,
+        (from_row, from_col, from_box): CellCoords,
+        to: CellCoords,
+        val: u8,
+    ) -> ElementSet {
+        let (to_row, to_col, to_box) = to;
+        let mut excluded = self.pairwise_excluded((from_row, from_col), (to_row, to_col), val);
+        let shares_window = self
+            .get_active_window_index((from_row, from_col))
+            .is_some_and(|window| self.get_active_window_index((to_row, to_col)) == Some(window));
+        if from_row == to_row || from_col == to_col || from_box == to_box || shares_window {
+            excluded.insert(val);
+        }
+        excluded
     }
 
     /// Returns a vector of pairs (cell index, value) for all filled cells in the grid.
@@ -120,87 +382,111 @@ impl ClassicPuzzle {
             .collect()
     }
 
-    // Collect empty neighbors in the same row, column, and box as the given coordinates
-    fn collect_empty_neighbors_for(&self, coords: CellCoords) -> ArrayVec<CellIndex, GROUP_SIZE> {
+    /// Collects the neighbors that the optional rules add outside of a cell's group, whether they
+    /// are empty or not.
+    fn collect_rule_neighbors_for(
+        &self,
+        coords: CellCoords,
+    ) -> ArrayVec<CellIndex, RULE_NEIGHBORS> {
         let (cell_row, cell_col, cell_box) = coords;
-        let mut out: ArrayVec<CellIndex, GROUP_SIZE> = ArrayVec::new();
-
-        // Collect empty neighbors in the same row
-        for col in 0..9 {
-            if self.grid.get_by_row_col((cell_row, col)).is_none() {
-                out.push(Self::get_cell_index((cell_row, col)));
-            }
-        }
-
-        // Collect empty neighbors in the same column
-        for row in 0..9 {
-            if row != cell_row && self.grid.get_by_row_col((row, cell_col)).is_none() {
-                out.push(Self::get_cell_index((row, cell_col)));
+        let mut out: ArrayVec<CellIndex, RULE_NEIGHBORS> = ArrayVec::new();
+
+        // Collect neighbors in the same hyper window that aren't in the group
+        if let Some(window_index) = self.get_active_window_index((cell_row, cell_col)) {
+            let tl_window_row = 1 + (window_index / 2) * 4;
+            let tl_window_col = 1 + (window_index % 2) * 4;
+            for off in 0..9u8 {
+                let row = tl_window_row + (off / 3);
+                let col = tl_window_col + (off % 3);
+                if row != cell_row && col != cell_col && Self::get_box_index((row, col)) != cell_box
+                {
+                    out.push(Self::get_cell_index((row, col)));
+                }
             }
         }
 
-        // Collect empty neighbors in the same box
-        let tl_box_row = (cell_box / 3) * 3;
-        let tl_box_col = (cell_box % 3) * 3;
-        for off in 0..9u8 {
-            let row = tl_box_row + (off / 3);
-            let col = tl_box_col + (off % 3);
-            if row != cell_row && col != cell_col && self.grid.get_by_row_col((row, col)).is_none()
-            {
-                out.push(Self::get_cell_index((row, col)));
+        // Collect neighbors a king's move away that weren't already collected. Orthogonal
+        // neighbors are always in the same row or column.
+        if self.rules.anti_king {
+            for (row, col) in Self::iter_adjacent((cell_row, cell_col)) {
+                let cell_index = Self::get_cell_index((row, col));
+                if row != cell_row
+                    && col != cell_col
+                    && Self::get_box_index((row, col)) != cell_box
+                    && !out.contains(&cell_index)
+                {
+                    out.push(cell_index);
+                }
             }
         }
         out
     }
 
+    // Collect the given cell (if empty) and its empty neighbors
+    fn collect_empty_neighbors_for(
+        &self,
+        coords: CellCoords,
+    ) -> ArrayVec<CellIndex, MAX_NEIGHBORS> {
+        let current_index = Self::get_cell_index((coords.0, coords.1));
+        let rule_neighbors = self.collect_rule_neighbors_for(coords);
+        std::iter::once(current_index)
+            .chain(PEERS[current_index as usize].iter().copied())
+            .chain(rule_neighbors)
+            .filter(|&ci| self.grid.get_by_cell_index(ci).is_none())
+            .collect()
+    }
+
     /// Applies `num` to `coords`' neighbors:
     /// - Returns true if an immediate dead-end is detected (some neighbor becomes empty).
-    /// - Otherwise, applies updates to neighbors that actually lose `num` and records undo entries.
+    /// - Otherwise, applies updates to neighbors that actually lose values and records undo entries.
     fn propagate_choice(
         &mut self,
         coords: CellCoords,
         val: u8,
-        undo: &mut ArrayVec<(CellIndex, ElementSet), GROUP_SIZE>,
+        undo: &mut ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS>,
     ) -> bool {
-        // Collect neighbors once
-        let neighbors = self.collect_empty_neighbors_for(coords);
         let current_index = Self::get_cell_index((coords.0, coords.1));
+        let rule_neighbors = self.collect_rule_neighbors_for(coords);
+        let has_pairwise = self.rules.has_pairwise();
 
         // First pass (single pass actually): detect immediate contradiction and gather updates
-        let mut to_update: ArrayVec<(CellIndex, ElementSet), GROUP_SIZE> = ArrayVec::new();
+        let mut to_update: ArrayVec<(CellIndex, ElementSet, ElementSet), MAX_NEIGHBORS> =
+            ArrayVec::new();
 
         // Attempt the value from the neighbor's possibilities
-        for &ci in &neighbors {
-            // Skip the current cell
-            if ci == current_index {
+        for &ci in PEERS[current_index as usize].iter().chain(&rule_neighbors) {
+            // Get the previous set of possible values for the neighbor, skipping filled neighbors
+            let Some(&old_set) = self.empty_cell_queue.get_priority_unsafe(ci as usize) else {
                 continue;
-            }
+            };
 
-            // Get the previous set of possible values for the empty neighbor
-            let &old_set = self
-                .empty_cell_queue
-                .get_priority_unsafe(ci as usize)
-                .unwrap();
+            // Remove every value that the choice rules out for this neighbor
+            let mut new_set = old_set;
+            if has_pairwise {
+                for num in &self.excluded_values(coords, Self::get_cell_coords(ci), val) {
+                    new_set.remove(num);
+                }
+            } else {
+                new_set.remove(val);
+            }
 
-            // Skip if the value is already excluded from the neighbor's possibilities
-            if !old_set.has(val) {
+            // Skip if the values are already excluded from the neighbor's possibilities
+            if new_set.len() == old_set.len() {
                 continue;
             }
 
-            // If this neighbor only had `num`, removing it would make it empty => dead end
-            if old_set.len() == 1 {
+            // If this neighbor has no possibilities left => dead end
+            if new_set.is_empty() {
                 return true;
             }
 
-            // Otherwise, we plan to remove `num` and record the value for undo
-            to_update.push((ci, old_set));
+            // Otherwise, we plan to update the neighbor and record the old set for undo
+            to_update.push((ci, old_set, new_set));
         }
 
         // Safe to apply updates now. Record undo entries and update the queue.
         undo.clear();
-        for &(ci, old_set) in &to_update {
-            let mut new_set = old_set;
-            new_set.remove(val);
+        for &(ci, old_set, new_set) in &to_update {
             undo.push((ci, old_set));
             self.empty_cell_queue.insert_unsafe((ci as usize, new_set));
         }
@@ -216,9 +502,11 @@ impl ClassicPuzzle {
         struct GenFrame {
             cell_index: CellIndex,
             possibilities: ElementSet, // remaining values for this cell (untried)
-            undo: ArrayVec<(CellIndex, ElementSet), GROUP_SIZE>, // (neighbor_index, old_set) for changed neighbors
+            undo: ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS>, // (neighbor_index, old_set) for changed neighbors
         }
 
+        let _span = tracing::info_span!("fill").entered();
+
         // List of cells used to initialize unfilled cell heap
         let mut all_cell_indexes: ArrayVec<CellIndex, BOARD_SIZE> = ArrayVec::new();
         for i in 0..BOARD_SIZE {
@@ -255,7 +543,7 @@ impl ClassicPuzzle {
 
                 // Update the possibilities left in the heap for each of the empty cells neighboring
                 // the current cell, recording undo info only for neighbors that change.
-                let mut undo: ArrayVec<(CellIndex, ElementSet), GROUP_SIZE> = ArrayVec::new();
+                let mut undo: ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS> = ArrayVec::new();
                 let dead_end = self.propagate_choice(current_cell_coords, num, &mut undo);
 
                 if dead_end {
@@ -318,19 +606,15 @@ impl ClassicPuzzle {
             return out;
         }
 
-        // Collect the empty neighbors once and prefetch their ElementSets
-        let neighbors = self.collect_empty_neighbors_for(coords);
+        // Prefetch the ElementSets of the empty neighbors once
         let current_index = Self::get_cell_index((coords.0, coords.1));
+        let rule_neighbors = self.collect_rule_neighbors_for(coords);
 
-        let mut neigh_sets: ArrayVec<ElementSet, GROUP_SIZE> = ArrayVec::new();
-        let mut neigh_idx: ArrayVec<CellIndex, GROUP_SIZE> = ArrayVec::new();
+        let mut neigh_sets: ArrayVec<ElementSet, MAX_NEIGHBORS> = ArrayVec::new();
 
-        for &ci in &neighbors {
-            if ci == current_index {
-                continue; // popped MRV cell isn't in the queue
-            }
+        for &ci in PEERS[current_index as usize].iter().chain(&rule_neighbors) {
+            // Filled neighbors (and the popped MRV cell) aren't in the queue
             if let Some(es) = self.empty_cell_queue.get_priority_unsafe(ci as usize) {
-                neigh_idx.push(ci);
                 neigh_sets.push(*es);
             }
         }
@@ -359,11 +643,28 @@ impl ClassicPuzzle {
 
     /// Visit all solutions recursively. Stops when the passed function returns false or all
     /// solutions have been visited.
-    pub fn visit_solutions_recursive<F>(mut puzzle: ClassicPuzzle, mut visit: F)
+    pub fn visit_solutions_recursive<F>(puzzle: ClassicPuzzle, visit: F)
     where
         F: FnMut(&ClassicGrid) -> bool,
     {
-        fn dfs<F>(puzzle: &mut ClassicPuzzle, visit: &mut F) -> bool
+        Self::visit_solutions_recursive_with_stats(puzzle, visit, &mut SolveStats::default());
+    }
+
+    /// Visit all solutions recursively like `visit_solutions_recursive`, adding the work done to
+    /// `stats`.
+    pub fn visit_solutions_recursive_with_stats<F>(
+        mut puzzle: ClassicPuzzle,
+        mut visit: F,
+        stats: &mut SolveStats,
+    ) where
+        F: FnMut(&ClassicGrid) -> bool,
+    {
+        fn dfs<F>(
+            puzzle: &mut ClassicPuzzle,
+            visit: &mut F,
+            stats: &mut SolveStats,
+            depth: usize,
+        ) -> bool
         where
             F: FnMut(&ClassicGrid) -> bool,
         {
@@ -375,9 +676,11 @@ impl ClassicPuzzle {
             // Choose MRV cell
             let (cell_index, cell_possibilities) = puzzle.empty_cell_queue.pop().unwrap();
             let cell_coords = ClassicPuzzle::get_cell_coords(cell_index as u8);
+            stats.nodes_expanded += 1;
+            stats.max_depth = stats.max_depth.max(depth + 1);
 
             // Undo log for changed neighbors
-            let mut undo: ArrayVec<(CellIndex, ElementSet), GROUP_SIZE> = ArrayVec::new();
+            let mut undo: ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS> = ArrayVec::new();
 
             // LCV ordering for this MRV cell
             let ordered_vals = puzzle.order_values_lcv(cell_coords, cell_possibilities);
@@ -388,7 +691,11 @@ impl ClassicPuzzle {
                 puzzle.set(cell_coords, num);
 
                 // Apply choice to neighbors (or detect contradiction early)
+                stats.propagation_calls += 1;
                 let dead_end = puzzle.propagate_choice(cell_coords, num, &mut undo);
+                if dead_end {
+                    stats.backtracks += 1;
+                }
 
                 let mut keep_going = true;
                 if !dead_end {
@@ -396,7 +703,7 @@ impl ClassicPuzzle {
                     if puzzle.empty_cell_queue.is_empty() {
                         keep_going = visit(&puzzle.grid);
                     } else {
-                        keep_going = dfs(puzzle, visit);
+                        keep_going = dfs(puzzle, visit, stats, depth + 1);
                     }
 
                     // Backtrack if necessary
@@ -424,7 +731,7 @@ impl ClassicPuzzle {
             true
         }
 
-        dfs(&mut puzzle, &mut visit);
+        dfs(&mut puzzle, &mut visit, stats, 0);
     }
 
     /// Find all solutions recursively.
@@ -484,9 +791,21 @@ impl ClassicPuzzle {
 
     /// Visit solutions iteratively. Stops when the passed function returns false or when all
     /// solutions have been visited.
-    pub fn visit_solutions_iterative<F>(mut puzzle: ClassicPuzzle, mut visit: F)
+    pub fn visit_solutions_iterative<F>(puzzle: ClassicPuzzle, visit: F)
     where
         F: FnMut(&ClassicGrid) -> bool,
+    {
+        Self::visit_solutions_iterative_with_stats(puzzle, visit, &mut SolveStats::default());
+    }
+
+    /// Visit solutions iteratively like `visit_solutions_iterative`, adding the work done to
+    /// `stats`.
+    pub fn visit_solutions_iterative_with_stats<F>(
+        mut puzzle: ClassicPuzzle,
+        mut visit: F,
+        stats: &mut SolveStats,
+    ) where
+        F: FnMut(&ClassicGrid) -> bool,
     {
         #[derive(Clone)]
         struct Frame {
@@ -494,7 +813,7 @@ impl ClassicPuzzle {
             order: ArrayVec<u8, 9>, // LCV-order values to try
             next_ix: u8,            // next index into `order` to try
             chosen: CellValue,      // currently chosen value (if any)
-            undo: ArrayVec<(CellIndex, ElementSet), GROUP_SIZE>,
+            undo: ArrayVec<(CellIndex, ElementSet), MAX_NEIGHBORS>,
         }
 
         // Already solved
@@ -514,6 +833,8 @@ impl ClassicPuzzle {
             chosen: None,
             undo: ArrayVec::new(),
         });
+        stats.nodes_expanded += 1;
+        stats.max_depth = stats.max_depth.max(stack.len());
 
         // Main loop
         'outer: while let Some(frame) = stack.last_mut() {
@@ -540,11 +861,13 @@ impl ClassicPuzzle {
                 frame.undo.clear();
 
                 // Apply choice to neighbors (or detect contradiction early)
+                stats.propagation_calls += 1;
                 let dead_end = puzzle.propagate_choice(coords, num, &mut frame.undo);
 
                 // Propagate choice and check for dead end, undoing if necessary
                 if dead_end {
                     // No updates were applied, so nothing to restore
+                    stats.backtracks += 1;
                     puzzle.delete(coords);
                     continue;
                 }
@@ -571,6 +894,8 @@ impl ClassicPuzzle {
                     chosen: None,
                     undo: ArrayVec::new(),
                 });
+                stats.nodes_expanded += 1;
+                stats.max_depth = stats.max_depth.max(stack.len());
             } else {
                 // Exhausted this cell: reinsert it and backtrack
                 let es = puzzle.get_element_set(coords);
@@ -637,13 +962,30 @@ impl ClassicPuzzle {
         count
     }
 
+    /// Returns the first solution found for the puzzle, if it has one.
+    #[must_use]
+    pub fn solution(&self) -> Option<ClassicGrid> {
+        Self::find_solutions_bounded_iterative(self.clone(), 1).pop()
+    }
+
     /// Checks if the puzzle has exactly one solution.
     fn is_well_posed(&self) -> bool {
+        let _span = tracing::info_span!("uniqueness_check").entered();
         Self::count_solutions_bounded_recursive(self.clone(), 2) == 1
     }
 
+    /// Returns `PuzzleError::NotWellPosed` if the puzzle doesn't have exactly one solution, e.g.
+    /// for givens that were typed in or imported rather than generated.
+    pub fn check_well_posed(&self) -> Result<(), PuzzleError> {
+        match Self::count_solutions_bounded_recursive(self.clone(), 2) {
+            1 => Ok(()),
+            solutions => Err(PuzzleError::NotWellPosed { solutions }),
+        }
+    }
+
     /// Clears cells from the puzzle until it has exactly one solution.
     pub fn minimize_from_rng<T: Rng>(&mut self, mut rng: &mut T) {
+        let _span = tracing::info_span!("minimize").entered();
         // Create a list of pairs (cell index, value) for all filled cells in the grid
         let mut unattempted_filled_cell_pairs = self.get_all_filled_cell_pairs();
 
@@ -699,7 +1041,13 @@ impl ClassicPuzzle {
     /// Creates and sets up a puzzle given some string seed
     #[must_use]
     pub fn from_seed(seed: String) -> Self {
-        let mut puzzle = ClassicPuzzle::new();
+        Self::from_seed_with_rules(seed, ClassicRules::default())
+    }
+
+    /// Creates and sets up a puzzle with some optional rules given some string seed
+    #[must_use]
+    pub fn from_seed_with_rules(seed: String, rules: ClassicRules) -> Self {
+        let mut puzzle = ClassicPuzzle::with_rules(rules);
 
         let mut rng: SipRng = SipHasher::from(seed).into_rng();
 
@@ -712,6 +1060,55 @@ impl ClassicPuzzle {
         puzzle
     }
 
+    /// Returns the values that can be placed in an empty cell without breaking a rule (or an empty
+    /// set if the cell is filled).
+    #[must_use]
+    pub fn legal_values(&self, position: (u8, u8)) -> ElementSet {
+        self.empty_cell_queue
+            .get_priority(Self::get_cell_index(position) as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Places a value in an empty cell and updates its neighbors' possibilities. Returns
+    /// `PuzzleError::InvalidValue` without changing anything if the cell isn't on the grid, or the
+    /// value isn't legal or would leave a neighbor without any possibilities. Moves must be undone
+    /// in the reverse order that they were applied.
+    pub fn apply(&mut self, position: (u8, u8), val: u8) -> Result<MoveToken, PuzzleError> {
+        let invalid = PuzzleError::InvalidValue { position, val };
+        if position.0 >= 9 || position.1 >= 9 || !(1..=9).contains(&val) {
+            return Err(invalid);
+        }
+        let old_set = self.legal_values(position);
+        if !old_set.has(val) {
+            return Err(invalid);
+        }
+        let cell_index = Self::get_cell_index(position);
+        let coords = Self::get_cell_coords(cell_index);
+        let mut undo = ArrayVec::new();
+        self.set(coords, val);
+        if self.propagate_choice(coords, val, &mut undo) {
+            self.delete(coords);
+            return Err(invalid);
+        }
+        self.empty_cell_queue.delete(cell_index as usize);
+        Ok(MoveToken {
+            cell_index,
+            old_set,
+            undo,
+        })
+    }
+
+    /// Undoes the most recent move that hasn't been undone yet.
+    pub fn undo(&mut self, token: MoveToken) {
+        for &(ci, old_set) in token.undo.iter().rev() {
+            self.empty_cell_queue.insert_unsafe((ci as usize, old_set));
+        }
+        self.delete(Self::get_cell_coords(token.cell_index));
+        self.empty_cell_queue
+            .insert_unsafe((token.cell_index as usize, token.old_set));
+    }
+
     #[must_use]
     pub fn num_clues(&self) -> u8 {
         (0..9).fold(0, |acc: u8, row| acc + (9 - self.row_sets[row].len()))
@@ -728,6 +1125,14 @@ impl Display for ClassicPuzzle {
 impl From<ClassicGrid> for ClassicPuzzle {
     /// Creates a new `ClassicPuzzle` from a `ClassicGrid`
     fn from(grid: ClassicGrid) -> Self {
+        Self::from_grid_with_rules(grid, ClassicRules::default())
+    }
+}
+
+impl ClassicPuzzle {
+    /// Creates a new `ClassicPuzzle` with some optional rules from a `ClassicGrid`
+    #[must_use]
+    pub fn from_grid_with_rules(grid: ClassicGrid, rules: ClassicRules) -> Self {
         fn set_from_iter<'a, I: Iterator<Item = &'a Option<u8>>>(iter: I) -> ElementSet {
             let mut element_set = ElementSet::CLASSIC;
             iter.for_each(|o| {
@@ -740,35 +1145,58 @@ impl From<ClassicGrid> for ClassicPuzzle {
         let row_sets = std::array::from_fn(|i| set_from_iter(grid.iter_row(i as u8)));
         let col_sets = std::array::from_fn(|i| set_from_iter(grid.iter_col(i as u8)));
         let box_sets = std::array::from_fn(|i| set_from_iter(grid.iter_box(i as u8)));
+        let window_sets = std::array::from_fn(|i| {
+            let tl_window_row = 1 + (i as u8 / 2) * 4;
+            let tl_window_col = 1 + (i as u8 % 2) * 4;
+            let window: ArrayVec<Option<u8>, 9> = (0..9u8)
+                .map(|off| grid.get_by_row_col((tl_window_row + off / 3, tl_window_col + off % 3)))
+                .collect();
+            set_from_iter(window.iter())
+        });
 
-        let mut empty_cell_queue = ArrayPriorityQueue::new();
-        empty_cell_queue.init_map_none(BOARD_SIZE);
-        empty_cell_queue.fill_from_iter_unsafe((0..BOARD_SIZE).filter_map(|cell_index| {
-            let (row_index, col_index, box_index) = Self::get_cell_coords(cell_index as u8);
-            if grid.get_by_row_col((row_index, col_index)).is_none() {
-                let element_set = row_sets[row_index as usize]
-                    .intersection(&col_sets[col_index as usize])
-                    .intersection(&box_sets[box_index as usize]);
-                Some((cell_index, element_set))
-            } else {
-                None
-            }
-        }));
-
-        Self {
+        let mut puzzle = Self {
             grid,
             row_sets,
             col_sets,
             box_sets,
-            empty_cell_queue,
-        }
+            window_sets,
+            rules,
+            empty_cell_queue: ArrayPriorityQueue::new(),
+        };
+        puzzle.empty_cell_queue.init_map_none(BOARD_SIZE);
+        let empty_cells: ArrayVec<(usize, ElementSet), BOARD_SIZE> = (0..BOARD_SIZE)
+            .filter_map(|cell_index| {
+                let coords = Self::get_cell_coords(cell_index as u8);
+                if grid.get_by_row_col((coords.0, coords.1)).is_none() {
+                    Some((cell_index, puzzle.get_element_set(coords)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        puzzle.empty_cell_queue.fill_from_iter_unsafe(empty_cells);
+
+        puzzle
+    }
+
+    /// Creates a new `ClassicPuzzle` like `from_grid_with_rules`, but returns
+    /// `PuzzleError::Conflict` if the grid's numbers break the rules, which the solver can't work
+    /// with.
+    pub fn try_from_grid_with_rules(
+        grid: ClassicGrid,
+        rules: ClassicRules,
+    ) -> Result<Self, PuzzleError> {
+        validate_givens(&grid, rules)?;
+        Ok(Self::from_grid_with_rules(grid, rules))
     }
 }
 
-impl From<&str> for ClassicPuzzle {
-    /// Creates a new `ClassicPuzzle` from a grid string
-    fn from(s: &str) -> Self {
-        Self::from(ClassicGrid::from(s))
+impl FromStr for ClassicPuzzle {
+    type Err = PuzzleError;
+
+    /// Creates a new `ClassicPuzzle` from a grid string, whose numbers can't break the rules
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from_grid_with_rules(s.parse()?, ClassicRules::default())
     }
 }
 
@@ -902,7 +1330,7 @@ mod tests {
     /// Test that loading a filled puzzle from a string sets the fields in the puzzle correctly.
     #[test]
     fn from_grid_filled() {
-        let grid = ClassicGrid::from(SEED_PUZZLE_SOLUTION_STR);
+        let grid = SEED_PUZZLE_SOLUTION_STR.parse::<ClassicGrid>().unwrap();
         let puzzle = ClassicPuzzle::from(grid);
         assert_eq!(puzzle.grid, grid);
         assert!(puzzle.row_sets.iter().all(|row_set| row_set.is_empty()));
@@ -914,7 +1342,7 @@ mod tests {
     /// Test that loading a puzzle from a string sets the fields in the puzzle correctly.
     #[test]
     fn from_grid_minimum() {
-        let grid = ClassicGrid::from(SEED_PUZZLE_MINIMUM_STR);
+        let grid = SEED_PUZZLE_MINIMUM_STR.parse::<ClassicGrid>().unwrap();
         let puzzle = ClassicPuzzle::from(grid);
         let expected_row_set_lens = [5, 7, 5, 6, 7, 8, 6, 4, 7];
         let expected_col_set_lens = [6, 7, 6, 5, 5, 6, 7, 8, 5];
@@ -937,7 +1365,7 @@ mod tests {
     /// Test that loading a puzzle from a string and back produces the original string.
     #[test]
     fn display() {
-        let puzzle = ClassicPuzzle::from(SEED_PUZZLE_SOLUTION_STR);
+        let puzzle = SEED_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         assert_eq!(puzzle.to_string(), SEED_PUZZLE_SOLUTION_STR);
     }
 
@@ -972,7 +1400,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution even when the puzzle is filled.
     #[test]
     fn find_solutions_filled_recursive() {
-        let puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         let solutions = ClassicPuzzle::find_solutions_recursive(puzzle.clone());
         assert_eq!(solutions.len(), 1);
         assert_eq!(solutions[0].to_string(), HARD_PUZZLE_SOLUTION_STR);
@@ -981,7 +1409,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution when one cell is empty.
     #[test]
     fn find_solutions_one_missing_recursive() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let mut puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         let cell_index = 7;
         let cell_coords = ClassicPuzzle::get_cell_coords(cell_index);
         puzzle.delete(cell_coords);
@@ -997,7 +1425,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution when one row is empty.
     #[test]
     fn find_solutions_row_missing_recursive() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let mut puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
 
         // Delete the entire first row (row = 0) with correct coords
         let mut row_cell_indexes = Vec::with_capacity(9);
@@ -1026,7 +1454,7 @@ mod tests {
     /// puzzle.
     #[test]
     fn find_solutions_minimum_recursive() {
-        let puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let solutions = ClassicPuzzle::find_solutions_recursive(puzzle.clone());
         assert_eq!(solutions.len(), 1);
         assert_eq!(solutions[0].to_string(), HARD_PUZZLE_SOLUTION_STR);
@@ -1036,7 +1464,7 @@ mod tests {
     /// puzzle that has had one cell cleared.
     #[test]
     fn find_solutions_multiple_recursive() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let cell_index = 7;
         let cell_coords = ClassicPuzzle::get_cell_coords(cell_index);
         puzzle.delete(cell_coords);
@@ -1051,7 +1479,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution even when the puzzle is filled.
     #[test]
     fn find_solutions_filled_iterative() {
-        let puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         let solutions = ClassicPuzzle::find_solutions_iterative(puzzle.clone());
         assert_eq!(solutions.len(), 1);
         assert_eq!(solutions[0].to_string(), HARD_PUZZLE_SOLUTION_STR);
@@ -1060,7 +1488,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution when one cell is empty.
     #[test]
     fn find_solutions_one_missing_iterative() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let mut puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         let cell_index = 7;
         let cell_coords = ClassicPuzzle::get_cell_coords(cell_index);
         puzzle.delete(cell_coords);
@@ -1076,7 +1504,7 @@ mod tests {
     /// Test that filling from RNG produces exactly one solution when one row is empty.
     #[test]
     fn find_solutions_row_missing_iterative() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_SOLUTION_STR);
+        let mut puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
 
         // Delete the entire first row (row = 0) with correct coords
         let mut row_cell_indexes = Vec::with_capacity(9);
@@ -1107,7 +1535,7 @@ mod tests {
     /// puzzle.
     #[test]
     fn find_solutions_minimum_iterative() {
-        let puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let solutions = ClassicPuzzle::find_solutions_iterative(puzzle.clone());
         assert_eq!(solutions.len(), 1);
         assert_eq!(solutions[0].to_string(), HARD_PUZZLE_SOLUTION_STR);
@@ -1117,7 +1545,7 @@ mod tests {
     /// puzzle that has had one cell cleared.
     #[test]
     fn find_solutions_multiple_iterative() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let cell_index = 7;
         let cell_coords = ClassicPuzzle::get_cell_coords(cell_index);
         puzzle.delete(cell_coords);
@@ -1132,14 +1560,14 @@ mod tests {
     /// Test that a puzzle with the minimum number of clues is well-posed.
     #[test]
     fn is_well_posed() {
-        let puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         assert!(puzzle.is_well_posed());
     }
 
     /// Test that a puzzle that has too few clues is not well-posed.
     #[test]
     fn is_not_well_posed() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let cell_index = 7;
         let cell_coords = ClassicPuzzle::get_cell_coords(cell_index);
         puzzle.delete(cell_coords);
@@ -1154,7 +1582,7 @@ mod tests {
     /// puzzle.
     #[test]
     fn minimize_from_rng_determinism() {
-        let mut puzzle = ClassicPuzzle::from(SEED_PUZZLE_SOLUTION_STR);
+        let mut puzzle = SEED_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
         let mut rng: SipRng = SipHasher::from(SEED).into_rng();
         puzzle.minimize_from_rng(&mut rng);
         let puzzle_str = puzzle.to_string();
@@ -1169,7 +1597,7 @@ mod tests {
 
     #[test]
     fn ill_posed_puzzle_has_more_than_one_solution_recursive() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let mut rng: SipRng = SipHasher::from(SEED).into_rng();
         puzzle.remove_n_random_filled_cells(&mut rng, 1);
         let num_solutions = ClassicPuzzle::count_solutions_recursive(puzzle);
@@ -1178,10 +1606,345 @@ mod tests {
 
     #[test]
     fn ill_posed_puzzle_has_more_than_one_solution_iterative() {
-        let mut puzzle = ClassicPuzzle::from(HARD_PUZZLE_MINIMUM_STR);
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
         let mut rng: SipRng = SipHasher::from(SEED).into_rng();
         puzzle.remove_n_random_filled_cells(&mut rng, 1);
         let num_solutions = ClassicPuzzle::count_solutions_iterative(puzzle);
         assert!(num_solutions > 1);
     }
+
+    /// Test that both searches do the same work, and that the minimum puzzle doesn't take more of
+    /// it than it used to.
+    #[test]
+    fn solve_stats_minimum() {
+        let puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
+        let mut recursive_stats = SolveStats::default();
+        ClassicPuzzle::visit_solutions_recursive_with_stats(
+            puzzle.clone(),
+            |_| true,
+            &mut recursive_stats,
+        );
+        let mut iterative_stats = SolveStats::default();
+        ClassicPuzzle::visit_solutions_iterative_with_stats(puzzle, |_| true, &mut iterative_stats);
+        assert_eq!(recursive_stats, iterative_stats);
+        // Currently 201,536 nodes and 228,338 propagations
+        assert!(recursive_stats.nodes_expanded < 220_000);
+        assert!(recursive_stats.propagation_calls < 250_000);
+        assert!(recursive_stats.max_depth <= 64);
+    }
+
+    /// Test that a filled puzzle is solved without any search.
+    #[test]
+    fn solve_stats_filled() {
+        let puzzle = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicPuzzle>().unwrap();
+        let mut stats = SolveStats::default();
+        ClassicPuzzle::visit_solutions_iterative_with_stats(puzzle, |_| true, &mut stats);
+        assert_eq!(stats, SolveStats::default());
+    }
+
+    /// Test that applying the solution one move at a time solves the puzzle, and that undoing every
+    /// move restores the possibilities of every cell.
+    #[test]
+    fn apply_and_undo() {
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
+        let solution = HARD_PUZZLE_SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        let legal_values = |puzzle: &ClassicPuzzle| -> Vec<Vec<u8>> {
+            (0..9)
+                .flat_map(|row| (0..9).map(move |col| (row, col)))
+                .map(|position| puzzle.legal_values(position).iter().collect())
+                .collect()
+        };
+        let before = legal_values(&puzzle);
+
+        let mut tokens = Vec::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                if puzzle.grid().get_by_row_col((row, col)).is_none() {
+                    let val = solution.get_by_row_col((row, col)).unwrap();
+                    tokens.push(puzzle.apply((row, col), val).unwrap());
+                }
+            }
+        }
+        assert_eq!(*puzzle.grid(), solution);
+        assert!(puzzle.empty_cell_queue.is_empty());
+
+        for token in tokens.into_iter().rev() {
+            puzzle.undo(token);
+        }
+        assert_eq!(
+            *puzzle.grid(),
+            HARD_PUZZLE_MINIMUM_STR.parse::<ClassicGrid>().unwrap()
+        );
+        assert_eq!(legal_values(&puzzle), before);
+    }
+
+    /// Test that illegal moves are rejected without changing the puzzle.
+    #[test]
+    fn apply_illegal() {
+        let mut puzzle = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicPuzzle>().unwrap();
+        // Filled cell
+        assert_eq!(
+            puzzle.apply((0, 7), 1).err(),
+            Some(PuzzleError::InvalidValue {
+                position: (0, 7),
+                val: 1
+            })
+        );
+        // 1 is already in the first row
+        assert!(!puzzle.legal_values((0, 0)).has(1));
+        assert!(puzzle.apply((0, 0), 1).is_err());
+        // Off the grid
+        assert!(puzzle.apply((9, 0), 1).is_err());
+        assert_eq!(
+            *puzzle.grid(),
+            HARD_PUZZLE_MINIMUM_STR.parse::<ClassicGrid>().unwrap()
+        );
+    }
+
+    /// Test that grids whose numbers break the rules, and puzzles without exactly one solution, are
+    /// reported.
+    #[test]
+    fn puzzle_errors() {
+        let mut grid = HARD_PUZZLE_MINIMUM_STR.parse::<ClassicGrid>().unwrap();
+        assert_eq!(ClassicPuzzle::from(grid).check_well_posed(), Ok(()));
+        grid.set((0, 0), grid.get_by_row_col((0, 7)));
+        assert!(matches!(
+            ClassicPuzzle::try_from_grid_with_rules(grid, ClassicRules::default()),
+            Err(PuzzleError::Conflict(_))
+        ));
+        assert_eq!(
+            ClassicPuzzle::new().check_well_posed(),
+            Err(PuzzleError::NotWellPosed { solutions: 2 })
+        );
+    }
+
+    /// Test that getting the hyper window index given a row and column works correctly.
+    #[test]
+    fn get_window_index() {
+        assert_eq!(ClassicPuzzle::get_window_index((0, 0)), None);
+        assert_eq!(ClassicPuzzle::get_window_index((1, 1)), Some(0));
+        assert_eq!(ClassicPuzzle::get_window_index((3, 7)), Some(1));
+        assert_eq!(ClassicPuzzle::get_window_index((5, 2)), Some(2));
+        assert_eq!(ClassicPuzzle::get_window_index((7, 7)), Some(3));
+        assert_eq!(ClassicPuzzle::get_window_index((4, 4)), None);
+        assert_eq!(ClassicPuzzle::get_window_index((2, 8)), None);
+    }
+
+    /// Test that filling from RNG with hyper windows produces windows with all numbers 1 to 9.
+    #[test]
+    fn fill_from_rng_hyper() {
+        let rules = ClassicRules {
+            hyper_windows: true,
+            ..ClassicRules::default()
+        };
+        let mut seed_rng: SipRng = SipHasher::from(SEED).into_rng();
+        for _ in 0..100 {
+            let seed = seed_rng.gen_seed();
+            let mut rng = SipHasher::from(seed).into_rng();
+            let mut puzzle = ClassicPuzzle::with_rules(rules);
+            puzzle.fill_from_rng(&mut rng);
+            let filled = ClassicPuzzle::from_grid_with_rules(puzzle.grid, rules);
+            assert!(filled.window_sets.iter().all(ElementSet::is_empty));
+            assert!(filled.row_sets.iter().all(ElementSet::is_empty));
+            assert!(filled.col_sets.iter().all(ElementSet::is_empty));
+            assert!(filled.box_sets.iter().all(ElementSet::is_empty));
+        }
+    }
+
+    /// Test that a hyper puzzle generated from a seed is well-posed under the hyper rules.
+    #[test]
+    fn from_seed_hyper_well_posed() {
+        let rules = ClassicRules {
+            hyper_windows: true,
+            ..ClassicRules::default()
+        };
+        let puzzle = ClassicPuzzle::from_seed_with_rules(SEED.to_string(), rules);
+        assert_eq!(puzzle.rules(), rules);
+        assert!(puzzle.is_well_posed());
+        let reloaded = ClassicPuzzle::from_grid_with_rules(puzzle.grid, rules);
+        assert_eq!(
+            ClassicPuzzle::count_solutions_bounded_iterative(reloaded, 2),
+            1
+        );
+    }
+
+    /// Test that random transformations keep puzzles well-posed with the transformed solution.
+    #[test]
+    fn random_transform_keeps_puzzles_equivalent() {
+        let mut rng: SipRng = SipHasher::from(SEED).into_rng();
+        for rules in [
+            ClassicRules::default(),
+            ClassicRules {
+                hyper_windows: true,
+                ..ClassicRules::default()
+            },
+            ClassicRules {
+                non_consecutive: true,
+                ..ClassicRules::default()
+            },
+        ] {
+            let puzzle = ClassicPuzzle::from_seed_with_rules(SEED.to_string(), rules);
+            let solution = puzzle.solution().unwrap();
+            let transform = rules.random_transform(&mut rng);
+            let transformed =
+                ClassicPuzzle::from_grid_with_rules(transform.apply(puzzle.grid()), rules);
+            assert_eq!(transformed.num_clues(), puzzle.num_clues());
+            assert!(transformed.is_well_posed());
+            assert_eq!(transformed.solution(), Some(transform.apply(&solution)));
+        }
+    }
+
+    /// Returns true if no pair of adjacent cells in the grid breaks the pairwise rules.
+    fn satisfies_pairwise(grid: &ClassicGrid, rules: ClassicRules) -> bool {
+        (0..9u8).all(|row| {
+            (0..9u8).all(|col| {
+                let val = grid.get_by_row_col((row, col)).unwrap();
+                ClassicPuzzle::iter_adjacent((row, col)).all(|(adjacent_row, adjacent_col)| {
+                    let adjacent_val = grid.get_by_row_col((adjacent_row, adjacent_col)).unwrap();
+                    let is_diagonal = adjacent_row != row && adjacent_col != col;
+                    let breaks_anti_king = is_diagonal && rules.anti_king && adjacent_val == val;
+                    let breaks_non_consecutive =
+                        !is_diagonal && rules.non_consecutive && adjacent_val.abs_diff(val) == 1;
+                    !breaks_anti_king && !breaks_non_consecutive
+                })
+            })
+        })
+    }
+
+    /// Test that filling from RNG with the pairwise rules produces grids that follow them.
+    #[test]
+    fn fill_from_rng_pairwise() {
+        let rule_sets = [
+            ClassicRules {
+                anti_king: true,
+                ..ClassicRules::default()
+            },
+            ClassicRules {
+                non_consecutive: true,
+                ..ClassicRules::default()
+            },
+            ClassicRules {
+                anti_king: true,
+                non_consecutive: true,
+                ..ClassicRules::default()
+            },
+        ];
+        let mut seed_rng: SipRng = SipHasher::from(SEED).into_rng();
+        for rules in rule_sets {
+            for _ in 0..5 {
+                let seed = seed_rng.gen_seed();
+                let mut rng = SipHasher::from(seed).into_rng();
+                let mut puzzle = ClassicPuzzle::with_rules(rules);
+                puzzle.fill_from_rng(&mut rng);
+                let filled = ClassicPuzzle::from_grid_with_rules(puzzle.grid, rules);
+                assert!(filled.row_sets.iter().all(ElementSet::is_empty));
+                assert!(filled.col_sets.iter().all(ElementSet::is_empty));
+                assert!(filled.box_sets.iter().all(ElementSet::is_empty));
+                assert!(satisfies_pairwise(&puzzle.grid, rules));
+            }
+        }
+    }
+
+    /// Test that the pairwise rules remove candidates from adjacent empty cells.
+    #[test]
+    fn get_element_set_pairwise() {
+        let rules = ClassicRules {
+            anti_king: true,
+            non_consecutive: true,
+            ..ClassicRules::default()
+        };
+        let mut puzzle = ClassicPuzzle::with_rules(rules);
+        puzzle.set((4, 4, 4), 5);
+        let diagonal = puzzle.get_element_set(ClassicPuzzle::get_cell_coords(30));
+        assert!(!diagonal.has(5));
+        assert!(diagonal.has(4) && diagonal.has(6));
+        let orthogonal = puzzle.get_element_set(ClassicPuzzle::get_cell_coords(39));
+        assert!(!orthogonal.has(4) && !orthogonal.has(5) && !orthogonal.has(6));
+        let distant = puzzle.get_element_set(ClassicPuzzle::get_cell_coords(0));
+        assert_eq!(distant.len(), 9);
+    }
+
+    /// Test that a puzzle generated with the pairwise rules is well-posed and solves to a grid that
+    /// follows them.
+    #[test]
+    fn from_seed_pairwise_well_posed() {
+        let rules = ClassicRules {
+            anti_king: true,
+            non_consecutive: true,
+            ..ClassicRules::default()
+        };
+        let puzzle = ClassicPuzzle::from_seed_with_rules(SEED.to_string(), rules);
+        let reloaded = ClassicPuzzle::from_grid_with_rules(puzzle.grid, rules);
+        let solutions = ClassicPuzzle::find_solutions_bounded_iterative(reloaded, 2);
+        assert_eq!(solutions.len(), 1);
+        assert!(satisfies_pairwise(&solutions[0], rules));
+    }
+
+    /// Test that the peer table lists the 20 distinct cells sharing a group with each cell.
+    #[test]
+    fn peers() {
+        for cell_index in 0..BOARD_SIZE as u8 {
+            let (row, col, box_index) = ClassicPuzzle::get_cell_coords(cell_index);
+            let peers = &PEERS[cell_index as usize];
+            for (i, &peer) in peers.iter().enumerate() {
+                let (peer_row, peer_col, peer_box) = ClassicPuzzle::get_cell_coords(peer);
+                assert_ne!(peer, cell_index);
+                assert!(peer_row == row || peer_col == col || peer_box == box_index);
+                assert!(!peers[..i].contains(&peer));
+            }
+        }
+        assert_eq!(ClassicPuzzle::iter_peers((4, 4)).count(), PEER_COUNT);
+        assert!(
+            ClassicPuzzle::iter_peers((4, 4)).all(|(row, col)| (row, col) != (4, 4)
+                && (row == 4 || col == 4 || (3..6).contains(&row) && (3..6).contains(&col)))
+        );
+    }
+
+    /// Test that collecting neighbors with every optional rule enabled never repeats a cell.
+    #[test]
+    fn collect_empty_neighbors_for_unique() {
+        let puzzle = ClassicPuzzle::with_rules(ClassicRules {
+            hyper_windows: true,
+            anti_king: true,
+            non_consecutive: true,
+        });
+        for cell_index in 0..BOARD_SIZE as u8 {
+            let neighbors =
+                puzzle.collect_empty_neighbors_for(ClassicPuzzle::get_cell_coords(cell_index));
+            for (i, neighbor) in neighbors.iter().enumerate() {
+                assert!(!neighbors[..i].contains(neighbor));
+            }
+        }
+    }
+
+    /// Test that every golden seed still generates the puzzle in the fixtures. If this fails,
+    /// generation has changed and `GENERATOR_VERSION` must be increased (see its stability policy)
+    /// rather than the fixtures being updated.
+    #[test]
+    fn from_seed_golden() {
+        assert_eq!(crate::utility::share_code::GENERATOR_VERSION, 1);
+        let fixtures = include_str!("fixtures/golden_seeds.txt");
+        for line in fixtures.lines().filter(|line| !line.starts_with('#')) {
+            let [seed, flags, cells] = line.split(' ').collect::<Vec<_>>()[..] else {
+                panic!("Malformed fixture {line:?}");
+            };
+            let rules = ClassicRules {
+                hyper_windows: flags.contains('H'),
+                anti_king: flags.contains('K'),
+                non_consecutive: flags.contains('N'),
+            };
+            let expected = cells
+                .as_bytes()
+                .chunks(9)
+                .map(|row| std::str::from_utf8(row).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let puzzle = ClassicPuzzle::from_seed_with_rules(seed.to_string(), rules);
+            assert_eq!(
+                *puzzle.grid(),
+                expected.as_str().parse::<ClassicGrid>().unwrap(),
+                "Seed {seed:?} with rules {flags} generated\n{puzzle}"
+            );
+        }
+    }
 }
@@ -0,0 +1,258 @@
+use std::fmt::Display;
+
+use crate::grids::classic::ClassicGrid;
+
+use super::{arrow::Arrow, classic::ClassicRules};
+
+/// The rule that a conflict breaks. Rules that belong to a region or a clue on the board say which
+/// one, so that it can be highlighted along with the cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictRule {
+    Row(u8),
+    Col(u8),
+    Box(u8),
+    /// One of the four hyper windows, numbered left to right and then top to bottom
+    Window(u8),
+    /// Cells a king's move apart have the same number
+    AntiKing,
+    /// Orthogonally adjacent cells have consecutive numbers
+    NonConsecutive,
+    /// The arrow at this index of the puzzle's arrows can't add up to its circle
+    Arrow(usize),
+}
+
+/// Cells whose numbers break a rule of the puzzle, whether or not they match the solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub rule: ConflictRule,
+    pub cells: Vec<(u8, u8)>,
+}
+
+impl Display for Conflict {
+    /// Displays the conflict like `r1c1 and r1c5 repeat a number in row 1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self
+            .cells
+            .iter()
+            .map(|(row, col)| format!("r{}c{}", row + 1, col + 1))
+            .collect();
+        let cells = match cells.as_slice() {
+            [rest @ .., last] if !rest.is_empty() => format!("{} and {last}", rest.join(", ")),
+            _ => cells.concat(),
+        };
+        match self.rule {
+            ConflictRule::Row(row) => write!(f, "{cells} repeat a number in row {}", row + 1),
+            ConflictRule::Col(col) => write!(f, "{cells} repeat a number in column {}", col + 1),
+            ConflictRule::Box(index) => write!(f, "{cells} repeat a number in box {}", index + 1),
+            ConflictRule::Window(index) => {
+                write!(f, "{cells} repeat a number in window {}", index + 1)
+            }
+            ConflictRule::AntiKing => write!(f, "{cells} are a king's move apart"),
+            ConflictRule::NonConsecutive => write!(f, "{cells} are adjacent and consecutive"),
+            ConflictRule::Arrow(_) => write!(f, "the arrow on {cells} can't add up"),
+        }
+    }
+}
+
+/// Returns the cells of one of the four hyper windows.
+fn window_cells(index: u8) -> impl Iterator<Item = (u8, u8)> {
+    let (top, left) = (1 + (index / 2) * 4, 1 + (index % 2) * 4);
+    (0..9).map(move |i| (top + i / 3, left + i % 3))
+}
+
+/// Returns a conflict for each number that appears more than once among some cells.
+fn repeats(
+    grid: &ClassicGrid,
+    rule: ConflictRule,
+    cells: impl Iterator<Item = (u8, u8)>,
+) -> impl Iterator<Item = Conflict> {
+    let mut cells_by_val: [Vec<(u8, u8)>; 9] = Default::default();
+    for position in cells {
+        if let Some(val) = grid.get_by_row_col(position) {
+            cells_by_val[val as usize - 1].push(position);
+        }
+    }
+    cells_by_val
+        .into_iter()
+        .filter(|cells| cells.len() > 1)
+        .map(move |cells| Conflict { rule, cells })
+}
+
+/// Returns every conflict among the filled cells of a classic grid played with some rules, with
+/// the region repeats first.
+#[must_use]
+pub fn classic_conflicts(grid: &ClassicGrid, rules: ClassicRules) -> Vec<Conflict> {
+    let mut conflicts: Vec<Conflict> = (0..9)
+        .flat_map(|row| {
+            repeats(
+                grid,
+                ConflictRule::Row(row),
+                (0..9).map(move |col| (row, col)),
+            )
+        })
+        .chain((0..9).flat_map(|col| {
+            repeats(
+                grid,
+                ConflictRule::Col(col),
+                (0..9).map(move |row| (row, col)),
+            )
+        }))
+        .chain((0..9).flat_map(|index| {
+            let (top, left) = ((index / 3) * 3, (index % 3) * 3);
+            repeats(
+                grid,
+                ConflictRule::Box(index),
+                (0..9).map(move |i| (top + i / 3, left + i % 3)),
+            )
+        }))
+        .collect();
+    if rules.hyper_windows {
+        conflicts.extend(
+            (0..4)
+                .flat_map(|index| repeats(grid, ConflictRule::Window(index), window_cells(index))),
+        );
+    }
+
+    // Each pair of neighbors is checked once, from the cell above or to the left
+    for row in 0..9 {
+        for col in 0..9 {
+            let Some(val) = grid.get_by_row_col((row, col)) else {
+                continue;
+            };
+            let neighbor = |position: (u8, u8)| {
+                (position.0 < 9 && position.1 < 9)
+                    .then(|| grid.get_by_row_col(position).map(|other| (position, other)))
+                    .flatten()
+            };
+            if rules.anti_king {
+                // Diagonal neighbors in the same box already repeat in the box
+                let diagonals = [(row + 1, col + 1), (row + 1, col.wrapping_sub(1))];
+                for (position, other) in diagonals.into_iter().filter_map(neighbor) {
+                    if other == val && (position.0 / 3 != row / 3 || position.1 / 3 != col / 3) {
+                        conflicts.push(Conflict {
+                            rule: ConflictRule::AntiKing,
+                            cells: vec![(row, col), position],
+                        });
+                    }
+                }
+            }
+            if rules.non_consecutive {
+                for (position, other) in [(row + 1, col), (row, col + 1)]
+                    .into_iter()
+                    .filter_map(neighbor)
+                {
+                    if other.abs_diff(val) == 1 {
+                        conflicts.push(Conflict {
+                            rule: ConflictRule::NonConsecutive,
+                            cells: vec![(row, col), position],
+                        });
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+/// Returns a conflict for each arrow whose filled cells can't add up to its circle, counting each
+/// empty cell along the path as at least 1 and an empty circle as at most 9.
+#[must_use]
+pub fn arrow_conflicts(grid: &ClassicGrid, arrows: &[Arrow]) -> Vec<Conflict> {
+    arrows
+        .iter()
+        .enumerate()
+        .filter(|(_, arrow)| {
+            let path_vals: Vec<Option<u8>> = arrow
+                .path
+                .iter()
+                .map(|&position| grid.get_by_row_col(position))
+                .collect();
+            let least_sum: u32 = path_vals
+                .iter()
+                .map(|val| u32::from(val.unwrap_or(1)))
+                .sum();
+            let path_filled = path_vals.iter().all(Option::is_some);
+            match grid.get_by_row_col(arrow.circle).map(u32::from) {
+                Some(circle) if path_filled => least_sum != circle,
+                Some(circle) => least_sum > circle,
+                None => least_sum > 9,
+            }
+        })
+        .map(|(index, arrow)| Conflict {
+            rule: ConflictRule::Arrow(index),
+            cells: std::iter::once(arrow.circle)
+                .chain(arrow.path.iter().copied())
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_conflicts_follow_the_rules() {
+        let mut grid = ClassicGrid::default();
+        grid.set((0, 0), Some(5));
+        grid.set((0, 4), Some(5));
+        // A king's move apart across boxes, and consecutive neighbors
+        grid.set((2, 2), Some(7));
+        grid.set((3, 3), Some(7));
+        grid.set((3, 4), Some(8));
+
+        let conflicts = classic_conflicts(&grid, ClassicRules::default());
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                rule: ConflictRule::Row(0),
+                cells: vec![(0, 0), (0, 4)],
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "r1c1 and r1c5 repeat a number in row 1"
+        );
+
+        let rules = ClassicRules {
+            anti_king: true,
+            non_consecutive: true,
+            ..ClassicRules::default()
+        };
+        let rules_broken: Vec<ConflictRule> = classic_conflicts(&grid, rules)
+            .into_iter()
+            .map(|conflict| conflict.rule)
+            .collect();
+        assert_eq!(
+            rules_broken,
+            vec![
+                ConflictRule::Row(0),
+                ConflictRule::AntiKing,
+                ConflictRule::NonConsecutive,
+            ]
+        );
+    }
+
+    #[test]
+    fn arrow_conflicts_bound_the_sum() {
+        let arrows = vec![Arrow {
+            circle: (0, 0),
+            path: vec![(0, 1), (0, 2)],
+        }];
+        let mut grid = ClassicGrid::default();
+        grid.set((0, 0), Some(4));
+        grid.set((0, 1), Some(3));
+        // The empty cell needs at least 1, which still fits
+        assert!(arrow_conflicts(&grid, &arrows).is_empty());
+        grid.set((0, 1), Some(4));
+        assert_eq!(
+            arrow_conflicts(&grid, &arrows)[0].rule,
+            ConflictRule::Arrow(0)
+        );
+        grid.set((0, 1), Some(2));
+        grid.set((0, 2), Some(1));
+        assert_eq!(arrow_conflicts(&grid, &arrows).len(), 1);
+        grid.set((0, 2), Some(2));
+        assert!(arrow_conflicts(&grid, &arrows).is_empty());
+    }
+}
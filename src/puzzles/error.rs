@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use super::{conflict::Conflict, validation::ValidationError};
+
+/// Why a grid couldn't be read, a number couldn't be placed, or a puzzle couldn't be generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// Text couldn't be read as a grid, and why
+    ParseError(String),
+    /// A number isn't from 1 to 9, the cell isn't on the board, or the number can't go in the cell
+    InvalidValue { position: (u8, u8), val: u8 },
+    /// Numbers break a rule of the puzzle
+    Conflict(Conflict),
+    /// The puzzle doesn't have exactly one solution (`solutions` stops counting at 2)
+    NotWellPosed { solutions: usize },
+    /// Generating gave up after trying this many seeds
+    BudgetExceeded { attempts: usize },
+}
+
+impl Display for PuzzleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PuzzleError::ParseError(reason) => write!(f, "the grid couldn't be read: {reason}"),
+            PuzzleError::InvalidValue {
+                position: (row, col),
+                val,
+            } => write!(f, "{val} can't go in r{}c{}", row + 1, col + 1),
+            PuzzleError::Conflict(conflict) => write!(f, "{conflict}"),
+            PuzzleError::NotWellPosed { solutions: 0 } => write!(f, "the puzzle has no solution"),
+            PuzzleError::NotWellPosed { .. } => write!(f, "the puzzle has more than one solution"),
+            PuzzleError::BudgetExceeded { attempts } => {
+                write!(f, "no puzzle was found after trying {attempts} seeds")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl From<ValidationError> for PuzzleError {
+    fn from(err: ValidationError) -> Self {
+        match err {
+            ValidationError::ConflictingGivens(conflict)
+            | ValidationError::InvalidSolution(conflict) => PuzzleError::Conflict(conflict),
+            _ => PuzzleError::ParseError(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::conflict::ConflictRule;
+
+    #[test]
+    fn test_from_validation_error() {
+        assert_eq!(
+            PuzzleError::from(ValidationError::WrongRowCount(1)),
+            PuzzleError::ParseError("the grid has 1 rows instead of 9".into())
+        );
+        let conflict = Conflict {
+            rule: ConflictRule::Row(0),
+            cells: vec![(0, 0), (0, 1)],
+        };
+        assert_eq!(
+            PuzzleError::from(ValidationError::ConflictingGivens(conflict.clone())),
+            PuzzleError::Conflict(conflict)
+        );
+    }
+}
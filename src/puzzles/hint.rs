@@ -0,0 +1,483 @@
+use std::fmt::Display;
+
+use rand_seeder::{SipHasher, SipRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::{bitboard::BitboardGrid, classic::ClassicGrid},
+    puzzles::{
+        classic::{ClassicPuzzle, SolveStats},
+        error::PuzzleError,
+    },
+};
+
+/// How many seeds `practice_puzzle` tries before giving up.
+const MAX_PRACTICE_ATTEMPTS: usize = 500;
+
+/// How many times `challenge_puzzle` clears the solution again before giving up.
+const MAX_CHALLENGE_ATTEMPTS: usize = 20;
+
+/// How far apart two grades can be for `similar_grades`, in numbers placed by a different technique
+/// and solver backtracks.
+const SIMILAR_GRADE_TOLERANCE: usize = 6;
+
+/// The solving techniques hints can be found with, from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Technique {
+    /// The only cell in a row, column, or box that can contain a number
+    HiddenSingle,
+    /// A cell that can only contain one number
+    NakedSingle,
+}
+
+impl Technique {
+    /// Every technique, from easiest to hardest
+    pub const ALL: [Technique; 2] = [Technique::HiddenSingle, Technique::NakedSingle];
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technique::HiddenSingle => write!(f, "Hidden single"),
+            Technique::NakedSingle => write!(f, "Naked single"),
+        }
+    }
+}
+
+/// The part of the grid a hint points the player towards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Region {
+    Row(u8),
+    Col(u8),
+    Box(u8),
+    Cell((u8, u8)),
+}
+
+impl Region {
+    /// Returns the cells in the region.
+    pub fn cells(self) -> impl Iterator<Item = (u8, u8)> {
+        (0..9).filter_map(move |i| match self {
+            Region::Row(row) => Some((row, i)),
+            Region::Col(col) => Some((i, col)),
+            Region::Box(box_index) => {
+                Some(((box_index / 3) * 3 + i / 3, (box_index % 3) * 3 + i % 3))
+            }
+            Region::Cell(position) => (i == 0).then_some(position),
+        })
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Region::Row(row) => write!(f, "row {}", row + 1),
+            Region::Col(col) => write!(f, "column {}", col + 1),
+            Region::Box(box_index) => write!(f, "box {}", box_index + 1),
+            Region::Cell((row, col)) => write!(f, "r{}c{}", row + 1, col + 1),
+        }
+    }
+}
+
+/// A number that can be placed using a technique, and where to look to find it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Hint {
+    pub technique: Technique,
+    pub region: Region,
+    pub position: (u8, u8),
+    pub val: u8,
+}
+
+/// Returns a hint for the next number to place in a classic grid, using the easiest technique
+/// that finds one. Only the row, column, and box rules are considered, and entries are assumed to
+/// be correct.
+#[must_use]
+pub fn find_hint(grid: &ClassicGrid) -> Option<Hint> {
+    find_hint_using(grid, &Technique::ALL)
+}
+
+/// Returns a hint like `find_hint`, but only using some of the techniques.
+#[must_use]
+pub fn find_hint_using(grid: &ClassicGrid, techniques: &[Technique]) -> Option<Hint> {
+    let _span = tracing::info_span!("hint_search").entered();
+    let bitboard = BitboardGrid::from(grid);
+    let is_empty = |position: &(u8, u8)| grid.get_by_row_col(*position).is_none();
+
+    if techniques.contains(&Technique::HiddenSingle) {
+        // Boxes come first since they're where most players look for hidden singles
+        let units = (0..9)
+            .map(Region::Box)
+            .chain((0..9).map(Region::Row))
+            .chain((0..9).map(Region::Col));
+        for region in units {
+            for val in 1..=9 {
+                let bit = 1 << (val - 1);
+                let mut positions = region.cells().filter(|position| {
+                    is_empty(position) && bitboard.candidate_mask(*position) & bit != 0
+                });
+                if let (Some(position), None) = (positions.next(), positions.next()) {
+                    return Some(Hint {
+                        technique: Technique::HiddenSingle,
+                        region,
+                        position,
+                        val,
+                    });
+                }
+            }
+        }
+    }
+
+    if !techniques.contains(&Technique::NakedSingle) {
+        return None;
+    }
+    (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .filter(is_empty)
+        .find_map(|position| {
+            let mask = bitboard.candidate_mask(position);
+            (mask.count_ones() == 1).then(|| Hint {
+                technique: Technique::NakedSingle,
+                region: Region::Cell(position),
+                position,
+                val: mask.trailing_zeros() as u8 + 1,
+            })
+        })
+}
+
+/// Fills in every number the techniques can find, one hint at a time. Returns true if the grid
+/// was solved.
+pub fn solve_using(grid: &mut ClassicGrid, techniques: &[Technique]) -> bool {
+    while let Some(hint) = find_hint_using(grid, techniques) {
+        grid.set(hint.position, Some(hint.val));
+    }
+    grid.iter_all().all(Option::is_some)
+}
+
+/// Returns true if the techniques can solve the grid, but not without `technique`.
+#[must_use]
+pub fn requires_technique(grid: &ClassicGrid, technique: Technique) -> bool {
+    let without: Vec<_> = Technique::ALL
+        .into_iter()
+        .filter(|other| *other != technique)
+        .collect();
+    solve_using(&mut grid.clone(), &Technique::ALL) && !solve_using(&mut grid.clone(), &without)
+}
+
+/// Returns the hardest technique needed to solve the grid with the techniques hints use, or
+/// `None` if they can't solve it. This is how difficult the puzzle is to solve by hand.
+#[must_use]
+pub fn hardest_technique(grid: &ClassicGrid) -> Option<Technique> {
+    (1..=Technique::ALL.len())
+        .find(|&count| solve_using(&mut grid.clone(), &Technique::ALL[..count]))
+        .map(|count| Technique::ALL[count - 1])
+}
+
+/// How hard a puzzle is to solve by hand, and what solving it takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GradeReport {
+    /// The hardest technique needed, or `None` if the techniques hints use can't solve it
+    pub difficulty: Option<Technique>,
+    /// How many numbers each technique placed, from easiest to hardest, leaving out techniques
+    /// that weren't used
+    pub techniques: Vec<(Technique, usize)>,
+    /// How many times the solver had to back up to finish the cells the techniques couldn't
+    pub backtracks_required: usize,
+}
+
+/// Grades a classic puzzle by solving it one hint at a time with the easiest technique that finds
+/// one, then finishing any cells the techniques can't with the solver. The app, the history, and
+/// anything else that shows a difficulty should use this so that they all agree.
+#[must_use]
+pub fn grade(puzzle: &ClassicPuzzle) -> GradeReport {
+    let mut grid = *puzzle.grid();
+    let mut counts = [0; Technique::ALL.len()];
+    while let Some(hint) = find_hint(&grid) {
+        grid.set(hint.position, Some(hint.val));
+        if let Some(index) = Technique::ALL.iter().position(|t| *t == hint.technique) {
+            counts[index] += 1;
+        }
+    }
+    let solved = grid.iter_all().all(Option::is_some);
+    let techniques: Vec<_> = Technique::ALL
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let mut stats = SolveStats::default();
+    if !solved {
+        ClassicPuzzle::visit_solutions_recursive_with_stats(
+            ClassicPuzzle::from_grid_with_rules(grid, puzzle.rules()),
+            |_| false,
+            &mut stats,
+        );
+    }
+    GradeReport {
+        // Since the easiest technique is always tried first, the hardest one used is the one the
+        // puzzle needs
+        difficulty: solved
+            .then(|| techniques.last().map(|(technique, _)| *technique))
+            .flatten(),
+        techniques,
+        backtracks_required: stats.backtracks,
+    }
+}
+
+/// Generates a classic puzzle that can be solved with the techniques hints use, but only by using
+/// `technique`. Seeds derived from `seed` are tried in order, so the same seed always gives the
+/// same puzzle. Returns `PuzzleError::BudgetExceeded` if none of the seeds needs `technique`.
+pub fn practice_puzzle(technique: Technique, seed: &str) -> Result<ClassicPuzzle, PuzzleError> {
+    (0..MAX_PRACTICE_ATTEMPTS)
+        .map(|attempt| ClassicPuzzle::from_seed(format!("{seed}-{attempt}")))
+        .find(|puzzle| requires_technique(puzzle.grid(), technique))
+        .ok_or(PuzzleError::BudgetExceeded {
+            attempts: MAX_PRACTICE_ATTEMPTS,
+        })
+}
+
+/// Returns how hard a grade is, for comparing puzzles: the hardest technique needed comes first,
+/// then how many numbers have to be found.
+fn hardness(report: &GradeReport) -> (usize, usize) {
+    let technique = report
+        .difficulty
+        .map_or(Technique::ALL.len(), |difficulty| {
+            Technique::ALL
+                .iter()
+                .position(|technique| *technique == difficulty)
+                .unwrap_or_default()
+        });
+    let found = report.techniques.iter().map(|(_, count)| count).sum();
+    (technique, found)
+}
+
+/// Returns how differently two grades solve their puzzles: how many numbers each technique placed
+/// in one but not the other, plus the difference in backtracks. Returns `None` if they need
+/// different hardest techniques, since then they aren't alike at all.
+#[must_use]
+pub fn grade_distance(a: &GradeReport, b: &GradeReport) -> Option<usize> {
+    if a.difficulty != b.difficulty {
+        return None;
+    }
+    let count = |report: &GradeReport, technique| {
+        report
+            .techniques
+            .iter()
+            .find(|(used, _)| *used == technique)
+            .map_or(0, |(_, count)| *count)
+    };
+    let techniques: usize = Technique::ALL
+        .into_iter()
+        .map(|technique| count(a, technique).abs_diff(count(b, technique)))
+        .sum();
+    Some(techniques + a.backtracks_required.abs_diff(b.backtracks_required))
+}
+
+/// Returns true if two grades need the same hardest technique and use each technique about as
+/// much, so their puzzles make for the same kind of challenge.
+#[must_use]
+pub fn similar_grades(a: &GradeReport, b: &GradeReport) -> bool {
+    grade_distance(a, b).is_some_and(|distance| distance <= SIMILAR_GRADE_TOLERANCE)
+}
+
+/// Generates a harder classic puzzle with the same solution, by clearing cells from the solution
+/// again in a different order. A puzzle is harder if it needs a harder technique, or the same one
+/// with fewer givens. Seeds derived from `seed` are tried in order, so the same seed always gives
+/// the same puzzle. Returns `None` if no harder puzzle was found.
+#[must_use]
+pub fn challenge_puzzle(
+    puzzle: &ClassicPuzzle,
+    solution: &ClassicGrid,
+    seed: &str,
+) -> Option<ClassicPuzzle> {
+    let to_beat = hardness(&grade(puzzle));
+    (0..MAX_CHALLENGE_ATTEMPTS)
+        .map(|attempt| {
+            let mut rng: SipRng = SipHasher::from(format!("{seed}-{attempt}")).into_rng();
+            let mut harder = ClassicPuzzle::from_grid_with_rules(*solution, puzzle.rules());
+            harder.minimize_from_rng(&mut rng);
+            harder
+        })
+        .find(|harder| hardness(&grade(harder)) > to_beat)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const SOLUTION_STR: &str = indoc! {"
+        5 6 2 | 1 4 3 | 9 8 7
+        3 7 8 | 2 5 9 | 6 1 4
+        9 4 1 | 7 8 6 | 3 2 5
+        ------|-------|------
+        4 8 6 | 5 1 2 | 7 3 9
+        7 2 3 | 9 6 8 | 5 4 1
+        1 5 9 | 4 3 7 | 8 6 2
+        ------|-------|------
+        2 3 4 | 8 7 5 | 1 9 6
+        8 9 7 | 6 2 1 | 4 5 3
+        6 1 5 | 3 9 4 | 2 7 8
+    "};
+
+    #[test]
+    fn test_hidden_single() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((4, 4), None);
+        assert_eq!(
+            find_hint(&grid),
+            Some(Hint {
+                technique: Technique::HiddenSingle,
+                region: Region::Box(4),
+                position: (4, 4),
+                val: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn test_naked_single() {
+        // r1c1 can only be 1, but every unit has other places a 1 could go
+        let grid: ClassicGrid = indoc! {"
+            . . . | 2 3 4 | . . .
+            . . . | . . . | . . .
+            . . . | . . . | . . .
+            ------|-------|------
+            5 . . | . . . | . . .
+            6 . . | . . . | . . .
+            7 . . | . . . | . . .
+            ------|-------|------
+            8 . . | . . . | . . .
+            9 . . | . . . | . . .
+            . . . | . . . | . . .
+        "}
+        .parse()
+        .unwrap();
+        let hint = find_hint(&grid).unwrap();
+        assert_eq!(hint.technique, Technique::NakedSingle);
+        assert_eq!(hint.position, (0, 0));
+        assert_eq!(hint.val, 1);
+    }
+
+    #[test]
+    fn test_no_hint() {
+        assert_eq!(
+            find_hint(&SOLUTION_STR.parse::<ClassicGrid>().unwrap()),
+            None
+        );
+        assert_eq!(find_hint(&ClassicGrid::default()), None);
+    }
+
+    #[test]
+    fn test_region_cells() {
+        assert_eq!(Region::Box(4).cells().next(), Some((3, 3)));
+        assert_eq!(Region::Box(8).cells().last(), Some((8, 8)));
+        assert_eq!(
+            Region::Cell((2, 5)).cells().collect::<Vec<_>>(),
+            vec![(2, 5)]
+        );
+    }
+
+    #[test]
+    fn test_requires_technique() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((4, 4), None);
+        // A single empty cell can be found either way
+        assert!(!requires_technique(&grid, Technique::HiddenSingle));
+        assert!(!requires_technique(&grid, Technique::NakedSingle));
+        assert!(!requires_technique(
+            &ClassicGrid::default(),
+            Technique::NakedSingle
+        ));
+    }
+
+    #[test]
+    fn test_hardest_technique() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((4, 4), None);
+        assert_eq!(hardest_technique(&grid), Some(Technique::HiddenSingle));
+        assert_eq!(hardest_technique(&ClassicGrid::default()), None);
+        let puzzle = practice_puzzle(Technique::NakedSingle, "test").unwrap();
+        assert_eq!(
+            hardest_technique(puzzle.grid()),
+            Some(Technique::NakedSingle)
+        );
+    }
+
+    #[test]
+    fn test_grade() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((4, 4), None);
+        assert_eq!(
+            grade(&ClassicPuzzle::from(grid)),
+            GradeReport {
+                difficulty: Some(Technique::HiddenSingle),
+                techniques: vec![(Technique::HiddenSingle, 1)],
+                backtracks_required: 0,
+            }
+        );
+        let puzzle = practice_puzzle(Technique::NakedSingle, "test").unwrap();
+        let report = grade(&puzzle);
+        assert_eq!(report.difficulty, hardest_technique(puzzle.grid()));
+        assert_eq!(
+            report
+                .techniques
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>(),
+            81 - usize::from(puzzle.num_clues())
+        );
+        let report = grade(&ClassicPuzzle::new());
+        assert_eq!(report.difficulty, None);
+        assert!(report.techniques.is_empty());
+    }
+
+    #[test]
+    fn test_similar_grades() {
+        let report = |difficulty, techniques, backtracks_required| GradeReport {
+            difficulty,
+            techniques,
+            backtracks_required,
+        };
+        let a = report(
+            Some(Technique::NakedSingle),
+            vec![(Technique::HiddenSingle, 40), (Technique::NakedSingle, 10)],
+            0,
+        );
+        let b = report(
+            Some(Technique::NakedSingle),
+            vec![(Technique::HiddenSingle, 43), (Technique::NakedSingle, 8)],
+            1,
+        );
+        assert_eq!(grade_distance(&a, &b), Some(6));
+        assert!(similar_grades(&a, &b));
+        let c = report(
+            Some(Technique::NakedSingle),
+            vec![(Technique::HiddenSingle, 30), (Technique::NakedSingle, 20)],
+            0,
+        );
+        assert!(!similar_grades(&a, &c));
+        let d = report(
+            Some(Technique::HiddenSingle),
+            vec![(Technique::HiddenSingle, 50)],
+            0,
+        );
+        assert_eq!(grade_distance(&a, &d), None);
+    }
+
+    #[test]
+    fn test_challenge_puzzle() {
+        let puzzle = ClassicPuzzle::from_seed("test".to_string());
+        let solution = puzzle.solution().unwrap();
+        let harder = challenge_puzzle(&puzzle, &solution, "test").unwrap();
+        assert_eq!(harder.solution(), Some(solution));
+        assert!(hardness(&grade(&harder)) > hardness(&grade(&puzzle)));
+    }
+
+    #[test]
+    fn test_practice_puzzle() {
+        for technique in Technique::ALL {
+            let puzzle = practice_puzzle(technique, "test").unwrap();
+            assert!(requires_technique(puzzle.grid(), technique));
+            assert!(puzzle.solution().is_some());
+        }
+    }
+}
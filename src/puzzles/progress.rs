@@ -0,0 +1,44 @@
+use crate::grids::classic::ClassicGrid;
+
+use super::hint::Region;
+
+/// Returns the rows, columns, and boxes whose cells all match the solution, in that order.
+#[must_use]
+pub fn completed_units(grid: &ClassicGrid, solution: &ClassicGrid) -> Vec<Region> {
+    (0..9)
+        .map(Region::Row)
+        .chain((0..9).map(Region::Col))
+        .chain((0..9).map(Region::Box))
+        .filter(|unit| {
+            unit.cells().all(|position| {
+                let val = grid.get_by_row_col(position);
+                val.is_some() && val == solution.get_by_row_col(position)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::classic::ClassicPuzzle;
+
+    #[test]
+    fn completed_units_of_solution_and_puzzle() {
+        let puzzle = ClassicPuzzle::from_seed("progress".into());
+        let solution = puzzle.solution().unwrap();
+        assert_eq!(completed_units(&solution, &solution).len(), 27);
+
+        let mut grid = *puzzle.grid();
+        // Fill in the first row from the solution
+        for col in 0..9 {
+            grid.set((0, col), solution.get_by_row_col((0, col)));
+        }
+        assert!(completed_units(&grid, &solution).contains(&Region::Row(0)));
+
+        // A wrong value doesn't complete a unit
+        let wrong = solution.get_by_row_col((0, 1));
+        grid.set((0, 0), wrong);
+        assert!(!completed_units(&grid, &solution).contains(&Region::Row(0)));
+    }
+}
@@ -0,0 +1,441 @@
+use std::{fmt::Display, sync::OnceLock};
+
+use rand::{seq::SliceRandom, Rng};
+use rand_seeder::{SipHasher, SipRng};
+
+use crate::{
+    grids::{
+        classic::ClassicGrid,
+        samurai::{SamuraiGrid, CENTER_SUBGRID, SAMURAI_SIZE, SHARED_BOXES, SUBGRID_ORIGINS},
+    },
+    puzzles::classic::ClassicPuzzle,
+    utility::element_set::ElementSet,
+};
+
+/// The number of positions on the 21x21 board, including those that are not part of any grid.
+const BOARD_AREA: usize = SAMURAI_SIZE as usize * SAMURAI_SIZE as usize;
+/// The number of units (rows, columns, and boxes) across the five grids.
+const UNIT_COUNT: usize = 5 * 27;
+
+/// The units that constrain the Samurai board. Boxes shared between two grids appear as a unit of
+/// both grids, which is harmless since they contain the same cells.
+struct SamuraiLayout {
+    /// The cells (as `row * 21 + col`) that make up each unit
+    units: Vec<[u16; 9]>,
+    /// The units each cell belongs to (3 for most cells, 6 for cells in a shared box)
+    cell_units: Vec<Vec<u8>>,
+    /// Every cell that is part of the board
+    cells: Vec<u16>,
+}
+
+impl SamuraiLayout {
+    fn get() -> &'static Self {
+        static LAYOUT: OnceLock<SamuraiLayout> = OnceLock::new();
+        LAYOUT.get_or_init(Self::build)
+    }
+
+    fn build() -> Self {
+        let index = |row: u8, col: u8| u16::from(row) * u16::from(SAMURAI_SIZE) + u16::from(col);
+        let mut units = Vec::with_capacity(UNIT_COUNT);
+        for &(origin_row, origin_col) in &SUBGRID_ORIGINS {
+            for i in 0..9 {
+                units.push(std::array::from_fn(|j| {
+                    index(origin_row + i, origin_col + j as u8)
+                }));
+                units.push(std::array::from_fn(|j| {
+                    index(origin_row + j as u8, origin_col + i)
+                }));
+                let (box_row, box_col) = (origin_row + (i / 3) * 3, origin_col + (i % 3) * 3);
+                units.push(std::array::from_fn(|j| {
+                    index(box_row + j as u8 / 3, box_col + j as u8 % 3)
+                }));
+            }
+        }
+        let mut cell_units = vec![Vec::new(); BOARD_AREA];
+        for (unit_index, unit) in units.iter().enumerate() {
+            for &cell in unit {
+                cell_units[cell as usize].push(unit_index as u8);
+            }
+        }
+        let cells = SamuraiGrid::iter_positions()
+            .map(|(row, col)| index(row, col))
+            .collect();
+        Self {
+            units,
+            cell_units,
+            cells,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SamuraiPuzzle {
+    /// The actual Samurai grid
+    grid: SamuraiGrid,
+    /// The remaining numbers that need to be placed for each unit
+    unit_sets: [ElementSet; UNIT_COUNT],
+}
+
+impl Default for SamuraiPuzzle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SamuraiPuzzle {
+    /// Creates a new, blank Samurai board
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            grid: SamuraiGrid::default(),
+            unit_sets: [ElementSet::CLASSIC; UNIT_COUNT],
+        }
+    }
+
+    fn position(cell: u16) -> (u8, u8) {
+        (
+            (cell / u16::from(SAMURAI_SIZE)) as u8,
+            (cell % u16::from(SAMURAI_SIZE)) as u8,
+        )
+    }
+
+    /// Returns the underlying grid.
+    #[must_use]
+    pub fn grid(&self) -> &SamuraiGrid {
+        &self.grid
+    }
+
+    /// Sets a cell in the grid and removes the value from the sets of the units containing it
+    pub fn set(&mut self, position: (u8, u8), val: u8) {
+        let cell = u16::from(position.0) * u16::from(SAMURAI_SIZE) + u16::from(position.1);
+        for &unit in &SamuraiLayout::get().cell_units[cell as usize] {
+            self.unit_sets[unit as usize].remove(val);
+        }
+        self.grid.set(position, Some(val));
+    }
+
+    /// Clears a cell in the grid and adds the value back to the sets of the units containing it
+    pub fn delete(&mut self, position: (u8, u8)) {
+        if let Some(val) = self.grid.get_by_row_col(position) {
+            let cell = u16::from(position.0) * u16::from(SAMURAI_SIZE) + u16::from(position.1);
+            for &unit in &SamuraiLayout::get().cell_units[cell as usize] {
+                self.unit_sets[unit as usize].insert(val);
+            }
+            self.grid.set(position, None);
+        }
+    }
+
+    /// Gets the set of values that can still be placed in a cell.
+    fn get_element_set(&self, cell: u16) -> ElementSet {
+        SamuraiLayout::get().cell_units[cell as usize]
+            .iter()
+            .fold(ElementSet::CLASSIC, |set, &unit| {
+                set.intersection(&self.unit_sets[unit as usize])
+            })
+    }
+
+    /// Fills the board with random values. The center grid is filled first, then each corner grid
+    /// is filled independently and has its digits relabeled so that the box it shares with the
+    /// center grid matches.
+    pub fn fill_from_rng<T: Rng>(&mut self, rng: &mut T) {
+        let mut fill_classic = || {
+            let mut puzzle = ClassicPuzzle::new();
+            puzzle.fill_from_rng(rng);
+            *puzzle.grid()
+        };
+
+        let mut grid = SamuraiGrid::default();
+        let center = fill_classic();
+        grid.set_subgrid(CENTER_SUBGRID, &center);
+
+        for &(corner_subgrid, corner_box, center_box) in &SHARED_BOXES {
+            let corner = fill_classic();
+            // Map each digit in the corner's shared box to the digit in the same spot in the center
+            let mut relabel = [0u8; 10];
+            for (from, to) in corner.iter_box(corner_box).zip(center.iter_box(center_box)) {
+                relabel[from.unwrap() as usize] = to.unwrap();
+            }
+            let mut relabeled = ClassicGrid::default();
+            for row in 0..9 {
+                for col in 0..9 {
+                    let val = corner.get_by_row_col((row, col)).unwrap();
+                    relabeled.set((row, col), Some(relabel[val as usize]));
+                }
+            }
+            grid.set_subgrid(corner_subgrid, &relabeled);
+        }
+
+        *self = Self::from(grid);
+    }
+
+    /// Repeatedly places naked singles (cells with one possibility) and hidden singles (digits with
+    /// one possible cell in a unit). Returns false if a contradiction is found.
+    fn propagate(&mut self) -> bool {
+        let layout = SamuraiLayout::get();
+        loop {
+            let mut progress = false;
+
+            // Naked singles
+            for &cell in &layout.cells {
+                let position = Self::position(cell);
+                if self.grid.get_by_row_col(position).is_some() {
+                    continue;
+                }
+                let set = self.get_element_set(cell);
+                match set.len() {
+                    0 => return false,
+                    1 => {
+                        self.set(position, set.iter().next().unwrap());
+                        progress = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Hidden singles
+            for (unit_index, unit) in layout.units.iter().enumerate() {
+                let remaining = self.unit_sets[unit_index];
+                for num in &remaining {
+                    // An earlier hidden single in this unit may have already placed this number
+                    if !self.unit_sets[unit_index].has(num) {
+                        continue;
+                    }
+                    let mut only_cell = None;
+                    let mut count = 0;
+                    for &cell in unit {
+                        if self.grid.get_by_row_col(Self::position(cell)).is_none()
+                            && self.get_element_set(cell).has(num)
+                        {
+                            only_cell = Some(cell);
+                            count += 1;
+                        }
+                    }
+                    match (count, only_cell) {
+                        (0, _) => return false,
+                        (1, Some(cell)) => {
+                            self.set(Self::position(cell), num);
+                            progress = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    /// Visit solutions recursively, propagating singles and then branching on the cell with the
+    /// fewest possibilities. Stops when the passed function returns false or all solutions have
+    /// been visited.
+    pub fn visit_solutions<F>(puzzle: SamuraiPuzzle, mut visit: F)
+    where
+        F: FnMut(&SamuraiGrid) -> bool,
+    {
+        fn dfs<F>(mut puzzle: SamuraiPuzzle, visit: &mut F) -> bool
+        where
+            F: FnMut(&SamuraiGrid) -> bool,
+        {
+            if !puzzle.propagate() {
+                return true;
+            }
+
+            // Find the empty cell with the fewest possibilities
+            let mut best: Option<(u16, ElementSet)> = None;
+            for &cell in &SamuraiLayout::get().cells {
+                if puzzle
+                    .grid
+                    .get_by_row_col(SamuraiPuzzle::position(cell))
+                    .is_some()
+                {
+                    continue;
+                }
+                let set = puzzle.get_element_set(cell);
+                if best.is_none_or(|(_, best_set)| set.len() < best_set.len()) {
+                    best = Some((cell, set));
+                    if set.len() <= 2 {
+                        break;
+                    }
+                }
+            }
+
+            // No empty cells means the board is solved
+            let Some((cell, possibilities)) = best else {
+                return visit(&puzzle.grid);
+            };
+
+            // Each branch works on its own copy, so nothing needs to be undone
+            let position = SamuraiPuzzle::position(cell);
+            for num in &possibilities {
+                let mut branch = puzzle.clone();
+                branch.set(position, num);
+                if !dfs(branch, visit) {
+                    return false;
+                }
+            }
+            true
+        }
+
+        dfs(puzzle, &mut visit);
+    }
+
+    /// Count solutions up to a maximum count.
+    #[must_use]
+    pub fn count_solutions_bounded(puzzle: SamuraiPuzzle, max_count: usize) -> usize {
+        let mut count = 0;
+        if max_count == 0 {
+            return count;
+        }
+        Self::visit_solutions(puzzle, |_| {
+            count += 1;
+            count < max_count
+        });
+        count
+    }
+
+    /// Returns the first solution found for the puzzle, if it has one.
+    #[must_use]
+    pub fn solution(&self) -> Option<SamuraiGrid> {
+        let mut solution = None;
+        Self::visit_solutions(self.clone(), |grid| {
+            solution = Some(*grid);
+            false
+        });
+        solution
+    }
+
+    /// Checks if the puzzle has exactly one solution.
+    #[must_use]
+    pub fn is_well_posed(&self) -> bool {
+        Self::count_solutions_bounded(self.clone(), 2) == 1
+    }
+
+    /// Checks if the puzzle can be completed by placing naked and hidden singles alone. Such a
+    /// puzzle always has exactly one solution.
+    #[must_use]
+    pub fn is_solvable_by_singles(&self) -> bool {
+        let mut puzzle = self.clone();
+        puzzle.propagate() && puzzle.num_clues() == SamuraiGrid::iter_positions().count()
+    }
+
+    /// Clears cells from the puzzle until removing any other clue would mean it can no longer be
+    /// solved with singles alone. Checking full uniqueness after every removal is far too slow on
+    /// sparse Samurai boards since the search thrashes between the loosely coupled grids.
+    pub fn minimize_from_rng<T: Rng>(&mut self, rng: &mut T) {
+        let mut filled_cells: Vec<((u8, u8), u8)> = SamuraiGrid::iter_positions()
+            .filter_map(|position| {
+                self.grid
+                    .get_by_row_col(position)
+                    .map(|val| (position, val))
+            })
+            .collect();
+        filled_cells.shuffle(rng);
+
+        while let Some((position, val)) = filled_cells.pop() {
+            self.delete(position);
+            if !self.is_solvable_by_singles() {
+                self.set(position, val);
+            }
+        }
+    }
+
+    /// Creates and sets up a puzzle given some string seed
+    #[must_use]
+    pub fn from_seed(seed: String) -> Self {
+        let mut puzzle = SamuraiPuzzle::new();
+
+        let mut rng: SipRng = SipHasher::from(seed).into_rng();
+
+        // Fill the board
+        puzzle.fill_from_rng(&mut rng);
+
+        // Remove numbers
+        puzzle.minimize_from_rng(&mut rng);
+
+        puzzle
+    }
+
+    #[must_use]
+    pub fn num_clues(&self) -> usize {
+        SamuraiGrid::iter_positions()
+            .filter(|&position| self.grid.get_by_row_col(position).is_some())
+            .count()
+    }
+}
+
+impl Display for SamuraiPuzzle {
+    /// Displays the puzzle as a string
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.grid.fmt(f)
+    }
+}
+
+impl From<SamuraiGrid> for SamuraiPuzzle {
+    /// Creates a new `SamuraiPuzzle` from a `SamuraiGrid`
+    fn from(grid: SamuraiGrid) -> Self {
+        let mut puzzle = Self::new();
+        for position in SamuraiGrid::iter_positions() {
+            if let Some(val) = grid.get_by_row_col(position) {
+                puzzle.set(position, val);
+            }
+        }
+        puzzle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "test";
+
+    /// Checks that every unit of every grid contains each digit exactly once.
+    fn assert_solved(grid: &SamuraiGrid) {
+        for subgrid_index in 0..SUBGRID_ORIGINS.len() {
+            let subgrid = grid.subgrid(subgrid_index);
+            for i in 0..9 {
+                let row = ElementSet::from(subgrid.iter_row(i).map(|v| v.unwrap()));
+                let col = ElementSet::from(subgrid.iter_col(i).map(|v| v.unwrap()));
+                let bx = ElementSet::from(subgrid.iter_box(i).map(|v| v.unwrap()));
+                assert_eq!(row.len(), 9);
+                assert_eq!(col.len(), 9);
+                assert_eq!(bx.len(), 9);
+            }
+        }
+    }
+
+    /// Test that filling from RNG produces a valid board where the shared boxes agree.
+    #[test]
+    fn fill_from_rng_valid() {
+        let mut rng: SipRng = SipHasher::from(SEED).into_rng();
+        let mut puzzle = SamuraiPuzzle::new();
+        puzzle.fill_from_rng(&mut rng);
+        assert_eq!(puzzle.num_clues(), SamuraiGrid::iter_positions().count());
+        assert_solved(puzzle.grid());
+    }
+
+    /// Test that a filled board has exactly one solution, itself.
+    #[test]
+    fn filled_is_well_posed() {
+        let mut rng: SipRng = SipHasher::from(SEED).into_rng();
+        let mut puzzle = SamuraiPuzzle::new();
+        puzzle.fill_from_rng(&mut rng);
+        assert!(puzzle.is_well_posed());
+    }
+
+    /// Test that generating from a seed produces a well-posed puzzle whose solution is valid.
+    #[test]
+    fn from_seed_well_posed() {
+        let puzzle = SamuraiPuzzle::from_seed(SEED.into());
+        assert!(puzzle.num_clues() < SamuraiGrid::iter_positions().count());
+        let mut solutions = Vec::new();
+        SamuraiPuzzle::visit_solutions(puzzle, |grid| {
+            solutions.push(*grid);
+            true
+        });
+        assert_eq!(solutions.len(), 1);
+        assert_solved(&solutions[0]);
+    }
+}
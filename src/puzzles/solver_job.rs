@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grids::classic::ClassicGrid,
+    puzzles::{
+        classic::{ClassicPuzzle, ClassicRules},
+        hint::{find_hint, Hint},
+    },
+};
+
+/// Solving work that can run away from the game, e.g. in a web worker. Grids are sent as grid
+/// strings so that requests can be passed as text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverRequest {
+    /// Count the solutions of a grid, stopping at `bound`
+    CountSolutions {
+        grid: String,
+        rules: ClassicRules,
+        bound: usize,
+    },
+    /// Find the next hint for a grid
+    FindHint { grid: String },
+}
+
+/// The result of a `SolverRequest`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverResponse {
+    SolutionCount(usize),
+    Hint(Option<Hint>),
+    /// The request's grid couldn't be worked on, and why
+    Failed(String),
+}
+
+/// A request along with the id its response is sent back with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolverMessage<T> {
+    pub id: u64,
+    pub body: T,
+}
+
+impl SolverRequest {
+    /// Does the work of the request.
+    #[must_use]
+    pub fn run(&self) -> SolverResponse {
+        match self {
+            SolverRequest::CountSolutions { grid, rules, bound } => {
+                match grid
+                    .parse()
+                    .and_then(|grid| ClassicPuzzle::try_from_grid_with_rules(grid, *rules))
+                {
+                    Ok(puzzle) => SolverResponse::SolutionCount(
+                        ClassicPuzzle::count_solutions_bounded_recursive(puzzle, *bound),
+                    ),
+                    Err(err) => SolverResponse::Failed(err.to_string()),
+                }
+            }
+            SolverRequest::FindHint { grid } => match grid.parse::<ClassicGrid>() {
+                Ok(grid) => SolverResponse::Hint(find_hint(&grid)),
+                Err(err) => SolverResponse::Failed(err.to_string()),
+            },
+        }
+    }
+}
+
+impl<T: Serialize> SolverMessage<T> {
+    /// Returns the message as text, for posting it to a worker.
+    #[must_use]
+    pub fn encode(&self) -> String {
+        ron::to_string(self).unwrap_or_default()
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> SolverMessage<T> {
+    /// Reads a message posted as text, or returns an error describing why it couldn't be read.
+    pub fn decode(text: &str) -> Result<Self, String> {
+        ron::from_str(text).map_err(|err| err.to_string())
+    }
+}
+
+/// Runs a request posted as text and returns its response as text. This is what a worker does
+/// with each message it receives.
+pub fn handle_solver_message(text: &str) -> Result<String, String> {
+    let SolverMessage { id, body } = SolverMessage::<SolverRequest>::decode(text)?;
+    Ok(SolverMessage {
+        id,
+        body: body.run(),
+    }
+    .encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const SOLUTION_STR: &str = indoc! {"
+        5 6 2 | 1 4 3 | 9 8 7
+        3 7 8 | 2 5 9 | 6 1 4
+        9 4 1 | 7 8 6 | 3 2 5
+        ------|-------|------
+        4 8 6 | 5 1 2 | 7 3 9
+        7 2 3 | 9 6 8 | 5 4 1
+        1 5 9 | 4 3 7 | 8 6 2
+        ------|-------|------
+        2 3 4 | 8 7 5 | 1 9 6
+        8 9 7 | 6 2 1 | 4 5 3
+        6 1 5 | 3 9 4 | 2 7 8
+    "};
+
+    #[test]
+    fn test_handle_solver_message() {
+        let mut grid = SOLUTION_STR.parse::<ClassicGrid>().unwrap();
+        grid.set((4, 4), None);
+        let requests = [
+            SolverRequest::CountSolutions {
+                grid: grid.to_string(),
+                rules: ClassicRules::default(),
+                bound: 10,
+            },
+            SolverRequest::FindHint {
+                grid: grid.to_string(),
+            },
+        ];
+        let responses = requests.map(|request| {
+            let message = SolverMessage {
+                id: 7,
+                body: request,
+            }
+            .encode();
+            let reply = handle_solver_message(&message).unwrap();
+            SolverMessage::<SolverResponse>::decode(&reply).unwrap()
+        });
+        assert!(responses.iter().all(|response| response.id == 7));
+        assert_eq!(responses[0].body, SolverResponse::SolutionCount(1));
+        assert!(matches!(
+            responses[1].body,
+            SolverResponse::Hint(Some(Hint {
+                position: (4, 4),
+                val: 6,
+                ..
+            }))
+        ));
+        assert!(handle_solver_message("not a message").is_err());
+        assert!(matches!(
+            SolverRequest::FindHint {
+                grid: "not a grid".into()
+            }
+            .run(),
+            SolverResponse::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::grids::classic::ClassicGrid;
+
+use super::{
+    arrow::ArrowPuzzle,
+    classic::{ClassicPuzzle, ClassicRules},
+    conflict::classic_conflicts,
+};
+
+/// How many solutions a puzzle has, as far as whether it's playable goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolutionCount {
+    None,
+    Unique,
+    /// Two or more
+    Many,
+}
+
+impl SolutionCount {
+    fn of(solutions: usize) -> Self {
+        match solutions {
+            0 => SolutionCount::None,
+            1 => SolutionCount::Unique,
+            _ => SolutionCount::Many,
+        }
+    }
+}
+
+/// A puzzle on a classic grid that's being edited one given or constraint at a time, which the
+/// verifier can check against the puzzle it checked before.
+pub trait DesignedPuzzle {
+    /// Returns the numbers placed on the grid.
+    fn givens(&self) -> &ClassicGrid;
+
+    /// Returns true if a finished grid agrees with the givens and follows every constraint.
+    fn accepts(&self, solution: &ClassicGrid) -> bool;
+
+    /// Returns true if every constraint of `other`, besides its givens, is also one of this
+    /// puzzle's.
+    fn has_constraints_of(&self, other: &Self) -> bool;
+
+    /// Returns up to `max_count` solutions.
+    fn find_solutions(&self, max_count: usize) -> Vec<ClassicGrid>;
+}
+
+/// Returns true if every given of `other` is also given, with the same number, in `grid`.
+fn has_givens_of(grid: &ClassicGrid, other: &ClassicGrid) -> bool {
+    (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .all(|position| {
+            other
+                .get_by_row_col(position)
+                .is_none_or(|val| grid.get_by_row_col(position) == Some(val))
+        })
+}
+
+/// Returns true if a finished grid has every given and follows the classic rules (along with any
+/// optional ones).
+fn accepts_classic(givens: &ClassicGrid, rules: ClassicRules, solution: &ClassicGrid) -> bool {
+    solution.iter_all().all(Option::is_some)
+        && has_givens_of(solution, givens)
+        && classic_conflicts(solution, rules).is_empty()
+}
+
+/// A classic puzzle being designed: its givens and the optional rules it's played with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClassicDesign {
+    pub givens: ClassicGrid,
+    pub rules: ClassicRules,
+}
+
+impl DesignedPuzzle for ClassicDesign {
+    fn givens(&self) -> &ClassicGrid {
+        &self.givens
+    }
+
+    fn accepts(&self, solution: &ClassicGrid) -> bool {
+        accepts_classic(&self.givens, self.rules, solution)
+    }
+
+    fn has_constraints_of(&self, other: &Self) -> bool {
+        (self.rules.hyper_windows || !other.rules.hyper_windows)
+            && (self.rules.anti_king || !other.rules.anti_king)
+            && (self.rules.non_consecutive || !other.rules.non_consecutive)
+    }
+
+    fn find_solutions(&self, max_count: usize) -> Vec<ClassicGrid> {
+        // Givens that break the rules can't be solved, and the solver can't work with them
+        ClassicPuzzle::try_from_grid_with_rules(self.givens, self.rules).map_or_else(
+            |_| Vec::new(),
+            |puzzle| ClassicPuzzle::find_solutions_bounded_recursive(puzzle, max_count),
+        )
+    }
+}
+
+impl DesignedPuzzle for ArrowPuzzle {
+    fn givens(&self) -> &ClassicGrid {
+        self.grid()
+    }
+
+    fn accepts(&self, solution: &ClassicGrid) -> bool {
+        accepts_classic(self.grid(), ClassicRules::default(), solution)
+            && self.arrows().iter().all(|arrow| {
+                let sum: u8 = arrow
+                    .path
+                    .iter()
+                    .filter_map(|position| solution.get_by_row_col(*position))
+                    .sum();
+                solution.get_by_row_col(arrow.circle) == Some(sum)
+            })
+    }
+
+    fn has_constraints_of(&self, other: &Self) -> bool {
+        other
+            .arrows()
+            .iter()
+            .all(|arrow| self.arrows().contains(arrow))
+    }
+
+    fn find_solutions(&self, max_count: usize) -> Vec<ClassicGrid> {
+        let mut solutions = Vec::new();
+        if max_count == 0 {
+            return solutions;
+        }
+        ArrowPuzzle::visit_solutions(self.clone(), |grid| {
+            solutions.push(*grid);
+            solutions.len() < max_count
+        });
+        solutions
+    }
+}
+
+/// Tells how many solutions a puzzle has as it's edited, fast enough to run after every edit.
+/// The solutions found by the last search are kept, and an edit is only searched again when they
+/// can't settle it:
+/// - Solutions that still fit the edited puzzle are solutions of it, so two of them mean it has
+///   many.
+/// - Adding a given or constraint can only take solutions away, so a puzzle without any stays
+///   without any, and a unique puzzle stays unique if its solution still fits.
+#[derive(Clone, Debug)]
+pub struct UniquenessVerifier<P> {
+    last: Option<(P, SolutionCount)>,
+    /// The solutions found for the last puzzle, up to two
+    solutions: Vec<ClassicGrid>,
+    searches: usize,
+}
+
+impl<P> Default for UniquenessVerifier<P> {
+    fn default() -> Self {
+        Self {
+            last: None,
+            solutions: Vec::new(),
+            searches: 0,
+        }
+    }
+}
+
+impl<P: DesignedPuzzle + Clone> UniquenessVerifier<P> {
+    /// Returns how many solutions the puzzle has, searching for them only if the last puzzle's
+    /// solutions don't tell.
+    pub fn verify(&mut self, puzzle: &P) -> SolutionCount {
+        self.solutions.retain(|solution| puzzle.accepts(solution));
+        let tightened = self.last.as_ref().and_then(|(last, count)| {
+            (puzzle.has_constraints_of(last) && has_givens_of(puzzle.givens(), last.givens()))
+                .then_some(*count)
+        });
+        let known = match tightened {
+            _ if self.solutions.len() >= 2 => Some(SolutionCount::Many),
+            Some(SolutionCount::None) => Some(SolutionCount::None),
+            Some(SolutionCount::Unique) => Some(SolutionCount::of(self.solutions.len())),
+            _ => None,
+        };
+        let count = known.unwrap_or_else(|| {
+            self.searches += 1;
+            self.solutions = puzzle.find_solutions(2);
+            SolutionCount::of(self.solutions.len())
+        });
+        self.last = Some((puzzle.clone(), count));
+        count
+    }
+
+    /// Returns how many times a puzzle had to be searched.
+    #[must_use]
+    pub fn searches(&self) -> usize {
+        self.searches
+    }
+
+    /// Returns the solution of the last puzzle if it's unique.
+    #[must_use]
+    pub fn solution(&self) -> Option<ClassicGrid> {
+        match (&self.last, self.solutions.as_slice()) {
+            (Some((_, SolutionCount::Unique)), [solution]) => Some(*solution),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIVENS: &str = "
+        53..7....
+        6..195...
+        .98....6.
+        8...6...3
+        4..8.3..1
+        7...2...6
+        .6....28.
+        ...419..5
+        ....8..79
+    ";
+
+    fn design() -> ClassicDesign {
+        ClassicDesign {
+            givens: GIVENS.parse().unwrap(),
+            rules: ClassicRules::default(),
+        }
+    }
+
+    #[test]
+    fn test_tightening_reuses_the_solution() {
+        let mut verifier = UniquenessVerifier::default();
+        let mut design = design();
+        assert_eq!(verifier.verify(&design), SolutionCount::Unique);
+        assert_eq!(verifier.searches(), 1);
+        let solution = verifier.solution().unwrap();
+
+        // A given from the solution keeps the puzzle unique
+        design.givens.set((0, 2), solution.get_by_row_col((0, 2)));
+        assert_eq!(verifier.verify(&design), SolutionCount::Unique);
+        // A given that contradicts it leaves no solutions
+        design.givens.set((0, 3), Some(1));
+        assert_eq!(verifier.verify(&design), SolutionCount::None);
+        design.givens.set((0, 5), Some(2));
+        assert_eq!(verifier.verify(&design), SolutionCount::None);
+        assert_eq!(verifier.searches(), 1);
+    }
+
+    #[test]
+    fn test_relaxing_searches_until_two_solutions_are_known() {
+        let mut verifier = UniquenessVerifier::default();
+        let mut design = design();
+        assert_eq!(verifier.verify(&design), SolutionCount::Unique);
+
+        // Clearing most of the givens makes room for more solutions
+        for row in 0..6 {
+            for col in 0..9 {
+                design.givens.set((row, col), None);
+            }
+        }
+        assert_eq!(verifier.verify(&design), SolutionCount::Many);
+        assert_eq!(verifier.searches(), 2);
+        // Relaxing further keeps both solutions
+        design.givens.set((6, 1), None);
+        assert_eq!(verifier.verify(&design), SolutionCount::Many);
+        assert_eq!(verifier.searches(), 2);
+    }
+
+    #[test]
+    fn test_rules_are_constraints() {
+        let mut verifier = UniquenessVerifier::default();
+        let mut design = design();
+        assert_eq!(verifier.verify(&design), SolutionCount::Unique);
+        let solution = verifier.solution().unwrap();
+
+        // Turning a rule on only searches again if it's needed, and the answer matches a fresh
+        // search either way
+        design.rules.anti_king = true;
+        let expected = SolutionCount::of(design.find_solutions(2).len());
+        assert_eq!(verifier.verify(&design), expected);
+        assert_eq!(verifier.searches(), 1);
+        assert_eq!(expected == SolutionCount::Unique, design.accepts(&solution));
+    }
+
+    #[test]
+    fn test_arrow_puzzle_without_arrows_matches_classic() {
+        let mut verifier = UniquenessVerifier::default();
+        let puzzle = ArrowPuzzle::from_grid(GIVENS.parse().unwrap(), Vec::new());
+        assert_eq!(verifier.verify(&puzzle), SolutionCount::Unique);
+        assert_eq!(verifier.verify(&puzzle), SolutionCount::Unique);
+        assert_eq!(verifier.searches(), 1);
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt::Display;
+
+use crate::grids::classic::ClassicGrid;
+
+use super::{
+    classic::ClassicRules,
+    conflict::{classic_conflicts, Conflict},
+};
+
+/// Why a saved or imported puzzle can't be played.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The grid doesn't have 9 rows
+    WrongRowCount(usize),
+    /// A row of the grid doesn't have 9 cells
+    WrongRowLength { row: u8, cells: usize },
+    /// A cell has something other than a number from 1 to 9
+    ValueOutOfRange { position: (u8, u8), found: char },
+    /// The givens break the rules
+    ConflictingGivens(Conflict),
+    /// The solution has an empty cell
+    IncompleteSolution((u8, u8)),
+    /// The solution breaks the rules
+    InvalidSolution(Conflict),
+    /// A given doesn't match the solution
+    SolutionMismatch {
+        position: (u8, u8),
+        given: u8,
+        solution: u8,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cell = |(row, col): (u8, u8)| format!("r{}c{}", row + 1, col + 1);
+        match self {
+            ValidationError::WrongRowCount(rows) => {
+                write!(f, "the grid has {rows} rows instead of 9")
+            }
+            ValidationError::WrongRowLength { row, cells } => {
+                write!(f, "row {} has {cells} cells instead of 9", row + 1)
+            }
+            ValidationError::ValueOutOfRange { position, found } => {
+                write!(
+                    f,
+                    "{} has {found:?}, which isn't a number from 1 to 9",
+                    cell(*position)
+                )
+            }
+            ValidationError::ConflictingGivens(conflict) => {
+                write!(f, "the givens break a rule: {conflict}")
+            }
+            ValidationError::IncompleteSolution(position) => {
+                write!(f, "the solution is missing {}", cell(*position))
+            }
+            ValidationError::InvalidSolution(conflict) => {
+                write!(f, "the solution breaks a rule: {conflict}")
+            }
+            ValidationError::SolutionMismatch {
+                position,
+                given,
+                solution,
+            } => write!(
+                f,
+                "{} is given as {given}, but the solution has {solution}",
+                cell(*position)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Parses a grid string, rejecting strings that aren't exactly 9 rows of 9 cells with numbers from
+/// 1 to 9 or `.` for empty cells. Spaces and the `|` and `-` box separators are allowed.
+pub fn parse_grid(s: &str) -> Result<ClassicGrid, ValidationError> {
+    let rows: Vec<&str> = s
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .collect();
+    if rows.len() != 9 {
+        return Err(ValidationError::WrongRowCount(rows.len()));
+    }
+    let mut grid = ClassicGrid::default();
+    for (row, line) in (0..9).zip(rows) {
+        let cells: Vec<char> = line.chars().filter(|&c| c != ' ' && c != '|').collect();
+        if cells.len() != 9 {
+            return Err(ValidationError::WrongRowLength {
+                row,
+                cells: cells.len(),
+            });
+        }
+        for (col, found) in (0..9).zip(cells) {
+            let val = match found {
+                '.' => None,
+                '1'..='9' => found.to_digit(10).map(|val| val as u8),
+                _ => {
+                    return Err(ValidationError::ValueOutOfRange {
+                        position: (row, col),
+                        found,
+                    })
+                }
+            };
+            grid.set((row, col), val);
+        }
+    }
+    Ok(grid)
+}
+
+/// Checks that givens don't break the rules, such as by repeating a number in a row.
+pub fn validate_givens(givens: &ClassicGrid, rules: ClassicRules) -> Result<(), ValidationError> {
+    match classic_conflicts(givens, rules).into_iter().next() {
+        Some(conflict) => Err(ValidationError::ConflictingGivens(conflict)),
+        None => Ok(()),
+    }
+}
+
+/// Parses and checks a saved puzzle's givens and solution: the givens can't break the rules, and
+/// the solution has to be a finished grid that follows the rules and agrees with the givens.
+pub fn validate_classic_puzzle(
+    givens: &str,
+    solution: &str,
+    rules: ClassicRules,
+) -> Result<(ClassicGrid, ClassicGrid), ValidationError> {
+    let givens = parse_grid(givens)?;
+    let solution = parse_grid(solution)?;
+    validate_givens(&givens, rules)?;
+    let positions = (0..9).flat_map(|row| (0..9).map(move |col| (row, col)));
+    if let Some(position) = positions
+        .clone()
+        .find(|&position| solution.get_by_row_col(position).is_none())
+    {
+        return Err(ValidationError::IncompleteSolution(position));
+    }
+    if let Some(conflict) = classic_conflicts(&solution, rules).into_iter().next() {
+        return Err(ValidationError::InvalidSolution(conflict));
+    }
+    for position in positions {
+        if let (Some(given), Some(solved)) = (
+            givens.get_by_row_col(position),
+            solution.get_by_row_col(position),
+        ) {
+            if given != solved {
+                return Err(ValidationError::SolutionMismatch {
+                    position,
+                    given,
+                    solution: solved,
+                });
+            }
+        }
+    }
+    Ok((givens, solution))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::puzzles::conflict::ConflictRule;
+
+    use super::*;
+
+    const GIVENS: &str = indoc! {"
+        53..7....
+        6..195...
+        .98....6.
+        8...6...3
+        4..8.3..1
+        7...2...6
+        .6....28.
+        ...419..5
+        ....8..79
+    "};
+
+    const SOLUTION: &str = indoc! {"
+        534678912
+        672195348
+        198342567
+        859761423
+        426853791
+        713924856
+        961537284
+        287419635
+        345286179
+    "};
+
+    #[test]
+    fn test_parse_grid() {
+        assert_eq!(
+            parse_grid(GIVENS),
+            Ok(GIVENS.parse::<ClassicGrid>().unwrap())
+        );
+        // The grids the app writes, with box separators, can be read back
+        let grid = SOLUTION.parse::<ClassicGrid>().unwrap();
+        assert_eq!(parse_grid(&grid.to_string()), Ok(grid));
+        assert_eq!(
+            parse_grid("53..7...."),
+            Err(ValidationError::WrongRowCount(1))
+        );
+        let short_row = GIVENS.replacen("53..7....", "53..7...", 1);
+        assert_eq!(
+            parse_grid(&short_row),
+            Err(ValidationError::WrongRowLength { row: 0, cells: 8 })
+        );
+        let zero = GIVENS.replacen("53..7....", "50..7....", 1);
+        assert_eq!(
+            parse_grid(&zero),
+            Err(ValidationError::ValueOutOfRange {
+                position: (0, 1),
+                found: '0'
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_classic_puzzle() {
+        let rules = ClassicRules::default();
+        assert!(validate_classic_puzzle(GIVENS, SOLUTION, rules).is_ok());
+
+        let repeated = GIVENS.replacen("53..7....", "53..7...5", 1);
+        let Err(ValidationError::ConflictingGivens(conflict)) =
+            validate_classic_puzzle(&repeated, SOLUTION, rules)
+        else {
+            panic!("the repeated given wasn't found");
+        };
+        assert_eq!(conflict.rule, ConflictRule::Row(0));
+
+        let incomplete = SOLUTION.replacen("534678912", "53467891.", 1);
+        assert_eq!(
+            validate_classic_puzzle(GIVENS, &incomplete, rules),
+            Err(ValidationError::IncompleteSolution((0, 8)))
+        );
+
+        // A different finished grid: the first two rows swapped
+        let swapped = SOLUTION.replacen("534678912\n672195348", "672195348\n534678912", 1);
+        assert_eq!(
+            validate_classic_puzzle(GIVENS, &swapped, rules),
+            Err(ValidationError::SolutionMismatch {
+                position: (0, 0),
+                given: 5,
+                solution: 6
+            })
+        );
+    }
+}
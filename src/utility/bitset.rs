@@ -1,16 +1,76 @@
-/// A fixed-size set of bits, stored in a `u16` with a maximum length of 16.
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not},
+};
+
+/// An unsigned integer that stores the bits of a `BitSetN`.
+pub trait Bits:
+    Copy
+    + Debug
+    + Default
+    + Eq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+{
+    /// The most numbers the bits can hold.
+    const CAPACITY: u8;
+
+    /// Returns the bits with only the bit at a position set.
+    fn mask(bit: u8) -> Self;
+
+    fn count_ones(self) -> u32;
+
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_bits {
+    ($($int:ty),*) => {
+        $(
+            impl Bits for $int {
+                #[allow(clippy::cast_possible_truncation)]
+                const CAPACITY: u8 = <$int>::BITS as u8;
+
+                fn mask(bit: u8) -> Self {
+                    1 << bit
+                }
+
+                fn count_ones(self) -> u32 {
+                    <$int>::count_ones(self)
+                }
+
+                fn trailing_zeros(self) -> u32 {
+                    <$int>::trailing_zeros(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_bits!(u16, u32, u64);
+
+/// A fixed-size set of bits, stored in an unsigned integer with a bit for each number.
 ///
 /// This struct is useful for efficiently representing a set of numbers between
-/// 1 and 16, inclusive. Each bit in the `u16` represents the presence or absence
-/// of a number in the set.
-#[derive(Copy, Clone, Debug, Default)]
-pub struct BitSet16(pub(super) u16);
+/// 1 and the number of bits in `B`, inclusive. Each bit in `B` represents the presence or
+/// absence of a number in the set.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitSetN<B: Bits>(pub(super) B);
+
+/// A set of the numbers 1 to 16, which is enough for boards up to 16x16.
+pub type BitSet16 = BitSetN<u16>;
 
-impl<I> From<I> for BitSet16
+/// A set of the numbers 1 to 32, which is enough for boards up to 32x32 (like 25x25 boards).
+pub type BitSet32 = BitSetN<u32>;
+
+impl<B, I> From<I> for BitSetN<B>
 where
+    B: Bits,
     I: Iterator<Item = u8>,
 {
-    /// Creates a new `BitSet16` from a range of numbers.
+    /// Creates a new `BitSetN` from a range of numbers.
     ///
     /// This function will set the bits corresponding to each number in the range.
     ///
@@ -19,18 +79,19 @@ where
     /// * `range` - A range of numbers to insert into the bitset.
     ///
     fn from(iter: I) -> Self {
-        Self(iter.fold(0, |acc, num| {
-            acc | BitSet16::mask(BitSet16::num_to_bit(num))
+        Self(iter.fold(B::default(), |acc, num| {
+            acc | B::mask(Self::num_to_bit(num))
         }))
     }
 }
 
-impl BitSet16 {
+impl<B: Bits> BitSetN<B> {
     /// Converts a number to the corresponding bit position in the bitset.
     ///
     /// # Arguments
     ///
-    /// * `num` - A number to convert to a bit position. This should be in the range 1..=16.
+    /// * `num` - A number to convert to a bit position. This should be in the range
+    ///   1..=`B::CAPACITY`.
     ///
     fn num_to_bit(num: u8) -> u8 {
         num - 1
@@ -46,8 +107,8 @@ impl BitSet16 {
     ///
     /// * `bit` - The position for which to create a bitmask.
     ///
-    fn mask(bit: u8) -> u16 {
-        1 << bit
+    fn mask(bit: u8) -> B {
+        B::mask(bit)
     }
 
     /// Returns the number of elements in the bitset.
@@ -55,15 +116,15 @@ impl BitSet16 {
     #[must_use]
     #[allow(clippy::cast_possible_truncation)]
     pub fn len(&self) -> u8 {
-        // There are only up to 16 1s in a u16, so no truncation can occur here.
+        // There are at most 64 1s in the bits, so no truncation can occur here.
         self.0.count_ones() as u8
     }
 
     /// Returns an iterator over the numbers in the bitset.
     ///
     #[must_use]
-    pub fn iter(&self) -> BitSet16Iter<'_> {
-        BitSet16Iter { set: self, bit: 0 }
+    pub fn iter(&self) -> BitSetNIter<'_, B> {
+        BitSetNIter { set: self, bit: 0 }
     }
 
     /// Gets the value of the bit at the specified position.
@@ -72,7 +133,7 @@ impl BitSet16 {
     ///
     /// * `bit` - The position of the bit to get.
     ///
-    fn get_bit(self, bit: u8) -> u16 {
+    fn get_bit(self, bit: u8) -> B {
         self.0 & Self::mask(bit)
     }
 
@@ -83,14 +144,15 @@ impl BitSet16 {
     /// * `bit` - The position of the bit to check.
     ///
     fn bit_is_one(self, bit: u8) -> bool {
-        self.get_bit(bit) != 0
+        self.get_bit(bit) != B::default()
     }
 
     /// Checks if the bitset contains a specific number.
     ///
     /// # Arguments
     ///
-    /// * `num` - The number to check for presence in the bitset. Should be in the range 1..=16.
+    /// * `num` - The number to check for presence in the bitset. Should be in the range
+    ///   1..=`B::CAPACITY`.
     ///
     #[must_use]
     pub fn has(&self, num: u8) -> bool {
@@ -113,7 +175,8 @@ impl BitSet16 {
     ///
     /// # Arguments
     ///
-    /// * `num` - The number to insert into the bitset. Should be in the range 1..=16.
+    /// * `num` - The number to insert into the bitset. Should be in the range
+    ///   1..=`B::CAPACITY`.
     ///
     pub fn insert(&mut self, num: u8) {
         self.set_bit(Self::num_to_bit(num));
@@ -135,20 +198,21 @@ impl BitSet16 {
     ///
     /// # Arguments
     ///
-    /// * `num` - The number to remove from the bitset. Should be in the range 1..=16.
+    /// * `num` - The number to remove from the bitset. Should be in the range
+    ///   1..=`B::CAPACITY`.
     ///
     pub fn remove(&mut self, num: u8) {
         self.clear_bit(Self::num_to_bit(num));
     }
 
-    /// Computes the intersection of two `BitSet16` instances, returning a new bitset.
+    /// Computes the intersection of two bitsets, returning a new bitset.
     ///
     /// The intersection is a new bitset containing only the numbers present in both
     /// `self` and `other`.
     ///
     /// # Arguments
     ///
-    /// * `other` - The other `BitSet16` to intersect with.
+    /// * `other` - The other bitset to intersect with.
     ///
     #[must_use]
     pub fn intersection(&self, other: &Self) -> Self {
@@ -160,6 +224,7 @@ impl BitSet16 {
         self.len() == 0
     }
 
+    #[allow(clippy::cast_possible_truncation)]
     pub fn pop(&mut self) -> Option<u8> {
         if self.is_empty() {
             return None;
@@ -170,16 +235,20 @@ impl BitSet16 {
     }
 }
 
-pub struct BitSet16Iter<'a> {
-    set: &'a BitSet16,
+pub struct BitSetNIter<'a, B: Bits> {
+    set: &'a BitSetN<B>,
     bit: u8,
 }
 
-impl Iterator for BitSet16Iter<'_> {
+pub type BitSet16Iter<'a> = BitSetNIter<'a, u16>;
+
+pub type BitSet32Iter<'a> = BitSetNIter<'a, u32>;
+
+impl<B: Bits> Iterator for BitSetNIter<'_, B> {
     type Item = u8;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.bit < 16 {
+        while self.bit < B::CAPACITY {
             let bit_is_one = self.set.bit_is_one(self.bit);
             self.bit += 1;
             if bit_is_one {
@@ -195,9 +264,9 @@ impl Iterator for BitSet16Iter<'_> {
     }
 }
 
-impl<'a> IntoIterator for &'a BitSet16 {
+impl<'a, B: Bits> IntoIterator for &'a BitSetN<B> {
     type Item = u8;
-    type IntoIter = BitSet16Iter<'a>;
+    type IntoIter = BitSetNIter<'a, B>;
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
@@ -280,6 +349,18 @@ mod tests {
         assert!(!intersection.has(3));
     }
 
+    #[test]
+    fn test_bitset32_holds_25_numbers() {
+        let mut bitset = BitSet32::from(1..=25);
+        assert_eq!(bitset.len(), 25);
+        assert!(bitset.has(25));
+        assert!(!bitset.has(26));
+        bitset.remove(17);
+        assert!(!bitset.has(17));
+        assert_eq!(bitset.iter().last(), Some(25));
+        assert_eq!(bitset.pop(), Some(1));
+    }
+
     #[test]
     fn test_pop() {
         let mut bitset = BitSet16::default();
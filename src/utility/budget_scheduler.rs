@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+/// How soon a background job should run compared to the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JobPriority {
+    Low,
+    Normal,
+    High,
+}
+
+/// What a background job reports after doing a step of its work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobStep {
+    /// There's more work, so the job should be run again
+    Continue,
+    Done,
+}
+
+/// A step of background work, given the context the scheduler runs jobs with. Each call should
+/// do a small amount of work, so that the scheduler can stop between steps to stay in budget.
+pub type Job<C> = Box<dyn FnMut(&mut C) -> JobStep + Send + Sync>;
+
+struct QueuedJob<C> {
+    name: &'static str,
+    priority: JobPriority,
+    /// When the job was queued or last run, so that jobs with the same priority take turns
+    turn: u64,
+    job: Job<C>,
+}
+
+/// Runs queued jobs a step at a time until a time budget is used up, so that background work can
+/// be spread across frames. The job with the highest priority runs first, and jobs with the same
+/// priority take turns.
+pub struct BudgetScheduler<C> {
+    jobs: Vec<QueuedJob<C>>,
+    next_turn: u64,
+}
+
+impl<C> Default for BudgetScheduler<C> {
+    fn default() -> Self {
+        Self {
+            jobs: Vec::new(),
+            next_turn: 0,
+        }
+    }
+}
+
+impl<C> BudgetScheduler<C> {
+    /// Queues a job, named for diagnostics.
+    pub fn push(
+        &mut self,
+        name: &'static str,
+        priority: JobPriority,
+        job: impl FnMut(&mut C) -> JobStep + Send + Sync + 'static,
+    ) {
+        let turn = self.take_turn();
+        self.jobs.push(QueuedJob {
+            name,
+            priority,
+            turn,
+            job: Box::new(job),
+        });
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Returns the names of the queued jobs, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.jobs.iter().map(|queued| queued.name)
+    }
+
+    /// Runs steps of the queued jobs until `elapsed` (the time since the caller started timing)
+    /// reaches the budget or there are no jobs left. At least one step is run if there's a job, so
+    /// that work is never starved by a budget that's too small. Returns how many steps were run.
+    pub fn run(
+        &mut self,
+        context: &mut C,
+        budget: Duration,
+        elapsed: impl Fn() -> Duration,
+    ) -> usize {
+        let mut steps = 0;
+        while steps == 0 || elapsed() < budget {
+            // The highest priority, then the job that has waited the longest
+            let Some(index) = (0..self.jobs.len()).max_by_key(|&index| {
+                let queued = &self.jobs[index];
+                (queued.priority, std::cmp::Reverse(queued.turn))
+            }) else {
+                break;
+            };
+            let step = (self.jobs[index].job)(context);
+            steps += 1;
+            match step {
+                JobStep::Continue => self.jobs[index].turn = self.take_turn(),
+                JobStep::Done => {
+                    self.jobs.swap_remove(index);
+                }
+            }
+        }
+        steps
+    }
+
+    fn take_turn(&mut self) -> u64 {
+        self.next_turn += 1;
+        self.next_turn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Counts down, logging the job's name at each step.
+    fn countdown(
+        name: &'static str,
+        mut steps: usize,
+    ) -> impl FnMut(&mut Vec<&'static str>) -> JobStep + Send + Sync {
+        move |log: &mut Vec<&'static str>| {
+            log.push(name);
+            steps -= 1;
+            if steps == 0 {
+                JobStep::Done
+            } else {
+                JobStep::Continue
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_by_priority_then_turns() {
+        let mut scheduler = BudgetScheduler::default();
+        scheduler.push("low", JobPriority::Low, countdown("low", 1));
+        scheduler.push("a", JobPriority::Normal, countdown("a", 2));
+        scheduler.push("b", JobPriority::Normal, countdown("b", 2));
+        scheduler.push("high", JobPriority::High, countdown("high", 1));
+        let mut log = Vec::new();
+        assert_eq!(scheduler.run(&mut log, Duration::MAX, || Duration::ZERO), 6);
+        assert_eq!(log, ["high", "a", "b", "a", "b", "low"]);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_run_stops_at_budget() {
+        let mut scheduler = BudgetScheduler::default();
+        scheduler.push("long", JobPriority::Normal, countdown("long", 10));
+        // Each check of the clock says another millisecond has passed
+        let clock = Cell::new(Duration::ZERO);
+        let elapsed = || {
+            clock.set(clock.get() + Duration::from_millis(1));
+            clock.get()
+        };
+        let mut log = Vec::new();
+        assert_eq!(
+            scheduler.run(&mut log, Duration::from_millis(3), elapsed),
+            3
+        );
+        assert_eq!(scheduler.names().collect::<Vec<_>>(), ["long"]);
+        // A step is still run when the budget is already used up
+        assert_eq!(scheduler.run(&mut log, Duration::ZERO, || Duration::MAX), 1);
+        assert_eq!(log.len(), 4);
+    }
+}
@@ -1,31 +1,58 @@
-use super::bitset::{BitSet16, BitSet16Iter};
+use super::bitset::{BitSetN, BitSetNIter, Bits};
 
 use std::cmp::Ordering;
 
-/// A wrapper around a `BitSet16`, where the `Eq`, `PartialEq`, `PartialOrd`, and `Ord` traits have
+/// A wrapper around a `BitSetN`, where the `Eq`, `PartialEq`, `PartialOrd`, and `Ord` traits have
 /// been implemented based on the cardinality (length) of the set. This is useful for use in when
 /// you need to compare sets based on their size alone, such as in a MRV (Minimum Remaining Values)
 /// priority queue.
 #[derive(Copy, Clone, Debug, Default)]
-pub struct ElementSet(BitSet16);
+pub struct ElementSetN<B: Bits>(BitSetN<B>);
 
-impl PartialEq for ElementSet {
+/// The size of a board, which picks the bits its element sets need.
+pub struct BoardSize<const N: usize>;
+
+/// Gives the bits that can hold every number on a board of some size.
+pub trait BoardBits {
+    type Bits: Bits;
+}
+
+macro_rules! impl_board_bits {
+    ($bits:ty => $($size:literal),*) => {
+        $(
+            impl BoardBits for BoardSize<$size> {
+                type Bits = $bits;
+            }
+        )*
+    };
+}
+
+impl_board_bits!(u16 => 4, 6, 8, 9, 12, 16);
+impl_board_bits!(u32 => 25);
+
+/// The element set of a board with `N` numbers, like `BoardElementSet<25>` for 25x25 boards.
+pub type BoardElementSet<const N: usize> = ElementSetN<<BoardSize<N> as BoardBits>::Bits>;
+
+/// The element set of classic 9x9 boards.
+pub type ElementSet = BoardElementSet<9>;
+
+impl<B: Bits> PartialEq for ElementSetN<B> {
     /// Checks if two element sets are equal by comparing their cardinality (length).
     fn eq(&self, other: &Self) -> bool {
         self.0.len() == other.0.len()
     }
 }
 
-impl Eq for ElementSet {}
+impl<B: Bits> Eq for ElementSetN<B> {}
 
-impl PartialOrd for ElementSet {
+impl<B: Bits> PartialOrd for ElementSetN<B> {
     /// Compares two element sets by their cardinality (length).
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for ElementSet {
+impl<B: Bits> Ord for ElementSetN<B> {
     /// Compares two element sets by their cardinality (length).
     fn cmp(&self, other: &Self) -> Ordering {
         other.0.len().cmp(&self.0.len())
@@ -34,7 +61,15 @@ impl Ord for ElementSet {
 
 impl ElementSet {
     /// Creates a new `ElementSet` containing all numbers from 1 to 9.
-    pub const CLASSIC: Self = Self(BitSet16(0b1_1111_1111));
+    pub const CLASSIC: Self = Self(BitSetN(0b1_1111_1111));
+}
+
+impl<B: Bits> ElementSetN<B> {
+    /// Creates a set containing all numbers from 1 to `size`.
+    #[must_use]
+    pub fn full(size: u8) -> Self {
+        Self(BitSetN::from(1..=size))
+    }
 
     /// Removes a number from the set.
     pub fn remove(&mut self, num: u8) {
@@ -54,7 +89,7 @@ impl ElementSet {
 
     /// Returns an iterator over the numbers in the set.
     #[must_use]
-    pub fn iter(&self) -> BitSet16Iter<'_> {
+    pub fn iter(&self) -> BitSetNIter<'_, B> {
         self.0.iter()
     }
 
@@ -82,20 +117,42 @@ impl ElementSet {
     }
 }
 
-impl<I> From<I> for ElementSet
+impl<B, I> From<I> for ElementSetN<B>
 where
+    B: Bits,
     I: Iterator<Item = u8>,
 {
-    /// Creates a new `ElementSet` from an iterator of numbers.
+    /// Creates a new `ElementSetN` from an iterator of numbers.
     fn from(iter: I) -> Self {
-        Self(BitSet16::from(iter))
+        Self(BitSetN::from(iter))
     }
 }
 
-impl<'a> IntoIterator for &'a ElementSet {
+impl<'a, B: Bits> IntoIterator for &'a ElementSetN<B> {
     type Item = u8;
-    type IntoIter = BitSet16Iter<'a>;
+    type IntoIter = BitSetNIter<'a, B>;
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_element_sets_hold_every_number() {
+        let classic = BoardElementSet::<9>::full(9);
+        assert_eq!(
+            classic.iter().collect::<Vec<_>>(),
+            ElementSet::CLASSIC.iter().collect::<Vec<_>>()
+        );
+
+        let mut large = BoardElementSet::<25>::full(25);
+        assert_eq!(large.len(), 25);
+        large.remove(25);
+        assert!(!large.has(25));
+        // Fewer remaining values sort first, like in the MRV queue
+        assert!(large > BoardElementSet::<25>::full(25));
+    }
+}
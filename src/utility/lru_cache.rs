@@ -0,0 +1,129 @@
+/// A small least-recently-used cache. Entries are kept in a vector ordered from least to most
+/// recently used, which is faster than hashing for the handful of entries it is meant to hold.
+#[derive(Clone, Debug)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: Vec<(K, V)>,
+}
+
+impl<K: PartialEq, V> LruCache<K, V> {
+    /// Creates an empty cache that holds at most `capacity` entries.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the cache has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value for a key and marks it as the most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(index);
+        self.entries.push(entry);
+        self.entries.last().map(|(_, v)| v)
+    }
+
+    /// Inserts a value as the most recently used, evicting the least recently used entry if the
+    /// cache is full. Returns the evicted entry, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        self.remove(&key);
+        let evicted = if self.entries.len() == self.capacity {
+            Some(self.entries.remove(0))
+        } else {
+            None
+        };
+        self.entries.push((key, value));
+        evicted
+    }
+
+    /// Removes and returns the value for a key.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Removes every entry that doesn't satisfy the predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&K, &V) -> bool) {
+        self.entries.retain(|(k, v)| predicate(k, v));
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_insert() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.insert(1, "one"), None);
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        // Using 1 makes 2 the least recently used
+        cache.get(&1);
+        assert_eq!(cache.insert(3, "three"), Some((2, "two")));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&3), Some(&"three"));
+    }
+
+    #[test]
+    fn test_insert_existing_key() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.insert(1, "uno"), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), Some(&"uno"));
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut cache = LruCache::new(0);
+        assert_eq!(cache.insert(1, "one"), Some((1, "one")));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_retain_remove_clear() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.insert(i, i * 10);
+        }
+        cache.retain(|k, _| k % 2 == 0);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.remove(&2), Some(20));
+        assert_eq!(cache.remove(&2), None);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
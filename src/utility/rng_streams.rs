@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use rand_seeder::{SipHasher, SipRng};
+
+/// Named random number streams that all come from one session seed, so that everything random in
+/// a session can be replayed by starting another with the same seed. Each stream only depends on
+/// the session seed and its name, so using one stream more doesn't change what the others give.
+#[derive(Clone, Debug)]
+pub struct RngStreams {
+    session_seed: String,
+    streams: HashMap<&'static str, SipRng>,
+}
+
+impl RngStreams {
+    #[must_use]
+    pub fn new(session_seed: String) -> Self {
+        Self {
+            session_seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// The seed the streams come from.
+    #[must_use]
+    pub fn session_seed(&self) -> &str {
+        &self.session_seed
+    }
+
+    /// Returns the stream with a name, starting it the first time it's asked for.
+    pub fn stream(&mut self, name: &'static str) -> &mut SipRng {
+        let session_seed = &self.session_seed;
+        self.streams
+            .entry(name)
+            .or_insert_with(|| SipHasher::from(format!("{session_seed}/{name}")).into_rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_streams_are_reproducible_and_independent() {
+        let mut first = RngStreams::new("session".into());
+        let mut second = RngStreams::new("session".into());
+        // Using another stream first doesn't change what a stream gives
+        let _: u64 = second.stream("remix").random();
+        let seeds: Vec<u64> = (0..4).map(|_| first.stream("seeds").random()).collect();
+        let same_seeds: Vec<u64> = (0..4).map(|_| second.stream("seeds").random()).collect();
+        assert_eq!(seeds, same_seeds);
+
+        let remix: u64 = first.stream("remix").random();
+        assert_ne!(seeds[0], remix);
+
+        let mut other_session = RngStreams::new("other session".into());
+        let other_seed: u64 = other_session.stream("seeds").random();
+        assert_ne!(seeds[0], other_seed);
+        assert_eq!(first.session_seed(), "session");
+    }
+}
@@ -0,0 +1,92 @@
+/// A point in the coordinates of the drawing being stroked.
+pub type Point = (f32, f32);
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let (apx, apy) = (p.0 - a.0, p.1 - a.1);
+    let length_squared = abx * abx + aby * aby;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        ((apx * abx + apy * aby) / length_squared).clamp(0.0, 1.0)
+    };
+    let (dx, dy) = (apx - t * abx, apy - t * aby);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Returns the points of a circular arc from one angle to another (in radians, clockwise from the
+/// positive x axis since y points down), including both ends.
+#[must_use]
+pub fn arc(center: Point, radius: f32, start: f32, end: f32) -> Vec<Point> {
+    // Enough segments that the flat sides don't show at icon sizes
+    let segments = ((end - start).abs() / (std::f32::consts::PI / 16.0))
+        .ceil()
+        .max(1.0) as usize;
+    (0..=segments)
+        .map(|i| {
+            let angle = start + (end - start) * i as f32 / segments as f32;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// Strokes polylines with round caps and joins, returning the coverage of each pixel of a square
+/// `size` by `size` image, row by row. `view_box` is the width of the drawing's coordinates, which
+/// are scaled to fit the image, and `stroke_width` is in the same coordinates.
+#[must_use]
+pub fn stroke_polylines(
+    polylines: &[Vec<Point>],
+    view_box: f32,
+    stroke_width: f32,
+    size: u32,
+) -> Vec<u8> {
+    let scale = size as f32 / view_box;
+    let half_width = stroke_width * scale / 2.0;
+    let segments: Vec<(Point, Point)> = polylines
+        .iter()
+        .flat_map(|polyline| match polyline.as_slice() {
+            [point] => vec![(*point, *point)],
+            points => points.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+        })
+        .map(|(a, b)| ((a.0 * scale, a.1 * scale), (b.0 * scale, b.1 * scale)))
+        .collect();
+    (0..size * size)
+        .map(|i| {
+            let pixel = ((i % size) as f32 + 0.5, (i / size) as f32 + 0.5);
+            let distance = segments
+                .iter()
+                .map(|&(a, b)| distance_to_segment(pixel, a, b))
+                .fold(f32::INFINITY, f32::min);
+            // Fade over one pixel at the edge of the stroke
+            let coverage = (half_width + 0.5 - distance).clamp(0.0, 1.0);
+            (coverage * 255.0).round() as u8
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stroke_covers_the_line_and_its_caps() {
+        // A horizontal line across the middle of an 8x8 image, 2 pixels wide
+        let coverage = stroke_polylines(&[vec![(2.0, 4.0), (6.0, 4.0)]], 8.0, 2.0, 8);
+        let at = |x: usize, y: usize| coverage[y * 8 + x];
+        assert_eq!(at(4, 3), 255);
+        assert_eq!(at(4, 4), 255);
+        assert_eq!(at(4, 0), 0);
+        assert_eq!(at(4, 7), 0);
+        // The round cap reaches one unit past each end
+        assert!(at(1, 3) > 0 && at(6, 4) > 0);
+        assert_eq!(at(0, 4), 0);
+
+        let quarter = arc((0.0, 0.0), 2.0, 0.0, std::f32::consts::FRAC_PI_2);
+        let (first, last) = (quarter[0], quarter[quarter.len() - 1]);
+        assert!((first.0 - 2.0).abs() < 1e-5 && first.1.abs() < 1e-5);
+        assert!(last.0.abs() < 1e-5 && (last.1 - 2.0).abs() < 1e-5);
+    }
+}
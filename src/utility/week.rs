@@ -0,0 +1,176 @@
+use std::{fmt::Display, time::Duration};
+
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// An ISO 8601 week. Weeks start on Monday and belong to the year that contains their Thursday,
+/// so the first few days of January can be in the last week of the previous year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IsoWeek {
+    year: i32,
+    week: u8,
+    /// The Monday that starts the week, in days since the Unix epoch
+    first_day: i64,
+}
+
+/// Returns the civil (proleptic Gregorian) year and day of the year (starting at 0) for a number
+/// of days since the Unix epoch.
+fn year_and_ordinal(days: i64) -> (i32, i64) {
+    // Shift to eras starting on March 1st, 0000 so that leap days come last
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    // January and February belong to the next year
+    let year = year_of_era + era * 400 + i64::from(month_index >= 10);
+    let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let ordinal = if month_index >= 10 {
+        day_of_year - 306
+    } else {
+        day_of_year + 59 + i64::from(is_leap)
+    };
+    (year as i32, ordinal)
+}
+
+impl IsoWeek {
+    /// Returns the week containing a day, given in days since the Unix epoch.
+    #[must_use]
+    pub fn from_unix_days(days: i64) -> Self {
+        // The epoch was a Thursday
+        let weekday = (days + 3).rem_euclid(7);
+        let first_day = days - weekday;
+        let (year, ordinal) = year_and_ordinal(first_day + 3);
+        Self {
+            year,
+            week: (ordinal / 7 + 1) as u8,
+            first_day,
+        }
+    }
+
+    /// Returns the week containing a time, given in seconds since the Unix epoch.
+    #[must_use]
+    pub fn from_unix_secs(secs: u64) -> Self {
+        Self::from_unix_days((secs / SECONDS_PER_DAY) as i64)
+    }
+
+    /// Returns the current week (in UTC).
+    #[must_use]
+    pub fn now() -> Self {
+        Self::from_unix_secs(unix_secs_now())
+    }
+
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    #[must_use]
+    pub fn week(&self) -> u8 {
+        self.week
+    }
+
+    /// Returns the number of weeks since the week containing the Unix epoch.
+    #[must_use]
+    pub fn index(&self) -> i64 {
+        (self.first_day + 3).div_euclid(7)
+    }
+
+    /// Returns the time from a moment (in seconds since the Unix epoch) until the next week starts.
+    #[must_use]
+    pub fn until_next(secs: u64) -> Duration {
+        let next_first_day = Self::from_unix_secs(secs).first_day + 7;
+        Duration::from_secs(next_first_day as u64 * SECONDS_PER_DAY - secs)
+    }
+}
+
+impl Display for IsoWeek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-W{:02}", self.year, self.week)
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+#[must_use]
+pub fn unix_secs_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Returns the day (counted from the Unix epoch, in UTC) that a moment falls on.
+#[must_use]
+pub fn unix_day(secs: u64) -> u64 {
+    secs / SECONDS_PER_DAY
+}
+
+/// Returns how many days in a row, up to `today`, are among `days`. A streak that reached
+/// yesterday still counts until today is over.
+#[must_use]
+pub fn day_streak(days: impl IntoIterator<Item = u64>, today: u64) -> u32 {
+    let days: std::collections::HashSet<u64> = days.into_iter().collect();
+    let last = if days.contains(&today) {
+        today
+    } else {
+        today.saturating_sub(1)
+    };
+    (0..=last)
+        .rev()
+        .take_while(|day| days.contains(day))
+        .count()
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_unix_days() {
+        let weeks = [
+            (0, "1970-W01"),
+            (18_627, "2020-W53"),
+            (18_628, "2020-W53"),
+            (20_087, "2025-W01"),
+            (20_740, "2026-W42"),
+            (20_821, "2026-W53"),
+        ];
+        for (days, expected) in weeks {
+            assert_eq!(IsoWeek::from_unix_days(days).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        assert_eq!(IsoWeek::from_unix_days(0).index(), 0);
+        assert_eq!(IsoWeek::from_unix_days(-3).index(), 0);
+        assert_eq!(IsoWeek::from_unix_days(-4).index(), -1);
+        assert_eq!(IsoWeek::from_unix_days(3).index(), 0);
+        assert_eq!(IsoWeek::from_unix_days(4).index(), 1);
+    }
+
+    #[test]
+    fn test_day_streak() {
+        assert_eq!(unix_day(20_740 * SECONDS_PER_DAY + 30), 20_740);
+        assert_eq!(day_streak([], 100), 0);
+        assert_eq!(day_streak([98, 99, 100, 100], 100), 3);
+        // Yesterday's streak isn't broken until today ends
+        assert_eq!(day_streak([97, 98, 99], 100), 3);
+        assert_eq!(day_streak([96, 97, 98], 100), 0);
+        assert_eq!(day_streak([0, 1], 1), 2);
+    }
+
+    #[test]
+    fn test_until_next() {
+        // Wednesday 2026-10-14 at 00:00:30 is four days and 23:59:30 before Monday
+        let secs = 20_740 * SECONDS_PER_DAY + 30;
+        assert_eq!(
+            IsoWeek::until_next(secs),
+            Duration::from_secs(5 * SECONDS_PER_DAY - 30)
+        );
+    }
+}
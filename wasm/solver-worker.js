@@ -0,0 +1,10 @@
+// Runs the game's solver off the main thread. Built with the `solver-worker` feature, the game
+// posts each solver request here as text and reads the reply the same way.
+import init, { solver_worker_handle } from "./sudoku_machine.js";
+
+const ready = init();
+
+self.onmessage = async (event) => {
+  await ready;
+  self.postMessage(solver_worker_handle(event.data));
+};
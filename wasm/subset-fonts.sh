@@ -0,0 +1,28 @@
+#!/bin/sh
+# Cuts the fonts in a copy of the game's assets down to the characters the game shows, so the web
+# build downloads less. Run it on the assets being deployed, not the repo's own `assets/`:
+#
+#   cp -r assets dist/assets && wasm/subset-fonts.sh dist/assets
+#
+# Needs `pyftsubset` from fontTools (`pip install fonttools`).
+set -eu
+
+if [ $# -ne 1 ]; then
+    echo "usage: $0 <assets dir>" >&2
+    exit 1
+fi
+fonts="$1/fonts"
+
+# Basic Latin, Latin-1 punctuation, the dashes, quotes, bullet, and ellipsis used in the text, and
+# the arrows and checks the symbol font draws
+text_unicodes="U+0020-007E,U+00A0-00BF,U+00D7,U+2013-2014,U+2018-201D,U+2022,U+2026"
+symbol_unicodes="U+2190-21FF,U+2713-2718,U+2B05-2B07"
+
+for font in OpenSans-Regular OpenSans-Bold; do
+    pyftsubset "$fonts/$font.ttf" --unicodes="$text_unicodes" --layout-features='*' \
+        --output-file="$fonts/$font.subset.ttf"
+    mv "$fonts/$font.subset.ttf" "$fonts/$font.ttf"
+done
+pyftsubset "$fonts/NotoSansSymbols2-Regular.ttf" --unicodes="$symbol_unicodes" \
+    --output-file="$fonts/NotoSansSymbols2-Regular.subset.ttf"
+mv "$fonts/NotoSansSymbols2-Regular.subset.ttf" "$fonts/NotoSansSymbols2-Regular.ttf"